@@ -0,0 +1,47 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use vrl::value;
+use vrl::value::{value::IterItem, Value};
+
+fn shallow_event() -> Value {
+    value!({
+        "message": "GET /index.html HTTP/1.1",
+        "status": 200,
+        "host": {"name": "web-1", "ip": "10.0.0.1"},
+        "tags": ["a", "b", "c"],
+        "http": {"request": {"method": "GET", "headers": {"user-agent": "curl/8.0"}}},
+    })
+}
+
+fn nested_event() -> Value {
+    (0..100).fold(
+        value!("leaf"),
+        |value, i| value!({"level": i, "child": [value]}),
+    )
+}
+
+fn count_values(value: Value) -> usize {
+    let mut iter = value.into_iter(true);
+    let count = iter
+        .by_ref()
+        .filter(|item| matches!(item, IterItem::KeyValue(..) | IterItem::IndexValue(..)))
+        .count();
+    black_box(Value::from(iter));
+    count
+}
+
+fn benchmark_recursive_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("vrl_value/value::into_iter");
+
+    group.bench_function("recursive shallow event", |b| {
+        b.iter_batched(shallow_event, count_values, BatchSize::SmallInput)
+    });
+
+    group.bench_function("recursive nested event", |b| {
+        b.iter_batched(nested_event, count_values, BatchSize::SmallInput)
+    });
+}
+
+criterion_group!(name = vrl_value_iter;
+                 config = Criterion::default();
+                 targets = benchmark_recursive_iter);
+criterion_main!(vrl_value_iter);
@@ -2,7 +2,7 @@ use chrono_tz::Tz;
 use clap::Parser;
 use glob::glob;
 
-use vrl::compiler::{CompileConfig, TimeZone, VrlRuntime};
+use vrl::compiler::{CompileConfig, TimeZone, UnusedExpression, VrlRuntime};
 use vrl::test::{get_tests_from_functions, run_tests, test_dir, Test, TestConfig};
 
 #[cfg(not(target_env = "msvc"))]
@@ -83,7 +83,13 @@ fn main() {
         tests,
         &cfg,
         &vrl::stdlib::all(),
-        || (CompileConfig::default(), ()),
+        || {
+            // Many tests evaluate expressions only to check that they compile, so unused
+            // expressions are only reported by tests that ask for it.
+            let mut config = CompileConfig::default();
+            config.set_unused_expression(UnusedExpression::Allow);
+            (config, ())
+        },
         |_| {},
     );
 }
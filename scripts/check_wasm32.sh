@@ -1,6 +1,10 @@
 #!/bin/bash
-echo "Add wasm32-unknown-unknown target"
-rustup target add wasm32-unknown-unknown
+set -e
 
-echo "Run check"
-cargo check --target wasm32-unknown-unknown --no-default-features --features stdlib
+for target in wasm32-unknown-unknown wasm32-wasi; do
+  echo "Add $target target"
+  rustup target add "$target"
+
+  echo "Run check for $target"
+  cargo check --target "$target" --no-default-features --features wasm --lib --test wasm
+done
@@ -26,6 +26,7 @@ use super::Error;
 
 #[derive(Parser, Debug)]
 #[command(name = "VRL", about = "Vector Remap Language CLI")]
+#[allow(clippy::struct_excessive_bools)] // command line flags
 pub struct Opts {
     /// The VRL program to execute. The program ".foo = true", for example, sets the event object's
     /// `foo` field to `true`.
@@ -56,6 +57,34 @@ pub struct Opts {
     // Should the CLI emit warnings
     #[arg(long = "print-warnings")]
     print_warnings: bool,
+
+    /// Print the error codes of runtime errors along with their messages.
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// List the signatures of all available functions instead of running a program.
+    #[arg(long = "list-functions", conflicts_with_all(["PROGRAM", "program_file"]))]
+    list_functions: bool,
+
+    /// The output format used by `--list-functions`.
+    #[arg(
+        long = "format",
+        value_enum,
+        default_value_t,
+        requires("list_functions")
+    )]
+    format: ListFormat,
+}
+
+/// The output formats supported by `--list-functions`.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListFormat {
+    /// One function name per line.
+    #[default]
+    Text,
+
+    /// A JSON array of function signatures.
+    Json,
 }
 
 impl Opts {
@@ -113,6 +142,10 @@ pub fn cmd(opts: &Opts, stdlib_functions: Vec<Box<dyn Function>>) -> exitcode::E
 }
 
 fn run(opts: &Opts, stdlib_functions: Vec<Box<dyn Function>>) -> Result<(), Error> {
+    if opts.list_functions {
+        return list_functions(opts.format, &stdlib_functions);
+    }
+
     let tz = opts.timezone()?;
     // Run the REPL if no program or program file is specified
     if opts.should_open_repl() {
@@ -178,6 +211,10 @@ fn run(opts: &Opts, stdlib_functions: Vec<Box<dyn Function>>) -> Result<(), Erro
             #[allow(clippy::print_stderr)]
             match result {
                 Ok(ok) => println!("{ok}"),
+                Err(Error::Runtime(err)) if opts.verbose => match err.code() {
+                    Some(code) => eprintln!("{err}\n  = error code: {code}"),
+                    None => eprintln!("{err}"),
+                },
                 Err(err) => eprintln!("{err}"),
             }
         }
@@ -186,6 +223,33 @@ fn run(opts: &Opts, stdlib_functions: Vec<Box<dyn Function>>) -> Result<(), Erro
     }
 }
 
+fn list_functions(format: ListFormat, functions: &[Box<dyn Function>]) -> Result<(), Error> {
+    let mut functions = functions.iter().collect::<Vec<_>>();
+    functions.sort_by_key(|function| function.identifier());
+
+    let output = match format {
+        ListFormat::Text => functions
+            .iter()
+            .map(|function| function.identifier())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ListFormat::Json => {
+            let signatures = functions
+                .iter()
+                .map(|function| function.signature())
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&signatures)?
+        }
+    };
+
+    #[allow(clippy::print_stdout)]
+    {
+        println!("{output}");
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::unnecessary_wraps)]
 fn repl(
     objects: Vec<Value>,
@@ -11,6 +11,20 @@ pub struct CompileConfig {
     /// Custom context injected by the external environment
     custom: AnyMap,
     read_only_paths: BTreeSet<ReadOnlyPath>,
+    unused_expression: UnusedExpression,
+}
+
+/// How the compiler reports an expression whose result is unused, when the expression has no
+/// side effects and so could be removed without changing the program.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnusedExpression {
+    /// Don't report unused expressions.
+    Allow,
+    /// Report unused expressions as warnings.
+    #[default]
+    Warn,
+    /// Report unused expressions as errors, failing the compilation.
+    Deny,
 }
 
 impl CompileConfig {
@@ -70,6 +84,16 @@ impl CompileConfig {
         self.read_only_paths
             .insert(ReadOnlyPath { path, recursive });
     }
+
+    /// Sets how expressions with unused results and no side effects are reported.
+    pub fn set_unused_expression(&mut self, unused_expression: UnusedExpression) {
+        self.unused_expression = unused_expression;
+    }
+
+    #[must_use]
+    pub fn unused_expression(&self) -> UnusedExpression {
+        self.unused_expression
+    }
 }
 
 #[derive(Debug, Clone, Ord, Eq, PartialEq, PartialOrd)]
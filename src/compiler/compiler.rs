@@ -4,14 +4,16 @@ use crate::compiler::{
     expression::{
         assignment, function_call, literal, predicate, query, Abort, Array, Assignment, Block,
         Container, Expr, Expression, FunctionArgument, FunctionCall, Group, IfStatement, Literal,
-        Noop, Not, Object, Op, Predicate, Query, Target, Unary, Variable,
+        Noop, Not, Object, Op, Predicate, Query, Target, Template, Try, Unary, Variable,
     },
     parser::ast::RootExpr,
     program::ProgramInfo,
-    CompileConfig, DeprecationWarning, Function, Program, TypeDef,
+    CompileConfig, DeprecationWarning, Function, Program, Span, TypeDef, UnusedExpression,
+    UnusedExpressionWarning,
 };
-use crate::diagnostic::{DiagnosticList, DiagnosticMessage, Note};
+use crate::diagnostic::{DiagnosticList, DiagnosticMessage, Note, Severity};
 use crate::parser::ast::{self, Node, QueryTarget};
+use crate::parser::{self, StringSegment, TemplateString};
 use crate::path::PathPrefix;
 use crate::path::{OwnedTargetPath, OwnedValuePath};
 use crate::prelude::{expression, ArgumentList};
@@ -164,6 +166,9 @@ impl<'a> Compiler<'a> {
             Op(node) => self.compile_op(node, state).map(Into::into),
             Assignment(node) => self.compile_assignment(node, state).map(Into::into),
             Query(node) => self.compile_query(node, state).map(Into::into),
+            FunctionCall(node) if node.ident.as_deref() == "try" => {
+                self.compile_try(node, state).map(Into::into)
+            }
             FunctionCall(node) => self.compile_function_call(node, state).map(Into::into),
             Variable(node) => self.compile_variable(node, state).map(Into::into),
             Unary(node) => self.compile_unary(node, state).map(Into::into),
@@ -195,6 +200,8 @@ impl<'a> Compiler<'a> {
             String(template) => {
                 if let Some(v) = template.as_literal_string() {
                     Ok(Literal::String(Bytes::from(v.to_string())))
+                } else if template.has_format_specs() {
+                    return self.compile_template(template, state).map(Into::into);
                 } else {
                     // Rewrite the template into an expression and compile that block.
                     return self.compile_expr(
@@ -224,6 +231,46 @@ impl<'a> Compiler<'a> {
             .ok()
     }
 
+    fn compile_template(
+        &mut self,
+        template: TemplateString,
+        state: &mut TypeState,
+    ) -> Option<Template> {
+        use expression::literal::ErrorVariant;
+        use expression::template::{FormatSpec, Segment};
+
+        let mut segments = Vec::with_capacity(template.0.len());
+        for segment in template.0 {
+            let segment = match segment {
+                StringSegment::Literal(literal, _) => Segment::Literal(literal),
+                StringSegment::Template(content, span) => {
+                    let (target, spec) = parser::split_format_spec(&content);
+                    let spec = match spec.map(str::parse::<FormatSpec>).transpose() {
+                        Ok(spec) => spec,
+                        Err(err) => {
+                            let variant = ErrorVariant::InvalidFormatSpec(err);
+                            self.diagnostics
+                                .push(Box::new(literal::Error::from((span, variant))));
+                            return None;
+                        }
+                    };
+                    let expr = self.compile_expr(
+                        Node::new(span, parser::template_target(target, span)),
+                        state,
+                    )?;
+
+                    Segment::Expr {
+                        expr: Box::new(expr),
+                        spec,
+                    }
+                }
+            };
+            segments.push(segment);
+        }
+
+        Some(Template::new(segments))
+    }
+
     fn compile_container(
         &mut self,
         node: Node<ast::Container>,
@@ -253,17 +300,24 @@ impl<'a> Compiler<'a> {
         state: &mut TypeState,
     ) -> Vec<Expr> {
         let mut node_exprs = vec![];
+        let mut nodes = nodes.into_iter().peekable();
 
-        for root_expr in nodes {
+        while let Some(root_expr) = nodes.next() {
             match root_expr.into_inner() {
                 RootExpr::Expr(node_expr) => {
                     self.fallible_expression_error = None;
 
+                    let span = node_expr.span();
                     if let Some(expr) = self.compile_expr(node_expr, state) {
                         if let Some(error) = self.fallible_expression_error.take() {
                             self.diagnostics.push(error.into_diagnostic_boxed());
                         }
 
+                        // The last expression is the result of the program.
+                        if nodes.peek().is_some() {
+                            self.check_unused_expr(&expr, span, state);
+                        }
+
                         node_exprs.push(expr);
                     }
                 }
@@ -288,7 +342,19 @@ impl<'a> Compiler<'a> {
         state: &mut TypeState,
     ) -> Option<(Block, TypeDef)> {
         let original_state = state.clone();
-        let exprs = self.compile_exprs(node.into_inner().into_iter(), state)?;
+        let mut nodes = node.into_inner().into_iter().peekable();
+        let mut exprs = vec![];
+        while let Some(node) = nodes.next() {
+            let span = node.span();
+            let expr = self.compile_expr(node, state)?;
+
+            // The last expression is the value of the block.
+            if nodes.peek().is_some() {
+                self.check_unused_expr(&expr, span, state);
+            }
+
+            exprs.push(expr);
+        }
         let block = Block::new_scoped(exprs);
 
         // The type information from `compile_exprs` doesn't applying the "scoping" from the block.
@@ -298,6 +364,24 @@ impl<'a> Compiler<'a> {
         Some((block, result))
     }
 
+    /// Reports an expression whose result is discarded, if it has no side effects, as configured
+    /// by [`CompileConfig::set_unused_expression`].
+    ///
+    /// The state is the one after the expression, which is the same as the one before it when
+    /// the expression has no side effects.
+    fn check_unused_expr(&mut self, expr: &Expr, span: Span, state: &TypeState) {
+        let severity = match self.config.unused_expression() {
+            UnusedExpression::Allow => return,
+            UnusedExpression::Warn => Severity::Warning,
+            UnusedExpression::Deny => Severity::Error,
+        };
+
+        if is_side_effect_free(expr, state) {
+            self.diagnostics
+                .push(Box::new(UnusedExpressionWarning::new(span, severity)));
+        }
+    }
+
     fn compile_array(&mut self, node: Node<ast::Array>, state: &mut TypeState) -> Option<Array> {
         let exprs = self.compile_exprs(node.into_inner().into_iter(), state)?;
 
@@ -610,6 +694,12 @@ impl<'a> Compiler<'a> {
                 let container = self.compile_container(Node::new(span, container), state)?;
                 Target::Container(container)
             }
+            // Queries on `try(...)` are queries on a group holding the try expression.
+            FunctionCall(call) if call.ident.as_deref() == "try" => {
+                let r#try = self.compile_try(Node::new(span, call), state)?;
+                let group = Group::new(r#try.into());
+                Target::Container(expression::Container::new(group.into()))
+            }
             FunctionCall(call) => {
                 let call = self.compile_function_call(Node::new(span, call), state)?;
                 Target::FunctionCall(call)
@@ -816,6 +906,49 @@ impl<'a> Compiler<'a> {
             .ok()
     }
 
+    /// `try(<expr>)` is parsed as a function call, but compiled into its own expression, as it
+    /// has to accept a fallible argument.
+    fn compile_try(&mut self, node: Node<ast::FunctionCall>, state: &mut TypeState) -> Option<Try> {
+        use expression::r#try::{Error, ErrorVariant};
+
+        let (span, call) = node.take();
+        let ast::FunctionCall {
+            abort_on_error,
+            mut arguments,
+            closure,
+            ..
+        } = call;
+
+        let variant = if abort_on_error {
+            Some(ErrorVariant::AbortOnError)
+        } else if closure.is_some() {
+            Some(ErrorVariant::Closure)
+        } else if arguments.len() != 1 {
+            Some(ErrorVariant::WrongNumberOfArgs)
+        } else if arguments[0].ident.is_some() {
+            Some(ErrorVariant::KeywordArgument)
+        } else {
+            None
+        };
+
+        if let Some(variant) = variant {
+            self.diagnostics.push(Box::new(Error { variant, span }));
+            return None;
+        }
+
+        // Save the errors of earlier expressions, so that only the error of the argument is
+        // discarded below.
+        let fallible_expression_error = self.fallible_expression_error.take();
+
+        let argument = arguments.pop().expect("exactly one argument").into_inner();
+        let expr = self.compile_expr(argument.expr, state)?;
+
+        // The error of the argument is handled, which makes it infallible.
+        self.fallible_expression_error = fallible_expression_error;
+
+        Some(Try::new(expr))
+    }
+
     fn handle_parser_error(&mut self, error: crate::parser::Error) {
         self.diagnostics.push(Box::new(error));
     }
@@ -840,3 +973,20 @@ impl<'a> Compiler<'a> {
         self.skip_missing_query_target.push(query);
     }
 }
+
+/// Returns `true` if the expression is a literal, a variable or path query, or a call to a pure
+/// function with such arguments, so that the result is the only reason to evaluate it.
+///
+/// Calls that can abort the program with an error are included, see [`FunctionCall::is_pure`].
+fn is_side_effect_free(expr: &Expr, state: &TypeState) -> bool {
+    match expr {
+        Expr::Literal(_) | Expr::Variable(_) => true,
+        Expr::Query(query) => matches!(query.target(), Target::Internal(_) | Target::External(_)),
+        Expr::FunctionCall(call) => {
+            call.arguments()
+                .all(|argument| is_side_effect_free(argument, state))
+                && call.is_pure(state)
+        }
+        _ => false,
+    }
+}
@@ -1,11 +1,13 @@
 use super::TimeZone;
 
-use super::{state::RuntimeState, Target};
+use super::{state::RuntimeState, LogHandler, Target, TracingLogHandler, ValueLimits};
 
 pub struct Context<'a> {
     target: &'a mut dyn Target,
     state: &'a mut RuntimeState,
     timezone: &'a TimeZone,
+    log_handler: &'a dyn LogHandler,
+    value_limits: ValueLimits,
 }
 
 impl<'a> Context<'a> {
@@ -19,9 +21,26 @@ impl<'a> Context<'a> {
             target,
             state,
             timezone,
+            log_handler: &TracingLogHandler,
+            value_limits: ValueLimits::default(),
         }
     }
 
+    /// Use the given [`LogHandler`] for the messages of the `log` function, instead of emitting
+    /// them as `tracing` events.
+    #[must_use]
+    pub fn with_log_handler(mut self, log_handler: &'a dyn LogHandler) -> Self {
+        self.log_handler = log_handler;
+        self
+    }
+
+    /// Limit the size of the values the program builds.
+    #[must_use]
+    pub fn with_value_limits(mut self, value_limits: ValueLimits) -> Self {
+        self.value_limits = value_limits;
+        self
+    }
+
     /// Get a reference to the [`Target`].
     #[must_use]
     pub fn target(&self) -> &dyn Target {
@@ -49,4 +68,16 @@ impl<'a> Context<'a> {
     pub fn timezone(&self) -> &TimeZone {
         self.timezone
     }
+
+    /// Get a reference to the [`LogHandler`].
+    #[must_use]
+    pub fn log_handler(&self) -> &dyn LogHandler {
+        self.log_handler
+    }
+
+    /// Get a reference to the [`ValueLimits`].
+    #[must_use]
+    pub fn value_limits(&self) -> &ValueLimits {
+        &self.value_limits
+    }
 }
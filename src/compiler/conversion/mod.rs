@@ -10,6 +10,7 @@ use ordered_float::NotNan;
 use snafu::{ResultExt, Snafu};
 
 use super::datetime::{datetime_to_utc, TimeZone};
+use super::ErrorCode;
 
 #[cfg(test)]
 mod tests;
@@ -54,6 +55,21 @@ pub enum Error {
     AutoTimestampParse { s: String },
 }
 
+impl Error {
+    /// The [`ErrorCode`] of the runtime errors caused by this error.
+    #[must_use]
+    pub fn error_code(&self) -> ErrorCode {
+        use std::num::IntErrorKind::{NegOverflow, PosOverflow};
+
+        match self {
+            Self::IntParse { source, .. } if matches!(source.kind(), PosOverflow | NegOverflow) => {
+                ErrorCode::OutOfRange
+            }
+            _ => ErrorCode::Parse,
+        }
+    }
+}
+
 /// Helper function to parse a conversion map and check against a list of names
 ///
 /// # Errors
@@ -7,8 +7,8 @@ use serde::{Deserialize, Serialize};
 
 /// Timezone reference.
 ///
-/// This can refer to any valid timezone as defined in the [TZ database][tzdb], or "local" which
-/// refers to the system local timezone.
+/// This can refer to any valid timezone as defined in the [TZ database][tzdb], a fixed offset from
+/// UTC such as "+02:00", or "local" which refers to the system local timezone.
 ///
 /// [tzdb]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
@@ -24,6 +24,9 @@ pub enum TimeZone {
     ///
     /// [tzdb]: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones
     Named(Tz),
+
+    /// A fixed offset from UTC.
+    Fixed(FixedOffset),
 }
 
 /// This is a wrapper trait to allow `TimeZone` types to be passed generically.
@@ -46,18 +49,54 @@ impl TimeZone {
                 let tz_datetime = parsed.to_datetime_with_timezone(tz)?;
                 Ok(datetime_to_utc(&tz_datetime))
             }
+            Self::Fixed(offset) => {
+                let offset_datetime = parsed.to_datetime_with_timezone(offset)?;
+                Ok(datetime_to_utc(&offset_datetime))
+            }
         }
     }
 
+    /// Parses a timezone reference: "local", a name from the TZ database, or a UTC offset in one
+    /// of the `+HH:MM`, `+HHMM` or `+HH` forms.
     #[must_use]
     pub fn parse(s: &str) -> Option<Self> {
         match s {
             "" | "local" => Some(Self::Local),
+            _ if s.starts_with(['+', '-']) => parse_offset(s).map(Self::Fixed),
             _ => s.parse::<Tz>().ok().map(Self::Named),
         }
     }
 }
 
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    // The splits below are at byte positions, which are only character boundaries in ASCII.
+    if !s.is_ascii() {
+        return None;
+    }
+
+    let (sign, digits) = s.split_at(1);
+    let (hours, minutes) = match digits.len() {
+        2 => (digits, "00"),
+        4 => digits.split_at(2),
+        5 if digits.as_bytes()[2] == b':' => (&digits[..2], &digits[3..]),
+        _ => return None,
+    };
+
+    if !hours
+        .bytes()
+        .chain(minutes.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+    match sign {
+        "+" => FixedOffset::east_opt(seconds),
+        _ => FixedOffset::west_opt(seconds),
+    }
+}
+
 /// Convert a timestamp with a non-UTC time zone into UTC
 pub(super) fn datetime_to_utc<TZ: chrono::TimeZone>(ts: &DateTime<TZ>) -> DateTime<Utc> {
     Utc.timestamp_opt(ts.timestamp(), ts.timestamp_subsec_nanos())
@@ -70,6 +109,7 @@ impl From<TimeZone> for String {
         match tz {
             TimeZone::Local => "local".to_string(),
             TimeZone::Named(tz) => tz.name().to_string(),
+            TimeZone::Fixed(offset) => offset.to_string(),
         }
     }
 }
@@ -90,6 +130,39 @@ impl From<TimeZone> for FixedOffset {
         match tz {
             TimeZone::Local => *Utc::now().with_timezone(&Local).offset(),
             TimeZone::Named(tz) => Utc::now().with_timezone(&tz).offset().fix(),
+            TimeZone::Fixed(offset) => offset,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timezone() {
+        let offset = |seconds| Some(TimeZone::Fixed(FixedOffset::east_opt(seconds).unwrap()));
+
+        assert_eq!(TimeZone::parse("local"), Some(TimeZone::Local));
+        assert_eq!(
+            TimeZone::parse("Europe/Berlin"),
+            Some(TimeZone::Named(chrono_tz::Europe::Berlin))
+        );
+        assert_eq!(TimeZone::parse("+02:00"), offset(7200));
+        assert_eq!(TimeZone::parse("-0530"), offset(-19800));
+        assert_eq!(TimeZone::parse("+09"), offset(32400));
+        assert_eq!(TimeZone::parse("+2:00"), None);
+        assert_eq!(TimeZone::parse("+02:0a"), None);
+        assert_eq!(TimeZone::parse("+25:00"), None);
+        assert_eq!(TimeZone::parse("Mars/Olympus_Mons"), None);
+        assert_eq!(TimeZone::parse("+0é0"), None);
+        assert_eq!(TimeZone::parse("-é:00"), None);
+        assert_eq!(TimeZone::parse("é0000"), None);
+    }
+
+    #[test]
+    fn fixed_offset_roundtrip() {
+        let timezone = TimeZone::parse("-03:30").unwrap();
+        assert_eq!(String::from(timezone), "-03:30");
+    }
+}
@@ -19,6 +19,8 @@ pub use object::Object;
 pub use op::Op;
 pub use predicate::Predicate;
 pub use query::{Query, Target};
+pub use r#try::Try;
+pub use template::Template;
 pub use unary::Unary;
 pub use variable::Variable;
 
@@ -49,6 +51,8 @@ pub(crate) mod function_call;
 pub(crate) mod literal;
 pub(crate) mod predicate;
 pub mod query;
+pub(crate) mod template;
+pub(crate) mod r#try;
 
 pub trait Expression: Send + Sync + fmt::Debug + DynClone {
     /// Resolve an expression to a concrete [`Value`].
@@ -115,6 +119,8 @@ pub enum Expr {
     Noop(Noop),
     Unary(Unary),
     Abort(Abort),
+    Try(Try),
+    Template(Template),
 }
 
 impl Expr {
@@ -122,7 +128,7 @@ impl Expr {
         use container::Variant::{Array, Block, Group, Object};
         use Expr::{
             Abort, Assignment, Container, FunctionCall, IfStatement, Literal, Noop, Op, Query,
-            Unary, Variable,
+            Template, Try, Unary, Variable,
         };
 
         match self {
@@ -142,6 +148,8 @@ impl Expr {
             Noop(..) => "noop",
             Unary(..) => "unary operation",
             Abort(..) => "abort operation",
+            Try(..) => "try expression",
+            Template(..) => "string template",
         }
     }
 
@@ -181,7 +189,7 @@ impl Expression for Expr {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         use Expr::{
             Abort, Assignment, Container, FunctionCall, IfStatement, Literal, Noop, Op, Query,
-            Unary, Variable,
+            Template, Try, Unary, Variable,
         };
 
         match self {
@@ -196,13 +204,15 @@ impl Expression for Expr {
             Noop(v) => v.resolve(ctx),
             Unary(v) => v.resolve(ctx),
             Abort(v) => v.resolve(ctx),
+            Try(v) => v.resolve(ctx),
+            Template(v) => v.resolve(ctx),
         }
     }
 
     fn resolve_constant(&self, state: &TypeState) -> Option<Value> {
         use Expr::{
             Abort, Assignment, Container, FunctionCall, IfStatement, Literal, Noop, Op, Query,
-            Unary, Variable,
+            Template, Try, Unary, Variable,
         };
 
         match self {
@@ -217,13 +227,15 @@ impl Expression for Expr {
             Noop(v) => Expression::resolve_constant(v, state),
             Unary(v) => Expression::resolve_constant(v, state),
             Abort(v) => Expression::resolve_constant(v, state),
+            Try(v) => Expression::resolve_constant(v, state),
+            Template(v) => Expression::resolve_constant(v, state),
         }
     }
 
     fn type_info(&self, state: &TypeState) -> TypeInfo {
         use Expr::{
             Abort, Assignment, Container, FunctionCall, IfStatement, Literal, Noop, Op, Query,
-            Unary, Variable,
+            Template, Try, Unary, Variable,
         };
 
         match self {
@@ -238,6 +250,8 @@ impl Expression for Expr {
             Noop(v) => v.type_info(state),
             Unary(v) => v.type_info(state),
             Abort(v) => v.type_info(state),
+            Try(v) => v.type_info(state),
+            Template(v) => v.type_info(state),
         }
     }
 }
@@ -246,7 +260,7 @@ impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Expr::{
             Abort, Assignment, Container, FunctionCall, IfStatement, Literal, Noop, Op, Query,
-            Unary, Variable,
+            Template, Try, Unary, Variable,
         };
 
         match self {
@@ -261,6 +275,8 @@ impl fmt::Display for Expr {
             Noop(v) => v.fmt(f),
             Unary(v) => v.fmt(f),
             Abort(v) => v.fmt(f),
+            Try(v) => v.fmt(f),
+            Template(v) => v.fmt(f),
         }
     }
 }
@@ -333,6 +349,18 @@ impl From<Abort> for Expr {
     }
 }
 
+impl From<Try> for Expr {
+    fn from(r#try: Try) -> Self {
+        Expr::Try(r#try)
+    }
+}
+
+impl From<Template> for Expr {
+    fn from(template: Template) -> Self {
+        Expr::Template(template)
+    }
+}
+
 impl From<Value> for Expr {
     fn from(value: Value) -> Self {
         use std::collections::BTreeMap;
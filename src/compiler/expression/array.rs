@@ -33,6 +33,7 @@ impl Expression for Array {
             .map(|expr| expr.resolve(ctx))
             .collect::<Result<Vec<_>, _>>()
             .map(Value::Array)
+            .and_then(|value| ctx.value_limits().check(&value).map(|()| value))
     }
 
     fn resolve_constant(&self, state: &TypeState) -> Option<Value> {
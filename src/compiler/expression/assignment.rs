@@ -9,10 +9,10 @@ use crate::compiler::{
         ast::{self, Ident},
         Node,
     },
-    state::{TypeInfo, TypeState},
+    state::{Origin, TypeInfo, TypeState},
     type_def::Details,
     value::kind::DefaultValue,
-    CompileConfig, Context, Expression, Span, TypeDef,
+    CompileConfig, Context, Expression, ExpressionError, Span, TypeDef,
 };
 use crate::diagnostic::{DiagnosticMessage, Label, Note};
 use crate::path::{OwnedSegment, OwnedTargetPath};
@@ -22,6 +22,10 @@ use crate::value::{Kind, Value};
 #[derive(Clone, PartialEq)]
 pub struct Assignment {
     variant: Variant<Target, Expr>,
+
+    /// The span of the whole assignment, which type errors point at to explain the types it gave
+    /// its targets.
+    span: Span,
 }
 
 impl Assignment {
@@ -33,12 +37,14 @@ impl Assignment {
         config: &CompileConfig,
     ) -> Result<Self, Error> {
         let (_, variant) = node.take();
+        let span;
 
         let variant = match variant {
             Variant::Single { target, expr } => {
                 let target_span = target.span();
                 let expr_span = expr.span();
                 let assignment_span = Span::new(target_span.start(), expr_span.start() - 1);
+                span = Span::new(target_span.start(), expr_span.end());
                 // Fallible expressions require infallible assignment.
                 if let Some(expr_error) = fallible_rhs {
                     let assignment_error_data = match expr_error {
@@ -95,6 +101,7 @@ impl Assignment {
                 let err_span = err.span();
                 let expr_span = expr.span();
                 let assignment_span = Span::new(ok_span.start(), err_span.end());
+                span = Span::new(ok_span.start(), expr_span.end());
                 let type_def = expr.type_info(state).result;
 
                 // Infallible expressions do not need fallible assignment.
@@ -164,7 +171,7 @@ impl Assignment {
             }
         };
 
-        Ok(Self { variant })
+        Ok(Self { variant, span })
     }
 
     /// Get a list of targets for this assignment.
@@ -283,6 +290,22 @@ fn verify_overwritable(
             Target::Noop => unreachable!(),
         };
 
+        // Only the assignments that made the parent something else than an object or array
+        // explain the error.
+        let origins = target
+            .origins(state, &path)
+            .into_iter()
+            .filter_map(|Origin { span, kind }| {
+                let kind = match variant {
+                    "object" => kind.without_object(),
+                    _ => kind.without_array(),
+                }
+                .without_undefined();
+
+                (!kind.is_never()).then_some(Origin { span, kind })
+            })
+            .collect();
+
         return Err(Error {
             variant: InvalidParentPathSegment {
                 variant,
@@ -292,6 +315,7 @@ fn verify_overwritable(
                 parent_str,
                 remainder_str,
                 rhs_expr,
+                origins,
             },
             expr_span,
             assignment_span,
@@ -307,7 +331,12 @@ impl Expression for Assignment {
     }
 
     fn type_info(&self, state: &TypeState) -> TypeInfo {
-        self.variant.type_info(state)
+        let mut info = self.variant.type_info(state);
+        for target in self.targets() {
+            target.insert_origin(&mut info.state, self.span);
+        }
+
+        info
     }
 }
 
@@ -383,7 +412,58 @@ impl Target {
         }
     }
 
-    fn insert(&self, value: Value, ctx: &mut Context) {
+    /// Records that the assignment at `span` gave the target its type in the state.
+    fn insert_origin(&self, state: &mut TypeState, span: Span) {
+        match self {
+            Self::Noop => {}
+            Self::Internal(ident, path) => state.local.insert_origin(ident, path, span),
+            Self::External(target_path) => {
+                state
+                    .external
+                    .insert_origin(target_path.prefix, &target_path.path, span);
+            }
+        }
+    }
+
+    fn origins(&self, state: &TypeState, path: &OwnedValuePath) -> Vec<Origin> {
+        match self {
+            Self::Noop => vec![],
+            Self::Internal(ident, _) => state.local.origins(ident, path),
+            Self::External(target_path) => state.external.origins(target_path.prefix, path),
+        }
+    }
+
+    fn insert(&self, value: Value, ctx: &mut Context) -> Result<(), ExpressionError> {
+        self.check_value_limits(&value, ctx)?;
+        self.insert_unchecked(value, ctx);
+        Ok(())
+    }
+
+    /// Checks that the value, and the nulls that inserting it pads arrays with, are within the
+    /// limits, before inserting it.
+    fn check_value_limits(&self, value: &Value, ctx: &Context) -> Result<(), ExpressionError> {
+        let limits = ctx.value_limits();
+        if limits.is_unlimited() {
+            return Ok(());
+        }
+
+        match self {
+            Self::Noop => Ok(()),
+            Self::Internal(ident, path) => {
+                limits.check_insert(ctx.state().variable(ident), path, value)
+            }
+            Self::External(path) => {
+                let root = match path.prefix {
+                    PathPrefix::Event => OwnedTargetPath::event_root(),
+                    PathPrefix::Metadata => OwnedTargetPath::metadata_root(),
+                };
+                let existing = ctx.target().target_get(&root).ok().flatten();
+                limits.check_insert(existing, &path.path, value)
+            }
+        }
+    }
+
+    fn insert_unchecked(&self, value: Value, ctx: &mut Context) {
         use Target::{External, Internal, Noop};
 
         match self {
@@ -524,7 +604,7 @@ where
         let value = match self {
             Single { target, expr } => {
                 let value = expr.resolve(ctx)?;
-                target.insert(value.clone(), ctx);
+                target.insert(value.clone(), ctx)?;
                 value
             }
             Infallible {
@@ -534,14 +614,14 @@ where
                 default,
             } => match expr.resolve(ctx) {
                 Ok(value) => {
-                    ok.insert(value.clone(), ctx);
-                    err.insert(Value::Null, ctx);
+                    ok.insert(value.clone(), ctx)?;
+                    err.insert(Value::Null, ctx)?;
                     value
                 }
                 Err(error) => {
-                    ok.insert(default.clone(), ctx);
+                    ok.insert(default.clone(), ctx)?;
                     let value = Value::from(error.to_string());
-                    err.insert(value.clone(), ctx);
+                    err.insert(value.clone(), ctx)?;
                     value
                 }
             },
@@ -648,6 +728,7 @@ pub(crate) enum ErrorVariant {
         segment_span: Span,
         remainder_str: String,
         rhs_expr: Expr,
+        origins: Vec<Origin>,
     },
 }
 
@@ -753,21 +834,27 @@ impl DiagnosticMessage for Error {
                 parent_kind,
                 parent_span,
                 segment_span,
+                parent_str,
+                origins,
                 ..
-            } => vec![
-                Label::primary(
-                    if variant == &"object" {
-                        "querying a field of a non-object type is unsupported"
-                    } else {
-                        "indexing into a non-array type is unsupported"
-                    },
-                    segment_span,
-                ),
-                Label::context(
-                    format!("this path resolves to a value of type {parent_kind}"),
-                    parent_span,
-                ),
-            ],
+            } => {
+                let mut labels = vec![
+                    Label::primary(
+                        if variant == &"object" {
+                            "querying a field of a non-object type is unsupported"
+                        } else {
+                            "indexing into a non-array type is unsupported"
+                        },
+                        segment_span,
+                    ),
+                    Label::context(
+                        format!("this path resolves to a value of type {parent_kind}"),
+                        parent_span,
+                    ),
+                ];
+                labels.extend(origins.iter().map(|origin| origin.label(parent_str)));
+                labels
+            }
         }
     }
 
@@ -1,8 +1,8 @@
 use std::{fmt, sync::Arc};
 
-use crate::compiler::state::{TypeInfo, TypeState};
+use crate::compiler::state::{Origin, TypeInfo, TypeState};
 use crate::compiler::{
-    expression::{levenstein, ExpressionError, FunctionArgument},
+    expression::{levenstein, query, Expr, ExpressionError, FunctionArgument},
     function::{
         closure::{self, VariableKind},
         ArgumentList, Example, FunctionClosure, FunctionCompileContext, Parameter,
@@ -14,6 +14,7 @@ use crate::compiler::{
     CompileConfig, Context, Expression, Function, Resolved, Span, TypeDef,
 };
 use crate::diagnostic::{DiagnosticMessage, Label, Note, Urls};
+use crate::path::OwnedValuePath;
 
 use super::Block;
 
@@ -140,6 +141,11 @@ impl<'a> Builder<'a> {
                             .collect::<Vec<_>>(),
                         parameter: *parameter,
                         got: expr_kind.clone(),
+                        origins: argument_origins(
+                            argument.expr(),
+                            state_before_function_args,
+                            &param_kind,
+                        ),
                         argument,
                         argument_span,
                     },
@@ -217,7 +223,7 @@ impl<'a> Builder<'a> {
             }
 
             // Error if closure is missing from function that expects one.
-            (Some(definition), None) => {
+            (Some(definition), None) if !definition.is_optional => {
                 let example = definition.inputs.get(0).map(|input| input.example);
 
                 return Err(FunctionCallError::MissingClosure { call_span, example });
@@ -464,6 +470,11 @@ impl<'a> Builder<'a> {
                             .type_info(state_before_function_args)
                             .result
                             .into(),
+                        origins: argument_origins(
+                            argument.expr(),
+                            state_before_function_args,
+                            &parameter.kind(),
+                        ),
                         argument: argument.clone().into_inner(),
                         argument_span: argument
                             .keyword_span()
@@ -632,6 +643,19 @@ impl FunctionCall {
             .map(|arg| format!("{:?}", arg.inner()))
             .collect::<Vec<_>>()
     }
+
+    pub(crate) fn arguments(&self) -> impl Iterator<Item = &Expr> {
+        self.arguments.iter().map(|arg| arg.inner().expr())
+    }
+
+    /// Returns `true` if the called function is pure and isn't given a closure that could have
+    /// side effects. This doesn't take the arguments into account.
+    ///
+    /// A call that can fail or abort is still pure: checking a value that way is better done with
+    /// `assert!`, which says so.
+    pub(crate) fn is_pure(&self, state: &TypeState) -> bool {
+        self.closure.is_none() && self.expr.type_def(state).is_pure()
+    }
 }
 
 impl Expression for FunctionCall {
@@ -647,6 +671,7 @@ impl Expression for FunctionCall {
                 message,
                 mut labels,
                 notes,
+                code,
             } => {
                 labels.push(Label::primary(message.clone(), self.span));
 
@@ -660,6 +685,7 @@ impl Expression for FunctionCall {
                     ),
                     labels,
                     notes,
+                    code,
                 }
             }
         })
@@ -813,6 +839,35 @@ impl PartialEq for FunctionCall {
 }
 
 // -----------------------------------------------------------------------------
+
+/// Returns the assignments that gave an argument that is a path a type the parameter doesn't
+/// accept.
+fn argument_origins(argument: &Expr, state: &TypeState, expected: &Kind) -> Vec<Origin> {
+    let origins = match argument {
+        Expr::Query(query) => match query.target() {
+            query::Target::External(prefix) => state.external.origins(*prefix, query.path()),
+            query::Target::Internal(variable) => {
+                state.local.origins(variable.ident(), query.path())
+            }
+            query::Target::FunctionCall(_) | query::Target::Container(_) => vec![],
+        },
+        Expr::Variable(variable) => state
+            .local
+            .origins(variable.ident(), &OwnedValuePath::root()),
+        _ => vec![],
+    };
+
+    origins
+        .into_iter()
+        .filter_map(|Origin { span, kind }| {
+            let kind = kind.without_undefined();
+
+            (!kind.is_never() && expected.is_superset(&kind).is_err())
+                .then_some(Origin { span, kind })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct InvalidArgumentErrorContext {
     pub(crate) function_ident: &'static str,
@@ -820,6 +875,7 @@ pub(crate) struct InvalidArgumentErrorContext {
     pub(crate) arguments_fmt: Vec<String>,
     pub(crate) parameter: Parameter,
     pub(crate) got: Kind,
+    pub(crate) origins: Vec<Origin>,
     pub(crate) argument: FunctionArgument,
     pub(crate) argument_span: Span,
 }
@@ -1036,7 +1092,7 @@ impl DiagnosticMessage for FunctionCallError {
                     }
                 };
 
-                vec![
+                let mut labels = vec![
                     Label::primary(
                         format!("this expression resolves to {}", kind_str(&context.got)),
                         expr_span,
@@ -1049,7 +1105,10 @@ impl DiagnosticMessage for FunctionCallError {
                         ),
                         context.argument_span,
                     ),
-                ]
+                ];
+                let argument = context.argument.expr().to_string();
+                labels.extend(context.origins.iter().map(|origin| origin.label(&argument)));
+                labels
             }
 
             FallibleArgument { expr_span } => vec![
@@ -1247,7 +1306,7 @@ mod tests {
     }
 
     fn create_argument(ident: Option<&str>, value: i64) -> FunctionArgument {
-        use crate::compiler::expression::{Expr, Literal};
+        use crate::compiler::expression::Literal;
 
         FunctionArgument::new(
             ident.map(|ident| create_node(Ident::new(ident))),
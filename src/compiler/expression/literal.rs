@@ -260,6 +260,9 @@ pub(crate) enum ErrorVariant {
 
     #[error("float literal can't be NaN")]
     NanFloat,
+
+    #[error("invalid format spec")]
+    InvalidFormatSpec(&'static str),
 }
 
 impl fmt::Display for Error {
@@ -276,17 +279,18 @@ impl std::error::Error for Error {
 
 impl DiagnosticMessage for Error {
     fn code(&self) -> usize {
-        use ErrorVariant::{InvalidRegex, InvalidTimestamp, NanFloat};
+        use ErrorVariant::{InvalidFormatSpec, InvalidRegex, InvalidTimestamp, NanFloat};
 
         match &self.variant {
             InvalidRegex(..) => 101,
             InvalidTimestamp(..) => 601,
             NanFloat => 602,
+            InvalidFormatSpec(..) => 603,
         }
     }
 
     fn labels(&self) -> Vec<Label> {
-        use ErrorVariant::{InvalidRegex, InvalidTimestamp, NanFloat};
+        use ErrorVariant::{InvalidFormatSpec, InvalidRegex, InvalidTimestamp, NanFloat};
 
         match &self.variant {
             InvalidRegex(err) => {
@@ -315,11 +319,15 @@ impl DiagnosticMessage for Error {
             )],
 
             NanFloat => vec![],
+            InvalidFormatSpec(err) => vec![Label::primary(
+                format!("invalid format spec: {err}"),
+                self.span,
+            )],
         }
     }
 
     fn notes(&self) -> Vec<Note> {
-        use ErrorVariant::{InvalidRegex, InvalidTimestamp, NanFloat};
+        use ErrorVariant::{InvalidFormatSpec, InvalidRegex, InvalidTimestamp, NanFloat};
 
         match &self.variant {
             InvalidRegex(_) => vec![Note::SeeDocs(
@@ -334,6 +342,10 @@ impl DiagnosticMessage for Error {
                 "floats".to_owned(),
                 Urls::expression_docs_url("#float"),
             )],
+            InvalidFormatSpec(_) => vec![Note::SeeDocs(
+                "strings".to_owned(),
+                Urls::expression_docs_url("#string"),
+            )],
         }
     }
 }
@@ -347,6 +359,12 @@ impl From<(Span, regex::Error)> for Error {
     }
 }
 
+impl From<(Span, ErrorVariant)> for Error {
+    fn from((span, variant): (Span, ErrorVariant)) -> Self {
+        Self { variant, span }
+    }
+}
+
 impl From<(Span, chrono::ParseError)> for Error {
     fn from((span, err): (Span, chrono::ParseError)) -> Self {
         Self {
@@ -34,6 +34,7 @@ impl Expression for Object {
             .map(|(key, expr)| expr.resolve(ctx).map(|v| (key.clone(), v)))
             .collect::<Result<BTreeMap<_, _>, _>>()
             .map(Value::Object)
+            .and_then(|value| ctx.value_limits().check(&value).map(|()| value))
     }
 
     fn resolve_constant(&self, state: &TypeState) -> Option<Value> {
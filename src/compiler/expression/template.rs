@@ -0,0 +1,433 @@
+use std::{fmt, str::FromStr};
+
+use chrono::SecondsFormat;
+
+use crate::compiler::{
+    expression::{Expr, Resolved},
+    state::{TypeInfo, TypeState},
+    value::{Kind, VrlValueConvert},
+    Context, Expression, TypeDef,
+};
+use crate::value::Value;
+
+/// A string template with format specs, such as `"latency={{ .latency : .3 }}s"`.
+///
+/// Templates without format specs are compiled as string concatenations instead, and their
+/// templated values have to be strings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Literal(String),
+    Expr {
+        expr: Box<Expr>,
+        spec: Option<FormatSpec>,
+    },
+}
+
+impl Template {
+    pub(crate) fn new(segments: Vec<Segment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl Expression for Template {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let mut string = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => string.push_str(literal),
+                Segment::Expr { expr, spec } => {
+                    let value = expr.resolve(ctx)?;
+                    match spec {
+                        Some(spec) => string.push_str(&spec.format(&value)?),
+                        None => string.push_str(&value.try_bytes_utf8_lossy()?),
+                    }
+                }
+            }
+        }
+
+        Ok(string.into())
+    }
+
+    fn type_info(&self, state: &TypeState) -> TypeInfo {
+        let mut state = state.clone();
+        let mut fallible = false;
+
+        for segment in &self.segments {
+            if let Segment::Expr { expr, spec } = segment {
+                let type_def = expr.apply_type_info(&mut state);
+                if type_def.is_never() {
+                    return TypeInfo::new(state, TypeDef::never().maybe_fallible(fallible));
+                }
+
+                fallible |= type_def.is_fallible()
+                    || match spec {
+                        Some(spec) => spec.is_fallible(type_def.kind()),
+                        None => !type_def.is_bytes(),
+                    };
+            }
+        }
+
+        TypeInfo::new(state, TypeDef::bytes().maybe_fallible(fallible))
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => literal
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace("{{", "\\{{")
+                    .replace("}}", "\\}}")
+                    .fmt(f)?,
+                Segment::Expr { expr, spec: None } => write!(f, "{{{{ {expr} }}}}")?,
+                Segment::Expr {
+                    expr,
+                    spec: Some(spec),
+                } => write!(f, "{{{{ {expr} : {spec} }}}}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// How a templated value is rendered, given after a `:` in the template.
+///
+/// The `json` spec embeds the JSON encoding of any value. Other specs follow Rust's format
+/// syntax, `[[fill]align][0][width][.precision]`, and render strings, numbers, booleans and
+/// timestamps as `to_string` does, with `null` rendered as an empty string. The precision is the
+/// number of decimals of floats, is ignored for integers, and is the maximum number of characters
+/// of other values. Numbers are aligned to the right by default, and other values to the left.
+/// As in Rust, the `0` flag pads numbers with zeros after their sign, ignoring the fill and
+/// alignment, and is ignored for other values.
+///
+/// The width and precision are at most [`MAX_WIDTH`], so that a template can't make every
+/// event allocate a huge string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FormatSpec {
+    Json,
+    Text {
+        fill: char,
+        align: Option<Align>,
+        zero: bool,
+        width: Option<usize>,
+        precision: Option<usize>,
+    },
+}
+
+/// The maximum width and precision of a format spec.
+pub(crate) const MAX_WIDTH: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '<' => Some(Self::Left),
+            '^' => Some(Self::Center),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    const fn as_char(self) -> char {
+        match self {
+            Self::Left => '<',
+            Self::Center => '^',
+            Self::Right => '>',
+        }
+    }
+}
+
+impl FormatSpec {
+    /// Returns whether formatting a value of the given kind can fail, which only objects and
+    /// arrays do, unless they're encoded as JSON.
+    fn is_fallible(self, kind: &Kind) -> bool {
+        match self {
+            Self::Json => false,
+            Self::Text { .. } => kind.contains_object() || kind.contains_array(),
+        }
+    }
+
+    fn format(self, value: &Value) -> Result<String, String> {
+        let (fill, align, zero, width, precision) = match self {
+            Self::Json => {
+                return serde_json::to_string(value)
+                    .map_err(|err| format!("unable to encode value as JSON: {err}"))
+            }
+            Self::Text {
+                fill,
+                align,
+                zero,
+                width,
+                precision,
+            } => (fill, align, zero, width, precision),
+        };
+
+        let (text, numeric) = match value {
+            Value::Bytes(bytes) => (String::from_utf8_lossy(bytes).into_owned(), false),
+            Value::Regex(regex) => (regex.as_str().to_owned(), false),
+            Value::Integer(int) => (int.to_string(), true),
+            Value::Float(float) => match precision {
+                Some(precision) => (format!("{:.precision$}", float.into_inner()), true),
+                None => (float.to_string(), true),
+            },
+            Value::Boolean(boolean) => (boolean.to_string(), false),
+            Value::Timestamp(ts) => (ts.to_rfc3339_opts(SecondsFormat::AutoSi, true), false),
+            Value::Null => (String::new(), false),
+            Value::Object(_) | Value::Array(_) => {
+                return Err(format!(
+                    "unable to format {} into string, use the `json` format spec",
+                    value.kind_str()
+                ))
+            }
+        };
+        let text = match precision {
+            Some(precision) if !numeric => text.chars().take(precision).collect(),
+            _ => text,
+        };
+
+        let padding = width.unwrap_or(0).saturating_sub(text.chars().count());
+        if zero && numeric {
+            let digits = text.strip_prefix('-');
+            let mut padded = String::with_capacity(text.len() + padding);
+            if digits.is_some() {
+                padded.push('-');
+            }
+            padded.extend(std::iter::repeat('0').take(padding));
+            padded.push_str(digits.unwrap_or(&text));
+            return Ok(padded);
+        }
+
+        let align = align.unwrap_or(if numeric { Align::Right } else { Align::Left });
+        let (before, after) = match align {
+            Align::Left => (0, padding),
+            Align::Center => (padding / 2, padding - padding / 2),
+            Align::Right => (padding, 0),
+        };
+
+        let mut padded = String::with_capacity(text.len() + padding * fill.len_utf8());
+        padded.extend(std::iter::repeat(fill).take(before));
+        padded.push_str(&text);
+        padded.extend(std::iter::repeat(fill).take(after));
+        Ok(padded)
+    }
+}
+
+impl FromStr for FormatSpec {
+    type Err = &'static str;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let spec = spec.trim();
+        if spec == "json" {
+            return Ok(Self::Json);
+        }
+
+        let mut chars = spec.chars();
+        let (fill, align, rest) = match (chars.next(), chars.next()) {
+            (Some(fill), Some(c)) if Align::from_char(c).is_some() => {
+                (fill, Align::from_char(c), chars.as_str())
+            }
+            (Some(c), _) if Align::from_char(c).is_some() => {
+                (' ', Align::from_char(c), &spec[c.len_utf8()..])
+            }
+            _ => (' ', None, spec),
+        };
+
+        let (zero, rest) = match rest.strip_prefix('0') {
+            Some(width) if width.starts_with(|c: char| c.is_ascii_digit()) => (true, width),
+            _ => (false, rest),
+        };
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision)),
+            None => (rest, None),
+        };
+        let number = |digits: &str| {
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err("expected `json`, or `[[fill]align][0][width][.precision]`");
+            }
+            digits
+                .parse()
+                .ok()
+                .filter(|number| *number <= MAX_WIDTH)
+                .ok_or("width or precision is larger than 1024")
+        };
+        let width = match width {
+            "" => None,
+            width => Some(number(width)?),
+        };
+        let precision = precision.map(number).transpose()?;
+
+        Ok(Self::Text {
+            fill,
+            align,
+            zero,
+            width,
+            precision,
+        })
+    }
+}
+
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => f.write_str("json"),
+            Self::Text {
+                fill,
+                align,
+                zero,
+                width,
+                precision,
+            } => {
+                if let Some(align) = align {
+                    if *fill != ' ' {
+                        write!(f, "{fill}")?;
+                    }
+                    write!(f, "{}", align.as_char())?;
+                }
+                if *zero {
+                    f.write_str("0")?;
+                }
+                if let Some(width) = width {
+                    write!(f, "{width}")?;
+                }
+                if let Some(precision) = precision {
+                    write!(f, ".{precision}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    fn format(spec: &str, value: Value) -> Result<String, String> {
+        FormatSpec::from_str(spec)
+            .expect("valid spec")
+            .format(&value)
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(FormatSpec::from_str("json"), Ok(FormatSpec::Json));
+        assert_eq!(
+            FormatSpec::from_str(" *^10.2 "),
+            Ok(FormatSpec::Text {
+                fill: '*',
+                align: Some(Align::Center),
+                zero: false,
+                width: Some(10),
+                precision: Some(2),
+            })
+        );
+        assert_eq!(
+            FormatSpec::from_str(">8"),
+            Ok(FormatSpec::Text {
+                fill: ' ',
+                align: Some(Align::Right),
+                zero: false,
+                width: Some(8),
+                precision: None,
+            })
+        );
+        assert_eq!(
+            FormatSpec::from_str("08.2"),
+            Ok(FormatSpec::Text {
+                fill: ' ',
+                align: None,
+                zero: true,
+                width: Some(8),
+                precision: Some(2),
+            })
+        );
+        assert!(FormatSpec::from_str("x").is_err());
+        assert!(FormatSpec::from_str(".").is_err());
+        assert!(FormatSpec::from_str("8.3f").is_err());
+        assert!(FormatSpec::from_str("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn width_and_precision_are_bounded() {
+        assert!(FormatSpec::from_str("1024.1024").is_ok());
+        assert_eq!(
+            FormatSpec::from_str("1025"),
+            Err("width or precision is larger than 1024")
+        );
+        assert_eq!(
+            FormatSpec::from_str(".999999999"),
+            Err("width or precision is larger than 1024")
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for spec in ["json", "", "8", ".3", "<8", "0>8.2", "-^12", "08", "<06.1"] {
+            assert_eq!(FormatSpec::from_str(spec).unwrap().to_string(), spec);
+        }
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(format(".3", value!(0.123_456)), Ok("0.123".to_owned()));
+        assert_eq!(format(".1", value!(2.25)), Ok("2.2".to_owned()));
+        assert_eq!(format(".2", value!(12345)), Ok("12345".to_owned()));
+        assert_eq!(format(".3", value!("abcdef")), Ok("abc".to_owned()));
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(format("8", value!(42)), Ok("      42".to_owned()));
+        assert_eq!(format("8", value!("id")), Ok("id      ".to_owned()));
+        assert_eq!(format(">8", value!("id")), Ok("      id".to_owned()));
+        assert_eq!(format("0>4", value!(7)), Ok("0007".to_owned()));
+        assert_eq!(format("-^7", value!("héllo")), Ok("-héllo-".to_owned()));
+        assert_eq!(format("2", value!("longer")), Ok("longer".to_owned()));
+    }
+
+    #[test]
+    fn zero_padding() {
+        assert_eq!(format("05", value!(42)), Ok("00042".to_owned()));
+        assert_eq!(format("05", value!(-7)), Ok("-0007".to_owned()));
+        assert_eq!(format("08.2", value!(-1.5)), Ok("-0001.50".to_owned()));
+        assert_eq!(format("*<05", value!(3)), Ok("00003".to_owned()));
+        assert_eq!(format("05", value!("ab")), Ok("ab   ".to_owned()));
+        assert_eq!(format("0", value!(5)), Ok("5".to_owned()));
+    }
+
+    #[test]
+    fn null() {
+        assert_eq!(format("", value!(null)), Ok(String::new()));
+        assert_eq!(format("3", value!(null)), Ok("   ".to_owned()));
+        assert_eq!(format("json", value!(null)), Ok("null".to_owned()));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            format("json", value!({"a": [1, "b"]})),
+            Ok(r#"{"a":[1,"b"]}"#.to_owned())
+        );
+        assert_eq!(format("json", value!("a\"b")), Ok(r#""a\"b""#.to_owned()));
+        assert_eq!(
+            format("", value!([1])),
+            Err("unable to format array into string, use the `json` format spec".to_owned())
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::compiler::{
+    expression::{ExpressionError, Resolved},
+    state::{TypeInfo, TypeState},
+    value::Kind,
+    Context, Expression, Span, TypeDef,
+};
+use crate::diagnostic::{DiagnosticMessage, Label, Note};
+use crate::value::{ObjectMap, Value};
+
+use super::Expr;
+
+/// Converts the result of a (fallible) expression into an infallible object.
+///
+/// The object has an `ok` boolean, the `value` the expression resolved to (or `null` if it failed)
+/// and the `error` message the expression failed with (or `null` if it succeeded).
+///
+/// Aborting the program using `abort` isn't caught.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Try {
+    expr: Box<Expr>,
+}
+
+impl Try {
+    #[must_use]
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            expr: Box::new(expr),
+        }
+    }
+}
+
+impl Expression for Try {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let (ok, value, error) = match self.expr.resolve(ctx) {
+            Ok(value) => (true, value, Value::Null),
+            Err(error @ ExpressionError::Abort { .. }) => return Err(error),
+            Err(error) => (false, Value::Null, error.to_string().into()),
+        };
+
+        Ok(ObjectMap::from([
+            ("ok".into(), ok.into()),
+            ("value".into(), value),
+            ("error".into(), error),
+        ])
+        .into())
+    }
+
+    fn type_info(&self, state: &TypeState) -> TypeInfo {
+        let mut state = state.clone();
+        let result = self.expr.apply_type_info(&mut state);
+
+        let (value, error) = if result.is_fallible() {
+            (result.kind().clone().or_null(), Kind::bytes().or_null())
+        } else {
+            (result.kind().clone(), Kind::null())
+        };
+
+        let type_def = TypeDef::object(BTreeMap::from([
+            ("ok".into(), Kind::boolean()),
+            ("value".into(), value),
+            ("error".into(), error),
+        ]))
+        .infallible();
+        let type_def = if result.is_pure() {
+            type_def
+        } else {
+            type_def.impure()
+        };
+
+        TypeInfo::new(state, type_def)
+    }
+}
+
+impl fmt::Display for Try {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "try({})", self.expr)
+    }
+}
+
+// -----------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub(crate) struct Error {
+    pub(crate) variant: ErrorVariant,
+    pub(crate) span: Span,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) enum ErrorVariant {
+    #[error("invalid try expression")]
+    WrongNumberOfArgs,
+    #[error("invalid try expression")]
+    KeywordArgument,
+    #[error("invalid try expression")]
+    AbortOnError,
+    #[error("invalid try expression")]
+    Closure,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.variant)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.variant)
+    }
+}
+
+impl DiagnosticMessage for Error {
+    fn code(&self) -> usize {
+        632
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        let message = match self.variant {
+            ErrorVariant::WrongNumberOfArgs => "try takes exactly one expression",
+            ErrorVariant::KeywordArgument => "try doesn't take keyword arguments",
+            ErrorVariant::AbortOnError => "try can't abort on errors, as it handles them",
+            ErrorVariant::Closure => "try doesn't take a closure",
+        };
+
+        vec![
+            Label::primary(message, self.span),
+            Label::context("use `try(<expression>)` instead", self.span),
+        ]
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        vec![Note::SeeErrorDocs]
+    }
+}
@@ -11,10 +11,17 @@ pub enum ExpressionError {
         span: Span,
         message: Option<String>,
     },
+    /// A runtime error. It's non-exhaustive so that fields can be added, so outside of this crate
+    /// it's created with [`ExpressionError::new`], [`ExpressionError::with_code`] or from a
+    /// message.
+    #[non_exhaustive]
     Error {
         message: String,
         labels: Vec<Label>,
         notes: Vec<Note>,
+
+        /// The category of the error, if known. See [`ErrorCode`].
+        code: Option<ErrorCode>,
     },
 
     Fallible {
@@ -27,6 +34,96 @@ pub enum ExpressionError {
     },
 }
 
+/// A stable code categorizing a runtime error, so that hosts can tell failure modes apart (for
+/// example, in metrics) without matching on error messages, which can change.
+///
+/// Codes are stable: the name and the number of a code never change once released, and a code
+/// is never reused for another category. New codes can be added in any release, and a function
+/// can start reporting a more specific code than it used to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// A value doesn't have a type the operation supports.
+    InvalidType,
+
+    /// A string couldn't be parsed into the requested format.
+    Parse,
+
+    /// A value is outside of the range the operation supports, such as an index past the end of
+    /// a string, or a number too large for an integer.
+    OutOfRange,
+
+    /// An argument has a value the function doesn't support, such as an invalid path.
+    InvalidArgument,
+
+    /// The program built a value larger than the host's [`ValueLimits`](super::ValueLimits).
+    LimitExceeded,
+}
+
+impl ErrorCode {
+    /// The number of the code, which is stable.
+    #[must_use]
+    pub const fn as_u16(self) -> u16 {
+        match self {
+            Self::InvalidType => 1,
+            Self::Parse => 2,
+            Self::OutOfRange => 3,
+            Self::InvalidArgument => 4,
+            Self::LimitExceeded => 5,
+        }
+    }
+
+    /// The name of the code, which is stable.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidType => "invalid_type",
+            Self::Parse => "parse",
+            Self::OutOfRange => "out_of_range",
+            Self::InvalidArgument => "invalid_argument",
+            Self::LimitExceeded => "limit_exceeded",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.as_str(), self.as_u16())
+    }
+}
+
+impl ExpressionError {
+    /// Creates a runtime error without a code.
+    pub fn new(message: impl Into<String>, labels: Vec<Label>, notes: Vec<Note>) -> Self {
+        Error {
+            message: message.into(),
+            labels,
+            notes,
+            code: None,
+        }
+    }
+
+    /// Creates a runtime error with the given `code`.
+    pub fn with_code(code: ErrorCode, message: impl Into<String>) -> Self {
+        Error {
+            message: message.into(),
+            labels: vec![],
+            notes: vec![],
+            code: Some(code),
+        }
+    }
+
+    /// Returns the code of a runtime error, if it has one. Aborts and compile-time errors don't
+    /// have codes.
+    #[must_use]
+    pub const fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Error { code, .. } => *code,
+            Abort { .. } | Fallible { .. } | Missing { .. } => None,
+        }
+    }
+}
+
 impl std::fmt::Display for ExpressionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.message().fmt(f)
@@ -106,6 +203,7 @@ impl From<String> for ExpressionError {
             message,
             labels: vec![],
             notes: vec![],
+            code: None,
         }
     }
 }
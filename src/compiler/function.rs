@@ -1,4 +1,5 @@
 pub mod closure;
+pub mod signature;
 
 use crate::diagnostic::{DiagnosticMessage, Label, Note};
 use crate::parser::ast::Ident;
@@ -61,6 +62,16 @@ pub trait Function: Send + Sync + fmt::Debug {
         &[]
     }
 
+    /// The value an optional parameter has when its argument isn't provided.
+    ///
+    /// Parameters whose default can't be written as an argument, such as a
+    /// limit that doesn't apply unless it's given, have a default of `null`.
+    /// This returns `None` by default, which is what required parameters
+    /// return.
+    fn parameter_default(&self, _keyword: &str) -> Option<Value> {
+        None
+    }
+
     /// An optional closure definition for the function.
     ///
     /// This returns `None` by default, indicating the function doesn't accept
@@ -68,6 +79,12 @@ pub trait Function: Send + Sync + fmt::Debug {
     fn closure(&self) -> Option<closure::Definition> {
         None
     }
+
+    /// A machine-readable description of the function's parameters, closure
+    /// and return type.
+    fn signature(&self) -> signature::FunctionSignature {
+        signature::FunctionSignature::new(self)
+    }
 }
 
 // -----------------------------------------------------------------------------
@@ -29,6 +29,12 @@ pub struct Definition {
     /// collection elements to determine the eventual type definition of the
     /// closure variable(s) (see `Variable`).
     pub is_iterator: bool,
+
+    /// Defines whether the function can also be called without a closure.
+    ///
+    /// If this is `false`, the compiler rejects any call to the function that
+    /// doesn't provide a closure.
+    pub is_optional: bool,
 }
 
 /// One input variant for a function-closure.
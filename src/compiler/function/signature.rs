@@ -0,0 +1,269 @@
+//! Machine-readable descriptions of VRL functions.
+//!
+//! A [`FunctionSignature`] describes how a function can be called: its parameters, the closure it
+//! accepts (if any), and the type of value it returns. This is meant for tooling such as editor
+//! autocompletion, which would otherwise have to scrape the documentation.
+
+use serde::Serialize;
+
+use crate::compiler::{
+    expression::{Block, Expr, Variable},
+    parser::ast::Ident,
+    state::TypeState,
+    type_def::Details,
+    value::{kind::DefaultValue, Kind},
+    CompileConfig, Span, TypeDef,
+};
+use crate::value::Value;
+
+use super::{
+    closure::{Definition, Input, VariableKind},
+    ArgumentList, Function, FunctionClosure, FunctionCompileContext, Parameter,
+};
+
+/// The signature of a single [`Function`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionSignature {
+    /// The identifier by which the function is called.
+    pub name: &'static str,
+
+    /// The parameters the function accepts, in positional order.
+    pub parameters: Vec<ParameterSignature>,
+
+    /// The closure the function accepts, if any.
+    pub closure: Option<ClosureSignature>,
+
+    /// The value returned by the function.
+    ///
+    /// This is `None` if it can't be determined without knowing the exact arguments the function
+    /// is called with (e.g. because it requires a literal argument that has to be valid).
+    pub returns: Option<ReturnSignature>,
+}
+
+/// The signature of a single function parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParameterSignature {
+    /// The keyword of the parameter.
+    pub name: &'static str,
+
+    /// The kinds of values the parameter accepts.
+    pub kind: KindSignature,
+
+    /// Whether the parameter has to be provided.
+    pub required: bool,
+
+    /// The value the parameter has when it isn't provided, which is `null` if it can't be written
+    /// as an argument. Required parameters have no default.
+    pub default: Option<Value>,
+}
+
+/// The signature of the value a function returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReturnSignature {
+    /// The kinds of values the function can return.
+    pub kind: KindSignature,
+
+    /// Whether calling the function can result in a runtime error, when called with any of the
+    /// values its parameters accept.
+    pub fallible: bool,
+
+    /// Whether the function is idempotent and free of side effects.
+    pub pure: bool,
+}
+
+/// The signature of the closure a function accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClosureSignature {
+    /// Whether the closure is called once for every element of a collection.
+    pub iterator: bool,
+
+    /// Whether the function can also be called without a closure.
+    pub optional: bool,
+
+    /// The supported closure shapes, depending on the value passed to the function.
+    pub inputs: Vec<ClosureInputSignature>,
+}
+
+/// One supported shape of a function closure.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClosureInputSignature {
+    /// The parameter this input variant depends on.
+    pub parameter: &'static str,
+
+    /// The kind of value the parameter has to be for this input variant to apply.
+    pub kind: KindSignature,
+
+    /// The variables passed to the closure.
+    pub variables: Vec<ClosureVariableSignature>,
+
+    /// The kind of value the closure has to return.
+    pub output: KindSignature,
+}
+
+/// The kind of a single closure variable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClosureVariableSignature {
+    /// The variable always has this kind.
+    Exact(KindSignature),
+
+    /// The variable has the kind of the closure target.
+    Target,
+
+    /// The variable has the kind of the values inside the closure target.
+    TargetInnerValue,
+
+    /// The variable has the kind of the keys or indices of the closure target.
+    TargetInnerKey,
+}
+
+/// A [`Kind`], rendered both for humans and for machines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KindSignature {
+    /// The human-readable representation of the kind, as used in diagnostics.
+    pub display: String,
+
+    /// The names of the primitive types the kind can be.
+    pub types: Vec<&'static str>,
+}
+
+impl FunctionSignature {
+    /// Describes the given function.
+    ///
+    /// The return type is determined by compiling the function with arguments of the most general
+    /// kinds its required parameters accept, falling back to literal arguments for functions that
+    /// require them.
+    pub fn new<F: Function + ?Sized>(function: &F) -> Self {
+        let closure = function.closure();
+        let returns = probe(function, closure.as_ref(), false)
+            .or_else(|| probe(function, closure.as_ref(), true))
+            .map(|type_def| ReturnSignature {
+                kind: type_def.kind().into(),
+                fallible: type_def.is_fallible(),
+                pure: type_def.is_pure(),
+            });
+
+        Self {
+            name: function.identifier(),
+            parameters: function
+                .parameters()
+                .iter()
+                .map(|parameter| ParameterSignature {
+                    default: function.parameter_default(parameter.keyword),
+                    ..parameter.into()
+                })
+                .collect(),
+            closure: closure.map(Into::into),
+            returns,
+        }
+    }
+}
+
+/// Compiles the function with placeholder arguments for all of its required parameters, and
+/// returns the resulting type definition.
+///
+/// If `literal` is `false`, the arguments are variables of the exact kind the parameter accepts.
+/// Otherwise they're the default literal value of the first kind the parameter accepts.
+fn probe<F: Function + ?Sized>(
+    function: &F,
+    closure: Option<&Definition>,
+    literal: bool,
+) -> Option<TypeDef> {
+    let mut state = TypeState::default();
+    let mut arguments = ArgumentList::default();
+
+    for parameter in function.parameters().iter().filter(|p| p.required) {
+        let expr = if literal {
+            // Only keep the lowest kind bit, to get a single concrete kind.
+            let kind = Parameter {
+                kind: parameter.kind & parameter.kind.wrapping_neg(),
+                ..*parameter
+            }
+            .kind();
+
+            Expr::from(kind.default_value())
+        } else {
+            let ident = Ident::new(parameter.keyword);
+            let details = Details {
+                type_def: TypeDef::from(parameter.kind().without_undefined()).infallible(),
+                value: None,
+            };
+            state.local.insert_variable(ident.clone(), details);
+
+            Expr::Variable(Variable::new(Span::default(), ident, &state.local).ok()?)
+        };
+
+        arguments.insert(parameter.keyword, expr);
+    }
+
+    // Optional closures are left out, to describe the function as it's called without one.
+    if let Some(definition) = closure.filter(|definition| !definition.is_optional) {
+        let input = definition.inputs.first()?;
+        let variables = (0..input.variables.len())
+            .map(|i| Ident::new(format!("_{i}")))
+            .collect::<Vec<_>>();
+        let output = input.output.clone().into_kind();
+        let block = Block::new_scoped(vec![Expr::from(output.default_value())]);
+
+        arguments.set_closure(FunctionClosure::new(
+            variables,
+            block,
+            TypeDef::from(output).infallible(),
+        ));
+    }
+
+    let mut ctx = FunctionCompileContext::new(Span::default(), CompileConfig::default());
+    let expression = function.compile(&state, &mut ctx, arguments).ok()?;
+
+    Some(expression.type_def(&state))
+}
+
+impl From<&Parameter> for ParameterSignature {
+    fn from(parameter: &Parameter) -> Self {
+        Self {
+            name: parameter.keyword,
+            kind: (&parameter.kind()).into(),
+            required: parameter.required,
+            default: None,
+        }
+    }
+}
+
+impl From<Definition> for ClosureSignature {
+    fn from(definition: Definition) -> Self {
+        Self {
+            iterator: definition.is_iterator,
+            optional: definition.is_optional,
+            inputs: definition.inputs.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<Input> for ClosureInputSignature {
+    fn from(input: Input) -> Self {
+        Self {
+            parameter: input.parameter_keyword,
+            kind: (&input.kind).into(),
+            variables: input
+                .variables
+                .into_iter()
+                .map(|variable| match variable.kind {
+                    VariableKind::Exact(kind) => ClosureVariableSignature::Exact((&kind).into()),
+                    VariableKind::Target => ClosureVariableSignature::Target,
+                    VariableKind::TargetInnerValue => ClosureVariableSignature::TargetInnerValue,
+                    VariableKind::TargetInnerKey => ClosureVariableSignature::TargetInnerKey,
+                })
+                .collect(),
+            output: (&input.output.into_kind()).into(),
+        }
+    }
+}
+
+impl From<&Kind> for KindSignature {
+    fn from(kind: &Kind) -> Self {
+        Self {
+            display: kind.to_string(),
+            types: kind.primitive_names(),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+use std::str::FromStr;
+
+use tracing::{debug, error, info, trace, warn};
+
+use crate::diagnostic::Span;
+use crate::value::Value;
+
+/// The severity of a message logged by the `log` function.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    #[default]
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "trace" => Ok(Self::Trace),
+            "debug" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "warn" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            _ => Err("log level not recognized"),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A message logged by the `log` function.
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord<'a> {
+    pub level: LogLevel,
+
+    /// The logged value. Objects are passed as they are, so that their fields can be logged as
+    /// structured fields rather than as a string.
+    pub value: &'a Value,
+
+    /// The span of the `log` call in the program, which identifies its call site.
+    pub span: Span,
+
+    /// The rate limit window of the call site, in seconds.
+    pub rate_limit_secs: u64,
+
+    /// The number of messages of the call site that were suppressed because of its rate limit,
+    /// since the previous message that was logged.
+    pub suppressed: usize,
+}
+
+/// Receives the messages logged by the `log` function.
+///
+/// Hosts can implement this to route log messages to their own logging, see
+/// [`Context::with_log_handler`](super::Context::with_log_handler) and
+/// [`Runtime::with_log_handler`](super::runtime::Runtime::with_log_handler). Messages are rate
+/// limited before they're passed to the handler.
+///
+/// Only custom handlers can log the fields of objects as structured fields: the default
+/// [`TracingLogHandler`] renders them as a string.
+pub trait LogHandler: fmt::Debug + Send + Sync {
+    fn log(&self, record: &LogRecord<'_>);
+}
+
+/// The default [`LogHandler`], which emits messages as [`tracing`] events.
+///
+/// Messages are already rate limited per call site before they get here, so events are emitted
+/// with `internal_log_rate_limit = false`, which tells the host's own rate limiting of `tracing`
+/// events (such as Vector's) not to limit them a second time. The number of messages suppressed
+/// since the previous one is emitted as the `suppressed` field.
+///
+/// The fields of `tracing` events must be known when the event is declared, so logged objects are
+/// rendered as a string in the `message` field rather than as fields of their own. Hosts that want
+/// them as structured fields can implement [`LogHandler`] to map them to their own logging.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingLogHandler;
+
+impl LogHandler for TracingLogHandler {
+    fn log(&self, record: &LogRecord<'_>) {
+        let message = record.value.to_string_lossy();
+        let suppressed = record.suppressed;
+        let position = record.span.start();
+
+        match record.level {
+            LogLevel::Trace => {
+                trace!(message = %message, suppressed, vrl_position = position, internal_log_rate_limit = false)
+            }
+            LogLevel::Debug => {
+                debug!(message = %message, suppressed, vrl_position = position, internal_log_rate_limit = false)
+            }
+            LogLevel::Info => {
+                info!(message = %message, suppressed, vrl_position = position, internal_log_rate_limit = false)
+            }
+            LogLevel::Warn => {
+                warn!(message = %message, suppressed, vrl_position = position, internal_log_rate_limit = false)
+            }
+            LogLevel::Error => {
+                error!(message = %message, suppressed, vrl_position = position, internal_log_rate_limit = false)
+            }
+        }
+    }
+}
@@ -38,8 +38,9 @@ pub use compiler::{CompilationResult, Compiler};
 pub use context::Context;
 pub use datetime::TimeZone;
 pub use expression::{Expression, FunctionExpression};
-pub use expression_error::{ExpressionError, Resolved};
+pub use expression_error::{ErrorCode, ExpressionError, Resolved};
 pub use function::{Function, Parameter};
+pub use log_handler::{LogHandler, LogLevel, LogRecord, TracingLogHandler};
 pub use program::{Program, ProgramInfo};
 pub use state::{TypeInfo, TypeState};
 pub use target::{SecretTarget, Target, TargetValue, TargetValueRef};
@@ -49,8 +50,10 @@ use crate::diagnostic::DiagnosticList;
 pub(crate) use crate::diagnostic::Span;
 use crate::parser::parse;
 
-pub use self::compile_config::CompileConfig;
+pub use self::compile_config::{CompileConfig, UnusedExpression};
 pub use self::deprecation_warning::DeprecationWarning;
+pub use self::unused_expression::UnusedExpressionWarning;
+pub use self::value_limits::ValueLimits;
 
 #[allow(clippy::module_inception)]
 mod compiler;
@@ -60,9 +63,12 @@ mod context;
 mod datetime;
 mod deprecation_warning;
 mod expression_error;
+mod log_handler;
 mod program;
 mod target;
 mod test_util;
+mod unused_expression;
+mod value_limits;
 
 pub mod conversion;
 pub mod expression;
@@ -23,8 +23,8 @@ pub use super::{
     state::{self, TypeInfo, TypeState},
     type_def,
     value::{kind, ValueError, VrlValueArithmetic, VrlValueConvert},
-    Context, Expression, ExpressionError, Function, FunctionExpression, Parameter, TimeZone,
-    TypeDef,
+    Context, ErrorCode, Expression, ExpressionError, Function, FunctionExpression, Parameter,
+    TimeZone, TypeDef,
 };
 
 pub type ExpressionResult<T> = Result<T, ExpressionError>;
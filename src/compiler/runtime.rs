@@ -1,17 +1,19 @@
-use std::{error::Error, fmt};
+use std::{error::Error, fmt, sync::Arc};
 
 use crate::path::OwnedTargetPath;
 use crate::value::Value;
 
-use super::ExpressionError;
 use super::TimeZone;
-use super::{state, Context, Program, Target};
+use super::{state, Context, LogHandler, Program, Target, ValueLimits};
+use super::{ErrorCode, ExpressionError};
 
 pub type RuntimeResult = Result<Value, Terminate>;
 
 #[derive(Debug, Default)]
 pub struct Runtime {
     state: state::RuntimeState,
+    log_handler: Option<Arc<dyn LogHandler>>,
+    value_limits: ValueLimits,
 }
 
 /// The error raised if the runtime is terminated.
@@ -36,6 +38,14 @@ impl Terminate {
             Terminate::Error(error) => error,
         }
     }
+
+    /// Returns the [`ErrorCode`] of the error that terminated the program, if it has one.
+    #[must_use]
+    pub const fn code(&self) -> Option<ErrorCode> {
+        match self {
+            Terminate::Abort(error) | Terminate::Error(error) => error.error_code(),
+        }
+    }
 }
 
 impl fmt::Display for Terminate {
@@ -56,7 +66,27 @@ impl Error for Terminate {
 impl Runtime {
     #[must_use]
     pub fn new(state: state::RuntimeState) -> Self {
-        Self { state }
+        Self {
+            state,
+            log_handler: None,
+            value_limits: ValueLimits::default(),
+        }
+    }
+
+    /// Use the given [`LogHandler`] for the messages of the `log` function, instead of emitting
+    /// them as `tracing` events.
+    #[must_use]
+    pub fn with_log_handler(mut self, log_handler: Arc<dyn LogHandler>) -> Self {
+        self.log_handler = Some(log_handler);
+        self
+    }
+
+    /// Limit the size of the values programs build, terminating them with an error if they don't
+    /// fit, instead of letting untrusted programs use unbounded memory.
+    #[must_use]
+    pub fn with_value_limits(mut self, value_limits: ValueLimits) -> Self {
+        self.value_limits = value_limits;
+        self
     }
 
     #[must_use]
@@ -91,7 +121,11 @@ impl Runtime {
             }
         };
 
-        let mut ctx = Context::new(target, &mut self.state, timezone);
+        let mut ctx =
+            Context::new(target, &mut self.state, timezone).with_value_limits(self.value_limits);
+        if let Some(log_handler) = &self.log_handler {
+            ctx = ctx.with_log_handler(log_handler.as_ref());
+        }
 
         program.resolve(&mut ctx).map_err(|err| match err {
             ExpressionError::Abort { .. }
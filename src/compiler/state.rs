@@ -1,6 +1,7 @@
-use crate::path::PathPrefix;
+use crate::diagnostic::{Label, Span};
+use crate::path::{OwnedValuePath, PathPrefix};
 use crate::value::{Kind, Value};
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
 
 use super::{parser::ast::Ident, type_def::Details, value::Collection, TypeDef};
 
@@ -47,10 +48,101 @@ impl TypeState {
     }
 }
 
+/// The assignments that gave paths of a value their current type, so that type errors can point
+/// at the assignment that made a path something the failing expression doesn't accept.
+///
+/// Only the latest assignment to a path is kept, unless paths were assigned in different branches
+/// of the program, in which case the latest assignment of each branch is kept.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Origins(BTreeMap<OwnedValuePath, Vec<Origin>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Origin {
+    /// The span of the assignment.
+    pub(crate) span: Span,
+
+    /// The type the assignment gave the path.
+    pub(crate) kind: Kind,
+}
+
+impl Origin {
+    /// Returns a label pointing at the assignment, for the given path.
+    pub(crate) fn label(&self, path: &str) -> Label {
+        Label::context(
+            format!(
+                "`{path}` may be of type {} because of this assignment",
+                self.kind
+            ),
+            self.span,
+        )
+    }
+}
+
+impl Origins {
+    /// Records that an assignment gave the path the given type, replacing the origins of the path
+    /// and of the paths nested in it.
+    pub(crate) fn insert(&mut self, path: &OwnedValuePath, span: Span, kind: Kind) {
+        self.0
+            .retain(|other, _| !other.segments.starts_with(&path.segments));
+        self.0.insert(path.clone(), vec![Origin { span, kind }]);
+    }
+
+    /// Returns the assignments that gave the path its type, with the type each gave the path.
+    ///
+    /// These are the assignments to the path, or to the closest parent path that was assigned.
+    pub(crate) fn get(&self, path: &OwnedValuePath) -> Vec<Origin> {
+        (0..=path.segments.len())
+            .rev()
+            .find_map(|len| {
+                let origins = self.0.get(&path.segments[..len].to_vec().into())?;
+                let rest = OwnedValuePath::from(path.segments[len..].to_vec());
+
+                Some(
+                    origins
+                        .iter()
+                        .map(|origin| Origin {
+                            span: origin.span,
+                            kind: origin.kind.at_path(&rest),
+                        })
+                        .collect(),
+                )
+            })
+            .unwrap_or_default()
+    }
+
+    /// Keeps the origins of both branches of the program.
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        for (path, other_origins) in other.0 {
+            let origins = self.0.entry(path).or_default();
+            for origin in other_origins {
+                if !origins.contains(&origin) {
+                    origins.push(origin);
+                }
+            }
+        }
+        self
+    }
+}
+
+fn merge_origins<K: Ord>(
+    mut origins: BTreeMap<K, Origins>,
+    other: BTreeMap<K, Origins>,
+) -> BTreeMap<K, Origins> {
+    for (key, other_origins) in other {
+        let merged = origins
+            .remove(&key)
+            .unwrap_or_default()
+            .merge(other_origins);
+        origins.insert(key, merged);
+    }
+    origins
+}
+
 /// Local environment, limited to a given scope.
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct LocalEnv {
     pub(crate) bindings: HashMap<Ident, Details>,
+    origins: BTreeMap<Ident, Origins>,
 }
 
 impl LocalEnv {
@@ -67,9 +159,27 @@ impl LocalEnv {
     }
 
     pub(crate) fn remove_variable(&mut self, ident: &Ident) -> Option<Details> {
+        self.origins.remove(ident);
         self.bindings.remove(ident)
     }
 
+    pub(crate) fn insert_origin(&mut self, ident: &Ident, path: &OwnedValuePath, span: Span) {
+        let kind = self
+            .variable(ident)
+            .map_or_else(Kind::any, |details| details.type_def.kind().at_path(path));
+        self.origins
+            .entry(ident.clone())
+            .or_default()
+            .insert(path, span, kind);
+    }
+
+    pub(crate) fn origins(&self, ident: &Ident, path: &OwnedValuePath) -> Vec<Origin> {
+        self.origins
+            .get(ident)
+            .map(|origins| origins.get(path))
+            .unwrap_or_default()
+    }
+
     /// Any state the child scope modified that was part of the parent is copied to the parent scope
     pub(crate) fn apply_child_scope(mut self, child: Self) -> Self {
         for (ident, child_details) in child.bindings {
@@ -77,6 +187,11 @@ impl LocalEnv {
                 *self_details = child_details;
             }
         }
+        for (ident, child_origins) in child.origins {
+            if self.bindings.contains_key(&ident) {
+                self.origins.insert(ident, child_origins);
+            }
+        }
 
         self
     }
@@ -92,6 +207,7 @@ impl LocalEnv {
                 self.bindings.insert(ident, other_details);
             }
         }
+        self.origins = merge_origins(self.origins, other.origins);
         self
     }
 }
@@ -104,6 +220,9 @@ pub struct ExternalEnv {
 
     /// The type of metadata
     metadata: Kind,
+
+    /// The assignments that gave paths their type.
+    origins: BTreeMap<PathPrefix, Origins>,
 }
 
 impl Default for ExternalEnv {
@@ -120,6 +239,7 @@ impl ExternalEnv {
         Self {
             target: self.target.merge(other.target),
             metadata: self.metadata.union(other.metadata),
+            origins: merge_origins(self.origins, other.origins),
         }
     }
 
@@ -133,6 +253,7 @@ impl ExternalEnv {
                 value: None,
             },
             metadata,
+            origins: BTreeMap::new(),
         }
     }
 
@@ -163,6 +284,21 @@ impl ExternalEnv {
     pub fn update_metadata(&mut self, kind: Kind) {
         self.metadata = kind;
     }
+
+    pub(crate) fn insert_origin(&mut self, prefix: PathPrefix, path: &OwnedValuePath, span: Span) {
+        let kind = self.kind(prefix).at_path(path);
+        self.origins
+            .entry(prefix)
+            .or_default()
+            .insert(path, span, kind);
+    }
+
+    pub(crate) fn origins(&self, prefix: PathPrefix, path: &OwnedValuePath) -> Vec<Origin> {
+        self.origins
+            .get(&prefix)
+            .map(|origins| origins.get(path))
+            .unwrap_or_default()
+    }
 }
 
 /// The state used at runtime to track changes as they happen.
@@ -0,0 +1,116 @@
+use std::fmt::{Display, Formatter};
+
+use crate::diagnostic::{DiagnosticMessage, Label, Note, Severity};
+
+use super::Span;
+
+/// An expression, other than the last one of a block or program, whose result is discarded even
+/// though the expression has no side effects.
+#[derive(Debug)]
+pub struct UnusedExpressionWarning {
+    span: Span,
+    severity: Severity,
+}
+
+impl UnusedExpressionWarning {
+    #[must_use]
+    pub fn new(span: Span, severity: Severity) -> Self {
+        Self { span, severity }
+    }
+}
+
+impl std::error::Error for UnusedExpressionWarning {}
+
+impl Display for UnusedExpressionWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl DiagnosticMessage for UnusedExpressionWarning {
+    fn code(&self) -> usize {
+        802
+    }
+
+    fn message(&self) -> String {
+        "unused expression".to_owned()
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        vec![Label::primary(
+            "the result of this expression is never used, and it has no side effects",
+            self.span,
+        )]
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        vec![Note::Hint(
+            "assign the result to a variable or path, or remove the expression".to_owned(),
+        )]
+    }
+
+    fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::{compile_with_external, state::ExternalEnv, CompileConfig};
+    use crate::stdlib;
+
+    use super::super::UnusedExpression;
+
+    fn compile(source: &str, unused_expression: UnusedExpression) -> (usize, usize) {
+        let mut config = CompileConfig::default();
+        config.set_unused_expression(unused_expression);
+
+        match compile_with_external(source, &stdlib::all(), &ExternalEnv::default(), config) {
+            Ok(result) => (0, result.warnings.len()),
+            Err(diagnostics) => (diagnostics.errors().len(), diagnostics.warnings().len()),
+        }
+    }
+
+    #[test]
+    fn warns_by_default() {
+        assert_eq!(compile(".foo\n.bar", UnusedExpression::default()), (0, 1));
+    }
+
+    #[test]
+    fn can_be_allowed() {
+        assert_eq!(compile(".foo\n.bar", UnusedExpression::Allow), (0, 0));
+    }
+
+    #[test]
+    fn can_be_denied() {
+        assert_eq!(compile(".foo\n.bar", UnusedExpression::Deny), (1, 0));
+    }
+
+    #[test]
+    fn side_effects_are_exempt() {
+        let source = r#"
+            log("foo")
+            del(.foo)
+            assert!(true)
+            assert_eq!(1, 1)
+            .foo = 1
+            { .foo }
+        "#;
+        assert_eq!(compile(source, UnusedExpression::Deny), (0, 0));
+    }
+
+    #[test]
+    fn abortable_calls_are_reported() {
+        let source = r#"
+            parse_json!(.message)
+            to_int!(.foo)
+            null
+        "#;
+        assert_eq!(compile(source, UnusedExpression::Warn), (0, 2));
+    }
+
+    #[test]
+    fn last_expression_is_exempt() {
+        assert_eq!(compile("x = { 1; 2 }\nx", UnusedExpression::Deny), (1, 0));
+    }
+}
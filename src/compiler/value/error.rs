@@ -1,4 +1,4 @@
-use crate::compiler::ExpressionError;
+use crate::compiler::{ErrorCode, ExpressionError};
 use crate::diagnostic::DiagnosticMessage;
 
 use super::Kind;
@@ -85,12 +85,42 @@ impl DiagnosticMessage for ValueError {
     }
 }
 
+impl ValueError {
+    /// The [`ErrorCode`] runtime errors caused by this error have.
+    #[must_use]
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        use ValueError::{
+            Add, And, Coerce, Div, DivideByZero, Expected, Ge, Gt, Le, Lt, Merge, Mul, NanFloat,
+            Or, Rem, Sub,
+        };
+
+        match self {
+            Expected { .. }
+            | Coerce(..)
+            | Rem(..)
+            | Mul(..)
+            | Div(..)
+            | Add(..)
+            | Sub(..)
+            | And(..)
+            | Gt(..)
+            | Ge(..)
+            | Lt(..)
+            | Le(..)
+            | Merge(..) => Some(ErrorCode::InvalidType),
+            DivideByZero | NanFloat => Some(ErrorCode::OutOfRange),
+            Or(err) => err.error_code(),
+        }
+    }
+}
+
 impl From<ValueError> for ExpressionError {
     fn from(err: ValueError) -> Self {
         Self::Error {
             message: err.message(),
             labels: vec![],
             notes: vec![],
+            code: err.error_code(),
         }
     }
 }
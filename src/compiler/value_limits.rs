@@ -0,0 +1,346 @@
+use crate::path::{OwnedSegment, OwnedValuePath};
+use crate::value::Value;
+
+use super::{ErrorCode, ExpressionError};
+
+/// Limits on the size of the values a program builds, so that hosts running untrusted programs
+/// can bound the memory they use.
+///
+/// The limits are checked against every value a program assigns to a variable or to the target,
+/// before assigning it, and against every array or object it builds. An assignment into an array
+/// also counts the nulls that pad the array up to the assigned index. A program that exceeds the
+/// limits is terminated with a runtime error. There are no limits by default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ValueLimits {
+    max_nodes: Option<usize>,
+    max_bytes: Option<usize>,
+}
+
+impl ValueLimits {
+    /// Limits the number of nodes in a value, where every value counts as one node, including
+    /// each element of an array or object.
+    #[must_use]
+    pub fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Limits the number of bytes in the strings of a value, including object keys.
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Returns `true` if neither the number of nodes nor bytes is limited.
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.max_nodes.is_none() && self.max_bytes.is_none()
+    }
+
+    #[must_use]
+    pub fn max_nodes(&self) -> Option<usize> {
+        self.max_nodes
+    }
+
+    #[must_use]
+    pub fn max_bytes(&self) -> Option<usize> {
+        self.max_bytes
+    }
+
+    /// Returns an error if the value exceeds the limits.
+    ///
+    /// # Errors
+    ///
+    /// The error has the [`ErrorCode::LimitExceeded`] code.
+    pub fn check(&self, value: &Value) -> Result<(), ExpressionError> {
+        if self.is_unlimited() {
+            return Ok(());
+        }
+
+        self.measure(value, &mut Size::default())
+    }
+
+    /// Returns an error if inserting `value` at `path` into `existing` exceeds the limits.
+    ///
+    /// Only `value` and the nulls that inserting it pads arrays with are measured, so the check
+    /// doesn't depend on the size of `existing`, and it can be done before the insertion
+    /// allocates anything.
+    pub(crate) fn check_insert(
+        &self,
+        existing: Option<&Value>,
+        path: &OwnedValuePath,
+        value: &Value,
+    ) -> Result<(), ExpressionError> {
+        if self.is_unlimited() {
+            return Ok(());
+        }
+
+        let mut size = Size {
+            nodes: padding(existing, path),
+            bytes: 0,
+        };
+        self.measure(value, &mut size)
+    }
+
+    /// Adds the size of the value to `size`, stopping as soon as a limit is exceeded, so that
+    /// checking a huge value costs no more than checking one just over the limit.
+    fn measure(&self, value: &Value, size: &mut Size) -> Result<(), ExpressionError> {
+        size.nodes += 1;
+        match value {
+            Value::Bytes(bytes) => size.bytes += bytes.len(),
+            Value::Object(object) => {
+                for (key, value) in object {
+                    size.bytes += key.len();
+                    self.measure(value, size)?;
+                }
+            }
+            Value::Array(array) => {
+                for value in array {
+                    self.measure(value, size)?;
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(max_nodes) = self.max_nodes.filter(|max| size.nodes > *max) {
+            return Err(ExpressionError::with_code(
+                ErrorCode::LimitExceeded,
+                format!("value exceeds the limit of {max_nodes} nodes"),
+            ));
+        }
+        if let Some(max_bytes) = self.max_bytes.filter(|max| size.bytes > *max) {
+            return Err(ExpressionError::with_code(
+                ErrorCode::LimitExceeded,
+                format!("value exceeds the limit of {max_bytes} bytes"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Size {
+    nodes: usize,
+    bytes: usize,
+}
+
+/// Returns the number of nulls that inserting a value at `path` into `existing` adds, to pad
+/// arrays up to the indexes of the path.
+fn padding(mut existing: Option<&Value>, path: &OwnedValuePath) -> usize {
+    let mut padding = 0_usize;
+
+    for segment in &path.segments {
+        existing = match (segment, existing) {
+            (OwnedSegment::Index(index), existing) => {
+                let array = existing.and_then(Value::as_array).unwrap_or_default();
+
+                // A negative index pads the front of the array rather than its end.
+                let (len, position) = if *index >= 0 {
+                    let position = index.unsigned_abs();
+                    (position.saturating_add(1), Some(position))
+                } else {
+                    let len = index.unsigned_abs();
+                    (len, array.len().checked_sub(len))
+                };
+
+                // One of the added elements is the one inserted into.
+                padding = padding.saturating_add(len.saturating_sub(array.len()).saturating_sub(1));
+                position.and_then(|position| array.get(position))
+            }
+            (OwnedSegment::Field(field), Some(Value::Object(object))) => object.get(field),
+            (OwnedSegment::Coalesce(fields), Some(Value::Object(object))) => {
+                fields.iter().find_map(|field| object.get(field))
+            }
+            _ => None,
+        };
+    }
+
+    padding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{compile, runtime::Runtime, state::RuntimeState, TargetValue, TimeZone};
+    use crate::owned_value_path;
+    use crate::value::Secrets;
+    use crate::{stdlib, value};
+
+    fn run(source: &str, limits: ValueLimits) -> Result<Value, String> {
+        run_with_target(source, limits).0
+    }
+
+    fn run_with_target(source: &str, limits: ValueLimits) -> (Result<Value, String>, Value) {
+        let program = compile(source, &stdlib::all()).unwrap().program;
+        let mut target = TargetValue {
+            value: value!({}),
+            metadata: value!({}),
+            secrets: Secrets::default(),
+        };
+
+        let result = Runtime::new(RuntimeState::default())
+            .with_value_limits(limits)
+            .resolve(&mut target, &program, &TimeZone::default())
+            .map_err(|error| error.to_string());
+        (result, target.value)
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let limits = ValueLimits::default();
+        assert_eq!(limits.max_nodes(), None);
+        assert_eq!(limits.max_bytes(), None);
+        assert!(limits.check(&value!({"a": [1, 2, "three"]})).is_ok());
+    }
+
+    #[test]
+    fn counts_nodes() {
+        let value = value!({"a": [1, 2], "b": {}});
+
+        assert!(ValueLimits::default()
+            .with_max_nodes(5)
+            .check(&value)
+            .is_ok());
+        let error = ValueLimits::default()
+            .with_max_nodes(4)
+            .check(&value)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "value exceeds the limit of 4 nodes");
+        assert_eq!(error.error_code(), Some(ErrorCode::LimitExceeded));
+    }
+
+    #[test]
+    fn counts_bytes() {
+        let value = value!({"ab": ["cde"]});
+
+        assert!(ValueLimits::default()
+            .with_max_bytes(5)
+            .check(&value)
+            .is_ok());
+        assert_eq!(
+            ValueLimits::default()
+                .with_max_bytes(4)
+                .check(&value)
+                .unwrap_err()
+                .to_string(),
+            "value exceeds the limit of 4 bytes"
+        );
+    }
+
+    #[test]
+    fn appending_past_the_limit_errors() {
+        let source = r#"
+            .items = []
+            for_each([1, 2, 3, 4, 5]) -> |_index, item| {
+                .items = push(.items, item)
+            }
+            .items
+        "#;
+
+        assert_eq!(
+            run(source, ValueLimits::default().with_max_nodes(5)),
+            Err(
+                r#"function call error for "for_each" at (37:140): value exceeds the limit of 5 nodes"#
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            run(source, ValueLimits::default().with_max_nodes(7)),
+            Ok(value!([1, 2, 3, 4, 5]))
+        );
+    }
+
+    #[test]
+    fn variables_are_limited() {
+        let source = r#"
+            x = "abc"
+            x = x + x
+            x = x + x
+        "#;
+
+        assert_eq!(
+            run(source, ValueLimits::default().with_max_bytes(10)),
+            Err("value exceeds the limit of 10 bytes".to_owned())
+        );
+        assert_eq!(
+            run(source, ValueLimits::default().with_max_bytes(12)),
+            Ok(value!("abcabcabcabc"))
+        );
+    }
+
+    #[test]
+    fn insertions_into_paths_are_limited() {
+        assert_eq!(
+            run(".a[1000] = 1", ValueLimits::default().with_max_nodes(100)),
+            Err("value exceeds the limit of 100 nodes".to_owned())
+        );
+    }
+
+    #[test]
+    fn large_indexes_are_rejected_before_inserting() {
+        assert_eq!(
+            run_with_target(
+                ".b = 1\n.a[100000] = 1",
+                ValueLimits::default().with_max_nodes(100)
+            ),
+            (
+                Err("value exceeds the limit of 100 nodes".to_owned()),
+                value!({"b": 1})
+            )
+        );
+        assert_eq!(
+            run(
+                "x = []\nx[-100000] = 1",
+                ValueLimits::default().with_max_nodes(100)
+            ),
+            Err("value exceeds the limit of 100 nodes".to_owned())
+        );
+    }
+
+    #[test]
+    fn only_the_inserted_value_is_measured() {
+        let limits = ValueLimits::default().with_max_nodes(3);
+        let existing = value!({"a": [1, 2, 3], "b": {"c": [4, 5, 6]}});
+
+        assert!(limits
+            .check_insert(Some(&existing), &owned_value_path!("a", 1), &value!([7, 8]))
+            .is_ok());
+        assert!(limits
+            .check_insert(
+                Some(&existing),
+                &owned_value_path!("a", 1),
+                &value!([7, 8, 9])
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn counts_padding() {
+        let existing = value!({"a": [1, 2, 3]});
+
+        for (path, expected) in [
+            (owned_value_path!("a", 1), 0),
+            (owned_value_path!("a", 3), 0),
+            (owned_value_path!("a", 5), 2),
+            (owned_value_path!("a", -3), 0),
+            (owned_value_path!("a", -5), 1),
+            (owned_value_path!("b", 2, "c", 3), 5),
+            (owned_value_path!("a", 4, 2), 3),
+        ] {
+            assert_eq!(padding(Some(&existing), &path), expected, "{path}");
+        }
+        assert_eq!(
+            padding(None, &owned_value_path!(isize::MAX, isize::MAX, isize::MAX)),
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn literals_are_limited() {
+        assert_eq!(
+            run("[[1, 2], [3, 4]]", ValueLimits::default().with_max_nodes(3)),
+            Err("value exceeds the limit of 3 nodes".to_owned())
+        );
+    }
+}
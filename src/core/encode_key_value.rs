@@ -1,6 +1,7 @@
 use std::{
     collections::BTreeMap,
     fmt::{self, Write},
+    str::FromStr,
 };
 
 use serde::ser::{
@@ -8,7 +9,7 @@ use serde::ser::{
     SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
 };
 
-use crate::value::KeyString;
+use crate::value::{KeyString, Value};
 
 #[derive(Debug, snafu::Snafu)]
 pub enum EncodingError {
@@ -29,6 +30,146 @@ impl Error for EncodingError {
     }
 }
 
+/// Controls how values are quoted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Quote {
+    /// Always wrap values in quotes.
+    Always,
+
+    /// Only wrap values in quotes if they contain whitespace, quotes or delimiters.
+    #[default]
+    WhenNeeded,
+
+    /// Never wrap values in quotes, embedded quotes and key value delimiters are escaped instead.
+    Never,
+}
+
+impl Quote {
+    #[must_use]
+    pub fn all_value() -> Vec<Value> {
+        use Quote::{Always, Never, WhenNeeded};
+
+        vec![Always, WhenNeeded, Never]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        use Quote::{Always, Never, WhenNeeded};
+
+        match self {
+            Always => "always",
+            WhenNeeded => "when_needed",
+            Never => "never",
+        }
+    }
+}
+
+impl FromStr for Quote {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Quote::{Always, Never, WhenNeeded};
+
+        match s {
+            "always" => Ok(Always),
+            "when_needed" => Ok(WhenNeeded),
+            "never" => Ok(Never),
+            _ => Err("quote not recognized"),
+        }
+    }
+}
+
+/// Controls how arrays are encoded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayEncoding {
+    /// Every element is encoded as its own field, with the index appended to the key
+    /// (`tags.0=a tags.1=b`).
+    #[default]
+    Index,
+
+    /// Every element is encoded as its own field, repeating the key (`tags=a tags=b`).
+    Repeat,
+
+    /// The array is encoded as a single JSON value (`tags="[\"a\",\"b\"]"`).
+    Json,
+}
+
+impl ArrayEncoding {
+    #[must_use]
+    pub fn all_value() -> Vec<Value> {
+        use ArrayEncoding::{Index, Json, Repeat};
+
+        vec![Index, Repeat, Json]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        use ArrayEncoding::{Index, Json, Repeat};
+
+        match self {
+            Index => "index",
+            Repeat => "repeat",
+            Json => "json",
+        }
+    }
+}
+
+impl FromStr for ArrayEncoding {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use ArrayEncoding::{Index, Json, Repeat};
+
+        match s {
+            "index" => Ok(Index),
+            "repeat" => Ok(Repeat),
+            "json" => Ok(Json),
+            _ => Err("array_encoding not recognized"),
+        }
+    }
+}
+
+/// Options for [`to_string_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options<'a> {
+    /// Separates keys from their values.
+    pub key_value_delimiter: &'a str,
+
+    /// Separates fields from each other.
+    pub field_delimiter: &'a str,
+
+    /// Encode `true` booleans as just the key, and leave out `false` ones.
+    pub flatten_boolean: bool,
+
+    /// Joins the keys of nested objects (and array indices) into a single key.
+    pub flatten_delimiter: &'a str,
+
+    /// How values are quoted.
+    pub quote: Quote,
+
+    /// How arrays are encoded.
+    pub array_encoding: ArrayEncoding,
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Self {
+            key_value_delimiter: "=",
+            field_delimiter: " ",
+            flatten_boolean: false,
+            flatten_delimiter: ".",
+            quote: Quote::default(),
+            array_encoding: ArrayEncoding::default(),
+        }
+    }
+}
+
 /// Encodes input to key value format with specified
 /// delimiters in field order where unspecified fields
 /// will follow after them. `Flattens_boolean` values
@@ -43,41 +184,50 @@ pub fn to_string<V: Serialize>(
     key_value_delimiter: &str,
     field_delimiter: &str,
     flatten_boolean: bool,
+) -> Result<String, EncodingError> {
+    to_string_with_options(
+        input,
+        fields_order,
+        &Options {
+            key_value_delimiter,
+            field_delimiter,
+            flatten_boolean,
+            ..Options::default()
+        },
+    )
+}
+
+/// Encodes input to key value format, like [`to_string`].
+///
+/// Nested fields are flattened into a single key, and the fields in `fields_order` are encoded
+/// first, followed by all others sorted by key.
+///
+/// # Errors
+///
+/// Returns an `EncodingError` if the input contains non-`String` map keys.
+pub fn to_string_with_options<V: Serialize>(
+    input: &BTreeMap<KeyString, V>,
+    fields_order: &[KeyString],
+    options: &Options<'_>,
 ) -> Result<String, EncodingError> {
     let mut output = String::new();
 
-    let mut input = flatten(input, '.')?;
+    let mut input = flatten(input, options)?;
 
     for field in fields_order {
-        match (input.remove(field), flatten_boolean) {
-            (Some(Data::Boolean(false)), true) | (None, _) => (),
-            (Some(Data::Boolean(true)), true) => {
-                encode_string(&mut output, field);
-                output.push_str(field_delimiter);
-            }
-            (Some(value), _) => {
-                encode_field(&mut output, field, &value.to_string(), key_value_delimiter);
-                output.push_str(field_delimiter);
-            }
-        };
+        for value in input.remove(field).unwrap_or_default() {
+            encode_entry(&mut output, field, &value, options);
+        }
     }
 
-    for (key, value) in &input {
-        match (value, flatten_boolean) {
-            (Data::Boolean(false), true) => (),
-            (Data::Boolean(true), true) => {
-                encode_string(&mut output, key);
-                output.push_str(field_delimiter);
-            }
-            (_, _) => {
-                encode_field(&mut output, key, &value.to_string(), key_value_delimiter);
-                output.push_str(field_delimiter);
-            }
-        };
+    for (key, values) in &input {
+        for value in values {
+            encode_entry(&mut output, key, value, options);
+        }
     }
 
-    if output.ends_with(field_delimiter) {
-        output.truncate(output.len() - field_delimiter.len());
+    if output.ends_with(options.field_delimiter) {
+        output.truncate(output.len() - options.field_delimiter.len());
     }
 
     Ok(output)
@@ -85,37 +235,71 @@ pub fn to_string<V: Serialize>(
 
 fn flatten<'a>(
     input: impl IntoIterator<Item = (&'a KeyString, impl Serialize)> + 'a,
-    separator: char,
-) -> Result<BTreeMap<KeyString, Data>, EncodingError> {
+    options: &Options<'_>,
+) -> Result<BTreeMap<KeyString, Vec<Data>>, EncodingError> {
     let mut map = BTreeMap::new();
     for (key, value) in input {
-        value.serialize(KeyValueSerializer::new(key.clone(), separator, &mut map))?;
+        value.serialize(KeyValueSerializer::new(
+            key.clone(),
+            options.flatten_delimiter,
+            options.array_encoding,
+            &mut map,
+        ))?;
     }
     Ok(map)
 }
 
-fn encode_field(output: &mut String, key: &str, value: &str, key_value_delimiter: &str) {
-    encode_string(output, key);
-    output.push_str(key_value_delimiter);
-    encode_string(output, value);
+fn encode_entry(output: &mut String, key: &str, value: &Data, options: &Options<'_>) {
+    match (value, options.flatten_boolean) {
+        (Data::Boolean(false), true) => return,
+        (Data::Boolean(true), true) => encode_string(output, key, Quote::WhenNeeded, options),
+        (_, _) => {
+            encode_string(output, key, Quote::WhenNeeded, options);
+            output.push_str(options.key_value_delimiter);
+            encode_string(output, &value.to_string(), options.quote, options);
+        }
+    }
+
+    output.push_str(options.field_delimiter);
 }
 
-fn encode_string(output: &mut String, str: &str) {
-    let needs_quoting = str
-        .chars()
-        .any(|c| c.is_whitespace() || c == '"' || c == '=');
+fn encode_string(output: &mut String, str: &str, quote: Quote, options: &Options<'_>) {
+    let contains = |delimiter: &str| !delimiter.is_empty() && str.contains(delimiter);
+    let needs_quoting = match quote {
+        Quote::Always => true,
+        Quote::WhenNeeded => {
+            str.chars()
+                .any(|c| c.is_whitespace() || c == '"' || c == '=')
+                || contains(options.key_value_delimiter)
+                || contains(options.field_delimiter)
+        }
+        Quote::Never => false,
+    };
 
     if needs_quoting {
         output.write_char('"').unwrap();
     }
 
-    for c in str.chars() {
+    let mut rest = str;
+    while let Some(c) = rest.chars().next() {
+        // Without quotes, the key value delimiter has to be escaped so that the value can't be
+        // mistaken for another key.
+        if quote == Quote::Never && contains(options.key_value_delimiter) {
+            if let Some(after) = rest.strip_prefix(options.key_value_delimiter) {
+                output.push('\\');
+                output.push_str(options.key_value_delimiter);
+                rest = after;
+                continue;
+            }
+        }
+
         match c {
             '\\' => output.push_str(r"\\"),
             '"' => output.push_str(r#"\""#),
             '\n' => output.push_str(r"\\n"),
             _ => output.push(c),
         }
+        rest = &rest[c.len_utf8()..];
     }
 
     if needs_quoting {
@@ -155,15 +339,22 @@ impl fmt::Display for Data {
 
 struct KeyValueSerializer<'a> {
     key: KeyString,
-    separator: char,
-    output: &'a mut BTreeMap<KeyString, Data>,
+    separator: &'a str,
+    arrays: ArrayEncoding,
+    output: &'a mut BTreeMap<KeyString, Vec<Data>>,
 }
 
 impl<'a> KeyValueSerializer<'a> {
-    fn new(key: KeyString, separator: char, output: &'a mut BTreeMap<KeyString, Data>) -> Self {
+    fn new(
+        key: KeyString,
+        separator: &'a str,
+        arrays: ArrayEncoding,
+        output: &'a mut BTreeMap<KeyString, Vec<Data>>,
+    ) -> Self {
         Self {
             key,
             separator,
+            arrays,
             output,
         }
     }
@@ -171,6 +362,7 @@ impl<'a> KeyValueSerializer<'a> {
     fn indexed(self) -> IndexedKeyValueSerializer<'a> {
         IndexedKeyValueSerializer {
             index: 0,
+            json: (self.arrays == ArrayEncoding::Json).then(Vec::new),
             ser: self,
         }
     }
@@ -191,13 +383,27 @@ impl<'a> KeyValueSerializer<'a> {
         KeyValueSerializer {
             key: format!("{}{}{child}", self.key, self.separator).into(),
             separator: self.separator,
+            arrays: self.arrays,
             output: self.output,
         }
     }
 
+    /// Elements of arrays encoded as repeated keys are stored under the key of the array itself.
+    fn element(&mut self, index: usize) -> KeyValueSerializer<'_> {
+        match self.arrays {
+            ArrayEncoding::Repeat => KeyValueSerializer {
+                key: self.key.clone(),
+                separator: self.separator,
+                arrays: self.arrays,
+                output: self.output,
+            },
+            ArrayEncoding::Index | ArrayEncoding::Json => self.child(index),
+        }
+    }
+
     #[allow(clippy::unnecessary_wraps)]
     fn process(self, data: Data) -> Result<(), EncodingError> {
-        self.output.insert(self.key, data);
+        self.output.entry(self.key).or_default().push(data);
         Ok(())
     }
 }
@@ -403,14 +609,30 @@ impl<'a> SerializeStructVariant for KeyValueSerializer<'a> {
 
 struct IndexedKeyValueSerializer<'a> {
     index: usize,
+    /// The elements collected so far, if the array is encoded as JSON.
+    json: Option<Vec<serde_json::Value>>,
     ser: KeyValueSerializer<'a>,
 }
 
 impl<'a> IndexedKeyValueSerializer<'a> {
     fn process<T: ?Sized + Serialize>(&mut self, data: &T) -> Result<(), EncodingError> {
-        let key = self.index;
+        if let Some(json) = &mut self.json {
+            json.push(serde_json::to_value(data).map_err(EncodingError::custom)?);
+            return Ok(());
+        }
+
+        let index = self.index;
         self.index += 1;
-        data.serialize(self.ser.child(key))
+        data.serialize(self.ser.element(index))
+    }
+
+    fn end(self) -> Result<(), EncodingError> {
+        match self.json {
+            Some(json) => self
+                .ser
+                .process(Data::String(serde_json::Value::Array(json).to_string())),
+            None => Ok(()),
+        }
     }
 }
 
@@ -423,7 +645,7 @@ impl<'a> SerializeTuple for IndexedKeyValueSerializer<'a> {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        IndexedKeyValueSerializer::end(self)
     }
 }
 
@@ -436,7 +658,7 @@ impl<'a> SerializeSeq for IndexedKeyValueSerializer<'a> {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        IndexedKeyValueSerializer::end(self)
     }
 }
 
@@ -449,7 +671,7 @@ impl<'a> SerializeTupleStruct for IndexedKeyValueSerializer<'a> {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        IndexedKeyValueSerializer::end(self)
     }
 }
 
@@ -462,7 +684,7 @@ impl<'a> SerializeTupleVariant for IndexedKeyValueSerializer<'a> {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        IndexedKeyValueSerializer::end(self)
     }
 }
 
@@ -749,6 +971,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn options() {
+        assert_eq!(
+            &to_string_with_options::<Value>(
+                &btreemap! {
+                    "lvl" => "info",
+                    "tags" => json!(["a", "b"]),
+                    "http" => json!({ "status": 200 }),
+                },
+                &[KeyString::from("lvl")],
+                &Options {
+                    flatten_delimiter: "_",
+                    quote: Quote::Always,
+                    array_encoding: ArrayEncoding::Repeat,
+                    ..Options::default()
+                }
+            )
+            .unwrap(),
+            r#"lvl="info" http_status="200" tags="a" tags="b""#
+        );
+    }
+
     #[test]
     fn non_string_keys() {
         #[derive(Serialize)]
@@ -38,9 +38,70 @@ pub fn parse(input: &str) -> Vec<&str> {
         .1
 }
 
+/// The error returned by [`parse_quoted`] when a quote isn't closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnterminatedQuote {
+    /// The byte offset of the opening quote.
+    pub position: usize,
+}
+
+impl std::fmt::Display for UnterminatedQuote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unterminated quote at position {}", self.position)
+    }
+}
+
+impl std::error::Error for UnterminatedQuote {}
+
+/// Splits the specified `input` on whitespace the way a shell does, and returns the tokens.
+///
+/// Single and double quoted spans are part of the surrounding token, without their quotes, so
+/// that `a"b c"` is the single token `ab c`. A `\` escapes the next character, except inside
+/// quotes where it only escapes the closing quote and `\` itself.
+///
+/// # Errors
+/// Returns an error if a quote is opened but never closed.
+pub fn parse_quoted(input: &str) -> Result<Vec<String>, UnterminatedQuote> {
+    let mut tokens = Vec::new();
+    // `None` until a token is started, so that `""` is an empty token rather than none at all
+    let mut token: Option<String> = None;
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let token = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => match chars.peek() {
+                            Some(&(_, next)) if next == c || next == '\\' => {
+                                token.push(next);
+                                chars.next();
+                            }
+                            _ => token.push('\\'),
+                        },
+                        Some((_, next)) if next == c => break,
+                        Some((_, next)) => token.push(next),
+                        None => return Err(UnterminatedQuote { position }),
+                    }
+                }
+            }
+            '\\' => {
+                let escaped = chars.next().map_or('\\', |(_, next)| next);
+                token.get_or_insert_with(String::new).push(escaped);
+            }
+            c if c.is_whitespace() => tokens.extend(token.take()),
+            c => token.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(token);
+
+    Ok(tokens)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse;
+    use super::{parse, parse_quoted, UnterminatedQuote};
 
     #[test]
     fn basic() {
@@ -132,4 +193,48 @@ mod tests {
         assert_eq!(parse("[][x"), &["", "[x"]);
         assert_eq!(parse("x[][x"), &["x", "", "[x"]);
     }
+
+    #[test]
+    fn quoted_tokens() {
+        assert_eq!(
+            parse_quoted(r#"foo "bar baz" 'qux'"#).unwrap(),
+            ["foo", "bar baz", "qux"]
+        );
+        assert_eq!(parse_quoted("  foo\t\n bar  ").unwrap(), ["foo", "bar"]);
+        assert_eq!(parse_quoted("").unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn quoted_adjacent() {
+        assert_eq!(
+            parse_quoted(r#"--name="John Doe"x 'a'"b""#).unwrap(),
+            ["--name=John Doex", "ab"]
+        );
+        assert_eq!(parse_quoted(r#"a "" ''"#).unwrap(), ["a", "", ""]);
+    }
+
+    #[test]
+    fn quoted_escapes() {
+        assert_eq!(
+            parse_quoted(r#""say \"hi\"" 'it\'s' "back\\slash" "\n""#).unwrap(),
+            [r#"say "hi""#, "it's", r"back\slash", r"\n"]
+        );
+        assert_eq!(
+            parse_quoted(r#"one\ token \"no quote\" end\"#).unwrap(),
+            ["one token", "\"no", "quote\"", "end\\"]
+        );
+        assert_eq!(parse_quoted(r#"'"' "'""#).unwrap(), ["\"", "'"]);
+    }
+
+    #[test]
+    fn quoted_unterminated() {
+        assert_eq!(
+            parse_quoted(r#"foo "bar"#),
+            Err(UnterminatedQuote { position: 4 })
+        );
+        assert_eq!(
+            parse_quoted(r"foo 'bar\'"),
+            Err(UnterminatedQuote { position: 4 })
+        );
+    }
 }
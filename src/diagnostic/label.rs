@@ -1,6 +1,6 @@
 use codespan_reporting::diagnostic;
 
-use super::Span;
+use super::{Location, SourceMap, Span};
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Label {
@@ -25,6 +25,15 @@ impl Label {
             span: span.into(),
         }
     }
+
+    /// Returns the locations of the start and end of the label's span in the source.
+    #[must_use]
+    pub fn resolve_locations(&self, source_map: &SourceMap<'_>) -> (Location, Location) {
+        (
+            source_map.location(self.span.start()),
+            source_map.location(self.span.end()),
+        )
+    }
 }
 
 impl From<Label> for diagnostic::Label<()> {
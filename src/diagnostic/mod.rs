@@ -21,6 +21,7 @@ pub use formatter::Formatter;
 pub use label::Label;
 pub use note::Note;
 pub use severity::Severity;
+pub use source_map::{Location, SourceMap};
 pub use span::{span, Span};
 
 #[allow(clippy::module_inception)]
@@ -29,6 +30,7 @@ mod formatter;
 mod label;
 mod note;
 mod severity;
+mod source_map;
 mod span;
 
 const VRL_DOCS_ROOT_URL: &str = "https://vrl.dev";
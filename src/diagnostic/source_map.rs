@@ -0,0 +1,162 @@
+/// A line and column in a source, both starting at `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Maps byte offsets, such as those of a [`Span`](super::Span), to lines and columns of a source.
+///
+/// Lines end with `\n`, which can be preceded by `\r`. Columns count Unicode scalar values by
+/// default, or UTF-16 code units with [`SourceMap::utf16_columns`], which is what the Language
+/// Server Protocol uses.
+#[derive(Clone, Debug)]
+pub struct SourceMap<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+    utf16: bool,
+}
+
+impl<'a> SourceMap<'a> {
+    #[must_use]
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+            utf16: false,
+        }
+    }
+
+    /// Counts columns in UTF-16 code units instead of Unicode scalar values.
+    #[must_use]
+    pub fn utf16_columns(mut self) -> Self {
+        self.utf16 = true;
+        self
+    }
+
+    /// Returns the number of lines of the source, which is one more than its number of `\n`.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the location of a byte offset. Offsets past the end of the source are at its end,
+    /// and offsets inside a multibyte character are at that character.
+    #[must_use]
+    pub fn location(&self, offset: usize) -> Location {
+        let offset = offset.min(self.source.len());
+        let index = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[index];
+
+        let before = self.source[start..]
+            .char_indices()
+            .take_while(|(i, c)| start + i + c.len_utf8() <= offset)
+            .map(|(_, c)| c);
+        let column = match self.utf16 {
+            true => before.map(char::len_utf16).sum(),
+            false => before.count(),
+        };
+
+        Location {
+            line: index + 1,
+            column: column + 1,
+        }
+    }
+
+    /// Returns the text of a line, starting at `1`, without its line ending, or `None` if the
+    /// source doesn't have that line.
+    #[must_use]
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = self
+            .line_starts
+            .get(line)
+            .map_or(self.source.len(), |next| next - 1);
+        let text = &self.source[start..end];
+
+        Some(text.strip_suffix('\r').unwrap_or(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::{Label, Span};
+
+    const fn location(line: usize, column: usize) -> Location {
+        Location { line, column }
+    }
+
+    #[test]
+    fn lines_and_columns() {
+        let map = SourceMap::new("foo\nbar\n\nbaz");
+
+        assert_eq!(map.line_count(), 4);
+        assert_eq!(map.location(0), location(1, 1));
+        assert_eq!(map.location(3), location(1, 4));
+        assert_eq!(map.location(4), location(2, 1));
+        assert_eq!(map.location(8), location(3, 1));
+        assert_eq!(map.location(10), location(4, 2));
+        assert_eq!(map.line_text(1), Some("foo"));
+        assert_eq!(map.line_text(3), Some(""));
+        assert_eq!(map.line_text(4), Some("baz"));
+        assert_eq!(map.line_text(0), None);
+        assert_eq!(map.line_text(5), None);
+    }
+
+    #[test]
+    fn crlf() {
+        let source = ".a = 1\r\n.b = 2\r\n";
+        let map = SourceMap::new(source);
+
+        assert_eq!(map.line_count(), 3);
+        assert_eq!(map.location(source.find(".b").unwrap()), location(2, 1));
+        assert_eq!(map.location(source.find('2').unwrap()), location(2, 6));
+        assert_eq!(map.location(source.find('\r').unwrap()), location(1, 7));
+        assert_eq!(map.line_text(1), Some(".a = 1"));
+        assert_eq!(map.line_text(2), Some(".b = 2"));
+        assert_eq!(map.line_text(3), Some(""));
+    }
+
+    #[test]
+    fn multibyte() {
+        let source = ".name = \"café 😀\" ++ 1";
+        let offset = source.find("++").unwrap();
+
+        assert_eq!(SourceMap::new(source).location(offset), location(1, 18));
+        assert_eq!(
+            SourceMap::new(source).utf16_columns().location(offset),
+            location(1, 19)
+        );
+
+        // An offset inside the emoji is at the emoji.
+        let emoji = source.find('😀').unwrap();
+        assert_eq!(SourceMap::new(source).location(emoji + 2), location(1, 15));
+    }
+
+    #[test]
+    fn end_of_file() {
+        let source = "foo\nbär";
+        let map = SourceMap::new(source);
+
+        assert_eq!(map.location(source.len()), location(2, 4));
+        assert_eq!(map.location(source.len() + 10), location(2, 4));
+        assert_eq!(SourceMap::new("").location(0), location(1, 1));
+    }
+
+    #[test]
+    fn label_locations() {
+        let source = "foo\r\nbär(\n  1,\n";
+        let map = SourceMap::new(source);
+        let label = Label::primary("unclosed call", Span::new(5, source.len()));
+
+        assert_eq!(
+            label.resolve_locations(&map),
+            (location(2, 1), location(4, 1))
+        );
+    }
+}
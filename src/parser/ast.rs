@@ -285,7 +285,7 @@ impl fmt::Display for Expr {
 // ident
 // -----------------------------------------------------------------------------
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Ident(pub(crate) String);
 
 impl Ident {
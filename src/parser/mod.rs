@@ -40,6 +40,10 @@ mod template_string;
 pub use crate::diagnostic::Span;
 pub use ast::{Literal, Program};
 pub use lex::{Error, Token};
+#[cfg(feature = "compiler")]
+pub(crate) use template_string::{
+    split_format_spec, template_target, StringSegment, TemplateString,
+};
 
 pub fn parse(input: impl AsRef<str>) -> Result<Program, Error> {
     let lexer = lex::Lexer::new(input.as_ref());
@@ -1,8 +1,9 @@
 use std::fmt;
 
 use crate::diagnostic::Span;
+use crate::path::{parse_target_path, parse_value_path};
 
-use super::ast::{Expr, Ident, Literal::RawString, Node, Op, Opcode};
+use super::ast::{Expr, Ident, Literal::RawString, Node, Op, Opcode, Query, QueryTarget};
 
 #[derive(Clone, PartialEq, Eq, Ord, PartialOrd, Debug, Hash)]
 pub enum StringSegment {
@@ -31,9 +32,7 @@ impl TemplateString {
                 StringSegment::Literal(s, span) => {
                     (*span, Expr::Literal(Node::new(*span, RawString(s.clone()))))
                 }
-                StringSegment::Template(s, span) => {
-                    (*span, Expr::Variable(Node::new(*span, Ident::new(s))))
-                }
+                StringSegment::Template(s, span) => (*span, template_target(s, *span)),
             })
             .reduce(|accum, item| {
                 let (item_span, item) = item;
@@ -71,6 +70,59 @@ impl TemplateString {
             _ => None,
         }
     }
+
+    /// Returns whether any templated segment has a format spec, in which case the template
+    /// can't be rewritten into string concatenations.
+    pub fn has_format_specs(&self) -> bool {
+        self.0.iter().any(|segment| match segment {
+            StringSegment::Literal(..) => false,
+            StringSegment::Template(s, _) => split_format_spec(s).1.is_some(),
+        })
+    }
+}
+
+/// Splits the content of a templated segment into its target and the format spec following the
+/// first `:` that isn't quoted, if any.
+pub(crate) fn split_format_spec(template: &str) -> (&str, Option<&str>) {
+    let mut quoted = false;
+
+    for (i, c) in template.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return (template[..i].trim(), Some(template[i + 1..].trim())),
+            _ => {}
+        }
+    }
+
+    (template, None)
+}
+
+/// Returns the expression rendered by the target of a templated segment: a variable, or a path
+/// such as `.field`, `%field` or `variable.field`. A target that isn't a valid path is a variable,
+/// which fails to compile.
+pub(crate) fn template_target(target: &str, span: Span) -> Expr {
+    let query = |target, path| {
+        Expr::Query(Node::new(
+            span,
+            Query {
+                target: Node::new(span, target),
+                path: Node::new(span, path),
+            },
+        ))
+    };
+
+    if target.starts_with(['.', '%']) {
+        if let Ok(path) = parse_target_path(target) {
+            return query(QueryTarget::External(path.prefix), path.path);
+        }
+    } else if let Some(i) = target.find(['.', '[']) {
+        let (ident, path) = target.split_at(i);
+        if let Ok(path) = parse_value_path(path) {
+            return query(QueryTarget::Internal(Ident::new(ident)), path);
+        }
+    }
+
+    Expr::Variable(Node::new(span, Ident::new(target)))
 }
 
 impl fmt::Display for TemplateString {
@@ -0,0 +1,102 @@
+use crate::compiler::prelude::*;
+
+fn adler32(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    Ok(Value::Integer(i64::from(adler::adler32_slice(&value))))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Adler32;
+
+impl Function for Adler32 {
+    fn identifier(&self) -> &'static str {
+        "adler32"
+    }
+
+    fn summary(&self) -> &'static str {
+        "calculate the Adler-32 checksum of a string"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Calculates the Adler-32 checksum of the provided `value`, as defined in RFC 1950, and
+            returns it as an integer. The checksum of `"123456789"` is `0x091E01DE`.
+        "#}
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "adler32",
+            source: r#"adler32("foobar")"#,
+            result: Ok("145425018"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Adler32Fn { value }.as_expr())
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Adler32Fn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for Adler32Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        adler32(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        adler32 => Adler32;
+
+        empty {
+             args: func_args![value: ""],
+             want: Ok(1_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        check {
+             args: func_args![value: "123456789"],
+             want: Ok(0x091E_01DE_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        foobar {
+             args: func_args![value: "foobar"],
+             want: Ok(145_425_018_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        quick_brown_fox {
+             args: func_args![value: "The quick brown fox jumps over the lazy dog"],
+             want: Ok(1_541_148_634_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,389 @@
+//! Scanning of ANSI escape sequences, as defined by ECMA-48.
+//!
+//! Sequences start with `ESC`, or with one of the C1 control characters, `U+0080` to `U+009F`,
+//! which are each equivalent to `ESC` followed by a character from `@` to `_`. C1 controls are
+//! recognized both as UTF-8 characters and as single bytes that aren't part of a valid UTF-8
+//! character, as sent by 8-bit terminals.
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+
+const CSI: u8 = 0x9b;
+const OSC: u8 = 0x9d;
+const ST: u8 = 0x9c;
+const DCS: u8 = 0x90;
+const SOS: u8 = 0x98;
+const PM: u8 = 0x9e;
+const APC: u8 = 0x9f;
+
+/// Removes the escape sequences from the input, keeping everything else, including other control
+/// characters such as newlines and tabs.
+///
+/// If `keep_link_text` is `false`, the text of OSC 8 hyperlinks is removed along with the
+/// sequences that start and end them.
+pub(crate) fn strip(input: &[u8], keep_link_text: bool) -> Vec<u8> {
+    let mut scanner = Scanner { input, pos: 0 };
+    let mut output = Vec::with_capacity(input.len());
+    let mut in_hidden_link = false;
+
+    while let Some(unit) = scanner.peek() {
+        match scanner.sequence() {
+            Some(Sequence::Osc(content)) => {
+                if let Some(uri) = hyperlink_uri(content) {
+                    in_hidden_link = !keep_link_text && !uri.is_empty();
+                }
+            }
+            Some(Sequence::Other) => {}
+            None => {
+                scanner.bump(unit);
+                if !in_hidden_link {
+                    output.extend_from_slice(&input[scanner.pos - unit.len..scanner.pos]);
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// Returns `true` if the input contains an escape sequence, or at least an `ESC` or C1 control
+/// character, which [`strip`] would remove.
+pub(crate) fn contains(input: &[u8]) -> bool {
+    // Fast path for the common case, since C1 controls have bytes in this range whether or not
+    // they're encoded as UTF-8.
+    if !input
+        .iter()
+        .any(|&byte| byte == ESC || (0x80..=0x9f).contains(&byte))
+    {
+        return false;
+    }
+
+    let mut scanner = Scanner { input, pos: 0 };
+    while let Some(unit) = scanner.peek() {
+        if unit.introducer().is_some() {
+            return true;
+        }
+        scanner.bump(unit);
+    }
+    false
+}
+
+/// Returns the URI of an OSC 8 hyperlink, which is empty for the sequence that ends a link, or
+/// `None` if the OSC sequence isn't a hyperlink.
+fn hyperlink_uri(content: &[u8]) -> Option<&[u8]> {
+    let params_and_uri = content.strip_prefix(b"8;")?;
+    let separator = params_and_uri.iter().position(|&byte| byte == b';')?;
+    Some(&params_and_uri[separator + 1..])
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Sequence<'a> {
+    /// An operating system command, such as a hyperlink or window title, with its content.
+    Osc(&'a [u8]),
+
+    /// Any other sequence.
+    Other,
+}
+
+/// A character, or a byte that isn't part of a valid UTF-8 character.
+#[derive(Clone, Copy, Debug)]
+struct Unit {
+    /// The character, or the invalid byte as a character in `U+0000` to `U+00FF`.
+    char: char,
+    len: usize,
+}
+
+impl Unit {
+    /// Returns the C1 control that starts a sequence, with `ESC` as itself.
+    fn introducer(self) -> Option<u8> {
+        match u32::from(self.char) {
+            0x1b => Some(ESC),
+            code @ 0x80..=0x9f => u8::try_from(code).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte of an ASCII character.
+    fn ascii(self) -> Option<u8> {
+        u8::try_from(self.char).ok().filter(u8::is_ascii)
+    }
+}
+
+struct Scanner<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn peek(&self) -> Option<Unit> {
+        let rest = &self.input[self.pos..];
+        let first = *rest.first()?;
+        let len = utf8_width::get_width(first).max(1);
+
+        let char = rest
+            .get(..len)
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+            .and_then(|str| str.chars().next());
+        Some(match char {
+            Some(char) => Unit { char, len },
+            None => Unit {
+                char: char::from(first),
+                len: 1,
+            },
+        })
+    }
+
+    fn peek_ascii(&self) -> Option<u8> {
+        self.peek().and_then(Unit::ascii)
+    }
+
+    fn bump(&mut self, unit: Unit) {
+        self.pos += unit.len;
+    }
+
+    /// Consumes the escape sequence at the current position, if there is one.
+    fn sequence(&mut self) -> Option<Sequence<'a>> {
+        let unit = self.peek()?;
+        let introducer = unit.introducer()?;
+        self.bump(unit);
+
+        let control = if introducer == ESC {
+            match self.escape() {
+                Some(control) => control,
+                None => return Some(Sequence::Other),
+            }
+        } else {
+            introducer
+        };
+
+        match control {
+            CSI => self.control_sequence(),
+            OSC => return Some(Sequence::Osc(self.control_string(true))),
+            DCS | SOS | PM | APC => {
+                self.control_string(false);
+            }
+            _ => {}
+        }
+        Some(Sequence::Other)
+    }
+
+    /// Consumes the rest of a sequence starting with `ESC`, returning the equivalent C1 control
+    /// if it's one.
+    fn escape(&mut self) -> Option<u8> {
+        let unit = self.peek()?;
+        match unit.ascii()? {
+            // `ESC [` is CSI, `ESC ]` is OSC, and so on.
+            byte @ 0x40..=0x5f => {
+                self.bump(unit);
+                Some(byte + 0x40)
+            }
+            // Intermediate bytes, followed by a final byte, such as `ESC ( B`.
+            0x20..=0x2f => {
+                self.bump(unit);
+                while let Some(byte) = self.peek_ascii() {
+                    match byte {
+                        0x20..=0x2f => self.pos += 1,
+                        0x30..=0x7e => {
+                            self.pos += 1;
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+                None
+            }
+            // Single-character escapes, such as `ESC 7` or `ESC c`.
+            0x30..=0x3f | 0x60..=0x7e => {
+                self.bump(unit);
+                None
+            }
+            // Anything else isn't part of the sequence, so only the `ESC` is removed.
+            _ => None,
+        }
+    }
+
+    /// Consumes the parameters and final byte of a control sequence, such as `1;31m`.
+    fn control_sequence(&mut self) {
+        while let Some(byte) = self.peek_ascii() {
+            match byte {
+                0x20..=0x3f => self.pos += 1,
+                0x40..=0x7e | CAN | SUB => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Consumes a control string, and the string terminator that ends it, returning its content.
+    /// OSC strings can also be ended by BEL.
+    fn control_string(&mut self, bel_terminates: bool) -> &'a [u8] {
+        let start = self.pos;
+        while let Some(unit) = self.peek() {
+            let end = self.pos;
+            match (unit.ascii(), unit.introducer()) {
+                (Some(BEL), _) if bel_terminates => {
+                    self.bump(unit);
+                    return &self.input[start..end];
+                }
+                (Some(CAN | SUB), _) => {
+                    self.bump(unit);
+                    return &self.input[start..end];
+                }
+                (_, Some(ESC)) => {
+                    // `ESC \` is the string terminator, while `ESC` followed by anything else
+                    // aborts the string and starts another sequence.
+                    if self.input.get(self.pos + 1) == Some(&b'\\') {
+                        self.pos += 2;
+                    }
+                    return &self.input[start..end];
+                }
+                (_, Some(ST)) => {
+                    self.bump(unit);
+                    return &self.input[start..end];
+                }
+                _ => self.bump(unit),
+            }
+        }
+        &self.input[start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{QuickCheck, TestResult};
+
+    use super::*;
+
+    fn stripped(input: &[u8]) -> String {
+        String::from_utf8(strip(input, true)).unwrap()
+    }
+
+    #[test]
+    fn strips_control_sequences() {
+        assert_eq!(stripped(b"\x1b[1;31mred\x1b[0m"), "red");
+        assert_eq!(stripped(b"\x1b[3;4Hfoo\x1b[2K"), "foo");
+        assert_eq!(
+            stripped(b"\x1b[?25lhidden cursor\x1b[?25h"),
+            "hidden cursor"
+        );
+        assert_eq!(stripped("\u{9b}1mbold\u{9b}0m".as_bytes()), "bold");
+    }
+
+    #[test]
+    fn strips_operating_system_commands() {
+        assert_eq!(stripped(b"\x1b]0;title\x07$ ls"), "$ ls");
+        assert_eq!(stripped(b"\x1b]2;title\x1b\\text"), "text");
+        assert_eq!(stripped("\u{9d}0;title\u{9c}text".as_bytes()), "text");
+        assert_eq!(stripped(b"\x1b]0;unterminated"), "");
+    }
+
+    #[test]
+    fn strips_control_strings() {
+        assert_eq!(stripped(b"a\x1bP1$r0m\x1b\\b"), "ab");
+        assert_eq!(stripped(b"a\x1b_Gf=100;data\x1b\\b"), "ab");
+        assert_eq!(stripped(b"a\x1b^privacy message\x1b\\b"), "ab");
+        assert_eq!(stripped(b"a\x1bXstring\x1b\\b"), "ab");
+        // BEL only ends OSC strings.
+        assert_eq!(stripped(b"a\x1bPfoo\x07bar\x1b\\b"), "ab");
+    }
+
+    #[test]
+    fn strips_single_character_escapes() {
+        assert_eq!(stripped(b"\x1b7saved\x1b8"), "saved");
+        assert_eq!(stripped(b"\x1bcreset"), "reset");
+        assert_eq!(stripped(b"\x1b(Bcharset"), "charset");
+        assert_eq!(stripped(b"\x1bMup"), "up");
+        assert_eq!(stripped(b"trailing\x1b"), "trailing");
+    }
+
+    #[test]
+    fn strips_8_bit_c1_controls() {
+        assert_eq!(strip(b"\x9b31mred\x9b0m", true), b"red");
+        assert_eq!(strip(b"\x9d0;title\x9cnext\x85line", true), b"nextline");
+        assert_eq!(stripped("caf\u{e9} \u{20ac}\u{85}".as_bytes()), "café €");
+    }
+
+    #[test]
+    fn keeps_other_control_characters() {
+        assert_eq!(stripped(b"a\tb\r\nc\x08"), "a\tb\r\nc\x08");
+    }
+
+    #[test]
+    fn aborted_sequences() {
+        assert_eq!(stripped(b"\x1b[31\x18red"), "red");
+        assert_eq!(stripped(b"\x1b]0;title\x1b[1mbold"), "bold");
+        assert_eq!(stripped(b"\x1b[31\nred"), "\nred");
+    }
+
+    #[test]
+    fn hyperlinks() {
+        let input = b"see \x1b]8;id=1;https://vrl.dev\x1b\\the docs\x1b]8;;\x1b\\ now";
+        assert_eq!(strip(input, true), b"see the docs now");
+        assert_eq!(strip(input, false), b"see  now");
+    }
+
+    #[test]
+    fn colored_ls_output() {
+        let input = b"\x1b]8;;file://host/home/user/bin\x1b\\\x1b[0m\x1b[01;34mbin\x1b[0m\x1b]8;;\x1b\\  \x1b]8;;file://host/home/user/notes.txt\x1b\\notes.txt\x1b]8;;\x1b\\\n";
+        assert_eq!(strip(input, true), b"bin  notes.txt\n");
+        assert_eq!(strip(input, false), b"  \n");
+    }
+
+    #[test]
+    fn colored_gcc_output() {
+        let input = b"\x1b[01m\x1b[Kmain.c:3:5:\x1b[m\x1b[K \x1b[01;31m\x1b[Kerror: \x1b[m\x1b[K\xe2\x80\x98x\xe2\x80\x99 undeclared";
+        assert_eq!(
+            stripped(input),
+            "main.c:3:5: error: \u{2018}x\u{2019} undeclared"
+        );
+    }
+
+    #[test]
+    fn tmux_passthrough() {
+        let input = b"\x1bPtmux;\x1b\x1b]52;c;Zm9v\x07\x1b\\done";
+        assert_eq!(stripped(input), "done");
+    }
+
+    #[test]
+    fn contains_sequences() {
+        assert!(!contains(b"plain text"));
+        assert!(!contains("caf\u{e9} \u{20ac}".as_bytes()));
+        assert!(contains(b"\x1b[0m"));
+        assert!(contains(b"lone \x1b"));
+        assert!(contains("\u{9b}0m".as_bytes()));
+        assert!(contains(b"\x9b0m"));
+    }
+
+    #[test]
+    fn output_has_no_escapes() {
+        // Bytes that often appear in sequences, to generate more of them than random bytes would.
+        const ALPHABET: &[u8] = b"\x1b\x1b\x07\x18[]P_^X\\;8:m0a \xc2\x85\x9b\x9c\x9d\xe2\x82\xac";
+
+        fn inner(input: Vec<u8>, keep_link_text: bool) -> TestResult {
+            let input = input
+                .into_iter()
+                .map(|byte| {
+                    if byte < 0x80 {
+                        ALPHABET[usize::from(byte) % ALPHABET.len()]
+                    } else {
+                        byte
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let output = strip(&input, keep_link_text);
+            assert!(!contains(&output), "{input:?} was stripped to {output:?}");
+            assert_eq!(strip(&output, keep_link_text), output);
+
+            TestResult::passed()
+        }
+
+        QuickCheck::new()
+            .tests(10000)
+            .quickcheck(inner as fn(Vec<u8>, bool) -> TestResult);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use crate::compiler::prelude::*;
+
+fn array_difference(a: Value, b: Value) -> Resolved {
+    let a = a.try_array()?;
+    let b = b.try_array()?;
+
+    #[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+    let b: HashSet<_> = b.iter().collect();
+    Ok(a.into_iter()
+        .filter(|value| !b.contains(value))
+        .collect::<Vec<_>>()
+        .into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArrayDifference;
+
+impl Function for ArrayDifference {
+    fn identifier(&self) -> &'static str {
+        "array_difference"
+    }
+
+    fn summary(&self) -> &'static str {
+        "remove the elements of an array that are in another array"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the elements of `a` that aren't elements of `b`, in the order they are in
+            `a`. Elements that are in `a` more than once are kept as many times, so use `unique`
+            on the result to remove them.
+
+            Elements are compared the same way as for `array_intersect`.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::ARRAY,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "remove tags",
+            source: r#"array_difference(["env:prod", "team:a", "tier:web"], ["team:a"])"#,
+            result: Ok(r#"["env:prod", "tier:web"]"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+
+        Ok(ArrayDifferenceFn { a, b }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArrayDifferenceFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ArrayDifferenceFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+
+        array_difference(a, b)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::array(Collection::any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        array_difference => ArrayDifference;
+
+        tags {
+            args: func_args![
+                a: value!(["env:prod", "team:a", "tier:web", "region:eu"]),
+                b: value!(["region:eu", "tier:db", "env:prod"]),
+            ],
+            want: Ok(value!(["team:a", "tier:web"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        duplicates_in_a_kept {
+            args: func_args![a: value!([1, 2, 1, 3, 2]), b: value!([3])],
+            want: Ok(value!([1, 2, 1, 2])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        nested_objects {
+            args: func_args![
+                a: value!([{id: 1, tags: ["a"]}, {id: 2, tags: ["b"]}, {id: 3}]),
+                b: value!([{tags: ["b"], id: 2}, {id: 1, tags: ["a", "b"]}]),
+            ],
+            want: Ok(value!([{id: 1, tags: ["a"]}, {id: 3}])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        types_differ {
+            args: func_args![a: value!([1, "1", true]), b: value!([1.0, "true"])],
+            want: Ok(value!([1, "1", true])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        everything_removed {
+            args: func_args![a: value!([1, 1]), b: value!([1])],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+    ];
+}
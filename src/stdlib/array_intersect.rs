@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+
+use crate::compiler::prelude::*;
+
+fn array_intersect(a: Value, b: Value) -> Resolved {
+    let a = a.try_array()?;
+    let b = b.try_array()?;
+
+    #[allow(clippy::mutable_key_type)] // false positive due to bytes::Bytes
+    let b: HashSet<_> = b.iter().collect();
+    Ok(a.into_iter()
+        .filter(|value| b.contains(value))
+        .collect::<Vec<_>>()
+        .into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArrayIntersect;
+
+impl Function for ArrayIntersect {
+    fn identifier(&self) -> &'static str {
+        "array_intersect"
+    }
+
+    fn summary(&self) -> &'static str {
+        "keep the elements of an array that are in another array"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the elements of `a` that are also elements of `b`, in the order they are in
+            `a`. Elements that are in `a` more than once are kept as many times, so use `unique`
+            on the result to remove them.
+
+            Elements are equal if they have the same type and value, including all the elements
+            or fields of arrays and objects, so `1` and `1.0` aren't equal.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::ARRAY,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "common tags",
+            source: r#"array_intersect(["env:prod", "team:a", "tier:web"], ["tier:web", "env:prod"])"#,
+            result: Ok(r#"["env:prod", "tier:web"]"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+
+        Ok(ArrayIntersectFn { a, b }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArrayIntersectFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ArrayIntersectFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+
+        array_intersect(a, b)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::array(Collection::any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        array_intersect => ArrayIntersect;
+
+        tags {
+            args: func_args![
+                a: value!(["env:prod", "team:a", "tier:web", "region:eu"]),
+                b: value!(["region:eu", "tier:db", "env:prod"]),
+            ],
+            want: Ok(value!(["env:prod", "region:eu"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        duplicates_in_a_kept {
+            args: func_args![a: value!([1, 2, 1, 3]), b: value!([1, 1, 3])],
+            want: Ok(value!([1, 1, 3])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        nested_objects {
+            args: func_args![
+                a: value!([{id: 1, tags: ["a"]}, {id: 2, tags: ["b"]}, {id: 3}]),
+                b: value!([{tags: ["b"], id: 2}, {id: 1, tags: ["a", "b"]}]),
+            ],
+            want: Ok(value!([{id: 2, tags: ["b"]}])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        types_differ {
+            args: func_args![a: value!([1, "1", true]), b: value!([1.0, "1", "true"])],
+            want: Ok(value!(["1"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        disjoint {
+            args: func_args![a: value!([1, 2]), b: value!([])],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+    ];
+}
@@ -0,0 +1,134 @@
+use indexmap::IndexSet;
+
+use crate::compiler::prelude::*;
+
+fn array_union(a: Value, b: Value) -> Resolved {
+    let a = a.try_array()?;
+    let b = b.try_array()?;
+
+    let set: IndexSet<_> = a.into_iter().chain(b).collect();
+    Ok(set.into_iter().collect())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ArrayUnion;
+
+impl Function for ArrayUnion {
+    fn identifier(&self) -> &'static str {
+        "array_union"
+    }
+
+    fn summary(&self) -> &'static str {
+        "combine the elements of two arrays without duplicates"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the elements of `a`, followed by the elements of `b` that aren't in `a`, in
+            the order they are in each array. Every element is only returned once, including
+            elements that are in either array more than once, as if by `unique(append(a, b))`.
+
+            Elements are compared the same way as for `array_intersect`.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::ARRAY,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "add tags",
+            source: r#"array_union(["env:prod", "team:a"], ["team:a", "tier:web"])"#,
+            result: Ok(r#"["env:prod", "team:a", "tier:web"]"#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+
+        Ok(ArrayUnionFn { a, b }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ArrayUnionFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ArrayUnionFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+
+        array_union(a, b)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::array(Collection::any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        array_union => ArrayUnion;
+
+        tags {
+            args: func_args![
+                a: value!(["env:prod", "team:a", "tier:web"]),
+                b: value!(["region:eu", "tier:web", "env:prod"]),
+            ],
+            want: Ok(value!(["env:prod", "team:a", "tier:web", "region:eu"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        duplicates_removed {
+            args: func_args![a: value!([1, 2, 1]), b: value!([3, 3, 2])],
+            want: Ok(value!([1, 2, 3])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        nested_objects {
+            args: func_args![
+                a: value!([{id: 1, tags: ["a"]}, {id: 2, tags: ["b"]}]),
+                b: value!([{tags: ["b"], id: 2}, {id: 1, tags: ["a", "b"]}]),
+            ],
+            want: Ok(value!([{id: 1, tags: ["a"]}, {id: 2, tags: ["b"]}, {id: 1, tags: ["a", "b"]}])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        types_differ {
+            args: func_args![a: value!([1]), b: value!([1.0, "1"])],
+            want: Ok(value!([1, 1.0, "1"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        empty {
+            args: func_args![a: value!([]), b: value!([])],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+    ];
+}
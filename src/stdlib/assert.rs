@@ -10,6 +10,7 @@ fn assert(condition: Value, message: Option<Value>, format: Option<String>) -> R
                     message: message.clone(),
                     labels: vec![],
                     notes: vec![Note::UserErrorMessage(message)],
+                    code: None,
                 })
             } else {
                 let message = match format {
@@ -45,6 +46,13 @@ impl Function for Assert {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "message" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -94,7 +102,7 @@ impl FunctionExpression for AssertFn {
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::boolean().fallible()
+        TypeDef::boolean().fallible().impure()
     }
 }
 
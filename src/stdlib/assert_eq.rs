@@ -9,6 +9,7 @@ fn assert_eq(left: Value, right: Value, message: Option<Value>) -> Resolved {
             message: message.clone(),
             labels: vec![],
             notes: vec![Note::UserErrorMessage(message)],
+            code: None,
         })
     } else {
         Err(ExpressionError::from(format!(
@@ -45,6 +46,13 @@ impl Function for AssertEq {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "message" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -103,7 +111,7 @@ impl FunctionExpression for AssertEqFn {
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::boolean().fallible()
+        TypeDef::boolean().fallible().impure()
     }
 }
 
@@ -117,19 +125,19 @@ mod tests {
         pass {
             args: func_args![left: "foo", right: "foo"],
             want: Ok(true),
-            tdef: TypeDef::boolean().fallible(),
+            tdef: TypeDef::boolean().fallible().impure(),
         }
 
         fail {
             args: func_args![left: "foo", right: "bar"],
             want: Err(r#"assertion failed: "foo" == "bar""#),
-            tdef: TypeDef::boolean().fallible(),
+            tdef: TypeDef::boolean().fallible().impure(),
         }
 
         message {
             args: func_args![left: "foo", right: "bar", message: "failure!"],
             want: Err("failure!"),
-            tdef: TypeDef::boolean().fallible(),
+            tdef: TypeDef::boolean().fallible().impure(),
         }
     ];
 }
@@ -44,6 +44,13 @@ impl Function for Ceil {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "precision" => Some(0.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
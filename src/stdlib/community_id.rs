@@ -100,6 +100,15 @@ impl Function for CommunityID {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "source_port" => Some(Value::Null),
+            "destination_port" => Some(Value::Null),
+            "seed" => Some(0.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
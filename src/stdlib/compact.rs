@@ -1,6 +1,7 @@
 use super::util;
 use crate::compiler::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 fn compact(
     recursive: Option<Value>,
     null: Option<Value>,
@@ -8,43 +9,33 @@ fn compact(
     object: Option<Value>,
     array: Option<Value>,
     nullish: Option<Value>,
+    blank_strings: Option<Value>,
+    zero_numbers: Option<Value>,
     value: Value,
 ) -> Resolved {
+    let flag = |value: Option<Value>, default| match value {
+        Some(value) => value.try_boolean(),
+        None => Ok(default),
+    };
     let options = CompactOptions {
-        recursive: match recursive {
-            Some(expr) => expr.try_boolean()?,
-            None => true,
-        },
-
-        null: match null {
-            Some(expr) => expr.try_boolean()?,
-            None => true,
-        },
-
-        string: match string {
-            Some(expr) => expr.try_boolean()?,
-            None => true,
-        },
-
-        object: match object {
-            Some(expr) => expr.try_boolean()?,
-            None => true,
-        },
-
-        array: match array {
-            Some(expr) => expr.try_boolean()?,
-            None => true,
-        },
-
-        nullish: match nullish {
-            Some(expr) => expr.try_boolean()?,
-            None => false,
+        depth: match recursive {
+            // A depth that isn't positive doesn't compact nested values, as `recursive: false`.
+            Some(Value::Integer(depth)) => Some(usize::try_from(depth).unwrap_or(0)),
+            Some(recursive) => (!recursive.try_boolean()?).then_some(0),
+            None => None,
         },
+        null: flag(null, true)?,
+        string: flag(string, true)?,
+        object: flag(object, true)?,
+        array: flag(array, true)?,
+        nullish: flag(nullish, false)?,
+        blank_strings: flag(blank_strings, false)?,
+        zero_numbers: flag(zero_numbers, false)?,
     };
 
     match value {
-        Value::Object(object) => Ok(Value::from(compact_object(object, &options))),
-        Value::Array(arr) => Ok(Value::from(compact_array(arr, &options))),
+        Value::Object(object) => Ok(Value::from(compact_object(object, &options, options.depth))),
+        Value::Array(arr) => Ok(Value::from(compact_array(arr, &options, options.depth))),
         value => Err(ValueError::Expected {
             got: value.kind(),
             expected: Kind::array(Collection::any()) | Kind::object(Collection::any()),
@@ -70,7 +61,7 @@ impl Function for Compact {
             },
             Parameter {
                 keyword: "recursive",
-                kind: kind::BOOLEAN,
+                kind: kind::BOOLEAN | kind::INTEGER,
                 required: false,
             },
             Parameter {
@@ -98,9 +89,33 @@ impl Function for Compact {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "blank_strings",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+            Parameter {
+                keyword: "zero_numbers",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "recursive" => Some(true.into()),
+            "null" => Some(true.into()),
+            "string" => Some(true.into()),
+            "object" => Some(true.into()),
+            "array" => Some(true.into()),
+            "nullish" => Some(false.into()),
+            "blank_strings" => Some(false.into()),
+            "zero_numbers" => Some(false.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -113,6 +128,16 @@ impl Function for Compact {
                 source: r#"compact(["-", "   ", "\n", null, true], nullish: true)"#,
                 result: Ok(r#"[true]"#),
             },
+            Example {
+                title: "blank strings and zero numbers",
+                source: r#"compact({ "a": " ", "b": 0, "c": 0.0, "d": "x", "e": 1 }, blank_strings: true, zero_numbers: true)"#,
+                result: Ok(r#"{ "d": "x", "e": 1 }"#),
+            },
+            Example {
+                title: "recursion depth",
+                source: r#"compact({ "a": { "b": { "c": null }, "d": null } }, recursive: 1)"#,
+                result: Ok(r#"{ "a": { "b": { "c": null } } }"#),
+            },
         ]
     }
 
@@ -129,6 +154,8 @@ impl Function for Compact {
         let object = arguments.optional("object");
         let array = arguments.optional("array");
         let nullish = arguments.optional("nullish");
+        let blank_strings = arguments.optional("blank_strings");
+        let zero_numbers = arguments.optional("zero_numbers");
 
         Ok(CompactFn {
             value,
@@ -138,6 +165,8 @@ impl Function for Compact {
             object,
             array,
             nullish,
+            blank_strings,
+            zero_numbers,
         }
         .as_expr())
     }
@@ -152,27 +181,35 @@ struct CompactFn {
     object: Option<Box<dyn Expression>>,
     array: Option<Box<dyn Expression>>,
     nullish: Option<Box<dyn Expression>>,
+    blank_strings: Option<Box<dyn Expression>>,
+    zero_numbers: Option<Box<dyn Expression>>,
 }
 
 #[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
 struct CompactOptions {
-    recursive: bool,
+    /// How many levels of nested values are compacted, or `None` to compact all of them.
+    depth: Option<usize>,
     null: bool,
     string: bool,
     object: bool,
     array: bool,
     nullish: bool,
+    blank_strings: bool,
+    zero_numbers: bool,
 }
 
 impl Default for CompactOptions {
     fn default() -> Self {
         Self {
-            recursive: true,
+            depth: None,
             null: true,
             string: true,
             object: true,
             array: true,
             nullish: false,
+            blank_strings: false,
+            zero_numbers: false,
         }
     }
 }
@@ -185,10 +222,15 @@ impl CompactOptions {
         }
 
         match value {
-            Value::Bytes(bytes) => self.string && bytes.len() == 0,
+            Value::Bytes(bytes) if bytes.is_empty() => self.string,
+            Value::Bytes(bytes) => {
+                self.blank_strings && String::from_utf8_lossy(bytes).trim().is_empty()
+            }
             Value::Null => self.null,
             Value::Object(object) => self.object && object.is_empty(),
             Value::Array(array) => self.array && array.is_empty(),
+            Value::Integer(int) => self.zero_numbers && *int == 0,
+            Value::Float(float) => self.zero_numbers && *float == 0.0,
             _ => false,
         }
     }
@@ -226,34 +268,65 @@ impl FunctionExpression for CompactFn {
             .as_ref()
             .map(|expr| expr.resolve(ctx))
             .transpose()?;
+        let blank_strings = self
+            .blank_strings
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
+        let zero_numbers = self
+            .zero_numbers
+            .as_ref()
+            .map(|expr| expr.resolve(ctx))
+            .transpose()?;
         let value = self.value.resolve(ctx)?;
 
-        compact(recursive, null, string, object, array, nullish, value)
+        compact(
+            recursive,
+            null,
+            string,
+            object,
+            array,
+            nullish,
+            blank_strings,
+            zero_numbers,
+            value,
+        )
     }
 
     fn type_def(&self, state: &state::TypeState) -> TypeDef {
-        if self.value.type_def(state).is_array() {
-            TypeDef::array(Collection::any())
-        } else {
-            TypeDef::object(Collection::any())
+        let value = self.value.type_def(state);
+
+        let mut kind = Kind::never();
+        if value.contains_array() {
+            kind.add_array(Collection::any());
+        }
+        if value.contains_object() || kind.is_never() {
+            kind.add_object(Collection::any());
         }
+
+        TypeDef::from(kind)
     }
 }
 
 /// Compact the value if we are recursing - otherwise, just return the value untouched.
-fn recurse_compact(value: Value, options: &CompactOptions) -> Value {
+fn recurse_compact(value: Value, options: &CompactOptions, depth: Option<usize>) -> Value {
+    if depth == Some(0) {
+        return value;
+    }
+    let depth = depth.map(|depth| depth - 1);
+
     match value {
-        Value::Array(array) if options.recursive => Value::from(compact_array(array, options)),
-        Value::Object(object) if options.recursive => Value::from(compact_object(object, options)),
+        Value::Array(array) => Value::from(compact_array(array, options, depth)),
+        Value::Object(object) => Value::from(compact_object(object, options, depth)),
         _ => value,
     }
 }
 
-fn compact_object(object: ObjectMap, options: &CompactOptions) -> ObjectMap {
+fn compact_object(object: ObjectMap, options: &CompactOptions, depth: Option<usize>) -> ObjectMap {
     object
         .into_iter()
         .filter_map(|(key, value)| {
-            let value = recurse_compact(value, options);
+            let value = recurse_compact(value, options, depth);
             if options.is_empty(&value) {
                 None
             } else {
@@ -263,11 +336,11 @@ fn compact_object(object: ObjectMap, options: &CompactOptions) -> ObjectMap {
         .collect()
 }
 
-fn compact_array(array: Vec<Value>, options: &CompactOptions) -> Vec<Value> {
+fn compact_array(array: Vec<Value>, options: &CompactOptions, depth: Option<usize>) -> Vec<Value> {
     array
         .into_iter()
         .filter_map(|value| {
-            let value = recurse_compact(value, options);
+            let value = recurse_compact(value, options, depth);
             if options.is_empty(&value) {
                 None
             } else {
@@ -280,7 +353,7 @@ fn compact_array(array: Vec<Value>, options: &CompactOptions) -> Vec<Value> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::btreemap;
+    use crate::{btreemap, value};
 
     #[test]
     fn test_compacted_array() {
@@ -338,7 +411,7 @@ mod test {
         ];
 
         for (expected, original, options) in cases {
-            assert_eq!(expected, compact_array(original, &options))
+            assert_eq!(expected, compact_array(original, &options, options.depth))
         }
     }
 
@@ -413,7 +486,7 @@ mod test {
                     (KeyString::from("key3"), Value::from(2)),
                 ]),
                 CompactOptions {
-                    recursive: false,
+                    depth: Some(0),
                     ..Default::default()
                 },
             ),
@@ -448,7 +521,86 @@ mod test {
         ];
 
         for (expected, original, options) in cases {
-            assert_eq!(expected, compact_object(original, &options))
+            assert_eq!(expected, compact_object(original, &options, options.depth))
+        }
+    }
+
+    fn mixed() -> Value {
+        value!({
+            "null": null,
+            "empty": "",
+            "blank": " \t",
+            "dash": "-",
+            "zero": 0,
+            "zero_float": 0.0,
+            "text": "x",
+            "number": 1,
+            "flag": false,
+            "empty_array": [],
+            "empty_object": {},
+            "nested": {
+                "blank": " ",
+                "zeros": [0, 0.0, {"zero": 0}],
+                "deeper": {"null": null, "empty": "", "values": [null, "", (-1), {"x": []}]},
+            },
+            "array": [null, "", " ", 0, [[]], [{"a": null}], "y"],
+        })
+    }
+
+    /// Returns the scalars of `value` that compacting it with `options` keeps.
+    fn kept_scalars(value: &Value, options: &CompactOptions, scalars: &mut Vec<Value>) {
+        match value {
+            Value::Object(object) => object
+                .values()
+                .for_each(|value| kept_scalars(value, options, scalars)),
+            Value::Array(array) => array
+                .iter()
+                .for_each(|value| kept_scalars(value, options, scalars)),
+            value if !options.is_empty(value) => scalars.push(value.clone()),
+            _ => {}
+        }
+    }
+
+    /// Asserts that no value of `value` is empty according to `options`.
+    fn assert_compacted(value: &Value, options: &CompactOptions) {
+        let children: Vec<&Value> = match value {
+            Value::Object(object) => object.values().collect(),
+            Value::Array(array) => array.iter().collect(),
+            _ => return,
+        };
+        for child in children {
+            assert!(!options.is_empty(child), "{child} left in {value}");
+            assert_compacted(child, options);
+        }
+    }
+
+    #[test]
+    fn every_flag_combination() {
+        for flags in 0..1 << 7 {
+            let flag = |bit: u32| flags & (1 << bit) != 0;
+            let options = CompactOptions {
+                depth: None,
+                null: flag(0),
+                string: flag(1),
+                object: flag(2),
+                array: flag(3),
+                nullish: flag(4),
+                blank_strings: flag(5),
+                zero_numbers: flag(6),
+            };
+            let compacted = Value::from(compact_object(
+                mixed().try_object().unwrap(),
+                &options,
+                None,
+            ));
+
+            assert_compacted(&compacted, &options);
+
+            let mut scalars = vec![];
+            kept_scalars(&mixed(), &options, &mut scalars);
+            let mut kept = vec![];
+            kept_scalars(&compacted, &options, &mut kept);
+            assert_eq!(scalars, kept, "{options:?}");
         }
     }
 
@@ -479,5 +631,85 @@ mod test {
             want: Ok(Value::Object(ObjectMap::from([(KeyString::from("key2"), Value::from(1))]))),
             tdef: TypeDef::object(Collection::any()),
         }
+
+        blank_strings {
+            args: func_args![value: value!(["", " ", "\t\n", "-", "a"]), blank_strings: true],
+            want: Ok(value!(["-", "a"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        blank_strings_keep_empty_strings {
+            args: func_args![value: value!(["", " ", "a"]), string: false, blank_strings: true],
+            want: Ok(value!(["", "a"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        zero_numbers {
+            args: func_args![value: value!({"a": 0, "b": 0.0, "c": (-0.0), "d": 1, "e": "0", "f": false}), zero_numbers: true],
+            want: Ok(value!({"d": 1, "e": "0", "f": false})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        zero_numbers_cascade {
+            args: func_args![value: value!({"a": {"b": [0]}, "c": 2}), zero_numbers: true],
+            want: Ok(value!({"c": 2})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        not_recursive {
+            args: func_args![value: value!({"a": {"b": null}, "c": null}), recursive: false],
+            want: Ok(value!({"a": {"b": null}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        recursion_depth {
+            args: func_args![value: value!([[null, [null, [null]]], null]), recursive: 1],
+            want: Ok(value!([[[null, [null]]]])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        recursion_depth_empties_parent {
+            args: func_args![value: value!({"a": {"b": {"c": null}}}), recursive: 2],
+            want: Ok(value!({})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        negative_recursion_depth {
+            args: func_args![value: value!({"a": {"b": null}}), recursive: (-1)],
+            want: Ok(value!({"a": {"b": null}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        mixed_all_flags {
+            args: func_args![value: mixed(), nullish: true, blank_strings: true, zero_numbers: true],
+            want: Ok(value!({
+                "flag": false,
+                "nested": {"deeper": {"values": [(-1)]}},
+                "number": 1,
+                "text": "x",
+                "array": ["y"],
+            })),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        mixed_defaults_depth {
+            args: func_args![value: mixed(), recursive: 1],
+            want: Ok(value!({
+                "blank": " \t",
+                "dash": "-",
+                "zero": 0,
+                "zero_float": 0.0,
+                "text": "x",
+                "number": 1,
+                "flag": false,
+                "nested": {
+                    "blank": " ",
+                    "zeros": [0, 0.0, {"zero": 0}],
+                    "deeper": {"null": null, "empty": "", "values": [null, "", (-1), {"x": []}]},
+                },
+                "array": [" ", 0, [[]], [{"a": null}], "y"],
+            })),
+            tdef: TypeDef::object(Collection::any()),
+        }
     ];
 }
@@ -47,6 +47,13 @@ impl Function for ContainsAll {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "case_sensitive" => Some(true.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -0,0 +1,119 @@
+use crate::compiler::prelude::*;
+use crate::stdlib::ansi;
+
+fn contains_ansi(value: Value) -> Resolved {
+    let bytes = value.try_bytes()?;
+    Ok(ansi::contains(&bytes).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ContainsAnsi;
+
+impl Function for ContainsAnsi {
+    fn identifier(&self) -> &'static str {
+        "contains_ansi"
+    }
+
+    fn summary(&self) -> &'static str {
+        "determine whether a string contains ANSI escape sequences"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Returns `true` if the provided `value` contains anything that `strip_ansi_escape_codes`
+            would remove: an `ESC` character or a C1 control character, which start ANSI escape
+            sequences. This is cheaper than stripping, so it can be used to skip values that don't
+            need it.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "colors",
+                source: r#"contains_ansi(decode_percent("%1B[1;31mred%1B[0m"))"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "plain text",
+                source: r#"contains_ansi("café")"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(ContainsAnsiFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ContainsAnsiFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ContainsAnsiFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        contains_ansi(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::boolean().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        contains_ansi => ContainsAnsi;
+
+        plain {
+            args: func_args![value: "foo bar"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        color {
+            args: func_args![value: "\x1b[46mfoo\x1b[0m bar"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        window_title {
+            args: func_args![value: "\x1b]0;title\x07foo bar"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        c1 {
+            args: func_args![value: "foo\u{85}bar"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        non_ascii {
+            args: func_args![value: "caf\u{e9} \u{20ac}"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,109 @@
+use crate::compiler::prelude::*;
+use crc::{Crc, CRC_32_ISO_HDLC};
+
+/// CRC-32/ISO-HDLC, as used by zlib, gzip and PNG.
+const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+
+fn crc32(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    Ok(Value::Integer(i64::from(CRC32.checksum(&value))))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32;
+
+impl Function for Crc32 {
+    fn identifier(&self) -> &'static str {
+        "crc32"
+    }
+
+    fn summary(&self) -> &'static str {
+        "calculate the CRC-32 checksum of a string"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Calculates the CRC-32 checksum of the provided `value` and returns it as an integer.
+
+            The algorithm is pinned to CRC-32/ISO-HDLC (the variant used by zlib, gzip and PNG):
+            polynomial `0x04C11DB7`, reflected input and output, initial value and final XOR
+            `0xFFFFFFFF`. The checksum of `"123456789"` is `0xCBF43926`.
+        "#}
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "crc32",
+            source: r#"crc32("foobar")"#,
+            result: Ok("2666930069"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Crc32Fn { value }.as_expr())
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Crc32Fn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for Crc32Fn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        crc32(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        crc32 => Crc32;
+
+        empty {
+             args: func_args![value: ""],
+             want: Ok(0_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        check {
+             args: func_args![value: "123456789"],
+             want: Ok(0xCBF4_3926_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        foobar {
+             args: func_args![value: "foobar"],
+             want: Ok(2_666_930_069_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        quick_brown_fox {
+             args: func_args![value: "The quick brown fox jumps over the lazy dog"],
+             want: Ok(1_095_738_169_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
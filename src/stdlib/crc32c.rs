@@ -0,0 +1,110 @@
+use crate::compiler::prelude::*;
+use crc::{Crc, CRC_32_ISCSI};
+
+/// CRC-32C (Castagnoli), as used by iSCSI, SCTP and ext4.
+const CRC32C: Crc<u32> = Crc::<u32>::new(&CRC_32_ISCSI);
+
+fn crc32c(value: Value) -> Resolved {
+    let value = value.try_bytes()?;
+    Ok(Value::Integer(i64::from(CRC32C.checksum(&value))))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Crc32c;
+
+impl Function for Crc32c {
+    fn identifier(&self) -> &'static str {
+        "crc32c"
+    }
+
+    fn summary(&self) -> &'static str {
+        "calculate the CRC-32C checksum of a string"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Calculates the CRC-32C (Castagnoli) checksum of the provided `value` and returns it as
+            an integer.
+
+            The algorithm is pinned to CRC-32/ISCSI: polynomial `0x1EDC6F41`, reflected input and
+            output, initial value and final XOR `0xFFFFFFFF`. The checksum of `"123456789"` is
+            `0xE3069283`.
+        "#}
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "crc32c",
+            source: r#"crc32c("foobar")"#,
+            result: Ok("224353407"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(Crc32cFn { value }.as_expr())
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Crc32cFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for Crc32cFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        crc32c(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        crc32c => Crc32c;
+
+        empty {
+             args: func_args![value: ""],
+             want: Ok(0_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        check {
+             args: func_args![value: "123456789"],
+             want: Ok(0xE306_9283_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        foobar {
+             args: func_args![value: "foobar"],
+             want: Ok(224_353_407_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+
+        quick_brown_fox {
+             args: func_args![value: "The quick brown fox jumps over the lazy dog"],
+             want: Ok(576_848_900_i64),
+             tdef: TypeDef::integer().infallible(),
+        }
+    ];
+}
@@ -50,6 +50,13 @@ impl Function for DecodeBase64 {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "charset" => Some("standard".into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
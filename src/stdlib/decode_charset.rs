@@ -0,0 +1,299 @@
+use std::str::FromStr;
+
+use encoding_rs::{DecoderResult, Encoding};
+
+use crate::compiler::prelude::*;
+
+fn decode_charset(value: Value, from: Value, unmappable: Unmappable) -> Resolved {
+    let bytes = value.try_bytes()?;
+    let encoding = resolve_encoding(from)?;
+
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut output = String::new();
+    let mut input = &bytes[..];
+    loop {
+        reserve(&mut output, decoder.max_utf8_buffer_length(input.len()));
+        let (result, read) = decoder.decode_to_string_without_replacement(input, &mut output, true);
+        input = &input[read..];
+
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => {}
+            DecoderResult::Malformed(malformed, consumed) => match unmappable {
+                Unmappable::Error => {
+                    let offset =
+                        bytes.len() - input.len() - usize::from(consumed) - usize::from(malformed);
+                    return Err(format!(
+                        "invalid {} byte sequence at offset {offset}",
+                        encoding.name()
+                    )
+                    .into());
+                }
+                Unmappable::Replace => output.push(char::REPLACEMENT_CHARACTER),
+                Unmappable::Skip => {}
+            },
+        }
+    }
+
+    Ok(output.into())
+}
+
+/// Makes room for at least `additional` more bytes, or one more character if the length is
+/// unknown.
+fn reserve(output: &mut String, additional: Option<usize>) {
+    output.reserve(additional.unwrap_or(4));
+}
+
+/// Looks up an encoding by one of its labels, as defined by the WHATWG Encoding Standard.
+pub(crate) fn resolve_encoding(
+    label: Value,
+) -> std::result::Result<&'static Encoding, ExpressionError> {
+    let label = label.try_bytes()?;
+    Encoding::for_label(&label)
+        .ok_or_else(|| format!("unknown charset: {}", String::from_utf8_lossy(&label)).into())
+}
+
+/// Controls what happens to input that can't be converted to or from the charset.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Unmappable {
+    /// Fail the conversion.
+    #[default]
+    Error,
+
+    /// Replace it with `U+FFFD` when decoding, or `?` when encoding.
+    Replace,
+
+    /// Leave it out of the output.
+    Skip,
+}
+
+impl Unmappable {
+    pub(crate) fn all_value() -> Vec<Value> {
+        use Unmappable::{Error, Replace, Skip};
+
+        vec![Error, Replace, Skip]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    pub(crate) const fn as_str(self) -> &'static str {
+        use Unmappable::{Error, Replace, Skip};
+
+        match self {
+            Error => "error",
+            Replace => "replace",
+            Skip => "skip",
+        }
+    }
+
+    pub(crate) fn from_arguments(
+        arguments: &ArgumentList,
+        state: &TypeState,
+    ) -> std::result::Result<Self, function::Error> {
+        Ok(arguments
+            .optional_enum("unmappable", &Self::all_value(), state)?
+            .map(|s| {
+                Self::from_str(&s.try_bytes_utf8_lossy().expect("unmappable not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default())
+    }
+}
+
+impl FromStr for Unmappable {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Unmappable::{Error, Replace, Skip};
+
+        match s {
+            "error" => Ok(Error),
+            "replace" => Ok(Replace),
+            "skip" => Ok(Skip),
+            _ => Err("unmappable not recognized"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeCharset;
+
+impl Function for DecodeCharset {
+    fn identifier(&self) -> &'static str {
+        "decode_charset"
+    }
+
+    fn summary(&self) -> &'static str {
+        "decode text from a charset to UTF-8"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Decodes the provided `value`, which is text in the charset named by `from`, to a UTF-8
+            string.
+
+            Charsets are named by their labels in the WHATWG Encoding Standard, such as
+            `"windows-1252"`, `"shift_jis"` or `"utf-16le"`, ignoring case. As in web browsers,
+            `"latin1"` and `"iso-8859-1"` are decoded as `"windows-1252"`, which is the same except
+            for bytes `0x80` to `0x9F`.
+
+            Bytes that aren't valid in the charset fail the function by default. Set `unmappable`
+            to `"replace"` to replace them with `U+FFFD`, or to `"skip"` to leave them out.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "from",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "unmappable",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "unmappable" => Some(Unmappable::default().as_str().into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "latin-1",
+                source: r#"decode_charset!(decode_base64!("Y2Fm6Q=="), "iso-8859-1")"#,
+                result: Ok("café"),
+            },
+            Example {
+                title: "shift_jis",
+                source: r#"decode_charset!(decode_base64!("k/qWe4zq"), "shift_jis")"#,
+                result: Ok("日本語"),
+            },
+            Example {
+                title: "replace invalid bytes",
+                source: r#"decode_charset!(decode_base64!("gX8="), "shift_jis", unmappable: "replace")"#,
+                result: Ok("\u{FFFD}\u{7F}"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let from = arguments.required("from");
+        let unmappable = Unmappable::from_arguments(&arguments, state)?;
+
+        Ok(DecodeCharsetFn {
+            value,
+            from,
+            unmappable,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeCharsetFn {
+    value: Box<dyn Expression>,
+    from: Box<dyn Expression>,
+    unmappable: Unmappable,
+}
+
+impl FunctionExpression for DecodeCharsetFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let from = self.from.resolve(ctx)?;
+
+        decode_charset(value, from, self.unmappable)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        decode_charset => DecodeCharset;
+
+        latin1 {
+            args: func_args![value: Bytes::from_static(b"Cr\xe8me br\xfbl\xe9e \xe0 l'\xe9rable"), from: "latin1"],
+            want: Ok("Crème brûlée à l'érable"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        windows_1252 {
+            args: func_args![value: Bytes::from_static(b"\x93quoted\x94 \x80"), from: "windows-1252"],
+            want: Ok("\u{201C}quoted\u{201D} \u{20AC}"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        label_case_and_whitespace {
+            args: func_args![value: Bytes::from_static(b"\xe9"), from: " ISO-8859-1 "],
+            want: Ok("é"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        shift_jis {
+            args: func_args![value: Bytes::from_static(b"\x93\xfa\x96\x7b\x8c\xea"), from: "shift_jis"],
+            want: Ok("日本語"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        utf_16le {
+            args: func_args![value: Bytes::from_static(b"h\x00\xe9\x00"), from: "utf-16le"],
+            want: Ok("hé"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_error {
+            args: func_args![value: Bytes::from_static(b"ok\x81\x7f"), from: "shift_jis"],
+            want: Err("invalid Shift_JIS byte sequence at offset 2"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_replace {
+            args: func_args![value: Bytes::from_static(b"ok\x81\x7f"), from: "shift_jis", unmappable: "replace"],
+            want: Ok("ok\u{FFFD}\u{7F}"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_skip {
+            args: func_args![value: Bytes::from_static(b"ok\x81\x7f"), from: "shift_jis", unmappable: "skip"],
+            want: Ok("ok\u{7F}"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        truncated {
+            args: func_args![value: Bytes::from_static(b"ok\x93"), from: "shift_jis"],
+            want: Err("invalid Shift_JIS byte sequence at offset 2"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unknown_charset {
+            args: func_args![value: "foo", from: "klingon"],
+            want: Err("unknown charset: klingon"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -1,16 +1,28 @@
 use crate::compiler::prelude::*;
-use flate2::read::MultiGzDecoder;
+use crate::stdlib::util::{decompress_members, resolve_max_size};
+use flate2::bufread::GzDecoder;
 use std::io::Read;
 
-fn decode_gzip(value: Value) -> Resolved {
+/// Gzip members start with the `1f 8b` magic bytes.
+fn is_member(input: &[u8]) -> bool {
+    input.starts_with(&[0x1f, 0x8b])
+}
+
+fn decode_gzip(value: Value, max_size: Option<usize>) -> Resolved {
     let value = value.try_bytes()?;
-    let mut buf = Vec::new();
-    let result = MultiGzDecoder::new(std::io::Cursor::new(value)).read_to_end(&mut buf);
 
-    match result {
-        Ok(_) => Ok(Value::Bytes(buf.into())),
-        Err(_) => Err("unable to decode value with Gzip decoder".into()),
-    }
+    decompress_members(
+        "Gzip",
+        &value,
+        max_size,
+        is_member,
+        |input, limit, output| {
+            let mut decoder = GzDecoder::new(*input);
+            let result = decoder.by_ref().take(limit).read_to_end(output);
+            *input = decoder.into_inner();
+            result.map(|_| ())
+        },
+    )
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -36,29 +48,46 @@ impl Function for DecodeGzip {
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
 
-        Ok(DecodeGzipFn { value }.as_expr())
+        Ok(DecodeGzipFn { value, max_size }.as_expr())
     }
 
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            kind: kind::BYTES,
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "max_size" => Some(Value::Null),
+            _ => None,
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 struct DecodeGzipFn {
     value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for DecodeGzipFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
+        let max_size = resolve_max_size(self.max_size.as_deref(), ctx)?;
 
-        decode_gzip(value)
+        decode_gzip(value, max_size)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -70,6 +99,7 @@ impl FunctionExpression for DecodeGzipFn {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stdlib::util::tests::{concatenated_members, trailing_data, truncated};
     use crate::value;
     use flate2::read::GzEncoder;
     use nom::AsBytes;
@@ -96,5 +126,47 @@ mod tests {
             want: Err("unable to decode value with Gzip decoder"),
             tdef: TypeDef::bytes().fallible(),
         }
+
+        concatenated_members {
+            args: func_args![value: concatenated_members(get_encoded_bytes)],
+            want: Ok(value!(b"first second")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        trailing_data {
+            args: func_args![value: trailing_data(get_encoded_bytes)],
+            want: Err("unable to decode value with Gzip decoder: trailing data after the compressed stream"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        truncated {
+            args: func_args![value: truncated(get_encoded_bytes)],
+            want: Err("unable to decode value with Gzip decoder: stream is truncated"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        within_max_size {
+            args: func_args![value: value!(get_encoded_bytes("sample").as_bytes()), max_size: 6],
+            want: Ok(value!(b"sample")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        exceeds_max_size {
+            args: func_args![value: value!(get_encoded_bytes(&"a".repeat(100_000)).as_bytes()), max_size: 1024],
+            want: Err("decoded value is larger than max_size of 1024 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        members_exceed_max_size {
+            args: func_args![value: concatenated_members(get_encoded_bytes), max_size: 8],
+            want: Err("decoded value is larger than max_size of 8 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        negative_max_size {
+            args: func_args![value: value!(get_encoded_bytes("sample").as_bytes()), max_size: -1],
+            want: Err("max_size must not be negative"),
+            tdef: TypeDef::bytes().fallible(),
+        }
     ];
 }
@@ -0,0 +1,149 @@
+use crate::compiler::prelude::*;
+
+use super::encode_idna::{config, invalid_domain};
+
+fn decode_idna(value: &Value, strict: bool) -> Resolved {
+    let domain = value.try_bytes_utf8_lossy()?;
+    let to_unicode = |domain: &str| config(strict).to_unicode(domain);
+
+    match to_unicode(&domain) {
+        (decoded, Ok(())) => Ok(decoded.into()),
+        (_, Err(_)) => Err(invalid_domain(&domain, |label| to_unicode(label).1)),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeIdna;
+
+impl Function for DecodeIdna {
+    fn identifier(&self) -> &'static str {
+        "decode_idna"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "strict",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "strict" => Some(true.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "decode domain",
+                source: r#"decode_idna!("xn--bcher-kva.example")"#,
+                result: Ok(r#""bücher.example""#),
+            },
+            Example {
+                title: "invalid punycode",
+                source: r#"decode_idna!("www.xn--99999999999999999.example")"#,
+                result: Err(
+                    r#"function call error for "decode_idna" at (0:49): invalid label "xn--99999999999999999""#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let strict = arguments.optional("strict").unwrap_or_else(|| expr!(true));
+
+        Ok(DecodeIdnaFn { value, strict }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeIdnaFn {
+    value: Box<dyn Expression>,
+    strict: Box<dyn Expression>,
+}
+
+impl FunctionExpression for DecodeIdnaFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let strict = self.strict.resolve(ctx)?.try_boolean()?;
+
+        decode_idna(&value, strict)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        decode_idna => DecodeIdna;
+
+        ascii {
+            args: func_args![value: "www.example.com"],
+            want: Ok("www.example.com"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        punycode {
+            args: func_args![value: "xn--bcher-kva.example"],
+            want: Ok("bücher.example"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        mixed_labels {
+            args: func_args![value: "mail.xn--mnchen-3ya.DE"],
+            want: Ok("mail.münchen.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unicode_input {
+            args: func_args![value: "日本語。ＪＰ"],
+            want: Ok("日本語.jp"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        deviation_non_transitional {
+            args: func_args![value: "xn--fa-hia.de"],
+            want: Ok("faß.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        deviation_transitional {
+            args: func_args![value: "faß.de", strict: false],
+            want: Ok("fass.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_punycode {
+            args: func_args![value: "www.xn--99999999999999999.example"],
+            want: Err(r#"invalid label "xn--99999999999999999""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        bidi_violation {
+            args: func_args![value: "xn--a-0hc.com"],
+            want: Err(r#"invalid label "xn--a-0hc""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -0,0 +1,254 @@
+use crate::compiler::prelude::*;
+
+use super::decode_mime_q::EncodedWord;
+
+/// Decodes the [RFC 2047] encoded words (`=?charset?encoding?text?=`) of a header.
+///
+/// Whitespace between adjacent encoded words is dropped, so that a text split across several
+/// words is concatenated. Encoded words that can't be decoded, for example because of an unknown
+/// charset or invalid base64, are kept as they are, unless `strict` is set, in which case an
+/// error is returned.
+///
+/// [RFC 2047]: https://datatracker.ietf.org/doc/html/rfc2047
+fn decode_mime_header(value: &str, strict: bool) -> ExpressionResult<String> {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let (text, candidate) = rest.split_at(start);
+
+        let Some((word, len)) = parse_encoded_word(candidate) else {
+            decoded.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_word = false;
+            continue;
+        };
+
+        match word.decode_word() {
+            Ok(word) => {
+                if !after_word || !text.chars().all(char::is_whitespace) {
+                    decoded.push_str(text);
+                }
+                decoded.push_str(&word);
+                after_word = true;
+            }
+            Err(error) if strict => return Err(error),
+            Err(_) => {
+                decoded.push_str(&rest[..start + len]);
+                after_word = false;
+            }
+        }
+
+        rest = &candidate[len..];
+    }
+
+    decoded.push_str(rest);
+
+    Ok(decoded)
+}
+
+/// Parses the encoded word at the start of `input`, returning it and its length.
+fn parse_encoded_word(input: &str) -> Option<(EncodedWord<'_>, usize)> {
+    let inner = input.strip_prefix("=?")?;
+
+    let (charset, inner) = inner.split_once('?')?;
+    let (encoding, inner) = inner.split_once('?')?;
+    let end = inner.find("?=")?;
+    let text = &inner[..end];
+
+    let is_token = |s: &str| !s.is_empty() && !s.contains(char::is_whitespace);
+    if !is_token(charset) || !is_token(encoding) || text.contains(char::is_whitespace) {
+        return None;
+    }
+
+    // RFC 2231 allows a language to follow the charset, as in `=?utf-8*en?q?...?=`.
+    let charset = charset
+        .split_once('*')
+        .map_or(charset, |(charset, _)| charset);
+    let len = input.len() - inner.len() + end + 2;
+
+    Some((
+        EncodedWord {
+            charset: Some(charset),
+            encoding,
+            input: text,
+        },
+        len,
+    ))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DecodeMimeHeader;
+
+impl Function for DecodeMimeHeader {
+    fn identifier(&self) -> &'static str {
+        "decode_mime_header"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "strict",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "strict" => Some(false.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "base64 encoded subject",
+                source: r#"decode_mime_header("=?UTF-8?B?SGVsbG8sIFdvcmxkIQ==?=")"#,
+                result: Ok(r#""Hello, World!""#),
+            },
+            Example {
+                title: "adjacent encoded words",
+                source: r#"decode_mime_header("Re: =?ISO-8859-1?Q?Caf=E9?= =?UTF-8?B?IOKYlQ==?= ok")"#,
+                result: Ok(r#""Re: Café ☕ ok""#),
+            },
+            Example {
+                title: "unknown charset",
+                source: r#"decode_mime_header!("=?x-unknown?Q?hi?=", strict: true)"#,
+                result: Err(
+                    r#"function call error for "decode_mime_header" at (0:55): Unable to decode "x-unknown" value"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let strict = arguments.optional("strict");
+
+        Ok(DecodeMimeHeaderFn { value, strict }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DecodeMimeHeaderFn {
+    value: Box<dyn Expression>,
+    strict: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DecodeMimeHeaderFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let strict = match &self.strict {
+            Some(strict) => strict.resolve(ctx)?.try_boolean()?,
+            None => false,
+        };
+
+        Ok(decode_mime_header(&value.try_bytes_utf8_lossy()?, strict)?.into())
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        // Encoded words that can't be decoded are only an error in strict mode.
+        let lenient = self.strict.as_ref().map_or(Some(false), |strict| {
+            strict
+                .resolve_constant(state)
+                .and_then(|strict| strict.as_boolean())
+        }) == Some(false);
+
+        TypeDef::bytes().maybe_fallible(!lenient)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        decode_mime_header => DecodeMimeHeader;
+
+        utf8_base64_subject {
+            args: func_args![value: "Subject: =?UTF-8?B?8J+OiSBQYXJ0eSB0b25pZ2h0IQ==?="],
+            want: Ok("Subject: 🎉 Party tonight!"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        mixed_header {
+            args: func_args![value: "From: =?iso-8859-1?q?Andr=E9?= Pirard <PIRARD@vm1.ulg.ac.be>, =?utf-8?b?5pel5pys?= <jp@example.com>"],
+            want: Ok("From: André Pirard <PIRARD@vm1.ulg.ac.be>, 日本 <jp@example.com>"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        adjacent_words {
+            args: func_args![value: "(=?ISO-8859-1?Q?a?=  \r\n  =?ISO-8859-1?Q?b?=) (=?ISO-8859-1?Q?a?= b)"],
+            want: Ok("(ab) (a b)"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        word_split_across_charsets {
+            args: func_args![value: "=?utf-8?q?caf=C3?= =?utf-8?q?=A9?= =?windows-1252?Q?=80uro?="],
+            want: Ok("caf\u{fffd}\u{fffd}€uro"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        underscore_and_language {
+            args: func_args![value: "=?UTF-8*en?Q?hello_world?="],
+            want: Ok("hello world"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        unknown_charset_raw {
+            args: func_args![value: "a =?x-unknown?Q?hi?= =?utf-8?q?there?="],
+            want: Ok("a =?x-unknown?Q?hi?= there"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        unknown_charset_strict {
+            args: func_args![value: "a =?x-unknown?Q?hi?=", strict: true],
+            want: Err(r#"Unable to decode "x-unknown" value"#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_base64_raw {
+            args: func_args![value: "=?utf-8?B?not*base64?=", strict: false],
+            want: Ok("=?utf-8?B?not*base64?="),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        invalid_base64_strict {
+            args: func_args![value: "=?utf-8?B?not*base64?=", strict: true],
+            want: Err("Unable to decode base64 value"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_encoding_strict {
+            args: func_args![value: "=?utf-8?X?abc?=", strict: true],
+            want: Err(r#"Invalid encoding: "X""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        not_encoded {
+            args: func_args![value: "Is =? equal to ?= or not? =?utf-8?q?no spaces allowed?="],
+            want: Ok("Is =? equal to ?= or not? =?utf-8?q?no spaces allowed?="),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        empty {
+            args: func_args![value: ""],
+            want: Ok(""),
+            tdef: TypeDef::bytes().infallible(),
+        }
+    ];
+}
@@ -148,14 +148,14 @@ fn parse_internal_q<'a, E: ParseError<&'a str> + ContextError<&'a str>>(
     )(input)
 }
 
-struct EncodedWord<'a> {
-    charset: Option<&'a str>,
-    encoding: &'a str,
-    input: &'a str,
+pub(super) struct EncodedWord<'a> {
+    pub(super) charset: Option<&'a str>,
+    pub(super) encoding: &'a str,
+    pub(super) input: &'a str,
 }
 
 impl<'a> EncodedWord<'a> {
-    fn decode_word(&self) -> Result<String, ExpressionError> {
+    pub(super) fn decode_word(&self) -> Result<String, ExpressionError> {
         // Modified version from https://github.com/staktrace/mailparse/blob/a83d961fe53fd6504d75ee951a0e91dfea03c830/src/header.rs#L39
 
         // Decode
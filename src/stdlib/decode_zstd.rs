@@ -1,14 +1,32 @@
 use crate::compiler::prelude::*;
-use nom::AsBytes;
+use crate::stdlib::util::{decompress_members, resolve_max_size};
+use std::io::Read;
+use zstd::stream::read::Decoder;
+
+/// Zstd frames start with a magic number, and skippable frames with one of 16 others.
+fn is_member(input: &[u8]) -> bool {
+    match input {
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => true,
+        [magic, 0x2a, 0x4d, 0x18, ..] => magic & 0xf0 == 0x50,
+        _ => false,
+    }
+}
 
-fn decode_zstd(value: Value) -> Resolved {
+fn decode_zstd(value: Value, max_size: Option<usize>) -> Resolved {
     let value = value.try_bytes()?;
-    let result = zstd::decode_all(value.as_bytes());
 
-    match result {
-        Ok(decoded_bytes) => Ok(Value::Bytes(decoded_bytes.into())),
-        Err(_) => Err("unable to decode value with Zstd decoder".into()),
-    }
+    decompress_members(
+        "Zstd",
+        &value,
+        max_size,
+        is_member,
+        |input, limit, output| {
+            let mut decoder = Decoder::with_buffer(*input)?.single_frame();
+            let result = decoder.by_ref().take(limit).read_to_end(output);
+            *input = decoder.finish();
+            result.map(|_| ())
+        },
+    )
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -34,29 +52,46 @@ impl Function for DecodeZstd {
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
+        let max_size = arguments.optional("max_size");
 
-        Ok(DecodeZstdFn { value }.as_expr())
+        Ok(DecodeZstdFn { value, max_size }.as_expr())
     }
 
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            kind: kind::BYTES,
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_size",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "max_size" => Some(Value::Null),
+            _ => None,
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 struct DecodeZstdFn {
     value: Box<dyn Expression>,
+    max_size: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for DecodeZstdFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
+        let max_size = resolve_max_size(self.max_size.as_deref(), ctx)?;
 
-        decode_zstd(value)
+        decode_zstd(value, max_size)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -68,6 +103,7 @@ impl FunctionExpression for DecodeZstdFn {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stdlib::util::tests::{concatenated_members, trailing_data, truncated};
     use crate::value;
     use nom::AsBytes;
 
@@ -92,5 +128,47 @@ mod tests {
             want: Err("unable to decode value with Zstd decoder"),
             tdef: TypeDef::bytes().fallible(),
         }
+
+        concatenated_members {
+            args: func_args![value: concatenated_members(get_encoded_bytes)],
+            want: Ok(value!(b"first second")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        trailing_data {
+            args: func_args![value: trailing_data(get_encoded_bytes)],
+            want: Err("unable to decode value with Zstd decoder: trailing data after the compressed stream"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        truncated {
+            args: func_args![value: truncated(get_encoded_bytes)],
+            want: Err("unable to decode value with Zstd decoder: stream is truncated"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        within_max_size {
+            args: func_args![value: value!(get_encoded_bytes("sample").as_bytes()), max_size: 6],
+            want: Ok(value!(b"sample")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        exceeds_max_size {
+            args: func_args![value: value!(get_encoded_bytes(&"a".repeat(100_000)).as_bytes()), max_size: 1024],
+            want: Err("decoded value is larger than max_size of 1024 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        members_exceed_max_size {
+            args: func_args![value: concatenated_members(get_encoded_bytes), max_size: 8],
+            want: Err("decoded value is larger than max_size of 8 bytes"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        negative_max_size {
+            args: func_args![value: value!(get_encoded_bytes("sample").as_bytes()), max_size: -1],
+            want: Err("max_size must not be negative"),
+            tdef: TypeDef::bytes().fallible(),
+        }
     ];
 }
@@ -0,0 +1,197 @@
+use crate::compiler::prelude::*;
+
+fn dedup_adjacent(value: Value, key: Option<Value>) -> Resolved {
+    let array = value.try_array()?;
+
+    let Some(key) = key else {
+        let mut array = array;
+        array.dedup();
+        return Ok(array.into());
+    };
+
+    let key = key.try_bytes_utf8_lossy()?;
+    let mut deduped: Vec<Value> = Vec::with_capacity(array.len());
+    for (index, element) in array.into_iter().enumerate() {
+        let Value::Object(object) = &element else {
+            return Err(format!("expected object at index {index}, got {}", element.kind()).into());
+        };
+
+        // Every element kept so far is an object.
+        let duplicate = deduped
+            .last()
+            .and_then(Value::as_object)
+            .is_some_and(|previous| previous.get(key.as_ref()) == object.get(key.as_ref()));
+        if !duplicate {
+            deduped.push(element);
+        }
+    }
+
+    Ok(deduped.into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DedupAdjacent;
+
+impl Function for DedupAdjacent {
+    fn identifier(&self) -> &'static str {
+        "dedup_adjacent"
+    }
+
+    fn summary(&self) -> &'static str {
+        "remove consecutive duplicate elements from an array"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Removes the elements of `value` that are equal to the element just before them,
+            keeping the first of each run of equal elements. Unlike `unique`, elements that are
+            equal to an earlier element, but not the one just before them, are kept.
+
+            Elements are equal if they have the same type and value, including all the elements
+            or fields of arrays and objects. If `key` is given, every element must be an object,
+            and objects are equal if they have the same value for the `key` field, or both don't
+            have it.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "key" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "dedup adjacent",
+                source: r#"dedup_adjacent([1, 1, 2, 1, 1])"#,
+                result: Ok("[1, 2, 1]"),
+            },
+            Example {
+                title: "by key",
+                source: r#"dedup_adjacent!([{"msg": "retry", "n": 1}, {"msg": "retry", "n": 2}, {"msg": "ok", "n": 3}], key: "msg")"#,
+                result: Ok(r#"[{"msg": "retry", "n": 1}, {"msg": "ok", "n": 3}]"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let key = arguments.optional("key");
+
+        Ok(DedupAdjacentFn { value, key }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DedupAdjacentFn {
+    value: Box<dyn Expression>,
+    key: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DedupAdjacentFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let key = self.key.as_ref().map(|key| key.resolve(ctx)).transpose()?;
+
+        dedup_adjacent(value, key)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::array(Collection::any()).maybe_fallible(self.key.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        dedup_adjacent => DedupAdjacent;
+
+        consecutive_only {
+            args: func_args![value: value!([1, 1, 2, 1])],
+            want: Ok(value!([1, 2, 1])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        runs {
+            args: func_args![value: value!(["a", "a", "a", "b", "b", "a", "c", "c"])],
+            want: Ok(value!(["a", "b", "a", "c"])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        structural_equality {
+            args: func_args![value: value!([[1, {a: 2}], [1, {a: 2}], [1, {a: 3}], null, null])],
+            want: Ok(value!([[1, {a: 2}], [1, {a: 3}], null])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        types_differ {
+            args: func_args![value: value!([1, 1.0, "1", true])],
+            want: Ok(value!([1, 1.0, "1", true])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        empty {
+            args: func_args![value: value!([])],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        by_key {
+            args: func_args![
+                value: value!([
+                    {host: "a", message: "disk full"},
+                    {host: "b", message: "disk full"},
+                    {host: "a", message: "disk ok"},
+                    {host: "c", message: "disk full"},
+                ]),
+                key: "message",
+            ],
+            want: Ok(value!([
+                {host: "a", message: "disk full"},
+                {host: "a", message: "disk ok"},
+                {host: "c", message: "disk full"},
+            ])),
+            tdef: TypeDef::array(Collection::any()).fallible(),
+        }
+
+        by_missing_key {
+            args: func_args![
+                value: value!([{id: 1}, {}, {other: 2}, {id: 1}]),
+                key: "id",
+            ],
+            want: Ok(value!([{id: 1}, {}, {id: 1}])),
+            tdef: TypeDef::array(Collection::any()).fallible(),
+        }
+
+        by_key_not_object {
+            args: func_args![value: value!([{id: 1}, 1]), key: "id"],
+            want: Err("expected object at index 1, got integer"),
+            tdef: TypeDef::array(Collection::any()).fallible(),
+        }
+    ];
+}
@@ -54,6 +54,13 @@ impl Function for Del {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "compact" => Some(false.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -0,0 +1,230 @@
+use crate::compiler::prelude::*;
+use crate::path::{OwnedSegment, OwnedValuePath};
+
+fn dig(value: &Value, path: &[Value]) -> Option<Value> {
+    let mut current = value;
+
+    for segment in path {
+        current = match (segment, current) {
+            (Value::Bytes(field), Value::Object(object)) => {
+                object.get(String::from_utf8_lossy(field).as_ref())?
+            }
+            (Value::Integer(index), Value::Array(array)) => {
+                let index = if *index < 0 {
+                    array.len().checked_sub(index.unsigned_abs() as usize)?
+                } else {
+                    *index as usize
+                };
+
+                array.get(index)?
+            }
+            _ => return None,
+        };
+    }
+
+    match current {
+        Value::Null => None,
+        value => Some(value.clone()),
+    }
+}
+
+/// Converts a static `path` argument into a [`OwnedValuePath`], returning `None` if any of its
+/// segments can never match (i.e. it's neither a string nor an integer).
+fn static_path(path: &Value) -> Option<OwnedValuePath> {
+    let mut value_path = OwnedValuePath::root();
+
+    for segment in path.as_array()? {
+        let segment = match segment {
+            Value::Bytes(field) => OwnedSegment::field(String::from_utf8_lossy(field).as_ref()),
+            Value::Integer(index) => OwnedSegment::index(*index as isize),
+            _ => return None,
+        };
+        value_path.push(segment);
+    }
+
+    Some(value_path)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Dig;
+
+impl Function for Dig {
+    fn identifier(&self) -> &'static str {
+        "dig"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::ANY,
+                required: true,
+            },
+            Parameter {
+                keyword: "path",
+                kind: kind::ARRAY,
+                required: true,
+            },
+            Parameter {
+                keyword: "default",
+                kind: kind::ANY,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "default" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "existing path",
+                source: r#"dig({"a": {"b": [1, {"c": true}]}}, ["a", "b", 1, "c"])"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "negative index",
+                source: r#"dig({"a": [1, 2, 3]}, ["a", -1])"#,
+                result: Ok("3"),
+            },
+            Example {
+                title: "missing path",
+                source: r#"dig({"a": {"b": null}}, ["a", "b", "c"])"#,
+                result: Ok("null"),
+            },
+            Example {
+                title: "wrong intermediate type with default",
+                source: r#"dig({"a": "foo"}, ["a", "b"], default: "none")"#,
+                result: Ok(r#""none""#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let path = arguments.required("path");
+        let default = arguments.optional("default");
+
+        Ok(DigFn {
+            value,
+            path,
+            default,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DigFn {
+    value: Box<dyn Expression>,
+    path: Box<dyn Expression>,
+    default: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for DigFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let path = self.path.resolve(ctx)?.try_array()?;
+
+        match dig(&value, &path) {
+            Some(value) => Ok(value),
+            None => self
+                .default
+                .as_ref()
+                .map_or(Ok(Value::Null), |default| default.resolve(ctx)),
+        }
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        let default = self
+            .default
+            .as_ref()
+            .map_or_else(Kind::null, |default| default.type_def(state).kind().clone());
+
+        let reachable = match self.path.resolve_constant(state) {
+            Some(path) => static_path(&path).map_or_else(Kind::never, |path| {
+                let mut kind = self.value.type_def(state).kind().at_path(&path);
+                kind.remove_undefined();
+                kind.remove_null();
+                kind
+            }),
+            None => Kind::any().without_undefined(),
+        };
+
+        TypeDef::from(reachable.union(default)).infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{btreemap, value};
+
+    test_function![
+        dig => Dig;
+
+        full_path {
+            args: func_args![value: value!({"a": {"b": [1, {"c": "d"}]}}), path: value!(["a", "b", 1, "c"])],
+            want: Ok("d"),
+            tdef: TypeDef::bytes().or_null().infallible(),
+        }
+
+        negative_index {
+            args: func_args![value: value!([1, 2, 3]), path: value!([(-3)])],
+            want: Ok(1),
+            tdef: TypeDef::integer().or_null().infallible(),
+        }
+
+        intermediate_wrong_type {
+            args: func_args![value: value!({"a": {"b": "foo"}}), path: value!(["a", "b", "c"])],
+            want: Ok(value!(null)),
+            tdef: TypeDef::null().infallible(),
+        }
+
+        intermediate_null {
+            args: func_args![value: value!({"a": null}), path: value!(["a", "b"]), default: 0],
+            want: Ok(0),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        index_out_of_bounds {
+            args: func_args![value: value!({"a": [1, 2]}), path: value!(["a", 5]), default: "missing"],
+            want: Ok("missing"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        negative_index_out_of_bounds {
+            args: func_args![value: value!({"a": [1, 2]}), path: value!(["a", (-3)]), default: "missing"],
+            want: Ok("missing"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        index_into_object {
+            args: func_args![value: value!({"a": {"b": 1}}), path: value!(["a", 0])],
+            want: Ok(value!(null)),
+            tdef: TypeDef::null().infallible(),
+        }
+
+        invalid_segment_type {
+            args: func_args![value: value!({"a": 1}), path: value!([true]), default: 2.5],
+            want: Ok(2.5),
+            tdef: TypeDef::float().infallible(),
+        }
+
+        empty_path {
+            args: func_args![value: value!({"a": 1}), path: value!([])],
+            want: Ok(value!({"a": 1})),
+            tdef: TypeDef::object(btreemap! { "a" => Kind::integer() }).or_null().infallible(),
+        }
+    ];
+}
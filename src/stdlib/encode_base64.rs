@@ -54,6 +54,14 @@ impl Function for EncodeBase64 {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "padding" => Some(true.into()),
+            "charset" => Some("standard".into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -158,4 +166,14 @@ mod test {
             tdef: TypeDef::bytes().infallible(),
         }
     ];
+
+    #[test]
+    fn binary_value() {
+        // not valid UTF-8: a lone continuation byte, an unassigned byte and a truncated sequence
+        let binary = Value::Bytes(Bytes::from_static(b"\x80\xff\x00\xe2\x82"));
+        assert!(std::str::from_utf8(binary.as_bytes().unwrap()).is_err());
+
+        let encoded = encode_base64(binary, None, None).unwrap();
+        assert_eq!(encoded, value!("gP8A4oI="));
+    }
 }
@@ -0,0 +1,256 @@
+use encoding_rs::{EncoderResult, UTF_16BE, UTF_16LE};
+
+use crate::compiler::prelude::*;
+use crate::stdlib::decode_charset::{resolve_encoding, Unmappable};
+
+fn encode_charset(value: &Value, to: Value, unmappable: Unmappable) -> Resolved {
+    let string = value.try_bytes_utf8_lossy()?;
+    let encoding = resolve_encoding(to)?;
+
+    // `encoding_rs` only encodes to charsets that web pages can be submitted in, so it encodes
+    // UTF-16 as UTF-8. UTF-16 can represent every character, so it's encoded directly instead.
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let units = string.encode_utf16();
+        let bytes: Vec<u8> = if encoding == UTF_16LE {
+            units.flat_map(u16::to_le_bytes).collect()
+        } else {
+            units.flat_map(u16::to_be_bytes).collect()
+        };
+        return Ok(Value::Bytes(bytes.into()));
+    }
+    if encoding.output_encoding() != encoding {
+        return Err(format!("can't encode to {}", encoding.name()).into());
+    }
+
+    let mut encoder = encoding.new_encoder();
+    let mut output = Vec::new();
+    let mut input = &string[..];
+    loop {
+        reserve(
+            &mut output,
+            encoder.max_buffer_length_from_utf8_without_replacement(input.len()),
+        );
+        let (result, read) =
+            encoder.encode_from_utf8_to_vec_without_replacement(input, &mut output, true);
+        input = &input[read..];
+
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(char) => match unmappable {
+                Unmappable::Error => {
+                    return Err(format!(
+                        "character {char:?} can't be encoded to {}",
+                        encoding.name()
+                    )
+                    .into());
+                }
+                Unmappable::Replace => {
+                    // The replacement goes through the encoder too, since stateful charsets such
+                    // as ISO-2022-JP may need to switch back to ASCII first.
+                    reserve(
+                        &mut output,
+                        encoder.max_buffer_length_from_utf8_without_replacement(1),
+                    );
+                    let (result, _) = encoder.encode_from_utf8_to_vec_without_replacement(
+                        "?",
+                        &mut output,
+                        false,
+                    );
+                    debug_assert!(matches!(result, EncoderResult::InputEmpty));
+                }
+                Unmappable::Skip => {}
+            },
+        }
+    }
+
+    Ok(Value::Bytes(output.into()))
+}
+
+/// Makes room for at least `additional` more bytes, or a few if the length is unknown.
+fn reserve(output: &mut Vec<u8>, additional: Option<usize>) {
+    output.reserve(additional.unwrap_or(8));
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeCharset;
+
+impl Function for EncodeCharset {
+    fn identifier(&self) -> &'static str {
+        "encode_charset"
+    }
+
+    fn summary(&self) -> &'static str {
+        "encode UTF-8 text to a charset"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Encodes the provided `value`, a UTF-8 string, as text in the charset named by `to`.
+            Any invalid UTF-8 in `value` is first replaced with `U+FFFD`.
+
+            Charsets are named the same way as for `decode_charset`. The `"replacement"` charset,
+            and charsets only supported for decoding, such as `"iso-2022-kr"`, can't be encoded
+            to.
+
+            Characters that the charset can't represent fail the function by default. Set
+            `unmappable` to `"replace"` to replace them with `?`, or to `"skip"` to leave them
+            out.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "to",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "unmappable",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "unmappable" => Some(Unmappable::default().as_str().into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "latin-1",
+                source: r#"encode_base64(encode_charset!("café", "iso-8859-1"))"#,
+                result: Ok("Y2Fm6Q=="),
+            },
+            Example {
+                title: "replace unmappable characters",
+                source: r#"encode_base64(encode_charset!("café ☕", "iso-8859-1", unmappable: "replace"))"#,
+                result: Ok("Y2Fm6SA/"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let to = arguments.required("to");
+        let unmappable = Unmappable::from_arguments(&arguments, state)?;
+
+        Ok(EncodeCharsetFn {
+            value,
+            to,
+            unmappable,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeCharsetFn {
+    value: Box<dyn Expression>,
+    to: Box<dyn Expression>,
+    unmappable: Unmappable,
+}
+
+impl FunctionExpression for EncodeCharsetFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let to = self.to.resolve(ctx)?;
+
+        encode_charset(&value, to, self.unmappable)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encode_charset => EncodeCharset;
+
+        latin1 {
+            args: func_args![value: "Crème brûlée", to: "latin1"],
+            want: Ok(Bytes::from_static(b"Cr\xe8me br\xfbl\xe9e")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        windows_1252 {
+            args: func_args![value: "\u{201C}quoted\u{201D} \u{20AC}", to: "windows-1252"],
+            want: Ok(Bytes::from_static(b"\x93quoted\x94 \x80")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        shift_jis {
+            args: func_args![value: "日本語", to: "shift_jis"],
+            want: Ok(Bytes::from_static(b"\x93\xfa\x96\x7b\x8c\xea")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        iso_2022_jp_replace {
+            args: func_args![value: "日☕本", to: "iso-2022-jp", unmappable: "replace"],
+            want: Ok(Bytes::from_static(b"\x1b$BF|\x1b(B?\x1b$BK\\\x1b(B")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        utf_16le {
+            args: func_args![value: "hé", to: "utf-16le"],
+            want: Ok(Bytes::from_static(b"h\x00\xe9\x00")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        utf_16be {
+            args: func_args![value: "hé", to: "utf-16be"],
+            want: Ok(Bytes::from_static(b"\x00h\x00\xe9")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unmappable_error {
+            args: func_args![value: "café ☕", to: "latin1"],
+            want: Err("character '☕' can't be encoded to windows-1252"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unmappable_replace {
+            args: func_args![value: "café ☕", to: "latin1", unmappable: "replace"],
+            want: Ok(Bytes::from_static(b"caf\xe9 ?")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unmappable_skip {
+            args: func_args![value: "café ☕!", to: "latin1", unmappable: "skip"],
+            want: Ok(Bytes::from_static(b"caf\xe9 !")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        decode_only_charset {
+            args: func_args![value: "foo", to: "iso-2022-kr"],
+            want: Err("can't encode to replacement"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        unknown_charset {
+            args: func_args![value: "foo", to: "klingon"],
+            want: Err("unknown charset: klingon"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
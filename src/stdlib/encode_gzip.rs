@@ -75,6 +75,13 @@ impl Function for EncodeGzip {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "compression_level" => Some(flate2::Compression::default().level().into()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
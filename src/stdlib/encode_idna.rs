@@ -0,0 +1,210 @@
+use crate::compiler::prelude::*;
+
+/// Characters UTS-46 maps to the label separator.
+const SEPARATORS: [char; 4] = ['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'];
+
+/// Non-transitional processing keeps deviation characters such as `ß` and `ς` (IDNA 2008), while
+/// transitional processing maps them (`ß` to `ss`) like IDNA 2003 did.
+pub(super) fn config(strict: bool) -> idna::Config {
+    idna::Config::default().transitional_processing(!strict)
+}
+
+/// Builds the error of a domain that failed to convert, naming the first label that fails on its
+/// own. Some rules, such as the bidi rules, depend on the rest of the domain, in which case the
+/// domain as a whole is named.
+///
+/// `idna` doesn't expose which rules a domain breaks, other than through its `Debug` output, so
+/// the error doesn't name them.
+pub(super) fn invalid_domain<E>(
+    domain: &str,
+    convert: impl Fn(&str) -> Result<(), E>,
+) -> ExpressionError {
+    match domain
+        .split(SEPARATORS)
+        .find(|label| convert(label).is_err())
+    {
+        Some(label) => format!(r#"invalid label "{label}""#).into(),
+        None => format!(r#"invalid domain "{domain}""#).into(),
+    }
+}
+
+fn encode_idna(value: &Value, strict: bool) -> Resolved {
+    let domain = value.try_bytes_utf8_lossy()?;
+    let to_ascii = |domain: &str| config(strict).verify_dns_length(true).to_ascii(domain);
+
+    match to_ascii(&domain) {
+        Ok(encoded) => Ok(encoded.into()),
+        Err(_) => Err(invalid_domain(&domain, |label| to_ascii(label).map(|_| ()))),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeIdna;
+
+impl Function for EncodeIdna {
+    fn identifier(&self) -> &'static str {
+        "encode_idna"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "strict",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "strict" => Some(true.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "encode domain",
+                source: r#"encode_idna!("Bücher.example")"#,
+                result: Ok(r#""xn--bcher-kva.example""#),
+            },
+            Example {
+                title: "transitional processing",
+                source: r#"encode_idna!("faß.de", strict: false)"#,
+                result: Ok(r#""fass.de""#),
+            },
+            Example {
+                title: "invalid label",
+                source: r#"encode_idna!("aא.example")"#,
+                result: Err(
+                    r#"function call error for "encode_idna" at (0:27): invalid label "aא""#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let strict = arguments.optional("strict").unwrap_or_else(|| expr!(true));
+
+        Ok(EncodeIdnaFn { value, strict }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeIdnaFn {
+    value: Box<dyn Expression>,
+    strict: Box<dyn Expression>,
+}
+
+impl FunctionExpression for EncodeIdnaFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let strict = self.strict.resolve(ctx)?.try_boolean()?;
+
+        encode_idna(&value, strict)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        encode_idna => EncodeIdna;
+
+        ascii {
+            args: func_args![value: "www.example.com"],
+            want: Ok("www.example.com"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        case_folding {
+            args: func_args![value: "Bücher.EXAMPLE"],
+            want: Ok("xn--bcher-kva.example"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        mixed_labels {
+            args: func_args![value: "mail.münchen.de"],
+            want: Ok("mail.xn--mnchen-3ya.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        emoji {
+            args: func_args![value: "💩.la"],
+            want: Ok("xn--ls8h.la"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        mapped_separators {
+            args: func_args![value: "日本語。ＪＰ"],
+            want: Ok("xn--wgv71a119e.jp"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        deviation_non_transitional {
+            args: func_args![value: "faß.de"],
+            want: Ok("xn--fa-hia.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        deviation_transitional {
+            args: func_args![value: "faß.de", strict: false],
+            want: Ok("fass.de"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        final_sigma_non_transitional {
+            args: func_args![value: "βόλος.com"],
+            want: Ok("xn--nxasmm1c.com"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        final_sigma_transitional {
+            args: func_args![value: "βόλος.com", strict: false],
+            want: Ok("xn--nxasmq6b.com"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        label_too_long {
+            args: func_args![value: format!("ok.{}.com", "a".repeat(64))],
+            want: Err(format!(r#"invalid label "{}""#, "a".repeat(64))),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        domain_too_long {
+            args: func_args![value: format!("{0}.{0}.{0}.{0}.{0}", "a".repeat(60))],
+            want: Err(format!(r#"invalid domain "{}""#, format!("{0}.{0}.{0}.{0}.{0}", "a".repeat(60)))),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        bidi_violation {
+            args: func_args![value: "example.aא.com"],
+            want: Err(r#"invalid label "aא""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        empty_label {
+            args: func_args![value: "a..com"],
+            want: Err(r#"invalid label """#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
@@ -1,17 +1,121 @@
+use std::str::FromStr;
+
+use serde::{ser::Error as _, Serialize, Serializer};
+
 use crate::compiler::prelude::*;
 
-fn encode_json(value: Value, pretty: bool) -> Value {
-    // With `vrl::Value` it should not be possible to get `Err`.
+fn encode_json(value: &Value, pretty: bool, nan_policy: NanPolicy) -> Resolved {
+    let value = EncodeValue { value, nan_policy };
 
+    // With `vrl::Value` the only possible `Err` is a non-finite float under `NanPolicy::Error`.
     let result = if pretty {
         serde_json::to_string_pretty(&value)
     } else {
         serde_json::to_string(&value)
     };
 
-    match result {
-        Ok(value) => value.into(),
-        Err(error) => unreachable!("unable encode to json: {}", error),
+    result
+        .map(Into::into)
+        .map_err(|error| format!("unable to encode to json: {error}").into())
+}
+
+/// Controls how non-finite floats (`NaN`, `Infinity` and `-Infinity`) are encoded, since JSON has
+/// no representation for them.
+///
+/// `Value::Float` can never hold `NaN`, so in practice this only applies to infinities, but `NaN`
+/// is handled the same way should it ever be encountered.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum NanPolicy {
+    /// Fail the encoding.
+    Error,
+
+    /// Encode as `null`, which keeps the output valid JSON.
+    #[default]
+    Null,
+
+    /// Encode as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+    String,
+}
+
+impl NanPolicy {
+    fn all_value() -> Vec<Value> {
+        use NanPolicy::{Error, Null, String};
+
+        vec![Error, Null, String]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use NanPolicy::{Error, Null, String};
+
+        match self {
+            Error => "error",
+            Null => "null",
+            String => "string",
+        }
+    }
+}
+
+impl FromStr for NanPolicy {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use NanPolicy::{Error, Null, String};
+
+        match s {
+            "error" => Ok(Error),
+            "null" => Ok(Null),
+            "string" => Ok(String),
+            _ => Err("nan_policy not recognized"),
+        }
+    }
+}
+
+/// Serializes a [`Value`] the same way its own `Serialize` implementation does, except for
+/// non-finite floats, which are handled according to the [`NanPolicy`].
+struct EncodeValue<'a> {
+    value: &'a Value,
+    nan_policy: NanPolicy,
+}
+
+impl<'a> EncodeValue<'a> {
+    fn nested(&self, value: &'a Value) -> Self {
+        Self {
+            value,
+            nan_policy: self.nan_policy,
+        }
+    }
+}
+
+impl Serialize for EncodeValue<'_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.value {
+            Value::Float(f) if !f.is_finite() => match self.nan_policy {
+                NanPolicy::Error => Err(S::Error::custom(format!(
+                    "non-finite float {f} can't be represented in JSON"
+                ))),
+                NanPolicy::Null => serializer.serialize_none(),
+                NanPolicy::String => serializer.serialize_str(if f.is_nan() {
+                    "NaN"
+                } else if f.is_sign_positive() {
+                    "Infinity"
+                } else {
+                    "-Infinity"
+                }),
+            },
+            Value::Object(map) => {
+                serializer.collect_map(map.iter().map(|(key, value)| (key, self.nested(value))))
+            }
+            Value::Array(array) => {
+                serializer.collect_seq(array.iter().map(|value| self.nested(value)))
+            }
+            value => value.serialize(serializer),
+        }
     }
 }
 
@@ -35,19 +139,44 @@ impl Function for EncodeJson {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "nan_policy",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "pretty" => Some(false.into()),
+            "nan_policy" => Some(NanPolicy::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let pretty = arguments.optional("pretty");
-
-        Ok(EncodeJsonFn { value, pretty }.as_expr())
+        let nan_policy = arguments
+            .optional_enum("nan_policy", &NanPolicy::all_value(), state)?
+            .map(|s| {
+                NanPolicy::from_str(&s.try_bytes_utf8_lossy().expect("nan_policy not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(EncodeJsonFn {
+            value,
+            pretty,
+            nan_policy,
+        }
+        .as_expr())
     }
 
     fn examples(&self) -> &'static [Example] {
@@ -64,6 +193,11 @@ impl Function for EncodeJson {
                     r#""{\n  \"another\": [\n    1,\n    2,\n    3\n  ],\n  \"field\": \"value\"\n}""#,
                 ),
             },
+            Example {
+                title: "encode non-finite floats as strings",
+                source: r#"encode_json({"ratio": parse_float!("inf")}, nan_policy: "string")"#,
+                result: Ok(r#"s'{"ratio":"Infinity"}'"#),
+            },
         ]
     }
 }
@@ -72,6 +206,7 @@ impl Function for EncodeJson {
 struct EncodeJsonFn {
     value: Box<dyn Expression>,
     pretty: Option<Box<dyn Expression>>,
+    nan_policy: NanPolicy,
 }
 
 impl FunctionExpression for EncodeJsonFn {
@@ -81,11 +216,11 @@ impl FunctionExpression for EncodeJsonFn {
             Some(pretty) => pretty.resolve(ctx)?.try_boolean()?,
             None => false,
         };
-        Ok(encode_json(value, pretty))
+        encode_json(&value, pretty, self.nan_policy)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::bytes().infallible()
+        TypeDef::bytes().maybe_fallible(self.nan_policy == NanPolicy::Error)
     }
 }
 
@@ -96,6 +231,12 @@ mod tests {
     use chrono::{DateTime, Utc};
     use regex::Regex;
 
+    use crate::value;
+
+    fn float(f: f64) -> Value {
+        Value::Float(NotNan::new(f).unwrap())
+    }
+
     test_function![
         encode_json => EncodeJson;
 
@@ -215,5 +356,35 @@ mod tests {
             want: Ok("null"),
             tdef: TypeDef::bytes().infallible(),
         }
+
+        infinity_default_policy {
+            args: func_args![value: value!({"a": (float(f64::INFINITY))})],
+            want: Ok(r#"{"a":null}"#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        infinity_null_policy {
+            args: func_args![value: value!([(float(f64::INFINITY)), (float(f64::NEG_INFINITY)), 1.5]), nan_policy: "null"],
+            want: Ok("[null,null,1.5]"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        infinity_string_policy {
+            args: func_args![value: value!({"a": [(float(f64::INFINITY)), (float(f64::NEG_INFINITY))], "b": 1.5}), nan_policy: "string"],
+            want: Ok(r#"{"a":["Infinity","-Infinity"],"b":1.5}"#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        infinity_error_policy {
+            args: func_args![value: value!({"a": {"b": (float(f64::INFINITY))}}), nan_policy: "error"],
+            want: Err("unable to encode to json: non-finite float inf can't be represented in JSON"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        finite_error_policy {
+            args: func_args![value: value!({"a": 1.5}), nan_policy: "error"],
+            want: Ok(r#"{"a":1.5}"#),
+            tdef: TypeDef::bytes().fallible(),
+        }
     ];
 }
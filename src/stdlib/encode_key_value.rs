@@ -1,32 +1,24 @@
+use std::str::FromStr;
+
 use crate::compiler::prelude::*;
-use crate::core::encode_key_value;
+use crate::core::encode_key_value::{self, ArrayEncoding, Options, Quote};
 use crate::value::KeyString;
 
-/// Also used by `encode_logfmt`.
-pub(crate) fn encode_key_value(
+fn encode_key_value(
     fields: Option<Value>,
     value: Value,
-    key_value_delimiter: Value,
-    field_delimiter: Value,
-    flatten_boolean: Value,
+    options: &Options<'_>,
 ) -> ExpressionResult<Value> {
     let fields = match fields {
         None => Ok(vec![]),
         Some(fields) => resolve_fields(fields),
     }?;
     let object = value.try_object()?;
-    let key_value_delimiter = key_value_delimiter.try_bytes_utf8_lossy()?;
-    let field_delimiter = field_delimiter.try_bytes_utf8_lossy()?;
-    let flatten_boolean = flatten_boolean.try_boolean()?;
-    Ok(encode_key_value::to_string(
-        &object,
-        &fields[..],
-        &key_value_delimiter,
-        &field_delimiter,
-        flatten_boolean,
+    Ok(
+        encode_key_value::to_string_with_options(&object, &fields[..], options)
+            .expect("Should always succeed.")
+            .into(),
     )
-    .expect("Should always succeed.")
-    .into())
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -64,12 +56,40 @@ impl Function for EncodeKeyValue {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "flatten_delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "quote",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "array_encoding",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "key_value_delimiter" => Some("=".into()),
+            "field_delimiter" => Some(" ".into()),
+            "flatten_boolean" => Some(false.into()),
+            "flatten_delimiter" => Some(".".into()),
+            "fields_ordering" => Some(Value::Array(vec![])),
+            "quote" => Some(Quote::default().as_str().into()),
+            "array_encoding" => Some(ArrayEncoding::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
@@ -88,12 +108,37 @@ impl Function for EncodeKeyValue {
             .optional("flatten_boolean")
             .unwrap_or_else(|| expr!(false));
 
+        let flatten_delimiter = arguments
+            .optional("flatten_delimiter")
+            .unwrap_or_else(|| expr!("."));
+
+        let quote = arguments
+            .optional_enum("quote", &Quote::all_value(), state)?
+            .map(|s| {
+                Quote::from_str(&s.try_bytes_utf8_lossy().expect("quote not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        let array_encoding = arguments
+            .optional_enum("array_encoding", &ArrayEncoding::all_value(), state)?
+            .map(|s| {
+                ArrayEncoding::from_str(
+                    &s.try_bytes_utf8_lossy().expect("array_encoding not bytes"),
+                )
+                .expect("validated enum")
+            })
+            .unwrap_or_default();
+
         Ok(EncodeKeyValueFn {
             value,
             fields,
             key_value_delimiter,
             field_delimiter,
             flatten_boolean,
+            flatten_delimiter,
+            quote,
+            array_encoding,
         }
         .as_expr())
     }
@@ -115,6 +160,21 @@ impl Function for EncodeKeyValue {
                 source: r#"encode_key_value({"start": "ool", "end": "kul", "stop1": "yyc", "stop2" : "gdx"}, key_value_delimiter: ":", field_delimiter: ",")"#,
                 result: Ok(r#"s'end:kul,start:ool,stop1:yyc,stop2:gdx'"#),
             },
+            Example {
+                title: "nested fields with a custom flatten delimiter",
+                source: r#"encode_key_value({"http": {"method": "GET", "status": 200}}, flatten_delimiter: "_")"#,
+                result: Ok(r#"s'http_method=GET http_status=200'"#),
+            },
+            Example {
+                title: "arrays as repeated keys",
+                source: r#"encode_key_value({"tags": ["a", "b"], "host": "web-1"}, array_encoding: "repeat")"#,
+                result: Ok(r#"s'host=web-1 tags=a tags=b'"#),
+            },
+            Example {
+                title: "always quote values",
+                source: r#"encode_key_value({"lvl": "info", "code": 200}, quote: "always")"#,
+                result: Ok(r#"s'code="200" lvl="info"'"#),
+            },
         ]
     }
 }
@@ -126,6 +186,9 @@ pub(crate) struct EncodeKeyValueFn {
     pub(crate) key_value_delimiter: Box<dyn Expression>,
     pub(crate) field_delimiter: Box<dyn Expression>,
     pub(crate) flatten_boolean: Box<dyn Expression>,
+    pub(crate) flatten_delimiter: Box<dyn Expression>,
+    pub(crate) quote: Quote,
+    pub(crate) array_encoding: ArrayEncoding,
 }
 
 fn resolve_fields(fields: Value) -> ExpressionResult<Vec<KeyString>> {
@@ -150,14 +213,23 @@ impl FunctionExpression for EncodeKeyValueFn {
             .transpose()?;
         let key_value_delimiter = self.key_value_delimiter.resolve(ctx)?;
         let field_delimiter = self.field_delimiter.resolve(ctx)?;
-        let flatten_boolean = self.flatten_boolean.resolve(ctx)?;
+        let key_value_delimiter = key_value_delimiter.try_bytes_utf8_lossy()?;
+        let field_delimiter = field_delimiter.try_bytes_utf8_lossy()?;
+        let flatten_boolean = self.flatten_boolean.resolve(ctx)?.try_boolean()?;
+        let flatten_delimiter = self.flatten_delimiter.resolve(ctx)?;
+        let flatten_delimiter = flatten_delimiter.try_bytes_utf8_lossy()?;
 
         encode_key_value(
             fields,
             value,
-            key_value_delimiter,
-            field_delimiter,
-            flatten_boolean,
+            &Options {
+                key_value_delimiter: &key_value_delimiter,
+                field_delimiter: &field_delimiter,
+                flatten_boolean,
+                flatten_delimiter: &flatten_delimiter,
+                quote: self.quote,
+                array_encoding: self.array_encoding,
+            },
         )
     }
 
@@ -170,6 +242,7 @@ impl FunctionExpression for EncodeKeyValueFn {
 mod tests {
     use crate::{btreemap, value};
 
+    use super::super::parse_key_value::{parse_key_value, Whitespace};
     use super::*;
 
     test_function![
@@ -340,5 +413,186 @@ mod tests {
                     })),
             tdef: TypeDef::bytes().fallible(),
         }
+
+        flatten_delimiter {
+            args: func_args![value:
+                btreemap! {
+                    "http" => btreemap! {
+                        "request" => btreemap! {
+                            "method" => "GET"
+                        },
+                        "status" => 200
+                    },
+                    "tags" => value!(["a", "b"])
+                },
+                flatten_delimiter: value!("_")
+            ],
+            want: Ok("http_request_method=GET http_status=200 tags_0=a tags_1=b"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        flatten_delimiter_fields_ordering {
+            args: func_args![value:
+                btreemap! {
+                    "timestamp" => "2024-01-01T00:00:00Z",
+                    "level" => "info",
+                    "message" => "hello world",
+                    "http" => btreemap! {
+                        "status" => 200
+                    },
+                    "app" => "web"
+                },
+                fields_ordering: value!(["timestamp", "level", "message"]),
+                flatten_delimiter: value!("/")
+            ],
+            want: Ok(r#"timestamp=2024-01-01T00:00:00Z level=info message="hello world" app=web http/status=200"#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        quote_always {
+            args: func_args![value:
+                btreemap! {
+                    "lvl" => "info",
+                    "code" => 200,
+                    "beta" => true,
+                },
+                quote: "always"
+            ],
+            want: Ok(r#"beta="true" code="200" lvl="info""#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        quote_always_flatten_boolean {
+            args: func_args![value:
+                btreemap! {
+                    "lvl" => "info",
+                    "beta" => true,
+                },
+                flatten_boolean: value!(true),
+                quote: "always"
+            ],
+            want: Ok(r#"beta lvl="info""#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        quote_when_needed_custom_delimiters {
+            args: func_args![value:
+                btreemap! {
+                    "a" => "x:y",
+                    "b" => "x,y",
+                    "c" => "xy",
+                },
+                key_value_delimiter: value!(":"),
+                field_delimiter: value!(","),
+                quote: "when_needed"
+            ],
+            want: Ok(r#"a:"x:y",b:"x,y",c:xy"#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        quote_never {
+            args: func_args![value:
+                btreemap! {
+                    "msg" => r#"say "hi" a=b"#,
+                    "lvl" => "info",
+                },
+                quote: "never"
+            ],
+            want: Ok(r#"lvl=info msg=say \"hi\" a\=b"#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        quote_never_multi_char_delimiter {
+            args: func_args![value:
+                btreemap! {
+                    "msg" => "a:=b:c",
+                },
+                key_value_delimiter: value!(":="),
+                quote: "never"
+            ],
+            want: Ok(r"msg:=a\:=b:c"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        array_encoding_repeat {
+            args: func_args![value:
+                btreemap! {
+                    "tags" => value!(["a", "b c"]),
+                    "host" => "web-1",
+                    "nested" => btreemap! {
+                        "ids" => value!([1, 2])
+                    },
+                },
+                array_encoding: "repeat"
+            ],
+            want: Ok(r#"host=web-1 nested.ids=1 nested.ids=2 tags=a tags="b c""#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        array_encoding_repeat_fields_ordering {
+            args: func_args![value:
+                btreemap! {
+                    "tags" => value!(["a", "b"]),
+                    "host" => "web-1",
+                },
+                fields_ordering: value!(["tags"]),
+                array_encoding: "repeat"
+            ],
+            want: Ok("tags=a tags=b host=web-1"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        array_encoding_json {
+            args: func_args![value:
+                btreemap! {
+                    "ids" => value!([1, 2]),
+                    "tags" => value!(["a", {"b": null}]),
+                    "empty" => value!([]),
+                },
+                array_encoding: "json"
+            ],
+            want: Ok(r#"empty=[] ids=[1,2] tags="[\"a\",{\"b\":null}]""#),
+            tdef: TypeDef::bytes().infallible(),
+        }
     ];
+
+    fn round_trip(value: Value, key_value_delimiter: &str, field_delimiter: &str, quote: Quote) {
+        let encoded = encode_key_value(
+            None,
+            value.clone(),
+            &Options {
+                key_value_delimiter,
+                field_delimiter,
+                quote,
+                array_encoding: ArrayEncoding::Repeat,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        let decoded = parse_key_value(
+            encoded.clone(),
+            key_value_delimiter.into(),
+            field_delimiter.into(),
+            true.into(),
+            Whitespace::Lenient,
+        )
+        .unwrap();
+
+        assert_eq!(decoded, value, "encoded as {encoded}");
+    }
+
+    #[test]
+    fn round_trip_parse_key_value() {
+        let value = value!({
+            "lvl": "info",
+            "msg": "This is a log message",
+            "eq": "a=b",
+            "tags": ["a", "b c", "d"],
+        });
+
+        round_trip(value.clone(), "=", " ", Quote::WhenNeeded);
+        round_trip(value.clone(), "=", " ", Quote::Always);
+        round_trip(value.clone(), ":", ",", Quote::WhenNeeded);
+        round_trip(value, ":", ",", Quote::Always);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::compiler::prelude::*;
 
 use super::encode_key_value::EncodeKeyValueFn;
+use crate::core::encode_key_value::{ArrayEncoding, Quote};
 
 #[derive(Clone, Copy, Debug)]
 pub struct EncodeLogfmt;
@@ -25,6 +26,13 @@ impl Function for EncodeLogfmt {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "fields_ordering" => Some(Value::Array(vec![])),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -36,6 +44,7 @@ impl Function for EncodeLogfmt {
         let key_value_delimiter = expr!("=");
         let field_delimiter = expr!(" ");
         let flatten_boolean = expr!(true);
+        let flatten_delimiter = expr!(".");
 
         let value = arguments.required("value");
         let fields = arguments.optional("fields_ordering");
@@ -46,6 +55,9 @@ impl Function for EncodeLogfmt {
             key_value_delimiter,
             field_delimiter,
             flatten_boolean,
+            flatten_delimiter,
+            quote: Quote::WhenNeeded,
+            array_encoding: ArrayEncoding::Index,
         }
         .as_expr())
     }
@@ -0,0 +1,131 @@
+use crate::compiler::prelude::*;
+use crate::stdlib::msgpack;
+
+fn encode_msgpack(value: &Value) -> Resolved {
+    let mut buf = Vec::new();
+    msgpack::encode(value, &mut buf)?;
+    Ok(Value::Bytes(buf.into()))
+}
+
+/// Returns `true` if a value of the kind might be, or contain, a regex, which can't be encoded.
+fn may_contain_regex(kind: &Kind) -> bool {
+    kind.contains_regex()
+        || kind.as_object().map_or(false, collection_may_contain_regex)
+        || kind.as_array().map_or(false, collection_may_contain_regex)
+}
+
+fn collection_may_contain_regex<T: Ord + Clone>(collection: &Collection<T>) -> bool {
+    collection.known().values().any(may_contain_regex)
+        || if collection.is_unknown_exact() {
+            may_contain_regex(&collection.unknown_kind())
+        } else {
+            // An infinite unknown kind is the same at every depth.
+            collection.unknown_kind().contains_regex()
+        }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeMsgpack;
+
+impl Function for EncodeMsgpack {
+    fn identifier(&self) -> &'static str {
+        "encode_msgpack"
+    }
+
+    fn summary(&self) -> &'static str {
+        "encode a value to MessagePack"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Encodes the provided `value` as MessagePack.
+
+            Strings are encoded as `bin`, since they are bytes, and timestamps as the MessagePack
+            timestamp extension type, in its most compact format. Regex values can't be encoded.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ANY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "encode object",
+            source: r#"encode_base64(encode_msgpack({"compact": true, "schema": 0}))"#,
+            result: Ok("gqdjb21wYWN0w6ZzY2hlbWEA"),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(EncodeMsgpackFn { value }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct EncodeMsgpackFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for EncodeMsgpackFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        encode_msgpack(&value)
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().maybe_fallible(may_contain_regex(self.value.type_def(state).kind()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+    use chrono::DateTime;
+
+    test_function![
+        encode_msgpack => EncodeMsgpack;
+
+        object {
+            args: func_args![value: value!({"compact": true, "schema": 0})],
+            want: Ok(Bytes::from_static(b"\x82\xa7compact\xc3\xa6schema\x00")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        bytes {
+            args: func_args![value: "foo"],
+            want: Ok(Bytes::from_static(b"\xc4\x03foo")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        timestamp {
+            args: func_args![value: Value::Timestamp(DateTime::parse_from_rfc3339("2021-02-01T12:34:56.789Z").unwrap().into())],
+            want: Ok(Bytes::from_static(b"\xd7\xff\xbc\x1c\xbd\x00\x60\x17\xf5\x70")),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        regex {
+            args: func_args![value: Value::from(regex::Regex::new("foo").unwrap())],
+            want: Err("regex values can't be encoded as msgpack"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        nested_regex {
+            args: func_args![value: Value::Array(vec![Value::from(regex::Regex::new("foo").unwrap())])],
+            want: Err("regex values can't be encoded as msgpack"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
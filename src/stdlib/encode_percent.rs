@@ -99,6 +99,13 @@ impl Function for EncodePercent {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "ascii_set" => Some("NON_ALPHANUMERIC".into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         state: &state::TypeState,
@@ -61,6 +61,13 @@ impl Function for EncodeZstd {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "compression_level" => Some(0.into()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Days, Duration, Utc};
+
+use crate::compiler::prelude::*;
+use crate::compiler::TimeZone;
+use crate::stdlib::start_of_day::{day_start, resolve_timezone};
+
+fn end_of_day(ts: Value, timezone: Option<Value>, host_timezone: TimeZone) -> Resolved {
+    let ts = ts.try_timestamp()?;
+    let timezone = resolve_timezone(timezone, host_timezone)?;
+
+    // The day ends just before the next one starts, however many hours it has.
+    Ok(day_start(ts, timezone, Days::new(1))
+        .map_or(DateTime::<Utc>::MAX_UTC, |next| {
+            next - Duration::microseconds(1)
+        })
+        .into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EndOfDay;
+
+impl Function for EndOfDay {
+    fn identifier(&self) -> &'static str {
+        "end_of_day"
+    }
+
+    fn summary(&self) -> &'static str {
+        "get the end of the day of a timestamp"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the last microsecond of the day that `value` falls on in `timezone`, which
+            is one microsecond before the next day starts, as returned by `start_of_day`. Days
+            when the clocks change are shorter or longer than 24 hours.
+
+            `timezone` is UTC by default, and is given the same way as for `start_of_day`. The
+            function only fails if `timezone` isn't valid.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timezone" => Some("UTC".into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "utc",
+                source: r#"end_of_day(t'2021-02-10T13:32:00Z')"#,
+                result: Ok("t'2021-02-10T23:59:59.999999Z'"),
+            },
+            Example {
+                title: "timezone",
+                source: r#"end_of_day!(t'2021-02-11T03:32:00Z', timezone: "America/New_York")"#,
+                result: Ok("t'2021-02-11T04:59:59.999999Z'"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let timezone = arguments.optional("timezone");
+
+        Ok(EndOfDayFn { value, timezone }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EndOfDayFn {
+    value: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for EndOfDayFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| timezone.resolve(ctx))
+            .transpose()?;
+
+        end_of_day(value, timezone, *ctx.timezone())
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::timestamp().maybe_fallible(self.timezone.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    fn just_before(ts: DateTime<Utc>) -> DateTime<Utc> {
+        ts - Duration::microseconds(1)
+    }
+
+    test_function![
+        end_of_day => EndOfDay;
+
+        utc {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 13, 32, 0).unwrap()],
+            want: Ok(just_before(Utc.with_ymd_and_hms(2021, 2, 11, 0, 0, 0).unwrap())),
+            tdef: TypeDef::timestamp().infallible(),
+        }
+
+        negative_offset {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 11, 3, 32, 0).unwrap(), timezone: "America/New_York"],
+            want: Ok(just_before(Utc.with_ymd_and_hms(2021, 2, 11, 5, 0, 0).unwrap())),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        // The clocks went forward on 2021-03-14 in New York, so the day lasted 23 hours, from
+        // 05:00 UTC to 04:00 UTC the next day.
+        short_day {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 3, 14, 12, 0, 0).unwrap(), timezone: "America/New_York"],
+            want: Ok(just_before(Utc.with_ymd_and_hms(2021, 3, 15, 4, 0, 0).unwrap())),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        // The clocks went back on 2021-11-07 in New York, so the day lasted 25 hours, from
+        // 04:00 UTC to 05:00 UTC the next day.
+        long_day {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 11, 7, 12, 0, 0).unwrap(), timezone: "America/New_York"],
+            want: Ok(just_before(Utc.with_ymd_and_hms(2021, 11, 8, 5, 0, 0).unwrap())),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        // Samoa skipped 2011-12-30 entirely, so 2011-12-29 ended when 2011-12-31 started.
+        next_day_skipped {
+            args: func_args![value: Utc.with_ymd_and_hms(2011, 12, 29, 22, 0, 0).unwrap(), timezone: "Pacific/Apia"],
+            want: Ok(just_before(Utc.with_ymd_and_hms(2011, 12, 30, 10, 0, 0).unwrap())),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        invalid_timezone {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 13, 32, 0).unwrap(), timezone: "Mars/Olympus_Mons"],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+    ];
+}
@@ -1,10 +1,15 @@
 use crate::compiler::prelude::*;
-use crate::stdlib::string_utils::convert_to_string;
+use crate::stdlib::string_utils::{
+    any_needle, compile_needles, convert_to_string, needles_are_fallible, Anchor, Needles,
+};
 
 fn ends_with(value: Value, substring: Value, case_sensitive: bool) -> Resolved {
     let value = convert_to_string(value, case_sensitive)?;
-    let substring = convert_to_string(substring, case_sensitive)?;
-    Ok(value.ends_with(&substring).into())
+    let found = any_needle(substring, |substring| {
+        let substring = convert_to_string(substring, case_sensitive)?;
+        Ok(value.ends_with(&substring))
+    })?;
+    Ok(found.into())
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -24,7 +29,7 @@ impl Function for EndsWith {
             },
             Parameter {
                 keyword: "substring",
-                kind: kind::BYTES,
+                kind: kind::BYTES | kind::ARRAY,
                 required: true,
             },
             Parameter {
@@ -35,20 +40,29 @@ impl Function for EndsWith {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "case_sensitive" => Some(true.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let substring = arguments.required("substring");
         let case_sensitive = arguments.optional("case_sensitive").unwrap_or(expr!(true));
+        let needles = compile_needles(state, &arguments, case_sensitive.as_ref(), Anchor::End)?;
 
         Ok(EndsWithFn {
             value,
             substring,
             case_sensitive,
+            needles,
         }
         .as_expr())
     }
@@ -79,10 +93,16 @@ struct EndsWithFn {
     value: Box<dyn Expression>,
     substring: Box<dyn Expression>,
     case_sensitive: Box<dyn Expression>,
+    needles: Option<Needles>,
 }
 
 impl FunctionExpression for EndsWithFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
+        if let Some(needles) = &self.needles {
+            let value = self.value.resolve(ctx)?;
+            return Ok(needles.is_match(&value.try_bytes()?).into());
+        }
+
         let case_sensitive = self.case_sensitive.resolve(ctx)?;
         let case_sensitive = case_sensitive.try_boolean()?;
         let substring = self.substring.resolve(ctx)?;
@@ -91,8 +111,11 @@ impl FunctionExpression for EndsWithFn {
         ends_with(value, substring, case_sensitive)
     }
 
-    fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::boolean().infallible()
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        let fallible =
+            self.needles.is_none() && needles_are_fallible(self.substring.type_def(state).kind());
+
+        TypeDef::boolean().maybe_fallible(fallible)
     }
 }
 
@@ -163,5 +186,41 @@ mod tests {
             want: Ok(value!(true)),
             tdef: TypeDef::boolean().infallible(),
         }
+
+        array_any {
+            args: func_args![value: "report.tar.gz",
+                             substring: value!([".zip", ".gz", ".bz2"])],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_none {
+            args: func_args![value: "report.gz.txt",
+                             substring: value!([".zip", ".gz", ".bz2"])],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_case_insensitive {
+            args: func_args![value: "PHOTO.JPG",
+                             substring: value!([".png", ".jpg"]),
+                             case_sensitive: false],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_empty {
+            args: func_args![value: "foo",
+                             substring: value!([])],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_not_strings {
+            args: func_args![value: "foo",
+                             substring: value!([null])],
+            want: Err("unexpected expression type"),
+            tdef: TypeDef::boolean().infallible(),
+        }
     ];
 }
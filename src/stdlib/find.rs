@@ -39,6 +39,13 @@ impl Function for Find {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "from" => Some(0.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[Example {
             title: "string",
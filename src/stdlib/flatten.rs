@@ -1,21 +1,41 @@
 use std::collections::btree_map;
+use std::iter::Enumerate;
+use std::str::FromStr;
+use std::vec;
 
 use crate::compiler::prelude::*;
+use crate::stdlib::util::{
+    max_depth_exceeded, may_exceed_max_depth, resolve_max_depth, DEFAULT_MAX_DEPTH,
+};
 
 static DEFAULT_SEPARATOR: &str = ".";
 
-fn flatten(value: Value, separator: Value) -> Resolved {
+/// Flattens `value`, keeping collections nested deeper than `max_depth` as values. Without a
+/// `max_depth`, input nested deeper than the default is an error instead.
+fn flatten(
+    value: Value,
+    separator: Value,
+    max_depth: Option<usize>,
+    array_style: ArrayStyle,
+) -> Resolved {
     let separator = separator.try_bytes_utf8_lossy()?;
+    let max_depth = match max_depth {
+        Some(max_depth) => max_depth,
+        None if value.exceeds_depth(DEFAULT_MAX_DEPTH) => {
+            value.dismantle();
+            return Err(max_depth_exceeded(DEFAULT_MAX_DEPTH));
+        }
+        None => DEFAULT_MAX_DEPTH,
+    };
 
     match value {
-        Value::Array(arr) => Ok(Value::Array(
-            ArrayFlatten::new(arr.iter()).cloned().collect(),
-        )),
-        Value::Object(map) => Ok(Value::Object(
-            MapFlatten::new(map.iter(), &separator)
-                .map(|(k, v)| (k, v.clone()))
-                .collect(),
-        )),
+        Value::Array(arr) => Ok(Value::Array(flatten_array(arr, max_depth))),
+        Value::Object(map) => Ok(Value::Object(flatten_map(
+            map,
+            &separator,
+            max_depth,
+            array_style,
+        ))),
         value => Err(ValueError::Expected {
             got: value.kind(),
             expected: Kind::array(Collection::any()) | Kind::object(Collection::any()),
@@ -24,6 +44,149 @@ fn flatten(value: Value, separator: Value) -> Resolved {
     }
 }
 
+/// Controls how arrays nested in an object are flattened.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum ArrayStyle {
+    /// Keep arrays as values.
+    #[default]
+    Preserve,
+
+    /// Flatten elements using their index as a key, as in `a.0.b`.
+    Indices,
+
+    /// Flatten elements using their index in brackets, as in `a[0].b`.
+    Brackets,
+}
+
+impl ArrayStyle {
+    fn all_value() -> Vec<Value> {
+        use ArrayStyle::{Brackets, Indices, Preserve};
+
+        vec![Preserve, Indices, Brackets]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use ArrayStyle::{Brackets, Indices, Preserve};
+
+        match self {
+            Preserve => "preserve",
+            Indices => "indices",
+            Brackets => "brackets",
+        }
+    }
+}
+
+impl FromStr for ArrayStyle {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use ArrayStyle::{Brackets, Indices, Preserve};
+
+        match s {
+            "preserve" => Ok(Preserve),
+            "indices" => Ok(Indices),
+            "brackets" => Ok(Brackets),
+            _ => Err("array style not recognized"),
+        }
+    }
+}
+
+/// The fields of an object, or the elements of an array that's flattened into an object.
+enum Children {
+    Object(btree_map::IntoIter<KeyString, Value>),
+    Array(Enumerate<vec::IntoIter<Value>>),
+}
+
+/// Flattens nested maps to a single level, joining the keys of nested fields with `separator`.
+///
+/// Collections nested `max_depth` levels deep are kept as values. Fields are visited in key
+/// order, so if flattening results in the same key more than once (as for `{"a": {"b": 1}, "a.b":
+/// 2}`), the last of them wins.
+///
+/// Nested maps are walked using an explicit stack, so deeply nested input can't overflow the call
+/// stack, and values are moved into the result rather than cloned.
+fn flatten_map(
+    map: ObjectMap,
+    separator: &str,
+    max_depth: usize,
+    array_style: ArrayStyle,
+) -> ObjectMap {
+    let mut flattened = ObjectMap::new();
+    let mut stack = vec![(None::<KeyString>, Children::Object(map.into_iter()))];
+
+    while let Some((parent, children)) = stack.last_mut() {
+        let (key, value) = match children {
+            Children::Object(fields) => match fields.next() {
+                Some((key, value)) => {
+                    let key: KeyString = match parent {
+                        None => key,
+                        Some(parent) => format!("{parent}{separator}{key}").into(),
+                    };
+                    (key, value)
+                }
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            },
+            Children::Array(elements) => match elements.next() {
+                Some((index, value)) => {
+                    let parent = parent.as_ref().expect("arrays are nested");
+                    let key: KeyString = match array_style {
+                        ArrayStyle::Brackets => format!("{parent}[{index}]").into(),
+                        _ => format!("{parent}{separator}{index}").into(),
+                    };
+                    (key, value)
+                }
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            },
+        };
+
+        match value {
+            Value::Object(map) if stack.len() < max_depth => {
+                stack.push((Some(key), Children::Object(map.into_iter())));
+            }
+            Value::Array(array)
+                if stack.len() < max_depth && array_style != ArrayStyle::Preserve =>
+            {
+                stack.push((Some(key), Children::Array(array.into_iter().enumerate())));
+            }
+            value => {
+                flattened.insert(key, value);
+            }
+        }
+    }
+
+    flattened
+}
+
+/// Flattens nested arrays to a single level, keeping arrays nested `max_depth` levels deep.
+///
+/// Nested arrays are walked using an explicit stack, so deeply nested input can't overflow the
+/// call stack, and values are moved into the result rather than cloned.
+fn flatten_array(array: Vec<Value>, max_depth: usize) -> Vec<Value> {
+    let mut flattened = Vec::with_capacity(array.len());
+    let mut stack = vec![array.into_iter()];
+
+    while let Some(values) = stack.last_mut() {
+        match values.next() {
+            Some(Value::Array(array)) if stack.len() < max_depth => stack.push(array.into_iter()),
+            Some(value) => flattened.push(value),
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    flattened
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Flatten;
 
@@ -44,9 +207,28 @@ impl Function for Flatten {
                 kind: kind::BYTES,
                 required: false,
             },
+            Parameter {
+                keyword: "max_depth",
+                kind: kind::INTEGER,
+                required: false,
+            },
+            Parameter {
+                keyword: "array_style",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "separator" => Some(DEFAULT_SEPARATOR.into()),
+            "max_depth" => Some(DEFAULT_MAX_DEPTH.into()),
+            "array_style" => Some(ArrayStyle::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -64,12 +246,22 @@ impl Function for Flatten {
                 source: r#"flatten([[true]])"#,
                 result: Ok(r#"[true]"#),
             },
+            Example {
+                title: "max depth",
+                source: r#"flatten({ "a": { "b": { "c": true }}}, max_depth: 2)"#,
+                result: Ok(r#"{ "a.b": { "c": true } }"#),
+            },
+            Example {
+                title: "array style",
+                source: r#"flatten({ "a": [{ "b": 1 }, 2] }, array_style: "brackets")"#,
+                result: Ok(r#"{ "a[0].b": 1, "a[1]": 2 }"#),
+            },
         ]
     }
 
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
@@ -77,7 +269,22 @@ impl Function for Flatten {
             .optional("separator")
             .unwrap_or_else(|| expr!(DEFAULT_SEPARATOR));
         let value = arguments.required("value");
-        Ok(FlattenFn { value, separator }.as_expr())
+        let max_depth = arguments.optional("max_depth");
+        let array_style = arguments
+            .optional_enum("array_style", &ArrayStyle::all_value(), state)?
+            .map(|s| {
+                ArrayStyle::from_str(&s.try_bytes_utf8_lossy().expect("array style not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(FlattenFn {
+            value,
+            separator,
+            max_depth,
+            array_style,
+        }
+        .as_expr())
     }
 }
 
@@ -85,144 +292,78 @@ impl Function for Flatten {
 struct FlattenFn {
     value: Box<dyn Expression>,
     separator: Box<dyn Expression>,
+    max_depth: Option<Box<dyn Expression>>,
+    array_style: ArrayStyle,
 }
 
 impl FunctionExpression for FlattenFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
         let separator = self.separator.resolve(ctx)?;
+        let max_depth = match &self.max_depth {
+            None => None,
+            Some(_) => Some(resolve_max_depth(self.max_depth.as_deref(), ctx)?),
+        };
 
-        flatten(value, separator)
+        flatten(value, separator, max_depth, self.array_style)
     }
 
     fn type_def(&self, state: &state::TypeState) -> TypeDef {
         let td = self.value.type_def(state);
 
+        // An explicit `max_depth` is only an error if it's less than one.
+        let fallible = match &self.max_depth {
+            None => may_exceed_max_depth(td.kind(), None, state),
+            Some(max_depth) => max_depth
+                .resolve_constant(state)
+                .and_then(|max_depth| max_depth.as_integer())
+                .map_or(true, |max_depth| max_depth < 1),
+        };
+
         if td.is_array() {
-            TypeDef::array(Collection::any())
+            TypeDef::array(Collection::any()).maybe_fallible(fallible)
         } else {
-            TypeDef::object(Collection::any())
-        }
-    }
-}
-
-/// An iterator to walk over maps allowing us to flatten nested maps to a single level.
-struct MapFlatten<'a> {
-    values: btree_map::Iter<'a, KeyString, Value>,
-    separator: &'a str,
-    inner: Option<Box<MapFlatten<'a>>>,
-    parent: Option<KeyString>,
-}
-
-impl<'a> MapFlatten<'a> {
-    fn new(values: btree_map::Iter<'a, KeyString, Value>, separator: &'a str) -> Self {
-        Self {
-            values,
-            separator,
-            inner: None,
-            parent: None,
-        }
-    }
-
-    fn new_from_parent(
-        parent: KeyString,
-        values: btree_map::Iter<'a, KeyString, Value>,
-        separator: &'a str,
-    ) -> Self {
-        Self {
-            values,
-            separator,
-            inner: None,
-            parent: Some(parent),
-        }
-    }
-
-    /// Returns the key with the parent prepended.
-    fn new_key(&self, key: &str) -> KeyString {
-        match self.parent {
-            None => key.to_string().into(),
-            Some(ref parent) => format!("{parent}{}{key}", self.separator).into(),
+            TypeDef::object(Collection::any()).maybe_fallible(fallible)
         }
     }
 }
 
-impl<'a> std::iter::Iterator for MapFlatten<'a> {
-    type Item = (KeyString, &'a Value);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(ref mut inner) = self.inner {
-            let next = inner.next();
-            match next {
-                Some(_) => return next,
-                None => self.inner = None,
-            }
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value;
 
-        let next = self.values.next();
-        match next {
-            Some((key, Value::Object(value))) => {
-                self.inner = Some(Box::new(MapFlatten::new_from_parent(
-                    self.new_key(key),
-                    value.iter(),
-                    self.separator,
-                )));
-                self.next()
-            }
-            Some((key, value)) => Some((self.new_key(key), value)),
-            None => None,
-        }
+    #[test]
+    fn unflatten_round_trip() {
+        let value = (0..200).fold(
+            value!({"b": 1, "c": [true]}),
+            |value, _| value!({"a": value}),
+        );
+
+        let flattened = flatten(
+            value.clone(),
+            DEFAULT_SEPARATOR.into(),
+            Some(usize::MAX),
+            ArrayStyle::Preserve,
+        )
+        .unwrap();
+        let unflattened =
+            crate::stdlib::unflatten::unflatten(flattened, &DEFAULT_SEPARATOR.into()).unwrap();
+
+        assert_eq!(unflattened, value);
     }
-}
 
-/// Create an iterator that can walk a tree of Array values.
-/// This can be used to flatten the array.
-struct ArrayFlatten<'a> {
-    values: std::slice::Iter<'a, Value>,
-    inner: Option<Box<ArrayFlatten<'a>>>,
-}
+    #[test]
+    fn hostile_depth() {
+        // Deep enough to overflow the stack if it were cloned or dropped recursively.
+        let value = (0..50_000).fold(value!(1), |value, _| value!({"a": [value]}));
 
-impl<'a> ArrayFlatten<'a> {
-    fn new(values: std::slice::Iter<'a, Value>) -> Self {
-        ArrayFlatten {
-            values,
-            inner: None,
-        }
+        assert_eq!(
+            flatten(value, DEFAULT_SEPARATOR.into(), None, ArrayStyle::default())
+                .map_err(|err| err.to_string()),
+            Err("maximum depth of 128 exceeded".to_owned())
+        );
     }
-}
-
-impl<'a> std::iter::Iterator for ArrayFlatten<'a> {
-    type Item = &'a Value;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        // Iterate over our inner list first.
-        if let Some(ref mut inner) = self.inner {
-            let next = inner.next();
-            match next {
-                Some(_) => return next,
-                None => {
-                    // The inner list has been exhausted.
-                    self.inner = None;
-                }
-            }
-        }
-
-        // Then iterate over our values.
-        let next = self.values.next();
-        match next {
-            Some(Value::Array(next)) => {
-                // Create a new iterator for this child list.
-                self.inner = Some(Box::new(ArrayFlatten::new(next.iter())));
-                self.next()
-            }
-            _ => next,
-        }
-    }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::value;
 
     test_function![
         flatten => Flatten;
@@ -359,5 +500,99 @@ mod test {
             })),
             tdef: TypeDef::object(Collection::any()),
         }
+
+        nested_within_default_max_depth {
+            args: func_args![value: (0..128).fold(value!(1), |value, _| Value::Array(vec![value]))],
+            want: Ok(value!([1])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        deeply_nested_array {
+            args: func_args![value: (0..200).fold(value!(1), |value, _| Value::Array(vec![value]))],
+            want: Err("maximum depth of 128 exceeded"),
+            tdef: TypeDef::array(Collection::any()).fallible(),
+        }
+
+        deeply_nested_map {
+            args: func_args![value: (0..200).fold(value!(1), |value, _| value!({"a": value}))],
+            want: Err("maximum depth of 128 exceeded"),
+            tdef: TypeDef::object(Collection::any()).fallible(),
+        }
+
+        deeply_nested_map_explicit_max_depth {
+            args: func_args![value: (0..200).fold(value!(1), |value, _| value!({"a": value})), max_depth: 128],
+            want: Ok(Value::from(ObjectMap::from([(
+                vec!["a"; 128].join(".").into(),
+                (0..72).fold(value!(1), |value, _| value!({"a": value})),
+            )]))),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        max_depth_map {
+            args: func_args![value: value!({"a": {"b": {"c": 1}}, "d": {"e": 2}, "f": 3}), max_depth: 2],
+            want: Ok(value!({"a.b": {"c": 1}, "d.e": 2, "f": 3})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        max_depth_one {
+            args: func_args![value: value!({"a": {"b": 1}}), max_depth: 1],
+            want: Ok(value!({"a": {"b": 1}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        max_depth_array {
+            args: func_args![value: value!([1, [2, [3, [4]]]]), max_depth: 2],
+            want: Ok(value!([1, 2, [3, [4]]])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        max_depth_not_reached {
+            args: func_args![value: value!([1, [2, [3]]]), max_depth: 3],
+            want: Ok(value!([1, 2, 3])),
+            tdef: TypeDef::array(Collection::any()),
+        }
+
+        invalid_max_depth {
+            args: func_args![value: value!([1]), max_depth: 0],
+            want: Err("max_depth must be at least 1"),
+            tdef: TypeDef::array(Collection::any()).fallible(),
+        }
+
+        array_style_indices {
+            args: func_args![value: value!({"a": [{"b": 1}, [2, 3], []], "c": {"d": []}}), array_style: "indices"],
+            want: Ok(value!({"a.0.b": 1, "a.1.0": 2, "a.1.1": 3})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        array_style_brackets {
+            args: func_args![value: value!({"a": [{"b": 1}, [2, 3]], "c": 4}), separator: "_", array_style: "brackets"],
+            want: Ok(value!({"a[0]_b": 1, "a[1][0]": 2, "a[1][1]": 3, "c": 4})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        array_style_preserve {
+            args: func_args![value: value!({"a": [{"b": 1}], "c": {"d": [2]}}), array_style: "preserve"],
+            want: Ok(value!({"a": [{"b": 1}], "c.d": [2]})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        array_style_max_depth {
+            args: func_args![value: value!({"a": [[1], 2]}), max_depth: 2, array_style: "brackets"],
+            want: Ok(value!({"a[0]": [1], "a[1]": 2})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        // Fields are visited in key order, and the last one to produce a key wins.
+        key_collision {
+            args: func_args![value: value!({"a": {"b": 1, "c": 2}, "a.b": 3, "a.c.d": 4, "x": {"y.z": 5}, "x.y": {"z": 6}})],
+            want: Ok(value!({"a.b": 3, "a.c": 2, "a.c.d": 4, "x.y.z": 6})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        key_collision_array_style {
+            args: func_args![value: value!({"a": [1, 2], "a.0": 3}), array_style: "indices"],
+            want: Ok(value!({"a.0": 3, "a.1": 2})),
+            tdef: TypeDef::object(Collection::any()),
+        }
     ];
 }
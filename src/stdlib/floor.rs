@@ -45,6 +45,13 @@ impl Function for Floor {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "precision" => Some(0.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -81,6 +81,7 @@ impl Function for ForEach {
                 },
             }],
             is_iterator: true,
+            is_optional: false,
         })
     }
 }
@@ -113,6 +113,15 @@ impl Function for FormatNumber {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "scale" => Some(Value::Null),
+            "decimal_separator" => Some(".".into()),
+            "grouping_separator" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
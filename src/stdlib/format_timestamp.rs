@@ -1,20 +1,37 @@
 use crate::compiler::prelude::*;
 use crate::compiler::TimeZone;
+use crate::stdlib::util::parse_timezone;
 use chrono::{
     format::{strftime::StrftimeItems, Item},
-    DateTime, Utc,
+    DateTime, Locale, Utc,
 };
 
-fn format_timestamp_with_tz(ts: Value, format: Value, timezone: Option<Value>) -> Resolved {
+fn format_timestamp_with_tz(
+    ts: Value,
+    format: Value,
+    timezone: Option<Value>,
+    locale: Option<Value>,
+    host_timezone: TimeZone,
+) -> Resolved {
     let ts: DateTime<Utc> = ts.try_timestamp()?;
 
     let format_bytes = format.try_bytes()?;
     let format = String::from_utf8_lossy(&format_bytes);
 
-    let timezone_bytes = timezone.map(VrlValueConvert::try_bytes).transpose()?;
-    let timezone = timezone_bytes.as_ref().map(|b| String::from_utf8_lossy(b));
+    let timezone = timezone
+        .map(|timezone| parse_timezone(&timezone, host_timezone))
+        .transpose()?;
+
+    let locale = locale.map(parse_locale).transpose()?;
+
+    try_format_with_timezone(ts, &format, timezone, locale).map(Into::into)
+}
 
-    try_format_with_timezone(ts, &format, timezone.as_deref()).map(Into::into)
+fn parse_locale(locale: Value) -> ExpressionResult<Locale> {
+    let locale_bytes = locale.try_bytes()?;
+    let locale = String::from_utf8_lossy(&locale_bytes);
+
+    Locale::try_from(locale.as_ref()).map_err(|_| format!("unknown locale: {locale}").into())
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -42,23 +59,50 @@ impl Function for FormatTimestamp {
                 kind: kind::BYTES,
                 required: false,
             },
+            Parameter {
+                keyword: "locale",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timezone" => Some(Value::Null),
+            "locale" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let format = arguments.required("format");
         let timezone = arguments.optional("timezone");
+        let locale = arguments.optional("locale");
+
+        if let Some(literal) = locale
+            .as_ref()
+            .and_then(|locale| locale.resolve_constant(state))
+        {
+            // check if the locale is known
+            parse_locale(literal.clone()).map_err(|_| function::Error::InvalidArgument {
+                keyword: "locale",
+                value: literal,
+                error: "unknown locale",
+            })?;
+        }
 
         Ok(FormatTimestampFn {
             value,
             format,
             timezone,
+            locale,
         }
         .as_expr())
     }
@@ -75,6 +119,16 @@ impl Function for FormatTimestamp {
                 source: r#"format_timestamp!(t'2021-02-10T23:32:00+00:00', format: "%d %B %Y %H:%M", timezone: "Europe/Berlin")"#,
                 result: Ok("11 February 2021 00:32"),
             },
+            Example {
+                title: "format timestamp with locale",
+                source: r#"format_timestamp!(t'2021-02-10T23:32:00+00:00', format: "%A %d %B %Y", locale: "fr_FR")"#,
+                result: Ok("mercredi 10 février 2021"),
+            },
+            Example {
+                title: "format ISO week date",
+                source: r#"format_timestamp!(t'2021-01-01T00:00:00+00:00', format: "%G-W%V-%u")"#,
+                result: Ok("2020-W53-5"),
+            },
         ]
     }
 }
@@ -84,6 +138,7 @@ struct FormatTimestampFn {
     value: Box<dyn Expression>,
     format: Box<dyn Expression>,
     timezone: Option<Box<dyn Expression>>,
+    locale: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for FormatTimestampFn {
@@ -95,8 +150,13 @@ impl FunctionExpression for FormatTimestampFn {
             .as_ref()
             .map(|tz| tz.resolve(ctx))
             .transpose()?;
+        let locale = self
+            .locale
+            .as_ref()
+            .map(|locale| locale.resolve(ctx))
+            .transpose()?;
 
-        format_timestamp_with_tz(ts, bytes, tz)
+        format_timestamp_with_tz(ts, bytes, tz, locale, *ctx.timezone())
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -107,31 +167,45 @@ impl FunctionExpression for FormatTimestampFn {
 fn try_format_with_timezone(
     dt: DateTime<Utc>,
     format: &str,
-    timezone: Option<&str>,
+    timezone: Option<TimeZone>,
+    locale: Option<Locale>,
 ) -> ExpressionResult<String> {
-    let items = StrftimeItems::new(format)
-        .map(|item| match item {
-            Item::Error => Err("invalid format".into()),
-            _ => Ok(item),
-        })
-        .collect::<ExpressionResult<Vec<_>>>()?;
-
-    let timezone = timezone
-        .map(|timezone| {
-            TimeZone::parse(timezone).ok_or(format!("unable to parse timezone: {timezone}"))
-        })
-        .transpose()?;
+    let items = match locale {
+        Some(locale) => StrftimeItems::new_with_locale(format, locale),
+        None => StrftimeItems::new(format),
+    }
+    .map(|item| match item {
+        Item::Error => Err("invalid format".into()),
+        _ => Ok(item),
+    })
+    .collect::<ExpressionResult<Vec<_>>>()?;
 
     match timezone {
-        Some(TimeZone::Named(tz)) => Ok(dt
-            .with_timezone(&tz)
-            .format_with_items(items.into_iter())
-            .to_string()),
-        Some(TimeZone::Local) => Ok(dt
-            .with_timezone(&chrono::Local)
-            .format_with_items(items.into_iter())
-            .to_string()),
-        None => Ok(dt.format_with_items(items.into_iter()).to_string()),
+        Some(TimeZone::Named(tz)) => Ok(format_with_locale(&dt.with_timezone(&tz), items, locale)),
+        Some(TimeZone::Local) => Ok(format_with_locale(
+            &dt.with_timezone(&chrono::Local),
+            items,
+            locale,
+        )),
+        Some(TimeZone::Fixed(offset)) => Ok(format_with_locale(
+            &dt.with_timezone(&offset),
+            items,
+            locale,
+        )),
+        None => Ok(format_with_locale(&dt, items, locale)),
+    }
+}
+
+fn format_with_locale<Tz>(dt: &DateTime<Tz>, items: Vec<Item<'_>>, locale: Option<Locale>) -> String
+where
+    Tz: chrono::TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    match locale {
+        Some(locale) => dt
+            .format_localized_with_items(items.into_iter(), locale)
+            .to_string(),
+        None => dt.format_with_items(items.into_iter()).to_string(),
     }
 }
 
@@ -181,6 +255,14 @@ mod tests {
             tdef: TypeDef::bytes().fallible(),
         }
 
+        tz_offset {
+            args: func_args![value: Utc.timestamp_opt(10, 0).single().expect("invalid timestamp"),
+                             format: "%+",
+                             timezone: "-03:30"],
+            want: Ok(value!("1969-12-31T20:30:10-03:30")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
         invalid_tz {
             args: func_args![value: Utc.timestamp_opt(10, 0).single().expect("invalid timestamp"),
                              format: "%+",
@@ -188,5 +270,105 @@ mod tests {
             want: Err("unable to parse timezone: llocal"),
             tdef: TypeDef::bytes().fallible(),
         }
+
+        locale_en_us {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%A %d %B %Y",
+                             locale: "en_US"],
+            want: Ok(value!("Wednesday 10 February 2021")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        locale_fr_fr {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%A %d %B %Y",
+                             locale: "fr_FR"],
+            want: Ok(value!("mercredi 10 février 2021")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        locale_de_de {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%A %d %B %Y",
+                             locale: "de_DE"],
+            want: Ok(value!("Mittwoch 10 Februar 2021")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        locale_es_es {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%a %d %b %Y",
+                             locale: "es_ES"],
+            want: Ok(value!("mié 10 feb 2021")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        locale_ja_jp {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%A %B",
+                             locale: "ja_JP"],
+            want: Ok(value!("水曜日 2月")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        locale_with_tz {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).single().expect("invalid timestamp"),
+                             format: "%A %d %B %Y %H:%M",
+                             timezone: "Europe/Paris",
+                             locale: "fr_FR"],
+            want: Ok(value!("jeudi 11 février 2021 00:32")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        iso_week_year_boundary {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).single().expect("invalid timestamp"),
+                             format: "%G-W%V-%u"],
+            want: Ok(value!("2020-W53-5")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        iso_week_first_week {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).single().expect("invalid timestamp"),
+                             format: "%G-W%V-%u"],
+            want: Ok(value!("2021-W01-1")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        iso_week_previous_year {
+            args: func_args![value: Utc.with_ymd_and_hms(2019, 12, 30, 0, 0, 0).single().expect("invalid timestamp"),
+                             format: "%G-W%V-%u %Y"],
+            want: Ok(value!("2020-W01-1 2019")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        iso_week_localized {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 1, 3, 0, 0, 0).single().expect("invalid timestamp"),
+                             format: "%G-W%V-%u %A",
+                             locale: "fr_FR"],
+            want: Ok(value!("2020-W53-7 dimanche")),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+
+    test_function![
+        format_timestamp_host => FormatTimestamp;
+
+        tz_local_uses_host_tz {
+            args: func_args![value: Utc.timestamp_opt(10, 0).single().expect("invalid timestamp"),
+                             format: "%+",
+                             timezone: "local"],
+            want: Ok(value!("1970-01-01T09:00:10+09:00")),
+            tdef: TypeDef::bytes().fallible(),
+            tz: crate::compiler::TimeZone::Named(chrono_tz::Asia::Tokyo),
+        }
+
+        tz_local_uses_host_offset {
+            args: func_args![value: Utc.timestamp_opt(10, 0).single().expect("invalid timestamp"),
+                             format: "%+",
+                             timezone: "local"],
+            want: Ok(value!("1970-01-01T02:00:10+02:00")),
+            tdef: TypeDef::bytes().fallible(),
+            tz: crate::compiler::TimeZone::Fixed(chrono::FixedOffset::east_opt(7200).unwrap()),
+        }
     ];
 }
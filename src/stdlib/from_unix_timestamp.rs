@@ -6,7 +6,8 @@ fn from_unix_timestamp(value: Value, unit: Unit) -> Resolved {
     use Value::Integer;
 
     let value = match value {
-        Integer(v) => match unit {
+        Integer(v) => match unit.resolve(v) {
+            Unit::Auto => unreachable!("auto unit is resolved"),
             Unit::Seconds => match Utc.timestamp_opt(v, 0).single() {
                 Some(time) => time.into(),
                 None => return Err(format!("unable to coerce {v} into timestamp").into()),
@@ -49,6 +50,13 @@ impl Function for FromUnixTimestamp {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "unit" => Some(Unit::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -71,6 +79,11 @@ impl Function for FromUnixTimestamp {
                 source: r#"from_unix_timestamp!(5000, unit: "nanoseconds")"#,
                 result: Ok("t'1970-01-01T00:00:00.000005Z'"),
             },
+            Example {
+                title: "integer with auto-detected unit",
+                source: r#"from_unix_timestamp!(1609459200000, unit: "auto")"#,
+                result: Ok("t'2021-01-01T00:00:00Z'"),
+            },
         ]
     }
 
@@ -101,26 +114,53 @@ enum Unit {
     Milliseconds,
     Microseconds,
     Nanoseconds,
+
+    /// Detects the unit from the magnitude of the timestamp, which is correct for dates between
+    /// 1973 and 5138. Timestamps below `10^11` are seconds, below `10^14` milliseconds, below
+    /// `10^17` microseconds, and any larger ones are nanoseconds. Negative timestamps are
+    /// compared by their absolute value.
+    Auto,
 }
 
+// The smallest timestamps, in absolute value, that `Unit::Auto` detects as milliseconds,
+// microseconds and nanoseconds. `10^11` seconds is in the year 5138, and `10^11` milliseconds in
+// 1973, so timestamps between those years are detected correctly in any unit.
+const AUTO_MILLISECONDS: u64 = 100_000_000_000;
+const AUTO_MICROSECONDS: u64 = AUTO_MILLISECONDS * 1000;
+const AUTO_NANOSECONDS: u64 = AUTO_MICROSECONDS * 1000;
+
 impl Unit {
     fn all_value() -> Vec<Value> {
-        use Unit::{Microseconds, Milliseconds, Nanoseconds, Seconds};
+        use Unit::{Auto, Microseconds, Milliseconds, Nanoseconds, Seconds};
 
-        vec![Seconds, Milliseconds, Microseconds, Nanoseconds]
+        vec![Seconds, Milliseconds, Microseconds, Nanoseconds, Auto]
             .into_iter()
             .map(|u| u.as_str().into())
             .collect::<Vec<_>>()
     }
 
     const fn as_str(self) -> &'static str {
-        use Unit::{Microseconds, Milliseconds, Nanoseconds, Seconds};
+        use Unit::{Auto, Microseconds, Milliseconds, Nanoseconds, Seconds};
 
         match self {
             Seconds => "seconds",
             Milliseconds => "milliseconds",
             Microseconds => "microseconds",
             Nanoseconds => "nanoseconds",
+            Auto => "auto",
+        }
+    }
+
+    /// Returns the unit of the given timestamp, detecting it if it's [`Unit::Auto`].
+    const fn resolve(self, timestamp: i64) -> Self {
+        match self {
+            Self::Auto => match timestamp.unsigned_abs() {
+                t if t < AUTO_MILLISECONDS => Self::Seconds,
+                t if t < AUTO_MICROSECONDS => Self::Milliseconds,
+                t if t < AUTO_NANOSECONDS => Self::Microseconds,
+                _ => Self::Nanoseconds,
+            },
+            unit => unit,
         }
     }
 }
@@ -129,13 +169,14 @@ impl FromStr for Unit {
     type Err = &'static str;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        use Unit::{Microseconds, Milliseconds, Nanoseconds, Seconds};
+        use Unit::{Auto, Microseconds, Milliseconds, Nanoseconds, Seconds};
 
         match s {
             "seconds" => Ok(Seconds),
             "milliseconds" => Ok(Milliseconds),
             "microseconds" => Ok(Microseconds),
             "nanoseconds" => Ok(Nanoseconds),
+            "auto" => Ok(Auto),
             _ => Err("unit not recognized"),
         }
     }
@@ -216,6 +257,48 @@ mod tests {
             tdef: TypeDef::timestamp().fallible(),
         }
 
+        auto_seconds {
+            args: func_args![value: 1_609_459_200_i64, unit: "auto"],
+            want: Ok(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0,0,0,0)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        auto_milliseconds {
+            args: func_args![value: 1_609_459_200_123_i64, unit: "auto"],
+            want: Ok(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0,0,0,123)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        auto_microseconds {
+            args: func_args![value: 1_609_459_200_000_000_i64, unit: "auto"],
+            want: Ok(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0,0,0,0)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        auto_nanoseconds {
+            args: func_args![value: 1_609_459_200_000_000_000_i64, unit: "auto"],
+            want: Ok(chrono::Utc.ymd(2021, 1, 1).and_hms_milli(0,0,0,0)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        auto_negative_seconds {
+            args: func_args![value: (-86_400_000_i64), unit: "auto"],
+            want: Ok(chrono::Utc.ymd(1967, 4, 7).and_hms_milli(0,0,0,0)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        explicit_unit_overrides_detection {
+            args: func_args![value: 1_609_459_200_i64, unit: "milliseconds"],
+            want: Ok(chrono::Utc.ymd(1970, 1, 19).and_hms_milli(15,4,19,200)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        explicit_milliseconds_for_small_value {
+            args: func_args![value: 5000, unit: "milliseconds"],
+            want: Ok(chrono::Utc.ymd(1970, 1, 1).and_hms_milli(0,0,5,0)),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
         float_type_invalid {
             args: func_args![value: 5.123],
             want: Err("unable to coerce float into timestamp"),
@@ -13,11 +13,13 @@ fn get(value: Value, value_path: Value) -> Resolved {
                     }
                     Value::Integer(index) => OwnedSegment::index(index as isize),
                     value => {
-                        return Err(format!(
-                            r#"path segment must be either string or integer, not {}"#,
-                            value.kind()
-                        )
-                        .into())
+                        return Err(ExpressionError::with_code(
+                            ErrorCode::InvalidType,
+                            format!(
+                                r#"path segment must be either string or integer, not {}"#,
+                                value.kind()
+                            ),
+                        ))
                     }
                 };
                 path.push(segment);
@@ -1,22 +1,15 @@
 use crate::compiler::prelude::*;
 
-fn is_empty(value: Value) -> Resolved {
-    let empty = match value {
+/// Returns whether the value is an empty string, array or object, or `null`. Other values, such
+/// as `0` or `false`, aren't empty.
+fn is_empty(value: &Value) -> bool {
+    match value {
         Value::Object(v) => v.is_empty(),
         Value::Array(v) => v.is_empty(),
         Value::Bytes(v) => v.is_empty(),
-        value => {
-            return Err(ValueError::Expected {
-                got: value.kind(),
-                expected: Kind::array(Collection::any())
-                    | Kind::object(Collection::any())
-                    | Kind::bytes(),
-            }
-            .into())
-        }
-    };
-
-    Ok(empty.into())
+        Value::Null => true,
+        _ => false,
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -30,7 +23,7 @@ impl Function for IsEmpty {
     fn parameters(&self) -> &'static [Parameter] {
         &[Parameter {
             keyword: "value",
-            kind: kind::OBJECT | kind::ARRAY | kind::BYTES,
+            kind: kind::ANY,
             required: true,
         }]
     }
@@ -57,6 +50,16 @@ impl Function for IsEmpty {
                 source: r#"is_empty([1,2,3])"#,
                 result: Ok("false"),
             },
+            Example {
+                title: "null",
+                source: r#"is_empty(null)"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "other values",
+                source: r#"is_empty(0)"#,
+                result: Ok("false"),
+            },
         ]
     }
 
@@ -80,7 +83,7 @@ struct IsEmptyFn {
 impl FunctionExpression for IsEmptyFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
-        is_empty(value)
+        Ok(is_empty(&value).into())
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -131,5 +134,29 @@ mod tests {
             want: Ok(value!(false)),
             tdef: TypeDef::boolean().infallible(),
         }
+
+        null {
+            args: func_args![value: value!(null)],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        whitespace_string {
+            args: func_args![value: " "],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        scalars {
+            args: func_args![value: 0],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        false_is_not_empty {
+            args: func_args![value: false],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
     ];
 }
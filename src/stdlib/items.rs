@@ -0,0 +1,113 @@
+use crate::compiler::prelude::*;
+
+fn items(value: Value) -> Resolved {
+    let object = value.try_object()?;
+    let items = object
+        .into_iter()
+        .map(|(key, value)| Value::Array(vec![Value::from(key), value]));
+    Ok(Value::Array(items.collect()))
+}
+
+#[derive(Debug)]
+pub struct Items;
+
+impl Function for Items {
+    fn identifier(&self) -> &'static str {
+        "items"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::OBJECT,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "get items",
+                source: r#"items({"key1": "val1", "key2": "val2"})"#,
+                result: Ok(r#"[["key1", "val1"], ["key2", "val2"]]"#),
+            },
+            Example {
+                title: "get items from a nested object",
+                source: r#"items({"key1": "val1", "key2": {"nestedkey1": "val3"}})"#,
+                result: Ok(r#"[["key1", "val1"], ["key2", { "nestedkey1": "val3" }]]"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        Ok(ItemsFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ItemsFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ItemsFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        items(self.value.resolve(ctx)?)
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        // Like `values`, the type of all possible values is merged together.
+        let merged_kind = self
+            .value
+            .type_def(state)
+            .kind()
+            .as_object()
+            .unwrap()
+            .reduced_kind();
+        let item = Collection::empty()
+            .with_known(0, Kind::bytes())
+            .with_known(1, merged_kind);
+        TypeDef::array(Collection::empty().with_unknown(Kind::array(item))).infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+    use std::collections::BTreeMap;
+
+    fn item_kind(value: Kind) -> Kind {
+        Kind::array(
+            Collection::empty()
+                .with_known(0, Kind::bytes())
+                .with_known(1, value),
+        )
+    }
+
+    test_function![
+        items => Items;
+
+        object {
+            args: func_args![value: value!({"b": 1, "a": {"c": true}})],
+            want: Ok(value!([["a", {"c": true}], ["b", 1]])),
+            tdef: TypeDef::array(Collection::empty().with_unknown(item_kind(
+                Kind::integer().or_object(Collection::from_parts(
+                    BTreeMap::from([("c".into(), Kind::boolean())]),
+                    Kind::undefined(),
+                )),
+            ))),
+        }
+
+        empty {
+            args: func_args![value: value!({})],
+            want: Ok(value!([])),
+            tdef: TypeDef::array(Collection::empty().with_unknown(item_kind(Kind::never()))),
+        }
+    ];
+}
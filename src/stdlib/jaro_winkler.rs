@@ -0,0 +1,197 @@
+use crate::compiler::prelude::*;
+
+/// How much a common prefix raises the similarity, per character.
+const PREFIX_SCALE: f64 = 0.1;
+
+/// The longest prefix that raises the similarity.
+const MAX_PREFIX: usize = 4;
+
+/// Returns the Jaro similarity of two strings of Unicode scalar values, from `0.0` for strings
+/// without matching characters to `1.0` for equal strings.
+#[allow(clippy::cast_precision_loss)] // strings are far from 2^52 characters
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // Characters match if they're equal and not farther apart than this.
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::with_capacity(a.len());
+    for (i, a) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        if start >= end {
+            continue;
+        }
+        if let Some(j) = (start..end).find(|&j| !b_matched[j] && b[j] == *a) {
+            b_matched[j] = true;
+            a_matches.push(*a);
+        }
+    }
+
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+
+    let b_matches = b
+        .iter()
+        .zip(&b_matched)
+        .filter_map(|(b, matched)| matched.then_some(b));
+    let transpositions = a_matches
+        .iter()
+        .zip(b_matches)
+        .filter(|(a, b)| a != b)
+        .count()
+        / 2;
+
+    let matches = a_matches.len() as f64;
+    (matches / a.len() as f64
+        + matches / b.len() as f64
+        + (matches - transpositions as f64) / matches)
+        / 3.0
+}
+
+#[allow(clippy::cast_precision_loss)] // the prefix is at most 4 characters
+fn jaro_winkler(a: &Value, b: &Value) -> Resolved {
+    let a: Vec<char> = a.try_bytes_utf8_lossy()?.chars().collect();
+    let b: Vec<char> = b.try_bytes_utf8_lossy()?.chars().collect();
+
+    let similarity = jaro(&a, &b);
+    let prefix = a
+        .iter()
+        .zip(&b)
+        .take(MAX_PREFIX)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let similarity = similarity + prefix as f64 * PREFIX_SCALE * (1.0 - similarity);
+
+    Ok(Value::from_f64_or_zero(similarity))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct JaroWinkler;
+
+impl Function for JaroWinkler {
+    fn identifier(&self) -> &'static str {
+        "jaro_winkler"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "similar",
+                source: r#"floor(jaro_winkler("martha", "marhta"), precision: 4)"#,
+                result: Ok("0.9611"),
+            },
+            Example {
+                title: "equal",
+                source: r#"jaro_winkler("vector", "vector")"#,
+                result: Ok("1.0"),
+            },
+            Example {
+                title: "different",
+                source: r#"jaro_winkler("abc", "xyz")"#,
+                result: Ok("0.0"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+
+        Ok(JaroWinklerFn { a, b }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct JaroWinklerFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+}
+
+impl FunctionExpression for JaroWinklerFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+
+        jaro_winkler(&a, &b)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::float().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn similarity(a: &str, b: &str) -> f64 {
+        let similarity = jaro_winkler(&a.into(), &b.into()).unwrap();
+        (similarity.try_float().unwrap() * 10_000.0).round() / 10_000.0
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)] // the similarities are rounded
+    fn known_similarities() {
+        assert_eq!(similarity("martha", "marhta"), 0.9611);
+        assert_eq!(similarity("dwayne", "duane"), 0.84);
+        assert_eq!(similarity("dixon", "dicksonx"), 0.8133);
+        assert_eq!(similarity("crate", "trace"), 0.7333);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+        assert_eq!(similarity("same", "same"), 1.0);
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("", "a"), 0.0);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)] // the similarities are rounded
+    fn multibyte() {
+        // Each accented letter is a single character, rather than two bytes.
+        assert_eq!(similarity("café", "cafe"), similarity("cafx", "cafe"));
+        assert_eq!(similarity("日本語", "日本人"), similarity("abc", "abd"));
+        assert_eq!(similarity("ü", "ü"), 1.0);
+    }
+
+    test_function![
+        jaro_winkler => JaroWinkler;
+
+        similar {
+            args: func_args![a: "martha", b: "martha"],
+            want: Ok(1.0),
+            tdef: TypeDef::float().infallible(),
+        }
+
+        different {
+            args: func_args![a: "abc", b: "def"],
+            want: Ok(0.0),
+            tdef: TypeDef::float().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,267 @@
+use crate::compiler::prelude::*;
+
+/// Returns the number of single character insertions, deletions and substitutions needed to turn
+/// `a` into `b`, comparing Unicode scalar values.
+///
+/// With a `max_distance`, the computation stops as soon as the distance is known to be larger,
+/// and `max_distance + 1` is returned.
+fn distance(a: &str, b: &str, max_distance: Option<usize>) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Common prefixes and suffixes don't change the distance.
+    let prefix = a.iter().zip(&b).take_while(|(a, b)| a == b).count();
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+
+    let exceeded = max_distance.map_or(usize::MAX, |max| max.saturating_add(1));
+    if a.len().abs_diff(b.len()) >= exceeded {
+        return exceeded;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&min| min >= exceeded) {
+            return exceeded;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()].min(exceeded)
+}
+
+fn max_distance(value: Value) -> ExpressionResult<usize> {
+    let max = value.try_integer()?;
+    usize::try_from(max).map_err(|_| "max_distance must not be negative".into())
+}
+
+fn levenshtein(a: &Value, b: &Value, max_distance: Option<usize>) -> Resolved {
+    let a = a.try_bytes_utf8_lossy()?;
+    let b = b.try_bytes_utf8_lossy()?;
+    let distance = distance(&a, &b, max_distance);
+
+    Ok(i64::try_from(distance).unwrap_or(i64::MAX).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Levenshtein;
+
+impl Function for Levenshtein {
+    fn identifier(&self) -> &'static str {
+        "levenshtein"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_distance",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "max_distance" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "distance",
+                source: r#"levenshtein("kitten", "sitting")"#,
+                result: Ok("3"),
+            },
+            Example {
+                title: "multibyte characters",
+                source: r#"levenshtein("café", "cafe")"#,
+                result: Ok("1"),
+            },
+            Example {
+                title: "max distance",
+                source: r#"levenshtein("kitten", "a much longer string", max_distance: 5)"#,
+                result: Ok("6"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+        let max_distance = arguments.optional("max_distance");
+
+        // A literal maximum is validated once, at compile time.
+        let constant_max_distance = match max_distance
+            .as_ref()
+            .and_then(|expr| expr.resolve_constant(state))
+        {
+            Some(value) => Some(max_distance_constant(value)?),
+            None => None,
+        };
+
+        Ok(LevenshteinFn {
+            a,
+            b,
+            max_distance,
+            constant_max_distance,
+        }
+        .as_expr())
+    }
+}
+
+fn max_distance_constant(value: Value) -> Result<usize, function::Error> {
+    max_distance(value.clone()).map_err(|_| function::Error::InvalidArgument {
+        keyword: "max_distance",
+        value,
+        error: "max_distance must not be negative",
+    })
+}
+
+#[derive(Debug, Clone)]
+struct LevenshteinFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+    max_distance: Option<Box<dyn Expression>>,
+    constant_max_distance: Option<usize>,
+}
+
+impl FunctionExpression for LevenshteinFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+        let max_distance = match (&self.max_distance, self.constant_max_distance) {
+            (_, Some(max)) => Some(max),
+            (Some(expr), None) => Some(max_distance(expr.resolve(ctx)?)?),
+            (None, None) => None,
+        };
+
+        levenshtein(&a, &b, max_distance)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // A maximum that is only known at runtime can be negative.
+        TypeDef::integer()
+            .maybe_fallible(self.max_distance.is_some() && self.constant_max_distance.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        levenshtein => Levenshtein;
+
+        kitten_sitting {
+            args: func_args![a: "kitten", b: "sitting"],
+            want: Ok(3),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        flaw_lawn {
+            args: func_args![a: "flaw", b: "lawn"],
+            want: Ok(2),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        equal {
+            args: func_args![a: "vector", b: "vector"],
+            want: Ok(0),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        empty {
+            args: func_args![a: "", b: "abc"],
+            want: Ok(3),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        transposition_is_two_edits {
+            args: func_args![a: "ab", b: "ba"],
+            want: Ok(2),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        multibyte {
+            args: func_args![a: "naïve café", b: "naive cafe"],
+            want: Ok(2),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        emoji {
+            args: func_args![a: "👍🏽", b: "👍"],
+            want: Ok(1),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        case_sensitive {
+            args: func_args![a: "Vector", b: "vector"],
+            want: Ok(1),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        within_max_distance {
+            args: func_args![a: "kitten", b: "sitting", max_distance: 3],
+            want: Ok(3),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        exceeds_max_distance {
+            args: func_args![a: "kitten", b: "sitting", max_distance: 2],
+            want: Ok(3),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        exceeds_max_distance_by_length {
+            args: func_args![a: "a", b: "a very long string", max_distance: 0],
+            want: Ok(1),
+            tdef: TypeDef::integer().infallible(),
+        }
+
+        negative_max_distance {
+            args: func_args![a: "a", b: "b", max_distance: -1],
+            want: Err("invalid argument"),
+            tdef: TypeDef::integer().fallible(),
+        }
+    ];
+
+    #[test]
+    fn max_distance_stops_early() {
+        let a = "a".repeat(1000);
+        let b = "b".repeat(1000);
+
+        assert_eq!(distance(&a, &b, None), 1000);
+        assert_eq!(distance(&a, &b, Some(10)), 11);
+        assert_eq!(distance("abcdef", "azcdef", Some(1)), 1);
+    }
+}
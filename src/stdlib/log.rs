@@ -1,30 +1,64 @@
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use crate::compiler::prelude::*;
+use crate::compiler::{LogLevel, LogRecord};
 use crate::value;
-use tracing::{debug, error, info, trace, warn};
-
-fn log(rate_limit_secs: Value, level: &Bytes, value: Value, span: Span) -> Resolved {
-    let rate_limit_secs = rate_limit_secs.try_integer()?;
-    let res = value.to_string_lossy();
-    match level.as_ref() {
-        b"trace" => {
-            trace!(message = %res, internal_log_rate_secs = rate_limit_secs, vrl_position = span.start())
-        }
-        b"debug" => {
-            debug!(message = %res, internal_log_rate_secs = rate_limit_secs, vrl_position = span.start())
-        }
-        b"warn" => {
-            warn!(message = %res, internal_log_rate_secs = rate_limit_secs, vrl_position = span.start())
-        }
-        b"error" => {
-            error!(message = %res, internal_log_rate_secs = rate_limit_secs, vrl_position = span.start())
-        }
-        _ => {
-            info!(message = %res, internal_log_rate_secs = rate_limit_secs, vrl_position = span.start())
-        }
+
+fn log(
+    ctx: &Context,
+    rate_limit: &Mutex<RateLimit>,
+    rate_limit_secs: Value,
+    level: LogLevel,
+    value: &Value,
+    span: Span,
+) -> Resolved {
+    // Negative windows don't limit anything, the same as a window of zero seconds.
+    let rate_limit_secs = u64::try_from(rate_limit_secs.try_integer()?).unwrap_or_default();
+
+    let suppressed = rate_limit
+        .lock()
+        .expect("rate limit poisoned")
+        .check(Instant::now(), Duration::from_secs(rate_limit_secs));
+
+    if let Some(suppressed) = suppressed {
+        ctx.log_handler().log(&LogRecord {
+            level,
+            value,
+            span,
+            rate_limit_secs,
+            suppressed,
+        });
     }
+
     Ok(Value::Null)
 }
 
+/// Suppresses the messages of a call site that follow a logged message within its window.
+#[derive(Debug, Default)]
+struct RateLimit {
+    window_start: Option<Instant>,
+    suppressed: usize,
+}
+
+impl RateLimit {
+    /// Returns whether a message at `now` is logged, with the number of messages suppressed
+    /// since the previous one that was, or `None` if it's suppressed.
+    fn check(&mut self, now: Instant, window: Duration) -> Option<usize> {
+        match self.window_start {
+            Some(start) if now.saturating_duration_since(start) < window => {
+                self.suppressed += 1;
+                None
+            }
+            _ => {
+                self.window_start = Some(now);
+                Some(std::mem::take(&mut self.suppressed))
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Log;
 
@@ -53,6 +87,14 @@ impl Function for Log {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "level" => Some(LogLevel::default().as_str().into()),
+            "rate_limit_secs" => Some(1.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -75,20 +117,23 @@ impl Function for Log {
         ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
-        let levels = vec![
-            "trace".into(),
-            "debug".into(),
-            "info".into(),
-            "warn".into(),
-            "error".into(),
-        ];
+        let levels = [
+            LogLevel::Trace,
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+        ]
+        .map(|level| level.as_str().into());
 
         let value = arguments.required("value");
         let level = arguments
             .optional_enum("level", &levels, state)?
-            .unwrap_or_else(|| "info".into())
-            .try_bytes()
-            .expect("log level not bytes");
+            .map(|level| {
+                LogLevel::from_str(&level.try_bytes_utf8_lossy().expect("log level not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
         let rate_limit_secs = arguments.optional("rate_limit_secs");
 
         Ok(LogFn {
@@ -96,6 +141,7 @@ impl Function for Log {
             value,
             level,
             rate_limit_secs,
+            rate_limit: Arc::default(),
         }
         .as_expr())
     }
@@ -115,8 +161,11 @@ impl Function for Log {
 struct LogFn {
     span: Span,
     value: Box<dyn Expression>,
-    level: Bytes,
+    level: LogLevel,
     rate_limit_secs: Option<Box<dyn Expression>>,
+
+    /// The rate limit of this call site, shared by all copies of the program.
+    rate_limit: Arc<Mutex<RateLimit>>,
 }
 
 impl FunctionExpression for LogFn {
@@ -127,9 +176,14 @@ impl FunctionExpression for LogFn {
             None => value!(1),
         };
 
-        let span = self.span;
-
-        log(rate_limit_secs, &self.level, value, span)
+        log(
+            ctx,
+            &self.rate_limit,
+            rate_limit_secs,
+            self.level,
+            &value,
+            self.span,
+        )
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -142,6 +196,8 @@ mod tests {
     use tracing_test::traced_test;
 
     use super::*;
+    use crate::compiler::{state::RuntimeState, LogHandler, TargetValue};
+    use crate::value::Secrets;
 
     test_function![
         log => Log;
@@ -155,19 +211,201 @@ mod tests {
         }
     ];
 
+    #[derive(Debug, Default)]
+    struct Recorder(Mutex<Vec<(LogLevel, Value, Span, usize)>>);
+
+    impl LogHandler for Recorder {
+        fn log(&self, record: &LogRecord<'_>) {
+            self.0.lock().unwrap().push((
+                record.level,
+                record.value.clone(),
+                record.span,
+                record.suppressed,
+            ));
+        }
+    }
+
+    impl Recorder {
+        fn take(&self) -> Vec<(LogLevel, Value, Span, usize)> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    fn log_fn(value: Value, rate_limit_secs: i64) -> LogFn {
+        LogFn {
+            span: Span::new(0, 10),
+            value: expr!(value),
+            level: LogLevel::Warn,
+            rate_limit_secs: Some(expr!(rate_limit_secs)),
+            rate_limit: Arc::default(),
+        }
+    }
+
+    fn resolve(log_fn: &LogFn, recorder: &Recorder) {
+        let mut target = TargetValue {
+            value: value!({}),
+            metadata: value!({}),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone).with_log_handler(recorder);
+
+        assert_eq!(log_fn.resolve(&mut ctx), Ok(Value::Null));
+    }
+
+    #[test]
+    fn structured_fields() {
+        let recorder = Recorder::default();
+        let value = value!({"message": "rejected", "reason": {"code": 400}, "tags": ["a"]});
+
+        resolve(&log_fn(value.clone(), 0), &recorder);
+
+        assert_eq!(
+            recorder.take(),
+            [(LogLevel::Warn, value, Span::new(0, 10), 0)]
+        );
+    }
+
+    #[test]
+    fn suppression() {
+        let recorder = Recorder::default();
+        let log_fn = log_fn(value!("rejected"), 60);
+
+        for _ in 0..5 {
+            resolve(&log_fn, &recorder);
+        }
+        assert_eq!(recorder.take().len(), 1);
+        assert_eq!(log_fn.rate_limit.lock().unwrap().suppressed, 4);
+
+        // Copies of the program share the rate limit of the call site.
+        resolve(&log_fn.clone(), &recorder);
+        assert!(recorder.take().is_empty());
+
+        // Once the window has passed, the next message reports how many were suppressed.
+        log_fn.rate_limit.lock().unwrap().window_start =
+            Instant::now().checked_sub(Duration::from_secs(61));
+        resolve(&log_fn, &recorder);
+        assert_eq!(
+            recorder.take(),
+            [(LogLevel::Warn, value!("rejected"), Span::new(0, 10), 5)]
+        );
+    }
+
+    #[test]
+    fn no_rate_limit() {
+        let recorder = Recorder::default();
+        let log_fn = log_fn(value!("rejected"), 0);
+
+        for _ in 0..3 {
+            resolve(&log_fn, &recorder);
+        }
+
+        let records = recorder.take();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|(.., suppressed)| *suppressed == 0));
+    }
+
+    #[test]
+    fn rate_limit_window() {
+        let start = Instant::now();
+        let window = Duration::from_secs(10);
+        let mut rate_limit = RateLimit::default();
+
+        assert_eq!(rate_limit.check(start, window), Some(0));
+        assert_eq!(
+            rate_limit.check(start + Duration::from_secs(1), window),
+            None
+        );
+        assert_eq!(
+            rate_limit.check(start + Duration::from_secs(9), window),
+            None
+        );
+        assert_eq!(
+            rate_limit.check(start + Duration::from_secs(10), window),
+            Some(2)
+        );
+        assert_eq!(
+            rate_limit.check(start + Duration::from_secs(11), window),
+            None
+        );
+        assert_eq!(
+            rate_limit.check(start + Duration::from_secs(30), window),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn call_sites_are_limited_separately() {
+        let recorder = Arc::new(Recorder::default());
+        let program = crate::compiler::compile(
+            r#"
+                log("first", rate_limit_secs: 60)
+                log("second", level: "error", rate_limit_secs: 60)
+            "#,
+            &crate::stdlib::all(),
+        )
+        .unwrap()
+        .program;
+        let mut runtime = crate::compiler::runtime::Runtime::default()
+            .with_log_handler(Arc::clone(&recorder) as _);
+
+        for _ in 0..3 {
+            let mut target = TargetValue {
+                value: value!({}),
+                metadata: value!({}),
+                secrets: Secrets::default(),
+            };
+            runtime
+                .resolve(&mut target, &program, &TimeZone::default())
+                .unwrap();
+            runtime.clear();
+        }
+
+        let records = recorder.take();
+        assert_eq!(
+            records
+                .iter()
+                .map(|(level, value, ..)| (*level, value.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (LogLevel::Info, value!("first")),
+                (LogLevel::Error, value!("second")),
+            ]
+        );
+        assert_ne!(records[0].2, records[1].2);
+    }
+
     #[traced_test]
     #[test]
     fn output_quotes() {
         // Check that a message is logged without additional quotes
-        log(
-            value!(1),
-            &Bytes::from("warn"),
-            value!("simple test message"),
-            Default::default(),
-        )
-        .unwrap();
+        crate::compiler::TracingLogHandler.log(&LogRecord {
+            level: LogLevel::Warn,
+            value: &value!("simple test message"),
+            span: Span::default(),
+            rate_limit_secs: 1,
+            suppressed: 0,
+        });
 
         assert!(!logs_contain("\"simple test message\""));
         assert!(logs_contain("simple test message"));
     }
+
+    #[traced_test]
+    #[test]
+    fn tracing_reports_suppressed() {
+        crate::compiler::TracingLogHandler.log(&LogRecord {
+            level: LogLevel::Info,
+            value: &value!("after a burst"),
+            span: Span::new(7, 20),
+            rate_limit_secs: 10,
+            suppressed: 3,
+        });
+
+        assert!(logs_contain("suppressed=3"));
+        assert!(logs_contain("vrl_position=7"));
+        assert!(logs_contain("internal_log_rate_limit=false"));
+        assert!(!logs_contain("internal_log_rate_secs"));
+    }
 }
@@ -1,14 +1,25 @@
 use crate::compiler::prelude::*;
+use crate::stdlib::util::{
+    is_recursive, max_depth_exceeded, may_exceed_max_depth, resolve_max_depth, DEFAULT_MAX_DEPTH,
+};
 
 fn map_keys<T>(
     value: Value,
     recursive: bool,
+    max_depth: usize,
     ctx: &mut Context,
     runner: closure::Runner<T>,
 ) -> Resolved
 where
     T: Fn(&mut Context) -> Resolved,
 {
+    // Input that's nested too deeply is rejected up front, as what's left of it would be dropped
+    // recursively if the closure failed while iterating over it.
+    if recursive && value.exceeds_depth(max_depth) {
+        value.dismantle();
+        return Err(max_depth_exceeded(max_depth));
+    }
+
     let mut iter = value.into_iter(recursive);
 
     for item in iter.by_ref() {
@@ -40,9 +51,22 @@ impl Function for MapKeys {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "max_depth",
+                kind: kind::INTEGER,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "recursive" => Some(false.into()),
+            "max_depth" => Some(DEFAULT_MAX_DEPTH.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -71,11 +95,13 @@ impl Function for MapKeys {
     ) -> Compiled {
         let value = arguments.required("value");
         let recursive = arguments.optional("recursive");
+        let max_depth = arguments.optional("max_depth");
         let closure = arguments.required_closure()?;
 
         Ok(MapKeysFn {
             value,
             recursive,
+            max_depth,
             closure,
         }
         .as_expr())
@@ -99,6 +125,7 @@ impl Function for MapKeys {
                 },
             }],
             is_iterator: true,
+            is_optional: false,
         })
     }
 }
@@ -107,6 +134,7 @@ impl Function for MapKeys {
 struct MapKeysFn {
     value: Box<dyn Expression>,
     recursive: Option<Box<dyn Expression>>,
+    max_depth: Option<Box<dyn Expression>>,
     closure: FunctionClosure,
 }
 
@@ -116,6 +144,7 @@ impl FunctionExpression for MapKeysFn {
             None => false,
             Some(expr) => expr.resolve(ctx)?.try_boolean()?,
         };
+        let max_depth = resolve_max_depth(self.max_depth.as_deref(), ctx)?;
 
         let value = self.value.resolve(ctx)?;
         let FunctionClosure {
@@ -125,10 +154,92 @@ impl FunctionExpression for MapKeysFn {
         } = &self.closure;
         let runner = closure::Runner::new(variables, |ctx| block.resolve(ctx));
 
-        map_keys(value, recursive, ctx, runner)
+        map_keys(value, recursive, max_depth, ctx, runner)
     }
 
     fn type_def(&self, ctx: &state::TypeState) -> TypeDef {
-        self.value.type_def(ctx)
+        let value = self.value.type_def(ctx);
+
+        if is_recursive(self.recursive.as_deref(), ctx)
+            && may_exceed_max_depth(value.kind(), self.max_depth.as_deref(), ctx)
+        {
+            value.fallible()
+        } else {
+            value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{compile, state::RuntimeState, TargetValue};
+    use crate::value;
+    use crate::value::Secrets;
+
+    fn run(source: &str, event: Value) -> Resolved {
+        let program = compile(source, &crate::stdlib::all())
+            .expect("program should compile")
+            .program;
+        let mut target = TargetValue {
+            value: event,
+            metadata: Value::from(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        program.resolve(&mut ctx)
+    }
+
+    fn nested(key: &str, depth: usize) -> Value {
+        (0..depth).fold(value!(1), |value, _| {
+            Value::from(ObjectMap::from([(key.into(), value)]))
+        })
+    }
+
+    #[test]
+    fn hostile_depth() {
+        // Deep enough to overflow the stack if it were cloned or dropped recursively.
+        let value = (0..50_000).fold(value!(1), |value, _| value!({"a": [value]}));
+
+        let mut target = TargetValue {
+            value: Value::from(ObjectMap::new()),
+            metadata: Value::from(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+        let runner = closure::Runner::new(&[], |_| Ok(value!("b")));
+
+        assert_eq!(
+            map_keys(value, true, DEFAULT_MAX_DEPTH, &mut ctx, runner)
+                .map_err(|err| err.to_string()),
+            Err("maximum depth of 128 exceeded".to_owned())
+        );
+    }
+
+    #[test]
+    fn too_deeply_nested() {
+        let source = "map_keys(., recursive: true) -> |key| { upcase(key) } ?? null";
+
+        assert_eq!(run(source, nested("a", 128)), Ok(nested("A", 128)));
+        assert_eq!(run(source, nested("a", 129)), Ok(Value::Null));
+    }
+
+    #[test]
+    fn explicit_max_depth() {
+        let source = "map_keys!(., recursive: true, max_depth: 2) -> |key| { upcase(key) }";
+
+        assert_eq!(run(source, nested("a", 2)), Ok(nested("A", 2)));
+        assert_eq!(
+            run(source, nested("a", 3)).map_err(|err| err.to_string()),
+            Err(
+                "function call error for \"map_keys\" at (0:68): maximum depth of 2 exceeded"
+                    .to_owned()
+            )
+        );
     }
 }
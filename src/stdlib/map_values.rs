@@ -1,15 +1,26 @@
 use crate::compiler::prelude::*;
+use crate::stdlib::util::{
+    is_recursive, max_depth_exceeded, may_exceed_max_depth, resolve_max_depth, DEFAULT_MAX_DEPTH,
+};
 
 fn map_values<T>(
     value: Value,
     recursive: bool,
+    max_depth: usize,
     ctx: &mut Context,
     runner: closure::Runner<T>,
 ) -> Resolved
 where
     T: Fn(&mut Context) -> Resolved,
 {
-    let mut iter = value.into_iter(recursive);
+    // Collections are cloned to pass them to the closure, which recurses as deep as they're
+    // nested, so input that's nested too deeply is rejected before the closure sees any of it.
+    if recursive && value.exceeds_depth(max_depth) {
+        value.dismantle();
+        return Err(max_depth_exceeded(max_depth));
+    }
+
+    let mut iter = value.into_iter(recursive).with_max_depth(max_depth);
 
     for item in iter.by_ref() {
         let value = match item {
@@ -21,6 +32,10 @@ where
         runner.map_value(ctx, value)?;
     }
 
+    if iter.max_depth_exceeded() {
+        return Err(max_depth_exceeded(max_depth));
+    }
+
     Ok(iter.into())
 }
 
@@ -44,9 +59,22 @@ impl Function for MapValues {
                 kind: kind::BOOLEAN,
                 required: false,
             },
+            Parameter {
+                keyword: "max_depth",
+                kind: kind::INTEGER,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "recursive" => Some(false.into()),
+            "max_depth" => Some(DEFAULT_MAX_DEPTH.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -56,7 +84,7 @@ impl Function for MapValues {
             },
             Example {
                 title: "recursively map object values",
-                source: r#"map_values({ "a": 1, "b": [{ "c": 2 }, { "d": 3 }], "e": { "f": 4 } }, recursive: true) -> |value| { if is_integer(value) { int!(value) + 1 } else { value } }"#,
+                source: r#"map_values!({ "a": 1, "b": [{ "c": 2 }, { "d": 3 }], "e": { "f": 4 } }, recursive: true) -> |value| { if is_integer(value) { int!(value) + 1 } else { value } }"#,
                 result: Ok(r#"{ "a": 2, "b": [{ "c": 3 }, { "d": 4 }], "e": { "f": 5 } }"#),
             },
         ]
@@ -70,11 +98,13 @@ impl Function for MapValues {
     ) -> Compiled {
         let value = arguments.required("value");
         let recursive = arguments.optional("recursive");
+        let max_depth = arguments.optional("max_depth");
         let closure = arguments.required_closure()?;
 
         Ok(MapValuesFn {
             value,
             recursive,
+            max_depth,
             closure,
         }
         .as_expr())
@@ -98,6 +128,7 @@ impl Function for MapValues {
                 },
             }],
             is_iterator: true,
+            is_optional: false,
         })
     }
 }
@@ -106,6 +137,7 @@ impl Function for MapValues {
 struct MapValuesFn {
     value: Box<dyn Expression>,
     recursive: Option<Box<dyn Expression>>,
+    max_depth: Option<Box<dyn Expression>>,
     closure: FunctionClosure,
 }
 
@@ -115,6 +147,7 @@ impl FunctionExpression for MapValuesFn {
             None => false,
             Some(expr) => expr.resolve(ctx)?.try_boolean()?,
         };
+        let max_depth = resolve_max_depth(self.max_depth.as_deref(), ctx)?;
 
         let value = self.value.resolve(ctx)?;
         let FunctionClosure {
@@ -124,15 +157,26 @@ impl FunctionExpression for MapValuesFn {
         } = &self.closure;
         let runner = closure::Runner::new(variables, |ctx| block.resolve(ctx));
 
-        map_values(value, recursive, ctx, runner)
+        map_values(value, recursive, max_depth, ctx, runner)
     }
 
     fn type_def(&self, ctx: &state::TypeState) -> TypeDef {
         let mut value = self.value.type_def(ctx);
         let closure = self.closure.block_type_def.kind().clone();
 
+        // Collections returned by the closure are recursed into as well, so their depth can't be
+        // known up front.
+        let fallible = is_recursive(self.recursive.as_deref(), ctx)
+            && (closure.contains_object()
+                || closure.contains_array()
+                || may_exceed_max_depth(value.kind(), self.max_depth.as_deref(), ctx));
+
         recursive_type_def(&mut value, closure, true);
-        value
+        if fallible {
+            value.fallible()
+        } else {
+            value
+        }
     }
 }
 
@@ -153,3 +197,94 @@ fn recursive_type_def(from: &mut Kind, to: Kind, root: bool) {
         *from = to;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{compile, state::RuntimeState, TargetValue};
+    use crate::value;
+    use crate::value::Secrets;
+
+    fn run(source: &str, event: Value) -> Resolved {
+        let program = compile(source, &crate::stdlib::all())
+            .expect("program should compile")
+            .program;
+        let mut target = TargetValue {
+            value: event,
+            metadata: Value::from(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        program.resolve(&mut ctx)
+    }
+
+    fn nested(depth: usize) -> Value {
+        (0..depth).fold(value!(1), |value, _| value!({"a": [value]}))
+    }
+
+    #[test]
+    fn hostile_depth() {
+        // Deep enough to overflow the stack if it were cloned or dropped recursively.
+        let value = (0..50_000).fold(value!(1), |value, _| value!({"a": [value]}));
+
+        let mut target = TargetValue {
+            value: Value::from(ObjectMap::new()),
+            metadata: Value::from(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let timezone = TimeZone::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+        let runner = closure::Runner::new(&[], |_| Ok(Value::Null));
+
+        assert_eq!(
+            map_values(value, true, DEFAULT_MAX_DEPTH, &mut ctx, runner)
+                .map_err(|err| err.to_string()),
+            Err("maximum depth of 128 exceeded".to_owned())
+        );
+    }
+
+    #[test]
+    fn too_deeply_nested() {
+        let source = "map_values(., recursive: true) -> |value| { value } ?? null";
+
+        assert_eq!(run(source, nested(64)), Ok(nested(64)));
+        assert_eq!(run(source, nested(65)), Ok(Value::Null));
+    }
+
+    #[test]
+    fn explicit_max_depth() {
+        let result = run(
+            "map_values!(., recursive: true, max_depth: 3) -> |value| { value }",
+            nested(2),
+        );
+
+        assert_eq!(
+            result.map_err(|err| err.to_string()),
+            Err(
+                "function call error for \"map_values\" at (0:66): maximum depth of 3 exceeded"
+                    .to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn closure_output_is_bounded() {
+        // Every level of recursion adds another level of nesting.
+        let result = run(
+            "map_values!(., recursive: true) -> |value| { if is_array(value) { [value] } else { value } }",
+            value!({"a": []}),
+        );
+
+        assert_eq!(
+            result.map_err(|err| err.to_string()),
+            Err(
+                "function call error for \"map_values\" at (0:92): maximum depth of 128 exceeded"
+                    .to_owned()
+            )
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use crate::compiler::prelude::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Returns the number of prefix bits to keep, if it's between `0` and `max` inclusive.
+fn prefix_bits(value: &Value, max: u32) -> Option<u32> {
+    value
+        .as_integer()
+        .and_then(|bits| u32::try_from(bits).ok())
+        .filter(|&bits| bits <= max)
+}
+
+fn mask_ip(value: &Value, ipv4_bits: u32, ipv6_bits: u32) -> Resolved {
+    let ip: IpAddr = value
+        .try_bytes_utf8_lossy()?
+        .parse()
+        .map_err(|err| format!("unable to parse IP address: {err}"))?;
+
+    let masked = match ip {
+        IpAddr::V4(ip) => {
+            let mask = u32::MAX.checked_shl(32 - ipv4_bits).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(u32::from(ip) & mask))
+        }
+        IpAddr::V6(ip) => {
+            let mask = u128::MAX.checked_shl(128 - ipv6_bits).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(u128::from(ip) & mask))
+        }
+    };
+
+    Ok(masked.to_string().into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MaskIp;
+
+impl Function for MaskIp {
+    fn identifier(&self) -> &'static str {
+        "mask_ip"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "ipv4_bits",
+                kind: kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "ipv6_bits",
+                kind: kind::INTEGER,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "IPv4",
+                source: r#"mask_ip!("192.168.1.55", ipv4_bits: 24, ipv6_bits: 48)"#,
+                result: Ok("192.168.1.0"),
+            },
+            Example {
+                title: "IPv6",
+                source: r#"mask_ip!("2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv4_bits: 24, ipv6_bits: 48)"#,
+                result: Ok("2001:db8:85a3::"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let ipv4_bits = arguments.required("ipv4_bits");
+        let ipv6_bits = arguments.required("ipv6_bits");
+
+        // Literal bit counts are validated once, at compile time.
+        for (keyword, bits, max, error) in [
+            (
+                "ipv4_bits",
+                &ipv4_bits,
+                32,
+                "ipv4_bits must be between 0 and 32",
+            ),
+            (
+                "ipv6_bits",
+                &ipv6_bits,
+                128,
+                "ipv6_bits must be between 0 and 128",
+            ),
+        ] {
+            if let Some(value) = bits.resolve_constant(state) {
+                if prefix_bits(&value, max).is_none() {
+                    return Err(function::Error::InvalidArgument {
+                        keyword,
+                        value,
+                        error,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(MaskIpFn {
+            value,
+            ipv4_bits,
+            ipv6_bits,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MaskIpFn {
+    value: Box<dyn Expression>,
+    ipv4_bits: Box<dyn Expression>,
+    ipv6_bits: Box<dyn Expression>,
+}
+
+impl FunctionExpression for MaskIpFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let ipv4_bits = prefix_bits(&self.ipv4_bits.resolve(ctx)?, 32)
+            .ok_or("ipv4_bits must be between 0 and 32")?;
+        let ipv6_bits = prefix_bits(&self.ipv6_bits.resolve(ctx)?, 128)
+            .ok_or("ipv6_bits must be between 0 and 128")?;
+
+        mask_ip(&value, ipv4_bits, ipv6_bits)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // The value might not be an IP address.
+        TypeDef::bytes().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        mask_ip => MaskIp;
+
+        ipv4 {
+            args: func_args![value: "192.168.1.55", ipv4_bits: 24, ipv6_bits: 48],
+            want: Ok("192.168.1.0"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_unaligned {
+            args: func_args![value: "192.168.1.55", ipv4_bits: 20, ipv6_bits: 48],
+            want: Ok("192.168.0.0"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_all_bits {
+            args: func_args![value: "192.168.1.55", ipv4_bits: 32, ipv6_bits: 48],
+            want: Ok("192.168.1.55"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_no_bits {
+            args: func_args![value: "192.168.1.55", ipv4_bits: 0, ipv6_bits: 48],
+            want: Ok("0.0.0.0"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6 {
+            args: func_args![value: "2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv4_bits: 24, ipv6_bits: 48],
+            want: Ok("2001:db8:85a3::"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6_unaligned {
+            args: func_args![value: "2001:db8:85a3:8d3:1319:8a2e:370:7348", ipv4_bits: 24, ipv6_bits: 36],
+            want: Ok("2001:db8:8000::"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6_all_bits {
+            args: func_args![value: "2001:db8::1", ipv4_bits: 24, ipv6_bits: 128],
+            want: Ok("2001:db8::1"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        invalid_address {
+            args: func_args![value: "192.168.1", ipv4_bits: 24, ipv6_bits: 48],
+            want: Err("unable to parse IP address: invalid IP address syntax"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv4_bits_out_of_range {
+            args: func_args![value: "192.168.1.55", ipv4_bits: 33, ipv6_bits: 48],
+            want: Err("invalid argument"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        negative_ipv4_bits {
+            args: func_args![value: "192.168.1.55", ipv4_bits: -1, ipv6_bits: 48],
+            want: Err("invalid argument"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        ipv6_bits_out_of_range {
+            args: func_args![value: "2001:db8::1", ipv4_bits: 24, ipv6_bits: 129],
+            want: Err("invalid argument"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
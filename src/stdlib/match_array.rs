@@ -79,6 +79,13 @@ impl Function for MatchArray {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "all" => Some(false.into()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
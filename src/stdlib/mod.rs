@@ -38,8 +38,13 @@ mod wasm_unsupported_function;
 cfg_if::cfg_if! {
     if #[cfg(feature = "stdlib")] {
         mod abs;
+        mod adler32;
+        mod ansi;
         mod append;
         mod array;
+        mod array_difference;
+        mod array_intersect;
+        mod array_union;
         mod assert;
         mod assert_eq;
         mod boolean;
@@ -48,28 +53,42 @@ cfg_if::cfg_if! {
         mod compact;
         mod contains;
         mod contains_all;
+        mod contains_ansi;
+        mod crc32;
+        mod crc32c;
         mod decode_base16;
         mod decode_base64;
+        #[cfg(feature = "charset_encoding")]
+        mod decode_charset;
         mod decode_gzip;
+        mod decode_idna;
+        mod decode_mime_header;
         mod decode_mime_q;
         mod decode_percent;
         mod decode_snappy;
         mod decode_zlib;
         mod decode_zstd;
         mod decrypt;
+        mod dedup_adjacent;
         mod del;
+        mod dig;
         mod downcase;
         mod encode_base16;
         mod encode_base64;
+        #[cfg(feature = "charset_encoding")]
+        mod encode_charset;
         mod encode_gzip;
+        mod encode_idna;
         mod encode_json;
         mod encode_key_value;
         mod encode_logfmt;
+        mod encode_msgpack;
         mod encode_percent;
         mod encode_snappy;
         mod encode_zlib;
         mod encode_zstd;
         mod encrypt;
+        mod end_of_day;
         mod ends_with;
         mod exists;
         mod filter;
@@ -110,19 +129,24 @@ cfg_if::cfg_if! {
         mod is_regex;
         mod is_string;
         mod is_timestamp;
+        mod items;
+        mod jaro_winkler;
         mod join;
         mod keys;
         mod length;
+        mod levenshtein;
         mod log;
         mod log_util;
         mod map_keys;
         mod map_values;
+        mod mask_ip;
         mod r#match;
         mod match_any;
         mod match_array;
         mod match_datadog_query;
         mod md5;
         mod merge;
+        mod msgpack;
         mod mod_func;
         mod now;
         mod object;
@@ -138,23 +162,29 @@ cfg_if::cfg_if! {
         mod parse_glog;
         mod parse_grok;
         mod parse_groks;
+        mod parse_ini;
         mod parse_int;
         mod parse_json;
         mod parse_key_value;
         mod parse_klog;
         mod parse_linux_authorization;
         mod parse_logfmt;
+        mod parse_msgpack;
         mod parse_nginx_log;
+        mod parse_number_array;
+        mod parse_php_serialized;
         mod parse_query_string;
         mod parse_regex;
         mod parse_regex_all;
         mod parse_ruby_hash;
+        mod parse_semver;
         mod parse_syslog;
         mod parse_timestamp;
         mod parse_tokens;
         mod parse_url;
         mod parse_user_agent;
         mod parse_xml;
+        mod point_in_polygon;
         mod push;
         mod random_bool;
         mod random_bytes;
@@ -166,12 +196,15 @@ cfg_if::cfg_if! {
         mod reverse_dns;
         mod round;
         mod seahash;
+        mod semver_compare;
         mod set;
         mod sha1;
         mod sha2;
         mod sha3;
+        mod should_sample;
         mod slice;
         mod split;
+        mod start_of_day;
         mod starts_with;
         mod string;
         mod strip_ansi_escape_codes;
@@ -180,7 +213,9 @@ cfg_if::cfg_if! {
         mod tag_types_externally;
         mod tally;
         mod tally_value;
+        mod template;
         mod timestamp;
+        mod timezone;
         mod to_bool;
         mod to_float;
         mod to_int;
@@ -192,18 +227,25 @@ cfg_if::cfg_if! {
         mod to_unix_timestamp;
         mod community_id;
         mod truncate;
+        mod truthy;
         mod type_def;
         mod unique;
+        mod unflatten;
         mod unnest;
         mod upcase;
         mod uuid_v4;
         mod values;
+        mod wildcard_match;
 
         // -----------------------------------------------------------------------------
 
         pub use self::hmac::Hmac;
         pub use abs::Abs;
+        pub use adler32::Adler32;
         pub use append::Append;
+        pub use array_difference::ArrayDifference;
+        pub use array_intersect::ArrayIntersect;
+        pub use array_union::ArrayUnion;
         pub use assert::Assert;
         pub use assert_eq::AssertEq;
         pub use boolean::Boolean;
@@ -212,28 +254,42 @@ cfg_if::cfg_if! {
         pub use compact::Compact;
         pub use contains::Contains;
         pub use contains_all::ContainsAll;
+        pub use contains_ansi::ContainsAnsi;
+        pub use crc32::Crc32;
+        pub use crc32c::Crc32c;
         pub use decode_base16::DecodeBase16;
         pub use decode_base64::DecodeBase64;
+        #[cfg(feature = "charset_encoding")]
+        pub use decode_charset::DecodeCharset;
         pub use decode_gzip::DecodeGzip;
+        pub use decode_idna::DecodeIdna;
+        pub use decode_mime_header::DecodeMimeHeader;
         pub use decode_mime_q::DecodeMimeQ;
         pub use decode_percent::DecodePercent;
         pub use decode_snappy::DecodeSnappy;
         pub use decode_zlib::DecodeZlib;
         pub use decode_zstd::DecodeZstd;
         pub use decrypt::Decrypt;
+        pub use dedup_adjacent::DedupAdjacent;
         pub use del::Del;
+        pub use dig::Dig;
         pub use downcase::Downcase;
         pub use encode_base16::EncodeBase16;
         pub use encode_base64::EncodeBase64;
+        #[cfg(feature = "charset_encoding")]
+        pub use encode_charset::EncodeCharset;
         pub use encode_gzip::EncodeGzip;
+        pub use encode_idna::EncodeIdna;
         pub use encode_json::EncodeJson;
         pub use encode_key_value::EncodeKeyValue;
         pub use encode_logfmt::EncodeLogfmt;
+        pub use encode_msgpack::EncodeMsgpack;
         pub use encode_percent::EncodePercent;
         pub use encode_snappy::EncodeSnappy;
         pub use encode_zlib::EncodeZlib;
         pub use encode_zstd::EncodeZstd;
         pub use encrypt::Encrypt;
+        pub use end_of_day::EndOfDay;
         pub use ends_with::EndsWith;
         pub use exists::Exists;
         pub use filter::Filter;
@@ -274,12 +330,16 @@ cfg_if::cfg_if! {
         pub use is_regex::IsRegex;
         pub use is_string::IsString;
         pub use is_timestamp::IsTimestamp;
+        pub use items::Items;
+        pub use jaro_winkler::JaroWinkler;
         pub use join::Join;
         pub use keys::Keys;
         pub use length::Length;
+        pub use levenshtein::Levenshtein;
         pub use log::Log;
         pub use map_keys::MapKeys;
         pub use map_values::MapValues;
+        pub use mask_ip::MaskIp;
         pub use match_any::MatchAny;
         pub use match_array::MatchArray;
         pub use match_datadog_query::MatchDatadogQuery;
@@ -299,23 +359,29 @@ cfg_if::cfg_if! {
         pub use parse_glog::ParseGlog;
         pub use parse_grok::ParseGrok;
         pub use parse_groks::ParseGroks;
+        pub use parse_ini::ParseIni;
         pub use parse_int::ParseInt;
         pub use parse_json::ParseJson;
         pub use parse_key_value::ParseKeyValue;
         pub use parse_klog::ParseKlog;
         pub use parse_linux_authorization::ParseLinuxAuthorization;
         pub use parse_logfmt::ParseLogFmt;
+        pub use parse_msgpack::ParseMsgpack;
         pub use parse_nginx_log::ParseNginxLog;
+        pub use parse_number_array::ParseNumberArray;
+        pub use parse_php_serialized::ParsePhpSerialized;
         pub use parse_query_string::ParseQueryString;
         pub use parse_regex::ParseRegex;
         pub use parse_regex_all::ParseRegexAll;
         pub use parse_ruby_hash::ParseRubyHash;
+        pub use parse_semver::ParseSemver;
         pub use parse_syslog::ParseSyslog;
         pub use parse_timestamp::ParseTimestamp;
         pub use parse_tokens::ParseTokens;
         pub use parse_url::ParseUrl;
         pub use parse_user_agent::ParseUserAgent;
         pub use parse_xml::ParseXml;
+        pub use point_in_polygon::PointInPolygon;
         pub use push::Push;
         pub use r#match::Match;
         pub use random_bool::RandomBool;
@@ -327,11 +393,14 @@ cfg_if::cfg_if! {
         pub use replace::Replace;
         pub use reverse_dns::ReverseDns;
         pub use round::Round;
+        pub use semver_compare::SemverCompare;
         pub use set::Set;
         pub use sha2::Sha2;
         pub use sha3::Sha3;
+        pub use should_sample::ShouldSample;
         pub use slice::Slice;
         pub use split::Split;
+        pub use start_of_day::StartOfDay;
         pub use starts_with::StartsWith;
         pub use string::String;
         pub use strip_ansi_escape_codes::StripAnsiEscapeCodes;
@@ -340,7 +409,9 @@ cfg_if::cfg_if! {
         pub use tag_types_externally::TagTypesExternally;
         pub use tally::Tally;
         pub use tally_value::TallyValue;
+        pub use template::Template;
         pub use timestamp::Timestamp;
+        pub use timezone::Timezone;
         pub use to_bool::ToBool;
         pub use to_float::ToFloat;
         pub use to_int::ToInt;
@@ -351,12 +422,15 @@ cfg_if::cfg_if! {
         pub use to_syslog_severity::ToSyslogSeverity;
         pub use to_unix_timestamp::ToUnixTimestamp;
         pub use truncate::Truncate;
+        pub use truthy::Truthy;
         pub use type_def::TypeDef;
         pub use unique::Unique;
+        pub use unflatten::Unflatten;
         pub use unnest::Unnest;
         pub use upcase::Upcase;
         pub use uuid_v4::UuidV4;
         pub use values::Values;
+        pub use wildcard_match::WildcardMatch;
         pub use self::array::Array;
         pub use self::md5::Md5;
         pub use self::seahash::Seahash;
@@ -369,8 +443,12 @@ cfg_if::cfg_if! {
 pub fn all() -> Vec<Box<dyn Function>> {
     vec![
         Box::new(Abs),
+        Box::new(Adler32),
         Box::new(Append),
         Box::new(Array),
+        Box::new(ArrayDifference),
+        Box::new(ArrayIntersect),
+        Box::new(ArrayUnion),
         Box::new(Assert),
         Box::new(AssertEq),
         Box::new(Boolean),
@@ -379,28 +457,42 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(Compact),
         Box::new(Contains),
         Box::new(ContainsAll),
+        Box::new(ContainsAnsi),
+        Box::new(Crc32),
+        Box::new(Crc32c),
         Box::new(DecodeBase16),
         Box::new(DecodeBase64),
+        #[cfg(feature = "charset_encoding")]
+        Box::new(DecodeCharset),
         Box::new(DecodeGzip),
+        Box::new(DecodeIdna),
         Box::new(DecodePercent),
+        Box::new(DecodeMimeHeader),
         Box::new(DecodeMimeQ),
         Box::new(DecodeSnappy),
         Box::new(DecodeZlib),
         Box::new(DecodeZstd),
         Box::new(Decrypt),
+        Box::new(DedupAdjacent),
         Box::new(Del),
+        Box::new(Dig),
         Box::new(Downcase),
         Box::new(EncodeBase16),
         Box::new(EncodeBase64),
+        #[cfg(feature = "charset_encoding")]
+        Box::new(EncodeCharset),
         Box::new(EncodeGzip),
+        Box::new(EncodeIdna),
         Box::new(EncodeJson),
         Box::new(EncodeKeyValue),
         Box::new(EncodeLogfmt),
+        Box::new(EncodeMsgpack),
         Box::new(EncodePercent),
         Box::new(EncodeSnappy),
         Box::new(EncodeZlib),
         Box::new(EncodeZstd),
         Box::new(Encrypt),
+        Box::new(EndOfDay),
         Box::new(EndsWith),
         Box::new(Exists),
         Box::new(Filter),
@@ -441,12 +533,16 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(IsRegex),
         Box::new(IsString),
         Box::new(IsTimestamp),
+        Box::new(Items),
+        Box::new(JaroWinkler),
         Box::new(Join),
         Box::new(Keys),
         Box::new(Length),
+        Box::new(Levenshtein),
         Box::new(Log),
         Box::new(MapKeys),
         Box::new(MapValues),
+        Box::new(MaskIp),
         Box::new(Match),
         Box::new(MatchAny),
         Box::new(MatchArray),
@@ -468,23 +564,29 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(ParseGlog),
         Box::new(ParseGrok),
         Box::new(ParseGroks),
+        Box::new(ParseIni),
         Box::new(ParseInt),
         Box::new(ParseJson),
         Box::new(ParseKeyValue),
         Box::new(ParseKlog),
         Box::new(ParseLinuxAuthorization),
         Box::new(ParseLogFmt),
+        Box::new(ParseMsgpack),
         Box::new(ParseNginxLog),
+        Box::new(ParseNumberArray),
+        Box::new(ParsePhpSerialized),
         Box::new(ParseQueryString),
         Box::new(ParseRegex),
         Box::new(ParseRegexAll),
         Box::new(ParseRubyHash),
+        Box::new(ParseSemver),
         Box::new(ParseSyslog),
         Box::new(ParseTimestamp),
         Box::new(ParseTokens),
         Box::new(ParseUrl),
         Box::new(ParseUserAgent),
         Box::new(ParseXml),
+        Box::new(PointInPolygon),
         Box::new(Push),
         Box::new(RandomBool),
         Box::new(RandomBytes),
@@ -496,12 +598,15 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(ReverseDns),
         Box::new(Round),
         Box::new(Seahash),
+        Box::new(SemverCompare),
         Box::new(Set),
         Box::new(Sha1),
         Box::new(Sha2),
         Box::new(Sha3),
+        Box::new(ShouldSample),
         Box::new(Slice),
         Box::new(Split),
+        Box::new(StartOfDay),
         Box::new(StartsWith),
         Box::new(String),
         Box::new(StripAnsiEscapeCodes),
@@ -509,8 +614,10 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(Strlen),
         Box::new(Tally),
         Box::new(TallyValue),
+        Box::new(Template),
         Box::new(TagTypesExternally),
         Box::new(Timestamp),
+        Box::new(Timezone),
         Box::new(ToBool),
         Box::new(ToFloat),
         Box::new(ToInt),
@@ -522,11 +629,214 @@ pub fn all() -> Vec<Box<dyn Function>> {
         Box::new(ToUnixTimestamp),
         Box::new(CommunityID),
         Box::new(Truncate),
+        Box::new(Truthy),
         Box::new(TypeDef),
         Box::new(Unique),
+        Box::new(Unflatten),
         Box::new(Unnest),
         Box::new(Upcase),
         Box::new(UuidV4),
         Box::new(Values),
+        Box::new(WildcardMatch),
     ]
 }
+
+/// Returns the signatures of all functions in the standard library.
+#[cfg(feature = "stdlib")]
+#[must_use]
+pub fn signatures() -> Vec<crate::compiler::function::signature::FunctionSignature> {
+    all().iter().map(|function| function.signature()).collect()
+}
+
+#[cfg(all(test, feature = "stdlib"))]
+mod tests {
+    use crate::compiler::function::signature::{
+        ClosureVariableSignature, FunctionSignature, KindSignature, ReturnSignature,
+    };
+
+    use crate::value;
+    use crate::value::Kind;
+
+    fn signature(name: &str) -> FunctionSignature {
+        super::signatures()
+            .into_iter()
+            .find(|signature| signature.name == name)
+            .expect("function exists")
+    }
+
+    fn kind(display: &str, types: &[&'static str]) -> KindSignature {
+        KindSignature {
+            display: display.to_owned(),
+            types: types.to_vec(),
+        }
+    }
+
+    #[test]
+    fn signatures_cover_all_functions() {
+        assert_eq!(super::signatures().len(), super::all().len());
+    }
+
+    #[test]
+    fn upcase_signature() {
+        let signature = signature("upcase");
+
+        assert_eq!(signature.parameters.len(), 1);
+        assert_eq!(signature.parameters[0].name, "value");
+        assert_eq!(signature.parameters[0].kind, kind("string", &["string"]));
+        assert!(signature.parameters[0].required);
+        assert!(signature.closure.is_none());
+        assert_eq!(
+            signature.returns,
+            Some(ReturnSignature {
+                kind: kind("string", &["string"]),
+                fallible: false,
+                pure: true,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_json_signature() {
+        let signature = signature("parse_json");
+        let parameters = signature
+            .parameters
+            .iter()
+            .map(|p| (p.name, p.required))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            parameters,
+            vec![("value", true), ("max_depth", false), ("relaxed", false)]
+        );
+
+        let returns = signature.returns.expect("return type is known");
+        assert!(returns.fallible);
+        assert!(returns.pure);
+        assert_eq!(
+            returns.kind.types,
+            vec!["string", "integer", "float", "boolean", "null", "array", "object"]
+        );
+    }
+
+    #[test]
+    fn parameter_defaults() {
+        let defaults = signature("parse_key_value")
+            .parameters
+            .into_iter()
+            .map(|p| (p.name, p.default))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            defaults,
+            vec![
+                ("value", None),
+                ("key_value_delimiter", Some(value!("="))),
+                ("field_delimiter", Some(value!(" "))),
+                ("whitespace", Some(value!("lenient"))),
+                ("accept_standalone_key", Some(value!(true))),
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_parameters_have_defaults() {
+        let missing = super::all()
+            .into_iter()
+            .flat_map(|function| {
+                function
+                    .parameters()
+                    .iter()
+                    .filter(|parameter| {
+                        !parameter.required
+                            && function.parameter_default(parameter.keyword).is_none()
+                    })
+                    .map(|parameter| format!("{}: {}", function.identifier(), parameter.keyword))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(
+            missing.is_empty(),
+            "optional parameters without a default: {missing:?}"
+        );
+    }
+
+    #[test]
+    fn parameter_defaults_have_accepted_kinds() {
+        for function in super::all() {
+            for parameter in function.parameters() {
+                if let Some(default) = function.parameter_default(parameter.keyword) {
+                    assert!(
+                        !parameter.required,
+                        "{}: required parameter {} has a default",
+                        function.identifier(),
+                        parameter.keyword
+                    );
+                    assert!(
+                        default.is_null()
+                            || parameter.kind().is_superset(&Kind::from(&default)).is_ok(),
+                        "{}: default of {} has the wrong kind",
+                        function.identifier(),
+                        parameter.keyword
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn log_signature() {
+        let returns = signature("log").returns.expect("return type is known");
+
+        assert!(!returns.fallible);
+        assert!(!returns.pure);
+    }
+
+    #[test]
+    fn for_each_signature() {
+        let signature = signature("for_each");
+        let closure = signature.closure.expect("for_each has a closure");
+
+        assert!(closure.iterator);
+        assert_eq!(closure.inputs.len(), 1);
+        assert_eq!(closure.inputs[0].parameter, "value");
+        assert_eq!(
+            closure.inputs[0].variables,
+            vec![
+                ClosureVariableSignature::TargetInnerKey,
+                ClosureVariableSignature::TargetInnerValue,
+            ]
+        );
+        assert_eq!(
+            signature.returns.expect("return type is known").kind,
+            kind("null", &["null"])
+        );
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let json = serde_json::to_value(signature("to_int")).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "to_int",
+                "parameters": [{
+                    "name": "value",
+                    "kind": {
+                        "display": "any",
+                        "types": ["string", "integer", "float", "boolean", "timestamp", "regex", "null", "undefined", "array", "object"],
+                    },
+                    "required": true,
+                    "default": null,
+                }],
+                "closure": null,
+                "returns": {
+                    "kind": { "display": "integer", "types": ["integer"] },
+                    "fallible": true,
+                    "pure": true,
+                },
+            })
+        );
+    }
+}
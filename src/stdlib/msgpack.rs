@@ -0,0 +1,590 @@
+//! A [MessagePack](https://github.com/msgpack/msgpack/blob/master/spec.md) codec for values.
+//!
+//! Bytes are encoded as `bin` and timestamps as the timestamp extension type, using its most
+//! compact format. Strings (`str`) and `bin` both decode to bytes.
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::compiler::prelude::*;
+use crate::stdlib::util::max_depth_exceeded;
+
+/// The extension type of timestamps, which is `-1`.
+const TIMESTAMP_EXT: u8 = 0xff;
+
+/// Encodes a value as MessagePack.
+pub(crate) fn encode(value: &Value, buf: &mut Vec<u8>) -> ExpressionResult<()> {
+    match value {
+        Value::Bytes(bytes) => {
+            encode_len(buf, bytes.len(), [0xc4, 0xc5, 0xc6], None)?;
+            buf.extend_from_slice(bytes);
+        }
+        Value::Integer(int) => encode_integer(buf, *int),
+        Value::Float(float) => {
+            buf.push(0xcb);
+            buf.extend_from_slice(&float.into_inner().to_be_bytes());
+        }
+        Value::Boolean(false) => buf.push(0xc2),
+        Value::Boolean(true) => buf.push(0xc3),
+        Value::Null => buf.push(0xc0),
+        Value::Timestamp(timestamp) => encode_timestamp(buf, timestamp),
+        Value::Object(object) => {
+            encode_len(buf, object.len(), [0xde, 0xde, 0xdf], Some((0x80, 16)))?;
+            for (key, value) in object {
+                encode_len(buf, key.len(), [0xd9, 0xda, 0xdb], Some((0xa0, 32)))?;
+                buf.extend_from_slice(key.as_bytes());
+                encode(value, buf)?;
+            }
+        }
+        Value::Array(array) => {
+            encode_len(buf, array.len(), [0xdc, 0xdc, 0xdd], Some((0x90, 16)))?;
+            for value in array {
+                encode(value, buf)?;
+            }
+        }
+        Value::Regex(_) => return Err("regex values can't be encoded as msgpack".into()),
+    }
+
+    Ok(())
+}
+
+/// Encodes the marker and length of a value, using the `fix` marker and its limit if the format
+/// has one, or else the smallest of the 8, 16 and 32-bit `markers` that fits the length.
+///
+/// Formats without an 8-bit length repeat their 16-bit marker.
+#[allow(clippy::cast_possible_truncation)] // A `fix` length is less than its limit.
+fn encode_len(
+    buf: &mut Vec<u8>,
+    len: usize,
+    [marker8, marker16, marker32]: [u8; 3],
+    fix: Option<(u8, usize)>,
+) -> ExpressionResult<()> {
+    match (
+        fix,
+        u8::try_from(len),
+        u16::try_from(len),
+        u32::try_from(len),
+    ) {
+        (Some((marker, limit)), ..) if len < limit => buf.push(marker | len as u8),
+        (_, Ok(len), ..) if marker8 != marker16 => buf.extend_from_slice(&[marker8, len]),
+        (_, _, Ok(len), _) => {
+            buf.push(marker16);
+            buf.extend_from_slice(&len.to_be_bytes());
+        }
+        (.., Ok(len)) => {
+            buf.push(marker32);
+            buf.extend_from_slice(&len.to_be_bytes());
+        }
+        _ => return Err(format!("length {len} is too large to encode as msgpack").into()),
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Every cast is range-checked.
+fn encode_integer(buf: &mut Vec<u8>, int: i64) {
+    match int {
+        -32..=0x7f => buf.push(int as u8),
+        0x80..=0xff => buf.extend_from_slice(&[0xcc, int as u8]),
+        0x100..=0xffff => {
+            buf.push(0xcd);
+            buf.extend_from_slice(&(int as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buf.push(0xce);
+            buf.extend_from_slice(&(int as u32).to_be_bytes());
+        }
+        0x1_0000_0000.. => {
+            buf.push(0xcf);
+            buf.extend_from_slice(&(int as u64).to_be_bytes());
+        }
+        -0x80..=-33 => buf.extend_from_slice(&[0xd0, int as u8]),
+        -0x8000..=-0x81 => {
+            buf.push(0xd1);
+            buf.extend_from_slice(&(int as i16).to_be_bytes());
+        }
+        -0x8000_0000..=-0x8001 => {
+            buf.push(0xd2);
+            buf.extend_from_slice(&(int as i32).to_be_bytes());
+        }
+        _ => {
+            buf.push(0xd3);
+            buf.extend_from_slice(&int.to_be_bytes());
+        }
+    }
+}
+
+/// Encodes a timestamp in the 32-bit format if it's whole seconds that fit an unsigned 32-bit
+/// integer, in the 64-bit format if its seconds fit 34 bits, or else in the 96-bit format.
+fn encode_timestamp(buf: &mut Vec<u8>, timestamp: &DateTime<Utc>) {
+    let seconds = timestamp.timestamp();
+    let nanoseconds = timestamp.timestamp_subsec_nanos();
+
+    match (u32::try_from(seconds), u64::try_from(seconds)) {
+        (Ok(seconds), _) if nanoseconds == 0 => {
+            buf.extend_from_slice(&[0xd6, TIMESTAMP_EXT]);
+            buf.extend_from_slice(&seconds.to_be_bytes());
+        }
+        (_, Ok(seconds)) if seconds >> 34 == 0 => {
+            buf.extend_from_slice(&[0xd7, TIMESTAMP_EXT]);
+            buf.extend_from_slice(&(u64::from(nanoseconds) << 34 | seconds).to_be_bytes());
+        }
+        _ => {
+            buf.extend_from_slice(&[0xc7, 12, TIMESTAMP_EXT]);
+            buf.extend_from_slice(&nanoseconds.to_be_bytes());
+            buf.extend_from_slice(&seconds.to_be_bytes());
+        }
+    }
+}
+
+/// Decodes a single MessagePack value, which must span all of `bytes`, failing if collections are
+/// nested more than `max_depth` levels deep.
+pub(crate) fn decode(bytes: &[u8], max_depth: usize) -> ExpressionResult<Value> {
+    let mut decoder = Decoder {
+        input: bytes,
+        remaining_depth: max_depth,
+    };
+    let value = decoder.value().map_err(|error| match error {
+        Error::MaxDepth => max_depth_exceeded(max_depth),
+        Error::Invalid(message) => ExpressionError::with_code(
+            ErrorCode::Parse,
+            format!("unable to parse msgpack: {message}"),
+        ),
+    })?;
+
+    match decoder.input.len() {
+        0 => Ok(value),
+        len => Err(ExpressionError::with_code(
+            ErrorCode::Parse,
+            format!("unable to parse msgpack: {len} trailing bytes after the value"),
+        )),
+    }
+}
+
+enum Error {
+    MaxDepth,
+    Invalid(String),
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Invalid(message.to_owned())
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Invalid(message)
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    remaining_depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.input.len() {
+            return Err("unexpected end of input".into());
+        }
+
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.take(N)?.try_into().expect("N bytes"))
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take_array::<1>()?[0])
+    }
+
+    fn len16(&mut self) -> Result<usize, Error> {
+        Ok(u16::from_be_bytes(self.take_array()?).into())
+    }
+
+    fn len32(&mut self) -> Result<usize, Error> {
+        let len = u32::from_be_bytes(self.take_array()?);
+        usize::try_from(len).map_err(|_| format!("length {len} is too large").into())
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        let marker = self.u8()?;
+
+        let value = match marker {
+            0x00..=0x7f => Value::Integer(marker.into()),
+            0x80..=0x8f => self.object(usize::from(marker & 0x0f))?,
+            0x90..=0x9f => self.array(usize::from(marker & 0x0f))?,
+            0xa0..=0xbf => self.bytes(usize::from(marker & 0x1f))?,
+            0xc0 => Value::Null,
+            0xc1 => return Err("invalid marker 0xc1".into()),
+            0xc2 => Value::Boolean(false),
+            0xc3 => Value::Boolean(true),
+            0xc4 | 0xd9 => {
+                let len = self.u8()?.into();
+                self.bytes(len)?
+            }
+            0xc5 | 0xda => {
+                let len = self.len16()?;
+                self.bytes(len)?
+            }
+            0xc6 | 0xdb => {
+                let len = self.len32()?;
+                self.bytes(len)?
+            }
+            0xc7 => {
+                let len = self.u8()?.into();
+                self.ext(len)?
+            }
+            0xc8 => {
+                let len = self.len16()?;
+                self.ext(len)?
+            }
+            0xc9 => {
+                let len = self.len32()?;
+                self.ext(len)?
+            }
+            0xca => float(f32::from_be_bytes(self.take_array()?).into())?,
+            0xcb => float(f64::from_be_bytes(self.take_array()?))?,
+            0xcc => Value::Integer(self.u8()?.into()),
+            0xcd => Value::Integer(u16::from_be_bytes(self.take_array()?).into()),
+            0xce => Value::Integer(u32::from_be_bytes(self.take_array()?).into()),
+            0xcf => {
+                let int = u64::from_be_bytes(self.take_array()?);
+                Value::Integer(
+                    i64::try_from(int).map_err(|_| format!("integer {int} is out of range"))?,
+                )
+            }
+            0xd0 => Value::Integer(i8::from_be_bytes(self.take_array()?).into()),
+            0xd1 => Value::Integer(i16::from_be_bytes(self.take_array()?).into()),
+            0xd2 => Value::Integer(i32::from_be_bytes(self.take_array()?).into()),
+            0xd3 => Value::Integer(i64::from_be_bytes(self.take_array()?)),
+            0xd4 => self.ext(1)?,
+            0xd5 => self.ext(2)?,
+            0xd6 => self.ext(4)?,
+            0xd7 => self.ext(8)?,
+            0xd8 => self.ext(16)?,
+            0xdc => {
+                let len = self.len16()?;
+                self.array(len)?
+            }
+            0xdd => {
+                let len = self.len32()?;
+                self.array(len)?
+            }
+            0xde => {
+                let len = self.len16()?;
+                self.object(len)?
+            }
+            0xdf => {
+                let len = self.len32()?;
+                self.object(len)?
+            }
+            0xe0..=0xff => Value::Integer(i8::from_be_bytes([marker]).into()),
+        };
+
+        Ok(value)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<Value, Error> {
+        Ok(Value::Bytes(Bytes::copy_from_slice(self.take(len)?)))
+    }
+
+    fn array(&mut self, len: usize) -> Result<Value, Error> {
+        self.nested(|decoder| {
+            // Every element takes at least a byte, which bounds the allocation.
+            let mut array = Vec::with_capacity(len.min(decoder.input.len()));
+            for _ in 0..len {
+                array.push(decoder.value()?);
+            }
+            Ok(Value::Array(array))
+        })
+    }
+
+    fn object(&mut self, len: usize) -> Result<Value, Error> {
+        self.nested(|decoder| {
+            let mut object = ObjectMap::new();
+            for _ in 0..len {
+                let key = match decoder.value()? {
+                    Value::Bytes(key) => {
+                        String::from_utf8(key.into()).map_err(|_| "map key is not valid UTF-8")?
+                    }
+                    key => {
+                        return Err(
+                            format!("map keys must be strings, not {}", key.kind_str()).into()
+                        )
+                    }
+                };
+                object.insert(key.into(), decoder.value()?);
+            }
+            Ok(Value::Object(object))
+        })
+    }
+
+    fn nested(
+        &mut self,
+        decode: impl FnOnce(&mut Self) -> Result<Value, Error>,
+    ) -> Result<Value, Error> {
+        self.remaining_depth = self.remaining_depth.checked_sub(1).ok_or(Error::MaxDepth)?;
+        let value = decode(self)?;
+        self.remaining_depth += 1;
+        Ok(value)
+    }
+
+    fn ext(&mut self, len: usize) -> Result<Value, Error> {
+        let ext_type = self.u8()?;
+        let data = self.take(len)?;
+        if ext_type != TIMESTAMP_EXT {
+            let ext_type = i8::from_be_bytes([ext_type]);
+            return Err(format!("unsupported extension type {ext_type}").into());
+        }
+
+        let (seconds, nanoseconds) = match data.len() {
+            4 => (
+                u32::from_be_bytes(data.try_into().expect("4 bytes")).into(),
+                0,
+            ),
+            8 => {
+                let data = u64::from_be_bytes(data.try_into().expect("8 bytes"));
+                #[allow(clippy::cast_possible_truncation)] // The nanoseconds are 30 bits.
+                let nanoseconds = (data >> 34) as u32;
+                #[allow(clippy::cast_possible_wrap)] // The seconds are 34 bits.
+                let seconds = (data & ((1 << 34) - 1)) as i64;
+                (seconds, nanoseconds)
+            }
+            12 => (
+                i64::from_be_bytes(data[4..].try_into().expect("8 bytes")),
+                u32::from_be_bytes(data[..4].try_into().expect("4 bytes")),
+            ),
+            len => return Err(format!("invalid timestamp length {len}").into()),
+        };
+
+        match Utc.timestamp_opt(seconds, nanoseconds) {
+            chrono::LocalResult::Single(timestamp) if nanoseconds < 1_000_000_000 => {
+                Ok(Value::Timestamp(timestamp))
+            }
+            _ => Err("timestamp is out of range".into()),
+        }
+    }
+}
+
+fn float(float: f64) -> Result<Value, Error> {
+    NotNan::new(float)
+        .map(Value::Float)
+        .map_err(|_| "NaN can't be represented".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{QuickCheck, TestResult};
+
+    use super::*;
+    use crate::value;
+
+    fn encoded(value: &Value) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode(value, &mut buf).unwrap();
+        buf
+    }
+
+    fn timestamp(seconds: i64, nanoseconds: u32) -> Value {
+        Value::Timestamp(Utc.timestamp_opt(seconds, nanoseconds).unwrap())
+    }
+
+    /// Values and their encoding, as given by the specification, or as output by the reference
+    /// `msgpack` implementations with their default options.
+    fn fixtures() -> Vec<(Value, Vec<u8>)> {
+        vec![
+            (
+                value!({"compact": true, "schema": 0}),
+                b"\x82\xa7compact\xc3\xa6schema\x00".to_vec(),
+            ),
+            (value!(null), vec![0xc0]),
+            (value!(false), vec![0xc2]),
+            (value!(127), vec![0x7f]),
+            (value!(128), vec![0xcc, 0x80]),
+            (value!(300), vec![0xcd, 0x01, 0x2c]),
+            (value!(70000), vec![0xce, 0x00, 0x01, 0x11, 0x70]),
+            (
+                Value::Integer(4_294_967_296),
+                vec![0xcf, 0, 0, 0, 0x01, 0, 0, 0, 0],
+            ),
+            (Value::Integer(-1), vec![0xff]),
+            (Value::Integer(-32), vec![0xe0]),
+            (Value::Integer(-33), vec![0xd0, 0xdf]),
+            (Value::Integer(-129), vec![0xd1, 0xff, 0x7f]),
+            (Value::Integer(-40000), vec![0xd2, 0xff, 0xff, 0x63, 0xc0]),
+            (
+                Value::Integer(i64::MIN),
+                vec![0xd3, 0x80, 0, 0, 0, 0, 0, 0, 0],
+            ),
+            (value!(1.5), vec![0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]),
+            (
+                Value::Bytes(Bytes::from_static(b"\x00\xff")),
+                vec![0xc4, 0x02, 0x00, 0xff],
+            ),
+            (value!([1, [], {}]), vec![0x93, 0x01, 0x90, 0x80]),
+            (timestamp(1, 0), vec![0xd6, 0xff, 0, 0, 0, 0x01]),
+            (
+                timestamp(1, 500),
+                vec![0xd7, 0xff, 0, 0, 0x07, 0xd0, 0, 0, 0, 0x01],
+            ),
+            (
+                timestamp(-1, 0),
+                vec![
+                    0xc7, 0x0c, 0xff, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn encode_fixtures() {
+        for (value, bytes) in fixtures() {
+            assert_eq!(encoded(&value), bytes, "{value}");
+        }
+    }
+
+    #[test]
+    fn decode_fixtures() {
+        for (value, bytes) in fixtures() {
+            assert_eq!(decode(&bytes, 128).unwrap(), value, "{value}");
+        }
+    }
+
+    #[test]
+    fn decode_other_formats() {
+        let cases = [
+            (vec![0xa3, b'f', b'o', b'o'], value!("foo")),
+            (vec![0xd9, 0x03, b'f', b'o', b'o'], value!("foo")),
+            (vec![0xca, 0x3f, 0xc0, 0, 0], value!(1.5)),
+            (vec![0xcc, 0x01], value!(1)),
+            (vec![0xd3, 0, 0, 0, 0, 0, 0, 0, 0x01], value!(1)),
+            (vec![0xdc, 0, 0x01, 0xc0], value!([null])),
+            (vec![0x81, 0xc4, 0x01, b'a', 0x01], value!({"a": 1})),
+        ];
+
+        for (bytes, value) in cases {
+            assert_eq!(decode(&bytes, 128).unwrap(), value, "{bytes:x?}");
+        }
+    }
+
+    #[test]
+    fn long_bytes() {
+        let value = Value::Bytes(vec![b'a'; 300].into());
+        let bytes = encoded(&value);
+
+        assert_eq!(bytes[..3], [0xc5, 0x01, 0x2c]);
+        assert_eq!(decode(&bytes, 128).unwrap(), value);
+    }
+
+    #[test]
+    fn encode_regex() {
+        let value = Value::from(regex::Regex::new("a").unwrap());
+
+        assert_eq!(
+            encode(&value, &mut Vec::new()).unwrap_err().to_string(),
+            "regex values can't be encoded as msgpack"
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        let cases = [
+            (vec![], "unable to parse msgpack: unexpected end of input"),
+            (
+                vec![0x92, 0x01],
+                "unable to parse msgpack: unexpected end of input",
+            ),
+            (vec![0xc1], "unable to parse msgpack: invalid marker 0xc1"),
+            (
+                vec![0x01, 0x02],
+                "unable to parse msgpack: 1 trailing bytes after the value",
+            ),
+            (
+                vec![0xcf, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+                "unable to parse msgpack: integer 18446744073709551615 is out of range",
+            ),
+            (
+                vec![0xcb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 0],
+                "unable to parse msgpack: NaN can't be represented",
+            ),
+            (
+                vec![0x81, 0x01, 0x01],
+                "unable to parse msgpack: map keys must be strings, not integer",
+            ),
+            (
+                vec![0x81, 0xa1, 0xff, 0x01],
+                "unable to parse msgpack: map key is not valid UTF-8",
+            ),
+            (
+                vec![0xd4, 0x01, 0x00],
+                "unable to parse msgpack: unsupported extension type 1",
+            ),
+            (
+                vec![0xd5, 0xff, 0x00, 0x00],
+                "unable to parse msgpack: invalid timestamp length 2",
+            ),
+            (
+                vec![0xd7, 0xff, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0],
+                "unable to parse msgpack: timestamp is out of range",
+            ),
+        ];
+
+        for (bytes, error) in cases {
+            assert_eq!(
+                decode(&bytes, 128).unwrap_err().to_string(),
+                error,
+                "{bytes:x?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decode_max_depth() {
+        let bytes = [0x91, 0x81, 0xa1, b'a', 0x91, 0x01];
+
+        assert_eq!(decode(&bytes, 3).unwrap(), value!([{"a": [1]}]));
+        assert_eq!(
+            decode(&bytes, 2).unwrap_err().to_string(),
+            "maximum depth of 2 exceeded"
+        );
+    }
+
+    #[test]
+    fn decode_deeply_nested() {
+        let bytes = vec![0x91; 100_000];
+
+        assert_eq!(
+            decode(&bytes, 128).unwrap_err().to_string(),
+            "maximum depth of 128 exceeded"
+        );
+    }
+
+    #[test]
+    fn decode_large_length() {
+        // The length claims more elements than there are bytes left.
+        let bytes = [0xdd, 0xff, 0xff, 0xff, 0xff, 0x01];
+
+        assert_eq!(
+            decode(&bytes, 128).unwrap_err().to_string(),
+            "unable to parse msgpack: unexpected end of input"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        fn inner(value: Value) -> TestResult {
+            let decoded = decode(&encoded(&value), usize::MAX).unwrap();
+            assert_eq!(decoded, value);
+
+            TestResult::passed()
+        }
+
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(inner as fn(Value) -> TestResult);
+    }
+}
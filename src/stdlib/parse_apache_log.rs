@@ -63,6 +63,13 @@ impl Function for ParseApacheLog {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timestamp_format" => Some("%d/%b/%Y:%T %z".into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         state: &state::TypeState,
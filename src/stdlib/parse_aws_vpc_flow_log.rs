@@ -1,16 +1,10 @@
 use crate::compiler::prelude::*;
 use std::collections::BTreeMap;
 
-fn parse_aws_vpc_flow_log(value: Value, format: Option<Value>) -> Resolved {
-    let bytes = value.try_bytes()?;
-    let input = String::from_utf8_lossy(&bytes);
-    if let Some(expr) = format {
-        let bytes = expr.try_bytes()?;
-        parse_log(&input, Some(&String::from_utf8_lossy(&bytes)))
-    } else {
-        parse_log(&input, None)
-    }
-    .map_err(Into::into)
+fn parse_aws_vpc_flow_log(value: &Value, fields: &[FormatField]) -> Resolved {
+    let input = value.try_bytes_utf8_lossy()?;
+
+    parse_log(&input, fields).map_err(Into::into)
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -60,14 +54,35 @@ impl Function for ParseAwsVpcFlowLog {
 
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let format = arguments.optional("format");
 
-        Ok(ParseAwsVpcFlowLogFn::new(value, format).as_expr())
+        // The fields of the format are resolved once when it's known, as it is by default.
+        let format = match format {
+            None => {
+                Format::Fields(resolve_format(DEFAULT_FORMAT).expect("default format is valid"))
+            }
+            Some(format) => match format.resolve_constant(state) {
+                Some(constant) => {
+                    let names = constant.try_bytes_utf8_lossy().expect("format not bytes");
+                    let fields = resolve_format(&names).map_err(|name| {
+                        function::Error::InvalidArgument {
+                            keyword: "format",
+                            value: name.into(),
+                            error: "unknown field name",
+                        }
+                    })?;
+                    Format::Fields(fields)
+                }
+                None => Format::Dynamic(format),
+            },
+        };
+
+        Ok(ParseAwsVpcFlowLogFn { value, format }.as_expr())
     }
 
     fn parameters(&self) -> &'static [Parameter] {
@@ -84,30 +99,42 @@ impl Function for ParseAwsVpcFlowLog {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "format" => Some(DEFAULT_FORMAT.into()),
+            _ => None,
+        }
+    }
 }
 
+/// The format of the log, as resolved fields when it's known at compile time.
 #[derive(Debug, Clone)]
-struct ParseAwsVpcFlowLogFn {
-    value: Box<dyn Expression>,
-    format: Option<Box<dyn Expression>>,
+enum Format {
+    Fields(Vec<FormatField>),
+    Dynamic(Box<dyn Expression>),
 }
 
-impl ParseAwsVpcFlowLogFn {
-    fn new(value: Box<dyn Expression>, format: Option<Box<dyn Expression>>) -> Self {
-        Self { value, format }
-    }
+#[derive(Debug, Clone)]
+struct ParseAwsVpcFlowLogFn {
+    value: Box<dyn Expression>,
+    format: Format,
 }
 
 impl FunctionExpression for ParseAwsVpcFlowLogFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
-        let format = self
-            .format
-            .as_ref()
-            .map(|expr| expr.resolve(ctx))
-            .transpose()?;
 
-        parse_aws_vpc_flow_log(value, format)
+        match &self.format {
+            Format::Fields(fields) => parse_aws_vpc_flow_log(&value, fields),
+            Format::Dynamic(format) => {
+                let format = format.resolve(ctx)?;
+                let fields = resolve_format(&format.try_bytes_utf8_lossy()?)
+                    .map_err(|name| format!("unknown key: `{name}`"))?;
+
+                parse_aws_vpc_flow_log(&value, &fields)
+            }
+        }
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -115,127 +142,120 @@ impl FunctionExpression for ParseAwsVpcFlowLogFn {
     }
 }
 
-fn inner_kind() -> BTreeMap<Field, Kind> {
-    BTreeMap::from([
-        (Field::from("account_id"), Kind::bytes() | Kind::null()),
-        (Field::from("action"), Kind::bytes() | Kind::null()),
-        (Field::from("az_id"), Kind::bytes() | Kind::null()),
-        (Field::from("bytes"), Kind::integer() | Kind::null()),
-        (Field::from("dstaddr"), Kind::bytes() | Kind::null()),
-        (Field::from("dstport"), Kind::integer() | Kind::null()),
-        (Field::from("end"), Kind::integer() | Kind::null()),
-        (Field::from("flow_direction"), Kind::bytes() | Kind::null()),
-        (Field::from("instance_id"), Kind::bytes() | Kind::null()),
-        (Field::from("interface_id"), Kind::bytes() | Kind::null()),
-        (Field::from("log_status"), Kind::bytes() | Kind::null()),
-        (Field::from("packets"), Kind::integer() | Kind::null()),
-        (Field::from("pkt_dstaddr"), Kind::bytes() | Kind::null()),
-        (
-            Field::from("pkt_dst_aws_service"),
-            Kind::bytes() | Kind::null(),
-        ),
-        (Field::from("pkt_srcaddr"), Kind::bytes() | Kind::null()),
-        (
-            Field::from("pkt_src_aws_service"),
-            Kind::bytes() | Kind::null(),
-        ),
-        (Field::from("protocol"), Kind::integer() | Kind::null()),
-        (Field::from("region"), Kind::bytes() | Kind::null()),
-        (Field::from("srcaddr"), Kind::bytes() | Kind::null()),
-        (Field::from("srcport"), Kind::integer() | Kind::null()),
-        (Field::from("start"), Kind::integer() | Kind::null()),
-        (Field::from("sublocation_id"), Kind::bytes() | Kind::null()),
-        (
-            Field::from("sublocation_type"),
-            Kind::bytes() | Kind::null(),
-        ),
-        (Field::from("subnet_id"), Kind::bytes() | Kind::null()),
-        (Field::from("tcp_flags"), Kind::integer() | Kind::null()),
-        (Field::from("traffic_path"), Kind::integer() | Kind::null()),
-        (Field::from("type"), Kind::bytes() | Kind::null()),
-        (Field::from("version"), Kind::integer() | Kind::null()),
-        (Field::from("vpc_id"), Kind::bytes() | Kind::null()),
-    ])
+/// The default (version 2) format.
+const DEFAULT_FORMAT: &str = "version account_id interface_id srcaddr dstaddr srcport dstport protocol packets bytes start end action log_status";
+
+/// A field of the format: its name in the parsed log, and whether its values are integers.
+type FormatField = (&'static str, bool);
+
+/// The fields available across all versions of the format.
+const FIELDS: &[FormatField] = &[
+    ("account_id", false),
+    ("action", false),
+    ("az_id", false),
+    ("bytes", true),
+    ("dstaddr", false),
+    ("dstport", true),
+    ("end", true),
+    ("flow_direction", false),
+    ("instance_id", false),
+    ("interface_id", false),
+    ("log_status", false),
+    ("packets", true),
+    ("pkt_dstaddr", false),
+    ("pkt_dst_aws_service", false),
+    ("pkt_srcaddr", false),
+    ("pkt_src_aws_service", false),
+    ("protocol", true),
+    ("region", false),
+    ("srcaddr", false),
+    ("srcport", true),
+    ("start", true),
+    ("sublocation_id", false),
+    ("sublocation_type", false),
+    ("subnet_id", false),
+    ("tcp_flags", true),
+    ("traffic_path", true),
+    ("type", false),
+    ("version", true),
+    ("vpc_id", false),
+];
+
+/// Looks up a field of the format. Fields can be named as in the AWS documentation
+/// (`pkt-srcaddr`), as in the `LogFormat` of the flow log (`${pkt-srcaddr}`) or with underscores
+/// (`pkt_srcaddr`), which is how they're named in the parsed log.
+fn field(name: &str) -> Option<FormatField> {
+    let name = name
+        .strip_prefix("${")
+        .and_then(|name| name.strip_suffix('}'))
+        .unwrap_or(name);
+
+    FIELDS
+        .iter()
+        .find(|(field, _)| {
+            field.len() == name.len()
+                && field
+                    .bytes()
+                    .zip(name.bytes())
+                    .all(|(field, name)| field == name || (field == b'_' && name == b'-'))
+        })
+        .copied()
 }
 
-type ParseResult<T> = std::result::Result<T, String>;
+/// Looks up the fields of a format, or returns the first name that isn't a field.
+fn resolve_format(format: &str) -> Result<Vec<FormatField>, &str> {
+    format
+        .split_whitespace()
+        .map(|name| field(name).ok_or(name))
+        .collect()
+}
+
+fn inner_kind() -> BTreeMap<Field, Kind> {
+    FIELDS
+        .iter()
+        .map(|(field, integer)| {
+            let kind = if *integer {
+                Kind::integer()
+            } else {
+                Kind::bytes()
+            };
 
-#[allow(clippy::unnecessary_wraps)] // match other parse methods
-fn identity<'a>(_key: &'a str, value: &'a str) -> ParseResult<&'a str> {
-    Ok(value)
+            (Field::from(*field), kind | Kind::null())
+        })
+        .collect()
 }
 
+type ParseResult<T> = std::result::Result<T, String>;
+
 fn parse_i64(key: &str, value: &str) -> ParseResult<i64> {
     value
         .parse()
         .map_err(|_| format!("failed to parse value as i64 (key: `{key}`): `{value}`"))
 }
 
-macro_rules! create_match {
-    ($log:expr, $key:expr, $value:expr, $($name:expr => $transform:expr),+) => {
-        match $key {
-            $($name => {
-                let value = match $value {
-                    "-" => Value::Null,
-                    value => $transform($name, value)?.into(),
-                };
-                if $log.insert($name.into(), value).is_some() {
-                    return Err(format!("value already exists for key: `{}`", $key));
-                }
-            })+
-            key => return Err(format!("unknown key: `{}`", key))
-        };
-    };
-}
-
-fn parse_log(input: &str, format: Option<&str>) -> ParseResult<Value> {
+fn parse_log(input: &str, fields: &[FormatField]) -> ParseResult<Value> {
     let mut log = BTreeMap::new();
 
     let mut input = input.split(' ');
-    let mut format = format
-        .unwrap_or("version account_id interface_id srcaddr dstaddr srcport dstport protocol packets bytes start end action log_status")
-        .split(' ');
+    let mut fields = fields.iter();
 
     loop {
-        return match (format.next(), input.next()) {
-            (Some(key), Some(value)) => {
-                create_match!(
-                    log, key, value,
-                    "account_id" => identity,
-                    "action" => identity,
-                    "az_id" => identity,
-                    "bytes" => parse_i64,
-                    "dstaddr" => identity,
-                    "dstport" => parse_i64,
-                    "end" => parse_i64,
-                    "flow_direction" => identity,
-                    "instance_id" => identity,
-                    "interface_id" => identity,
-                    "log_status" => identity,
-                    "packets" => parse_i64,
-                    "pkt_dstaddr" => identity,
-                    "pkt_dst_aws_service" => identity,
-                    "pkt_srcaddr" => identity,
-                    "pkt_src_aws_service" => identity,
-                    "protocol" => parse_i64,
-                    "region" => identity,
-                    "srcaddr" => identity,
-                    "srcport" => parse_i64,
-                    "start" => parse_i64,
-                    "sublocation_id" => identity,
-                    "sublocation_type" => identity,
-                    "subnet_id" => identity,
-                    "tcp_flags" => parse_i64,
-                    "traffic_path" => parse_i64,
-                    "type" => identity,
-                    "version" => parse_i64,
-                    "vpc_id" => identity
-                );
+        return match (fields.next(), input.next()) {
+            (Some(&(name, integer)), Some(value)) => {
+                let value = match value {
+                    "-" => Value::Null,
+                    value if integer => parse_i64(name, value)?.into(),
+                    value => value.into(),
+                };
+
+                if log.insert(name.into(), value).is_some() {
+                    return Err(format!("value already exists for key: `{name}`"));
+                }
 
                 continue;
             }
             (None, Some(value)) => Err(format!("no key for value: `{value}`")),
-            (Some(key), None) => Err(format!("no item for key: `{key}`")),
+            (Some((name, _)), None) => Err(format!("no item for key: `{name}`")),
             (None, None) => Ok(log.into()),
         };
     }
@@ -299,8 +319,9 @@ mod tests {
          )];
 
         for (format, logs) in logs {
+            let fields = resolve_format(format.unwrap_or(DEFAULT_FORMAT)).unwrap();
             for log in logs {
-                assert!(parse_log(log, format).is_ok());
+                assert!(parse_log(log, &fields).is_ok());
             }
         }
     }
@@ -357,5 +378,93 @@ mod tests {
              })),
              tdef: TypeDef::object(inner_kind()).fallible(),
          }
+
+        v5_fields {
+             args: func_args![value: "5 vpc-abcdefab012345678 10.0.0.71 52.95.128.179 - S3 8 egress 3 OK",
+                              format: "version vpc-id pkt-srcaddr pkt-dstaddr pkt-src-aws-service pkt-dst-aws-service traffic-path flow-direction tcp-flags log-status"],
+             want: Ok(value!({
+                 "flow_direction": "egress",
+                 "log_status": "OK",
+                 "pkt_dst_aws_service": "S3",
+                 "pkt_dstaddr": "52.95.128.179",
+                 "pkt_src_aws_service": null,
+                 "pkt_srcaddr": "10.0.0.71",
+                 "tcp_flags": 3,
+                 "traffic_path": 8,
+                 "version": 5,
+                 "vpc_id": "vpc-abcdefab012345678"
+             })),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        aws_log_format {
+             args: func_args![value: "5 10.0.0.71 ingress",
+                              format: "${version} ${pkt-srcaddr} ${flow-direction}"],
+             want: Ok(value!({
+                 "flow_direction": "ingress",
+                 "pkt_srcaddr": "10.0.0.71",
+                 "version": 5
+             })),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        nodata {
+             args: func_args![value: "2 123456789010 eni-1235b8ca123456789 - - - - - - - 1431280876 1431280934 - NODATA"],
+             want: Ok(value!({
+                 "account_id": "123456789010",
+                 "action": null,
+                 "bytes": null,
+                 "dstaddr": null,
+                 "dstport": null,
+                 "end": 1_431_280_934,
+                 "interface_id": "eni-1235b8ca123456789",
+                 "log_status": "NODATA",
+                 "packets": null,
+                 "protocol": null,
+                 "srcaddr": null,
+                 "srcport": null,
+                 "start": 1_431_280_876,
+                 "version": 2
+             })),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        skipdata {
+             args: func_args![value: "5 eni-11111111aaaaaaaaa - - - SKIPDATA",
+                              format: "version interface-id packets bytes flow-direction log-status"],
+             want: Ok(value!({
+                 "bytes": null,
+                 "flow_direction": null,
+                 "interface_id": "eni-11111111aaaaaaaaa",
+                 "log_status": "SKIPDATA",
+                 "packets": null,
+                 "version": 5
+             })),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        fewer_tokens_than_fields {
+             args: func_args![value: "2 123456789010 eni-1235b8ca123456789 - - - - - - - 1431280876 1431280934 -"],
+             want: Err("no item for key: `log_status`"),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        more_tokens_than_fields {
+             args: func_args![value: "5 ingress OK", format: "version flow-direction"],
+             want: Err("no key for value: `OK`"),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        invalid_integer {
+             args: func_args![value: "5 many", format: "version packets"],
+             want: Err("failed to parse value as i64 (key: `packets`): `many`"),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
+
+        unknown_field {
+             args: func_args![value: "5 ingress", format: "version flow-directions"],
+             want: Err("invalid argument"),
+             tdef: TypeDef::object(inner_kind()).fallible(),
+         }
     ];
 }
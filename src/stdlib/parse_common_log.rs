@@ -42,6 +42,13 @@ impl Function for ParseCommonLog {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timestamp_format" => Some("%d/%b/%Y:%T %z".into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -71,6 +71,13 @@ impl Function for ParseCsv {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "delimiter" => Some(",".into()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +94,13 @@ impl Function for ParseGroks {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "aliases" => Some(Value::Object(ObjectMap::new())),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[Example {
             title: "parse grok pattern",
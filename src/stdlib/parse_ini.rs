@@ -0,0 +1,296 @@
+use std::collections::btree_map::Entry;
+
+use crate::compiler::prelude::*;
+
+/// Parses an INI document into an object of sections.
+///
+/// - Lines starting with `;` or `#` are comments. In unquoted values, a `;` or `#` preceded by
+///   whitespace starts an inline comment.
+/// - Keys before the first `[section]` header belong to the `default_section`.
+/// - Values wrapped in matching single or double quotes are taken verbatim, without the quotes.
+/// - Repeating a key within a section collects all of its values into an array.
+/// - Repeating a section header continues the existing section.
+fn parse_ini(value: &[u8], default_section: &str) -> Resolved {
+    let value = String::from_utf8_lossy(value);
+
+    let mut sections = ObjectMap::new();
+    let mut section = KeyString::from(default_section);
+
+    for (index, line) in value.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with([';', '#']) {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header
+                .strip_suffix(']')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| format!("invalid section header at line {}", index + 1))?;
+
+            section = name.into();
+            sections
+                .entry(section.clone())
+                .or_insert_with(|| Value::from(ObjectMap::new()));
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, _)| !key.is_empty())
+            .ok_or_else(|| format!("expected key = value at line {}", index + 1))?;
+
+        let fields = sections
+            .entry(section.clone())
+            .or_insert_with(|| Value::from(ObjectMap::new()))
+            .as_object_mut()
+            .expect("sections are objects");
+
+        let value = Value::from(parse_value(value));
+        match fields.entry(key.into()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => match entry.get_mut() {
+                Value::Array(values) => values.push(value),
+                existing => {
+                    let first = std::mem::replace(existing, Value::Null);
+                    *existing = Value::Array(vec![first, value]);
+                }
+            },
+        }
+    }
+
+    Ok(sections.into())
+}
+
+fn parse_value(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(quoted) = value
+            .strip_prefix(quote)
+            .and_then(|value| value.strip_suffix(quote))
+        {
+            return quoted;
+        }
+    }
+
+    let end = value
+        .char_indices()
+        .zip(value.chars().skip(1))
+        .find(|((_, c), next)| c.is_whitespace() && (*next == ';' || *next == '#'))
+        .map_or(value.len(), |((index, _), _)| index);
+
+    value[..end].trim_end()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseIni;
+
+impl Function for ParseIni {
+    fn identifier(&self) -> &'static str {
+        "parse_ini"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "default_section",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "default_section" => Some("default".into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "sections",
+                source: r#"parse_ini!("[server]\nhost = example.com\nport = 8080 ; the default\n\n[client]\nretries = 3")"#,
+                result: Ok(
+                    r#"{"client": {"retries": "3"}, "server": {"host": "example.com", "port": "8080"}}"#,
+                ),
+            },
+            Example {
+                title: "default section",
+                source: r#"parse_ini!("name = app\n[db]\nuser = 'admin ; root'", default_section: "global")"#,
+                result: Ok(r#"{"db": {"user": "admin ; root"}, "global": {"name": "app"}}"#),
+            },
+            Example {
+                title: "duplicate keys",
+                source: r#"parse_ini!("[paths]\ninclude = a\ninclude = b")"#,
+                result: Ok(r#"{"paths": {"include": ["a", "b"]}}"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let default_section = arguments.optional("default_section");
+
+        Ok(ParseIniFn {
+            value,
+            default_section,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseIniFn {
+    value: Box<dyn Expression>,
+    default_section: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for ParseIniFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let default_section = self
+            .default_section
+            .as_ref()
+            .map(|section| section.resolve(ctx))
+            .transpose()?
+            .map(VrlValueConvert::try_bytes)
+            .transpose()?;
+
+        parse_ini(
+            &value.try_bytes()?,
+            &default_section.map_or("default".into(), |section| {
+                String::from_utf8_lossy(&section).into_owned()
+            }),
+        )
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        type_def()
+    }
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::object(Collection::from_unknown(Kind::object(
+        Collection::from_unknown(
+            Kind::bytes() | Kind::array(Collection::from_unknown(Kind::bytes())),
+        ),
+    )))
+    .fallible()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        parse_ini => ParseIni;
+
+        comments {
+            args: func_args![value: indoc::indoc! {r#"
+                ; top comment
+                # another comment
+                [server]
+                host = example.com   # inline comment
+                port=8080;not a comment
+                url = http://example.com/#anchor
+                ; indented comment
+            "#}],
+            want: Ok(value!({
+                "server": {
+                    "host": "example.com",
+                    "port": "8080;not a comment",
+                    "url": "http://example.com/#anchor",
+                },
+            })),
+            tdef: type_def(),
+        }
+
+        default_section {
+            args: func_args![value: "name = app\nversion = 1\n[db]\nuser = admin"],
+            want: Ok(value!({
+                "default": {"name": "app", "version": "1"},
+                "db": {"user": "admin"},
+            })),
+            tdef: type_def(),
+        }
+
+        custom_default_section {
+            args: func_args![value: "name = app", default_section: "global"],
+            want: Ok(value!({"global": {"name": "app"}})),
+            tdef: type_def(),
+        }
+
+        duplicate_key {
+            args: func_args![value: "[paths]\ninclude = a\ninclude = b\nexclude = c\ninclude = d"],
+            want: Ok(value!({"paths": {"include": ["a", "b", "d"], "exclude": "c"}})),
+            tdef: type_def(),
+        }
+
+        duplicate_section {
+            args: func_args![value: "[a]\nx = 1\n[b]\ny = 2\n[a]\nz = 3"],
+            want: Ok(value!({"a": {"x": "1", "z": "3"}, "b": {"y": "2"}})),
+            tdef: type_def(),
+        }
+
+        quoted_values {
+            args: func_args![value: r#"[q]
+                double = "  spaced ; not a comment  "
+                single = 'it''s'
+                empty = ""
+                unbalanced = "open
+                bare =
+            "#],
+            want: Ok(value!({
+                "q": {
+                    "double": "  spaced ; not a comment  ",
+                    "single": "it''s",
+                    "empty": "",
+                    "unbalanced": "\"open",
+                    "bare": "",
+                },
+            })),
+            tdef: type_def(),
+        }
+
+        empty_section {
+            args: func_args![value: "[ empty ]\n"],
+            want: Ok(value!({"empty": {}})),
+            tdef: type_def(),
+        }
+
+        empty_input {
+            args: func_args![value: ""],
+            want: Ok(value!({})),
+            tdef: type_def(),
+        }
+
+        invalid_header {
+            args: func_args![value: "[a]\nx = 1\n[broken"],
+            want: Err("invalid section header at line 3"),
+            tdef: type_def(),
+        }
+
+        missing_delimiter {
+            args: func_args![value: "[a]\njust a line"],
+            want: Err("expected key = value at line 2"),
+            tdef: type_def(),
+        }
+    ];
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
 use serde_json::{
     value::{RawValue, Value as JsonValue},
@@ -7,24 +7,32 @@ use serde_json::{
 
 use crate::compiler::prelude::*;
 
-fn parse_json(value: Value) -> Resolved {
+fn parse_json(value: Value, relaxed: bool) -> Resolved {
     let bytes = value.try_bytes()?;
-    let value = serde_json::from_slice::<'_, Value>(&bytes)
-        .map_err(|e| format!("unable to parse json: {e}"))?;
+    let bytes = relax(&bytes, relaxed);
+    let value = serde_json::from_slice::<'_, Value>(&bytes).map_err(|e| {
+        ExpressionError::with_code(ErrorCode::Parse, format!("unable to parse json: {e}"))
+    })?;
     Ok(value)
 }
 
 // parse_json_with_depth method recursively traverses the value and returns raw JSON-formatted bytes
 // after reaching provided depth.
-fn parse_json_with_depth(value: Value, max_depth: Value) -> Resolved {
+fn parse_json_with_depth(value: Value, max_depth: Value, relaxed: bool) -> Resolved {
     let bytes = value.try_bytes()?;
+    let bytes = relax(&bytes, relaxed);
     let parsed_depth = validate_depth(max_depth)?;
 
-    let raw_value = serde_json::from_slice::<'_, &RawValue>(&bytes)
-        .map_err(|e| format!("unable to read json: {e}"))?;
+    let raw_value = serde_json::from_slice::<'_, &RawValue>(&bytes).map_err(|e| {
+        ExpressionError::with_code(ErrorCode::Parse, format!("unable to read json: {e}"))
+    })?;
 
-    let res = parse_layer(raw_value, parsed_depth)
-        .map_err(|e| format!("unable to parse json with max depth: {e}"))?;
+    let res = parse_layer(raw_value, parsed_depth).map_err(|e| {
+        ExpressionError::with_code(
+            ErrorCode::Parse,
+            format!("unable to parse json with max depth: {e}"),
+        )
+    })?;
 
     Ok(Value::from(res))
 }
@@ -71,6 +79,74 @@ fn parse_layer(value: &RawValue, remaining_depth: u8) -> std::result::Result<Jso
     }
 }
 
+fn relax(bytes: &[u8], relaxed: bool) -> Cow<'_, [u8]> {
+    match relaxed {
+        true => Cow::Owned(strip_jsonc(bytes)),
+        false => Cow::Borrowed(bytes),
+    }
+}
+
+/// Turns comment-tolerant JSON (JSONC) into strict JSON by replacing `//` and `/* */` comments,
+/// and commas after the last element of objects and arrays, with spaces.
+///
+/// Newlines are kept so that parse errors point at the same line and column as in the original
+/// input, and everything else is left to the JSON parser, including unterminated comments.
+fn strip_jsonc(input: &[u8]) -> Vec<u8> {
+    let mut output = input.to_vec();
+    let mut last = None;
+    let mut trailing_comma = None;
+
+    let mut i = 0;
+    while i < output.len() {
+        match (output[i], output.get(i + 1)) {
+            (b'"', _) => {
+                i += 1;
+                while i < output.len() && output[i] != b'"' {
+                    i += if output[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            (b'/', Some(b'/')) => {
+                while i < output.len() && output[i] != b'\n' {
+                    output[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            (b'/', Some(b'*')) => {
+                let Some(len) = output[i + 2..].windows(2).position(|w| w == b"*/") else {
+                    break;
+                };
+                for byte in &mut output[i..i + len + 4] {
+                    if *byte != b'\n' {
+                        *byte = b' ';
+                    }
+                }
+                i += len + 4;
+                continue;
+            }
+            (byte, _) if byte.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            (b',', _) if !matches!(last, Some(b'[' | b'{' | b',')) => trailing_comma = Some(i),
+            (b'}' | b']', _) => {
+                if let Some(comma) = trailing_comma {
+                    output[comma] = b' ';
+                }
+            }
+            _ => {}
+        }
+
+        if output.get(i) != Some(&b',') {
+            trailing_comma = None;
+        }
+        last = output.get(i).copied();
+        i += 1;
+    }
+
+    output
+}
+
 fn validate_depth(value: Value) -> ExpressionResult<u8> {
     let res = value.try_integer()?;
 
@@ -82,9 +158,10 @@ fn validate_depth(value: Value) -> ExpressionResult<u8> {
     if (1..=128).contains(&res) {
         Ok(res as u8)
     } else {
-        Err(ExpressionError::from(format!(
-            "max_depth value should be greater than 0 and less than 128, got {res}"
-        )))
+        Err(ExpressionError::with_code(
+            ErrorCode::OutOfRange,
+            format!("max_depth value should be greater than 0 and less than 128, got {res}"),
+        ))
     }
 }
 
@@ -106,6 +183,10 @@ impl Function for ParseJson {
 
             Only JSON types are returned. If you need to convert a `string` into a `timestamp`,
             consider the `parse_timestamp` function.
+
+            With `relaxed` set to `true`, `//` and `/* */` comments and trailing commas in objects
+            and arrays are allowed, as in JSONC. Other JSON5 extensions, such as single quoted
+            strings or hexadecimal numbers, are still rejected.
         "#}
     }
 
@@ -121,9 +202,22 @@ impl Function for ParseJson {
                 kind: kind::INTEGER,
                 required: false,
             },
+            Parameter {
+                keyword: "relaxed",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "relaxed" => Some(false.into()),
+            "max_depth" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -168,6 +262,11 @@ impl Function for ParseJson {
                 source: r#"parse_json!(s'{"first_level":{"second_level":"finish"}}', max_depth: 1)"#,
                 result: Ok(r#"{"first_level":"{\"second_level\":\"finish\"}"}"#),
             },
+            Example {
+                title: "relaxed",
+                source: r#"parse_json!(s'{ "field": "value", /* comment */ }', relaxed: true)"#,
+                result: Ok(r#"{ "field": "value" }"#),
+            },
         ]
     }
 
@@ -179,10 +278,16 @@ impl Function for ParseJson {
     ) -> Compiled {
         let value = arguments.required("value");
         let max_depth = arguments.optional("max_depth");
+        let relaxed = arguments.optional("relaxed");
 
         match max_depth {
-            Some(max_depth) => Ok(ParseJsonMaxDepthFn { value, max_depth }.as_expr()),
-            None => Ok(ParseJsonFn { value }.as_expr()),
+            Some(max_depth) => Ok(ParseJsonMaxDepthFn {
+                value,
+                max_depth,
+                relaxed,
+            }
+            .as_expr()),
+            None => Ok(ParseJsonFn { value, relaxed }.as_expr()),
         }
     }
 }
@@ -190,12 +295,14 @@ impl Function for ParseJson {
 #[derive(Debug, Clone)]
 struct ParseJsonFn {
     value: Box<dyn Expression>,
+    relaxed: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for ParseJsonFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
-        parse_json(value)
+        let relaxed = resolve_relaxed(self.relaxed.as_deref(), ctx)?;
+        parse_json(value, relaxed)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -207,13 +314,15 @@ impl FunctionExpression for ParseJsonFn {
 struct ParseJsonMaxDepthFn {
     value: Box<dyn Expression>,
     max_depth: Box<dyn Expression>,
+    relaxed: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for ParseJsonMaxDepthFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
         let max_depth = self.max_depth.resolve(ctx)?;
-        parse_json_with_depth(value, max_depth)
+        let relaxed = resolve_relaxed(self.relaxed.as_deref(), ctx)?;
+        parse_json_with_depth(value, max_depth, relaxed)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -221,6 +330,13 @@ impl FunctionExpression for ParseJsonMaxDepthFn {
     }
 }
 
+fn resolve_relaxed(relaxed: Option<&dyn Expression>, ctx: &mut Context) -> ExpressionResult<bool> {
+    match relaxed {
+        Some(relaxed) => Ok(relaxed.resolve(ctx)?.try_boolean()?),
+        None => Ok(false),
+    }
+}
+
 fn inner_kind() -> Kind {
     Kind::null()
         | Kind::bytes()
@@ -317,6 +433,88 @@ mod tests {
             tdef: type_def(),
         }
 
+        relaxed_comments_and_trailing_commas {
+            args: func_args![
+                value: indoc! {r#"
+                    // A configuration.
+                    {
+                        "name": "vector", // The name.
+                        /* Listening
+                           addresses. */
+                        "addresses": ["0.0.0.0:80", "[::]:80",],
+                        "nested": {"enabled": true,},
+                    }
+                "#},
+                relaxed: true,
+            ],
+            want: Ok(value!({
+                name: "vector",
+                addresses: ["0.0.0.0:80", "[::]:80"],
+                nested: {enabled: true},
+            })),
+            tdef: type_def(),
+        }
+
+        relaxed_comment_markers_in_strings {
+            args: func_args![value: r#"{"url": "http://example.com/*", "path": "a,]\"//"}"#, relaxed: true],
+            want: Ok(value!({url: "http://example.com/*", path: "a,]\"//"})),
+            tdef: type_def(),
+        }
+
+        relaxed_with_max_depth {
+            args: func_args![value: r#"{"a": {"b": 1, /* c */},}"#, max_depth: 1, relaxed: true],
+            want: Ok(value!({a: r#"{"b": 1         }"#})),
+            tdef: type_def(),
+        }
+
+        strict_rejects_comments {
+            args: func_args![value: r#"{"a": 1 /* b */}"#],
+            want: Err("unable to parse json: expected `,` or `}` at line 1 column 9"),
+            tdef: type_def(),
+        }
+
+        strict_rejects_trailing_commas {
+            args: func_args![value: "[1, 2,]", relaxed: false],
+            want: Err("unable to parse json: trailing comma at line 1 column 7"),
+            tdef: type_def(),
+        }
+
+        relaxed_keeps_error_position {
+            args: func_args![value: "{\n  /* a */ \"a\": 1,\n  \"b\": nope\n}", relaxed: true],
+            want: Err("unable to parse json: expected ident at line 3 column 9"),
+            tdef: type_def(),
+        }
+
+        relaxed_rejects_leading_comma {
+            args: func_args![value: "[,]", relaxed: true],
+            want: Err("unable to parse json: expected value at line 1 column 2"),
+            tdef: type_def(),
+        }
+
+        relaxed_rejects_double_trailing_comma {
+            args: func_args![value: "[1,,]", relaxed: true],
+            want: Err("unable to parse json: trailing comma at line 1 column 5"),
+            tdef: type_def(),
+        }
+
+        relaxed_rejects_unterminated_comment {
+            args: func_args![value: "[1] /* a", relaxed: true],
+            want: Err("unable to parse json: trailing characters at line 1 column 5"),
+            tdef: type_def(),
+        }
+
+        relaxed_rejects_json5_numbers {
+            args: func_args![value: "[0x1F, .5, +1]", relaxed: true],
+            want: Err("unable to parse json: expected `,` or `]` at line 1 column 3"),
+            tdef: type_def(),
+        }
+
+        relaxed_rejects_unquoted_keys {
+            args: func_args![value: "{a: 1}", relaxed: true],
+            want: Err("unable to parse json: key must be a string at line 1 column 2"),
+            tdef: type_def(),
+        }
+
         lossy_float_conversion {
             args: func_args![ value: r#"{"num": 9223372036854775808}"#],
             want: Ok(value!({"num": 9.223_372_036_854_776e18})),
@@ -102,6 +102,16 @@ impl Function for ParseKeyValue {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "key_value_delimiter" => Some("=".into()),
+            "field_delimiter" => Some(" ".into()),
+            "accept_standalone_key" => Some(true.into()),
+            "whitespace" => Some(Whitespace::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
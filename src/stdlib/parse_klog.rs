@@ -49,11 +49,82 @@ fn parse_klog(bytes: Value) -> Resolved {
         );
     }
     if let Some(message) = captures.name("message").map(|capture| capture.as_str()) {
-        log.insert("message".into(), Value::Bytes(message.to_owned().into()));
+        match parse_structured(message) {
+            Some((message, fields)) => {
+                log.insert("message".into(), message.into());
+                if !fields.is_empty() {
+                    log.insert("fields".into(), fields.into());
+                }
+            }
+            None => {
+                log.insert("message".into(), Value::Bytes(message.to_owned().into()));
+            }
+        }
     }
     Ok(log.into())
 }
 
+/// Parses the message of a structured log line, as logged by `klog.InfoS` and `klog.ErrorS`: a
+/// quoted message, followed by `key=value` pairs whose values are quoted unless they're a single
+/// word. Returns `None` for other messages.
+fn parse_structured(message: &str) -> Option<(String, ObjectMap)> {
+    let (message, mut rest) = parse_quoted(message)?;
+    let mut fields = ObjectMap::new();
+
+    loop {
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        let (key, value) = rest.split_once('=')?;
+        if key.is_empty() || key.contains(|c: char| c.is_whitespace() || c == '"') {
+            return None;
+        }
+        let value = if value.starts_with('"') {
+            parse_quoted(value)?
+        } else {
+            let end = value.find(char::is_whitespace).unwrap_or(value.len());
+            (value[..end].to_owned(), &value[end..])
+        };
+
+        fields.insert(key.into(), value.0.into());
+        rest = value.1;
+    }
+
+    Some((message, fields))
+}
+
+/// Parses the double quoted, Go-escaped string at the start of `input`, returning it unescaped
+/// along with the rest of the input.
+fn parse_quoted(input: &str) -> Option<(String, &str)> {
+    let quoted = input.strip_prefix('"')?;
+    let mut chars = quoted.char_indices();
+    let mut string = String::new();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((string, &quoted[i + 1..])),
+            '\\' => match chars.next()?.1 {
+                'n' => string.push('\n'),
+                'r' => string.push('\r'),
+                't' => string.push('\t'),
+                c @ ('"' | '\\') => string.push(c),
+                c => {
+                    string.push('\\');
+                    string.push(c);
+                }
+            },
+            c => string.push(c),
+        }
+    }
+
+    None
+}
+
 static REGEX_KLOG: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
         r"(?x)                                                        # Ignore whitespace and comments in the regex expression.
@@ -79,10 +150,11 @@ impl Function for ParseKlog {
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "valid",
-            source: r#"parse_klog!("I0505 17:59:40.692994   28133 klog.go:70] hello from klog")"#,
-            result: Ok(indoc! { r#"{
+        &[
+            Example {
+                title: "valid",
+                source: r#"parse_klog!("I0505 17:59:40.692994   28133 klog.go:70] hello from klog")"#,
+                result: Ok(indoc! { r#"{
                     "file": "klog.go",
                     "id": 28133,
                     "level": "info",
@@ -90,7 +162,13 @@ impl Function for ParseKlog {
                     "message": "hello from klog",
                     "timestamp": "2023-05-05T17:59:40.692994Z"
                 }"#}),
-        }]
+            },
+            Example {
+                title: "structured fields",
+                source: r#"parse_klog!(s'I0424 11:07:41.311731       1 controller.go:107] "Starting workers" controller="deployment" count=5').fields"#,
+                result: Ok(r#"{ "controller": "deployment", "count": "5" }"#),
+            },
+        ]
     }
 
     fn compile(
@@ -147,6 +225,10 @@ fn inner_kind() -> BTreeMap<Field, Kind> {
         ("file".into(), Kind::bytes()),
         ("line".into(), Kind::integer()),
         ("message".into(), Kind::bytes()),
+        (
+            "fields".into(),
+            Kind::object(Collection::from_unknown(Kind::bytes())).or_undefined(),
+        ),
     ])
 }
 
@@ -209,5 +291,94 @@ mod tests {
             want: Err("failed parsing id"),
             tdef: TypeDef::object(inner_kind()).fallible(),
         }
+
+        kube_apiserver {
+            args: func_args![value: "I0512 09:41:07.151869       1 controller.go:624] quota admission added evaluator for: leases.coordination.k8s.io"],
+            want: Ok(btreemap! {
+                "level" => "info",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:07.151869Z", Utc::now().year())).unwrap().into()),
+                "id" => 1,
+                "file" => "controller.go",
+                "line" => 624,
+                "message" => "quota admission added evaluator for: leases.coordination.k8s.io",
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        kube_apiserver_structured {
+            args: func_args![value: r#"E0512 09:41:07.828816       1 authentication.go:63] "Unable to authenticate the request" err="[invalid bearer token, service account token has been invalidated]""#],
+            want: Ok(btreemap! {
+                "level" => "error",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:07.828816Z", Utc::now().year())).unwrap().into()),
+                "id" => 1,
+                "file" => "authentication.go",
+                "line" => 63,
+                "message" => "Unable to authenticate the request",
+                "fields" => btreemap! {
+                    "err" => "[invalid bearer token, service account token has been invalidated]",
+                },
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        kubelet {
+            args: func_args![value: r#"W0512 09:41:09.512306    2148 reflector.go:535] k8s.io/client-go/informers/factory.go:150: failed to list *v1.Service: Get "https://10.0.0.1:6443/api/v1/services": dial tcp 10.0.0.1:6443: connect: connection refused"#],
+            want: Ok(btreemap! {
+                "level" => "warning",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:09.512306Z", Utc::now().year())).unwrap().into()),
+                "id" => 2148,
+                "file" => "reflector.go",
+                "line" => 535,
+                "message" => r#"k8s.io/client-go/informers/factory.go:150: failed to list *v1.Service: Get "https://10.0.0.1:6443/api/v1/services": dial tcp 10.0.0.1:6443: connect: connection refused"#,
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        kubelet_structured {
+            args: func_args![value: r#"I0512 09:41:10.004224    2148 kuberuntime_manager.go:1032] "Creating container in pod" containerType="init container" restartCount=0 pod="kube-system/coredns-5dd5756b68-x2v9q""#],
+            want: Ok(btreemap! {
+                "level" => "info",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:10.004224Z", Utc::now().year())).unwrap().into()),
+                "id" => 2148,
+                "file" => "kuberuntime_manager.go",
+                "line" => 1032,
+                "message" => "Creating container in pod",
+                "fields" => btreemap! {
+                    "containerType" => "init container",
+                    "restartCount" => "0",
+                    "pod" => "kube-system/coredns-5dd5756b68-x2v9q",
+                },
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        structured_escapes {
+            args: func_args![value: r#"F0512 09:41:10.004224    2148 server.go:12] "Failed to \"start\"" err="line\nbreak""#],
+            want: Ok(btreemap! {
+                "level" => "fatal",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:10.004224Z", Utc::now().year())).unwrap().into()),
+                "id" => 2148,
+                "file" => "server.go",
+                "line" => 12,
+                "message" => r#"Failed to "start""#,
+                "fields" => btreemap! {
+                    "err" => "line\nbreak",
+                },
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        quoted_message_not_structured {
+            args: func_args![value: r#"I0512 09:41:10.004224    2148 server.go:12] "quoted" and then some text"#],
+            want: Ok(btreemap! {
+                "level" => "info",
+                "timestamp" => Value::Timestamp(DateTime::parse_from_rfc3339(&format!("{}-05-12T09:41:10.004224Z", Utc::now().year())).unwrap().into()),
+                "id" => 2148,
+                "file" => "server.go",
+                "line" => 12,
+                "message" => r#""quoted" and then some text"#,
+            }),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
     ];
 }
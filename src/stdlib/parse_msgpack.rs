@@ -0,0 +1,193 @@
+use crate::compiler::prelude::*;
+use crate::stdlib::msgpack;
+use crate::stdlib::util::resolve_max_depth;
+
+/// The number of levels of nested collections that are parsed, unless the `max_depth` argument is
+/// given, so that deeply nested input can't overflow the stack.
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+fn parse_msgpack(value: Value, max_depth: usize) -> Resolved {
+    msgpack::decode(&value.try_bytes()?, max_depth)
+}
+
+fn inner_kind() -> Kind {
+    Kind::null()
+        | Kind::bytes()
+        | Kind::integer()
+        | Kind::float()
+        | Kind::boolean()
+        | Kind::timestamp()
+        | Kind::array(Collection::any())
+        | Kind::object(Collection::any())
+}
+
+fn type_def() -> TypeDef {
+    TypeDef::bytes()
+        .fallible()
+        .or_boolean()
+        .or_integer()
+        .or_float()
+        .or_timestamp()
+        .add_null()
+        .or_array(Collection::from_unknown(inner_kind()))
+        .or_object(Collection::from_unknown(inner_kind()))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseMsgpack;
+
+impl Function for ParseMsgpack {
+    fn identifier(&self) -> &'static str {
+        "parse_msgpack"
+    }
+
+    fn summary(&self) -> &'static str {
+        "parse MessagePack bytes to a value"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Parses the provided `value` as a single MessagePack value, failing if any bytes follow
+            it.
+
+            Both `str` and `bin` are returned as strings, and the MessagePack timestamp extension
+            type as a timestamp. Other extension types, and map keys that aren't strings, are
+            rejected. Collections nested deeper than `max_depth`, 128 by default, are rejected.
+        "}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "max_depth",
+                kind: kind::INTEGER,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "max_depth" => Some(DEFAULT_MAX_DEPTH.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "object",
+                source: r#"parse_msgpack!(decode_base64!("gqdjb21wYWN0w6ZzY2hlbWEA"))"#,
+                result: Ok(r#"{ "compact": true, "schema": 0 }"#),
+            },
+            Example {
+                title: "timestamp",
+                source: r#"parse_msgpack!(decode_base64!("1v8AAAAB"))"#,
+                result: Ok("t'1970-01-01T00:00:01Z'"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let max_depth = arguments.optional("max_depth");
+
+        Ok(ParseMsgpackFn { value, max_depth }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ParseMsgpackFn {
+    value: Box<dyn Expression>,
+    max_depth: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for ParseMsgpackFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let max_depth = match self.max_depth.as_deref() {
+            None => DEFAULT_MAX_DEPTH,
+            max_depth => resolve_max_depth(max_depth, ctx)?,
+        };
+        parse_msgpack(value, max_depth)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        type_def()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+    use chrono::DateTime;
+
+    test_function![
+        parse_msgpack => ParseMsgpack;
+
+        object {
+            args: func_args![value: Bytes::from_static(b"\x82\xa7compact\xc3\xa6schema\x00")],
+            want: Ok(value!({"compact": true, "schema": 0})),
+            tdef: type_def(),
+        }
+
+        timestamp {
+            args: func_args![value: Bytes::from_static(b"\xd7\xff\xbc\x1c\xbd\x00\x60\x17\xf5\x70")],
+            want: Ok(Value::Timestamp(DateTime::parse_from_rfc3339("2021-02-01T12:34:56.789Z").unwrap().into())),
+            tdef: type_def(),
+        }
+
+        trailing_bytes {
+            args: func_args![value: Bytes::from_static(b"\xc0\xc0")],
+            want: Err("unable to parse msgpack: 1 trailing bytes after the value"),
+            tdef: type_def(),
+        }
+
+        truncated {
+            args: func_args![value: Bytes::from_static(b"\x92\x01")],
+            want: Err("unable to parse msgpack: unexpected end of input"),
+            tdef: type_def(),
+        }
+
+        max_depth {
+            args: func_args![value: Bytes::from_static(b"\x91\x91\x91\xc0"), max_depth: 2],
+            want: Err("maximum depth of 2 exceeded"),
+            tdef: type_def(),
+        }
+
+        within_max_depth {
+            args: func_args![value: Bytes::from_static(b"\x91\x91\x91\xc0"), max_depth: 3],
+            want: Ok(value!([[[null]]])),
+            tdef: type_def(),
+        }
+
+        within_default_max_depth {
+            args: func_args![value: Bytes::from([vec![0x91; 128], vec![0xc0]].concat())],
+            want: Ok(value!((0..128).fold(value!(null), |value, _| value!([value])))),
+            tdef: type_def(),
+        }
+
+        exceeds_default_max_depth {
+            args: func_args![value: Bytes::from([vec![0x91; 200_000], vec![0xc0]].concat())],
+            want: Err("maximum depth of 128 exceeded"),
+            tdef: type_def(),
+        }
+
+        invalid_max_depth {
+            args: func_args![value: Bytes::from_static(b"\xc0"), max_depth: 0],
+            want: Err("max_depth must be at least 1"),
+            tdef: type_def(),
+        }
+    ];
+}
@@ -59,6 +59,13 @@ impl Function for ParseNginxLog {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timestamp_format" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         state: &state::TypeState,
@@ -0,0 +1,278 @@
+use std::str::FromStr;
+
+use crate::compiler::prelude::*;
+
+fn parse_number_array(value: &[u8], delimiter: &[u8], empty: Empty) -> Resolved {
+    let value = String::from_utf8_lossy(value);
+    let delimiter = String::from_utf8_lossy(delimiter);
+
+    let mut tokens = Vec::new();
+    for (position, token) in value.split(delimiter.as_ref()).enumerate() {
+        let token = token.trim();
+
+        if token.is_empty() {
+            match empty {
+                Empty::Skip => continue,
+                Empty::Error => return Err(format!("empty token at position {position}").into()),
+            }
+        }
+
+        tokens.push((position, token));
+    }
+
+    // Integers are only returned if every token is one, so that the array is homogeneous.
+    let integers = tokens
+        .iter()
+        .map(|(_, token)| i64::from_str(token).ok())
+        .collect::<Option<Vec<_>>>();
+    if let Some(integers) = integers {
+        return Ok(integers
+            .into_iter()
+            .map(Value::from)
+            .collect::<Vec<_>>()
+            .into());
+    }
+
+    tokens
+        .into_iter()
+        .map(|(position, token)| {
+            f64::from_str(token)
+                .ok()
+                .and_then(|float| NotNan::new(float).ok())
+                .map(Value::from)
+                .ok_or_else(|| format!(r#"invalid number "{token}" at position {position}"#).into())
+        })
+        .collect::<Result<Vec<_>, ExpressionError>>()
+        .map(Into::into)
+}
+
+/// Controls how empty tokens (e.g. in `"1,,2"`) are handled.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum Empty {
+    /// Return an error.
+    #[default]
+    Error,
+
+    /// Leave them out of the result.
+    Skip,
+}
+
+impl Empty {
+    fn all_value() -> Vec<Value> {
+        use Empty::{Error, Skip};
+
+        vec![Error, Skip]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use Empty::{Error, Skip};
+
+        match self {
+            Error => "error",
+            Skip => "skip",
+        }
+    }
+}
+
+impl FromStr for Empty {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Empty::{Error, Skip};
+
+        match s {
+            "error" => Ok(Error),
+            "skip" => Ok(Skip),
+            _ => Err("empty not recognized"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseNumberArray;
+
+impl Function for ParseNumberArray {
+    fn identifier(&self) -> &'static str {
+        "parse_number_array"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "delimiter",
+                kind: kind::BYTES,
+                required: false,
+            },
+            Parameter {
+                keyword: "empty",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "delimiter" => Some(",".into()),
+            "empty" => Some(Empty::default().as_str().into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "floats",
+                source: r#"parse_number_array!("1.2,3.4,5.6")"#,
+                result: Ok("[1.2, 3.4, 5.6]"),
+            },
+            Example {
+                title: "integers",
+                source: r#"parse_number_array!("1 2 3", delimiter: " ")"#,
+                result: Ok("[1, 2, 3]"),
+            },
+            Example {
+                title: "skip empty tokens",
+                source: r#"parse_number_array!("1,,2,", empty: "skip")"#,
+                result: Ok("[1, 2]"),
+            },
+            Example {
+                title: "invalid token",
+                source: r#"parse_number_array!("1.2,foo,3.4")"#,
+                result: Err(
+                    r#"function call error for "parse_number_array" at (0:34): invalid number "foo" at position 1"#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let delimiter = arguments.optional("delimiter");
+        let empty = arguments
+            .optional_enum("empty", &Empty::all_value(), state)?
+            .map(|s| {
+                Empty::from_str(&s.try_bytes_utf8_lossy().expect("empty not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(ParseNumberArrayFn {
+            value,
+            delimiter,
+            empty,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseNumberArrayFn {
+    value: Box<dyn Expression>,
+    delimiter: Option<Box<dyn Expression>>,
+    empty: Empty,
+}
+
+impl FunctionExpression for ParseNumberArrayFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let delimiter = self
+            .delimiter
+            .as_ref()
+            .map(|delimiter| delimiter.resolve(ctx))
+            .transpose()?
+            .map(VrlValueConvert::try_bytes)
+            .transpose()?;
+
+        parse_number_array(
+            &value.try_bytes()?,
+            delimiter.as_deref().unwrap_or(b","),
+            self.empty,
+        )
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::array(Collection::from_unknown(Kind::integer().or_float())).fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    fn tdef() -> TypeDef {
+        TypeDef::array(Collection::from_unknown(Kind::integer().or_float())).fallible()
+    }
+
+    test_function![
+        parse_number_array => ParseNumberArray;
+
+        floats {
+            args: func_args![value: "1.2,3.4,5.6"],
+            want: Ok(value!([1.2, 3.4, 5.6])),
+            tdef: tdef(),
+        }
+
+        integers {
+            args: func_args![value: "1,-2,3"],
+            want: Ok(value!([1, (-2), 3])),
+            tdef: tdef(),
+        }
+
+        mixed_integers_and_floats {
+            args: func_args![value: "1,2.5,3"],
+            want: Ok(value!([1.0, 2.5, 3.0])),
+            tdef: tdef(),
+        }
+
+        custom_delimiter_and_whitespace {
+            args: func_args![value: " 1.5 ; 2e3 ", delimiter: ";"],
+            want: Ok(value!([1.5, 2000.0])),
+            tdef: tdef(),
+        }
+
+        invalid_token {
+            args: func_args![value: "1.2,3.4,abc,5.6"],
+            want: Err(r#"invalid number "abc" at position 2"#),
+            tdef: tdef(),
+        }
+
+        nan_token {
+            args: func_args![value: "1,NaN"],
+            want: Err(r#"invalid number "NaN" at position 1"#),
+            tdef: tdef(),
+        }
+
+        empty_token_error {
+            args: func_args![value: "1,,2"],
+            want: Err("empty token at position 1"),
+            tdef: tdef(),
+        }
+
+        empty_token_skip {
+            args: func_args![value: "1,,2,", empty: "skip"],
+            want: Ok(value!([1, 2])),
+            tdef: tdef(),
+        }
+
+        empty_string_skip {
+            args: func_args![value: "", empty: "skip"],
+            want: Ok(value!([])),
+            tdef: tdef(),
+        }
+    ];
+}
@@ -0,0 +1,494 @@
+use std::fmt;
+
+use crate::compiler::prelude::*;
+
+/// Arrays and objects nested deeper than this fail to parse, rather than overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+fn parse_php_serialized(value: Value) -> Resolved {
+    let bytes = value.try_bytes()?;
+    parse(&bytes).map_err(|error| {
+        ExpressionError::with_code(
+            ErrorCode::Parse,
+            format!("unable to parse PHP serialized value: {error}"),
+        )
+    })
+}
+
+fn parse(input: &[u8]) -> Result<Value, Error> {
+    let mut parser = Parser { input, offset: 0 };
+    let value = parser.value(0)?;
+    if parser.offset < input.len() {
+        return Err(parser.error("unexpected data after the value"));
+    }
+    Ok(value)
+}
+
+#[derive(Debug)]
+struct Error {
+    message: String,
+    offset: usize,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+/// A key of a PHP array, which is either an integer or a string.
+enum Key {
+    Integer(i64),
+    String(Bytes),
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> Error {
+        Error {
+            message: message.into(),
+            offset: self.offset,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.offset).copied()
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), Error> {
+        match self.peek() {
+            Some(byte) if byte == expected => {
+                self.offset += 1;
+                Ok(())
+            }
+            Some(byte) => Err(self.error(format!(
+                "expected {:?}, got {:?}",
+                char::from(expected),
+                char::from(byte)
+            ))),
+            None => Err(self.error(format!(
+                "expected {:?}, got end of input",
+                char::from(expected)
+            ))),
+        }
+    }
+
+    /// Returns the text up to `terminator`, and skips past the terminator.
+    fn until(&mut self, terminator: u8) -> Result<&str, Error> {
+        let start = self.offset;
+        let Some(len) = self.input[start..].iter().position(|b| *b == terminator) else {
+            self.offset = self.input.len();
+            return Err(self.error(format!(
+                "expected {:?}, got end of input",
+                char::from(terminator)
+            )));
+        };
+        self.offset += len + 1;
+
+        std::str::from_utf8(&self.input[start..start + len]).map_err(|_| Error {
+            message: "invalid number".to_owned(),
+            offset: start,
+        })
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, terminator: u8, kind: &str) -> Result<T, Error> {
+        let start = self.offset;
+        let text = self.until(terminator)?;
+        text.parse().map_err(|_| Error {
+            message: format!("invalid {kind} {text:?}"),
+            offset: start,
+        })
+    }
+
+    /// Parses the `<length>:"<bytes>"` part of a string, and returns the bytes.
+    fn string_contents(&mut self) -> Result<Bytes, Error> {
+        let len: usize = self.number(b':', "length")?;
+        self.expect(b'"')?;
+
+        let start = self.offset;
+        let end = start
+            .checked_add(len)
+            .filter(|end| *end <= self.input.len());
+        let Some(end) = end else {
+            return Err(self.error(format!("string of length {len} is longer than the input")));
+        };
+        self.offset = end;
+        self.expect(b'"')?;
+
+        Ok(Bytes::copy_from_slice(&self.input[start..end]))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, Error> {
+        let start = self.offset;
+        let Some(kind) = self.peek() else {
+            return Err(self.error("expected a value, got end of input"));
+        };
+        self.offset += 1;
+
+        if kind == b'N' {
+            self.expect(b';')?;
+            return Ok(Value::Null);
+        }
+        self.expect(b':')?;
+
+        match kind {
+            b'b' => match self.until(b';')? {
+                "0" => Ok(false.into()),
+                "1" => Ok(true.into()),
+                text => Err(Error {
+                    message: format!("invalid boolean {text:?}"),
+                    offset: start + 2,
+                }),
+            },
+            b'i' => self.number::<i64>(b';', "integer").map(Value::from),
+            b'd' => {
+                let float: f64 = self.number(b';', "float")?;
+                NotNan::new(float)
+                    .ok()
+                    .filter(|float| float.is_finite())
+                    .map(Value::Float)
+                    .ok_or_else(|| Error {
+                        message: format!("float {float} can't be represented"),
+                        offset: start + 2,
+                    })
+            }
+            b's' | b'E' => {
+                let string = self.string_contents()?;
+                self.expect(b';')?;
+                Ok(Value::Bytes(string))
+            }
+            b'a' => {
+                let entries = self.entries(depth)?;
+                Ok(array_or_object(entries))
+            }
+            b'O' => {
+                // The class name isn't kept, only the properties.
+                self.string_contents()?;
+                self.expect(b':')?;
+                let properties = self.entries(depth)?;
+                Ok(properties
+                    .into_iter()
+                    .map(|(key, value)| (property_name(key), value))
+                    .collect::<ObjectMap>()
+                    .into())
+            }
+            _ => Err(Error {
+                message: format!("unsupported type {:?}", char::from(kind)),
+                offset: start,
+            }),
+        }
+    }
+
+    /// Parses the `<count>:{<key><value>...}` part of an array or object.
+    fn entries(&mut self, depth: usize) -> Result<Vec<(Key, Value)>, Error> {
+        if depth >= MAX_DEPTH {
+            return Err(self.error(format!("nested deeper than {MAX_DEPTH} levels")));
+        }
+
+        let count: usize = self.number(b':', "count")?;
+        self.expect(b'{')?;
+
+        // Each entry takes at least 4 bytes, so the count can't make this allocate more than the
+        // input could hold.
+        let mut entries = Vec::with_capacity(count.min(self.input.len() / 4));
+        for _ in 0..count {
+            let key = self.key()?;
+            let value = self.value(depth + 1)?;
+            entries.push((key, value));
+        }
+        self.expect(b'}')?;
+
+        Ok(entries)
+    }
+
+    fn key(&mut self) -> Result<Key, Error> {
+        let start = self.offset;
+        match self.peek() {
+            Some(b'i') => {
+                self.offset += 1;
+                self.expect(b':')?;
+                self.number(b';', "integer").map(Key::Integer)
+            }
+            Some(b's') => {
+                self.offset += 1;
+                self.expect(b':')?;
+                let string = self.string_contents()?;
+                self.expect(b';')?;
+                Ok(Key::String(string))
+            }
+            Some(kind) => Err(Error {
+                message: format!("unsupported key type {:?}", char::from(kind)),
+                offset: start,
+            }),
+            None => Err(self.error("expected a key, got end of input")),
+        }
+    }
+}
+
+impl From<Key> for KeyString {
+    fn from(key: Key) -> Self {
+        match key {
+            Key::Integer(integer) => integer.to_string().into(),
+            Key::String(string) => String::from_utf8_lossy(&string).into(),
+        }
+    }
+}
+
+/// Arrays whose keys are `0`, `1`, `2` and so on, in order, are lists, as in `json_encode`.
+fn array_or_object(entries: Vec<(Key, Value)>) -> Value {
+    let is_list = entries.iter().enumerate().all(
+        |(index, (key, _))| matches!(key, Key::Integer(key) if usize::try_from(*key) == Ok(index)),
+    );
+
+    if is_list {
+        entries
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>()
+            .into()
+    } else {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect::<ObjectMap>()
+            .into()
+    }
+}
+
+/// Private and protected properties are serialized with a `\0<class>\0` or `\0*\0` prefix,
+/// which is removed.
+fn property_name(key: Key) -> KeyString {
+    if let Key::String(string) = &key {
+        if let Some(rest) = string.strip_prefix(b"\0") {
+            if let Some(end) = rest.iter().position(|b| *b == 0) {
+                return String::from_utf8_lossy(&rest[end + 1..]).into();
+            }
+        }
+    }
+    key.into()
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParsePhpSerialized;
+
+impl Function for ParsePhpSerialized {
+    fn identifier(&self) -> &'static str {
+        "parse_php_serialized"
+    }
+
+    fn summary(&self) -> &'static str {
+        "parse a value serialized by PHP"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Parses the provided `value` as the output of PHP's `serialize` function.
+
+            PHP's `null`, booleans, integers, floats and strings become the same VRL types, and
+            enum cases become strings such as `"Suit:Hearts"`. Arrays whose keys are `0`, `1`,
+            `2` and so on, in order, become arrays, and other arrays become objects, with
+            integer keys as strings, as with PHP's `json_encode`. Objects become objects of their
+            properties, without their class name, and without the prefix PHP adds to the names
+            of private and protected properties.
+
+            Strings keep their bytes as they are, since PHP strings don't have an encoding.
+            References and objects with custom serialization can't be parsed, and neither can
+            `INF` or `NAN` floats, which VRL can't represent.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "associative array",
+                source: r#"parse_php_serialized!(s'a:2:{s:4:"user";s:5:"alice";s:5:"roles";a:2:{i:0;s:5:"admin";i:1;s:3:"dev";}}')"#,
+                result: Ok(r#"{"roles": ["admin", "dev"], "user": "alice"}"#),
+            },
+            Example {
+                title: "integer",
+                source: r#"parse_php_serialized!("i:42;")"#,
+                result: Ok("42"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(ParsePhpSerializedFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParsePhpSerializedFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ParsePhpSerializedFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        parse_php_serialized(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::from(kind()).fallible()
+    }
+}
+
+fn kind() -> Kind {
+    Kind::null()
+        | Kind::boolean()
+        | Kind::integer()
+        | Kind::float()
+        | Kind::bytes()
+        | Kind::array(Collection::any())
+        | Kind::object(Collection::any())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        parse_php_serialized => ParsePhpSerialized;
+
+        associative_array {
+            args: func_args![value: r#"a:3:{s:4:"name";s:5:"Alice";s:3:"age";i:30;s:6:"active";b:1;}"#],
+            want: Ok(value!({name: "Alice", age: 30, active: true})),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        nested {
+            args: func_args![value: r#"a:2:{s:4:"user";a:2:{s:2:"id";i:7;s:5:"email";N;}s:4:"tags";a:2:{i:0;s:3:"php";i:1;s:3:"vrl";}}"#],
+            want: Ok(value!({user: {id: 7, email: null}, tags: ["php", "vrl"]})),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        integer_keys_out_of_order {
+            args: func_args![value: r#"a:2:{i:1;s:1:"b";i:0;s:1:"a";}"#],
+            want: Ok(value!({"0": "a", "1": "b"})),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        empty_array {
+            args: func_args![value: "a:0:{}"],
+            want: Ok(value!([])),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        scalars {
+            args: func_args![value: r#"a:5:{i:0;i:-12;i:1;d:0.5;i:2;d:1.0E+25;i:3;b:0;i:4;N;}"#],
+            want: Ok(Value::Array(vec![value!(-12), value!(0.5), value!(1.0e25), value!(false), Value::Null])),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        // Lengths are in bytes, and strings can contain quotes and semicolons.
+        string_bytes {
+            args: func_args![value: r#"s:11:"café "a;b"";"#],
+            want: Ok(value!(r#"café "a;b""#)),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        object {
+            args: func_args![value: "O:4:\"User\":3:{s:4:\"name\";s:3:\"Bob\";s:8:\"\0User\0id\";i:1;s:7:\"\0*\0role\";s:5:\"admin\";}"],
+            want: Ok(value!({name: "Bob", id: 1, role: "admin"})),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        enum_case {
+            args: func_args![value: r#"E:11:"Suit:Hearts";"#],
+            want: Ok(value!("Suit:Hearts")),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        missing_terminator {
+            args: func_args![value: r#"a:1:{s:1:"a";i:1}"#],
+            want: Err("unable to parse PHP serialized value: expected ';', got end of input at offset 17"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        wrong_string_length {
+            args: func_args![value: r#"s:3:"hello";"#],
+            want: Err(r#"unable to parse PHP serialized value: expected '"', got 'l' at offset 8"#),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        string_longer_than_input {
+            args: func_args![value: r#"s:99:"hello";"#],
+            want: Err("unable to parse PHP serialized value: string of length 99 is longer than the input at offset 6"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        invalid_integer {
+            args: func_args![value: r#"a:1:{i:0;i:12x;}"#],
+            want: Err(r#"unable to parse PHP serialized value: invalid integer "12x" at offset 11"#),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        missing_entries {
+            args: func_args![value: r#"a:2:{i:0;i:1;}"#],
+            want: Err("unable to parse PHP serialized value: unsupported key type '}' at offset 13"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        trailing_data {
+            args: func_args![value: "i:1;i:2;"],
+            want: Err("unable to parse PHP serialized value: unexpected data after the value at offset 4"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        reference {
+            args: func_args![value: r#"a:2:{i:0;s:1:"a";i:1;R:2;}"#],
+            want: Err("unable to parse PHP serialized value: unsupported type 'R' at offset 21"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        not_a_number {
+            args: func_args![value: "d:NAN;"],
+            want: Err("unable to parse PHP serialized value: float NaN can't be represented at offset 2"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+
+        empty {
+            args: func_args![value: ""],
+            want: Err("unable to parse PHP serialized value: expected a value, got end of input at offset 0"),
+            tdef: TypeDef::from(kind()).fallible(),
+        }
+    ];
+
+    #[test]
+    fn deeply_nested() {
+        let input = "a:1:{i:0;".repeat(200) + "N;" + &"}".repeat(200);
+        let error = parse(input.as_bytes()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "nested deeper than 128 levels at offset 1154"
+        );
+    }
+
+    #[test]
+    fn error_code() {
+        let error = parse_php_serialized("x".into()).unwrap_err();
+        assert_eq!(error.error_code(), Some(ErrorCode::Parse));
+    }
+}
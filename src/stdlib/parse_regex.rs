@@ -41,6 +41,13 @@ impl Function for ParseRegex {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "numeric_groups" => Some(false.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         state: &state::TypeState,
@@ -42,6 +42,13 @@ impl Function for ParseRegexAll {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "numeric_groups" => Some(false.into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         _state: &state::TypeState,
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+
+use crate::compiler::prelude::*;
+
+fn parse_semver(value: Value) -> Resolved {
+    let version = parse_version(value)?;
+
+    let mut map = BTreeMap::<&str, Value>::new();
+    map.insert("major", version_number(version.major)?);
+    map.insert("minor", version_number(version.minor)?);
+    map.insert("patch", version_number(version.patch)?);
+    map.insert("prerelease", optional_identifiers(version.pre.as_str()));
+    map.insert("build", optional_identifiers(version.build.as_str()));
+
+    Ok(map
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect::<Value>())
+}
+
+/// Parses a string as a version, following the Semantic Versioning 2.0.0 specification.
+pub(crate) fn parse_version(value: Value) -> std::result::Result<Version, ExpressionError> {
+    let string = value.try_bytes_utf8_lossy()?;
+    Version::parse(&string).map_err(|e| {
+        ExpressionError::with_code(ErrorCode::Parse, format!("unable to parse semver: {e}"))
+    })
+}
+
+fn version_number(number: u64) -> std::result::Result<Value, ExpressionError> {
+    i64::try_from(number).map(Value::from).map_err(|_| {
+        ExpressionError::with_code(
+            ErrorCode::OutOfRange,
+            format!("version number {number} is too large"),
+        )
+    })
+}
+
+fn optional_identifiers(identifiers: &str) -> Value {
+    if identifiers.is_empty() {
+        Value::Null
+    } else {
+        identifiers.to_owned().into()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ParseSemver;
+
+impl Function for ParseSemver {
+    fn identifier(&self) -> &'static str {
+        "parse_semver"
+    }
+
+    fn summary(&self) -> &'static str {
+        "parse a semantic version"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Parses the provided `value` as a version following the Semantic Versioning 2.0.0
+            specification, such as `"1.2.3-rc.1+build.5"`.
+
+            Returns an object with the `major`, `minor` and `patch` numbers, and the `prerelease`
+            and `build` identifiers as strings, which are `null` if the version has none.
+
+            The version must match the specification exactly, so a leading `v`, leading zeros,
+            and missing `minor` or `patch` numbers fail the function.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::BYTES,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "release",
+                source: r#"parse_semver!("1.2.3")"#,
+                result: Ok(indoc! {r#"
+                    {
+                        "build": null,
+                        "major": 1,
+                        "minor": 2,
+                        "patch": 3,
+                        "prerelease": null
+                    }
+                "#}),
+            },
+            Example {
+                title: "prerelease and build metadata",
+                source: r#"parse_semver!("2.0.0-rc.1+build.5")"#,
+                result: Ok(indoc! {r#"
+                    {
+                        "build": "build.5",
+                        "major": 2,
+                        "minor": 0,
+                        "patch": 0,
+                        "prerelease": "rc.1"
+                    }
+                "#}),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+
+        Ok(ParseSemverFn { value }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ParseSemverFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ParseSemverFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        parse_semver(value)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::object(inner_kind()).fallible()
+    }
+}
+
+fn inner_kind() -> BTreeMap<Field, Kind> {
+    BTreeMap::from([
+        ("major".into(), Kind::integer()),
+        ("minor".into(), Kind::integer()),
+        ("patch".into(), Kind::integer()),
+        ("prerelease".into(), Kind::bytes().or_null()),
+        ("build".into(), Kind::bytes().or_null()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        parse_semver => ParseSemver;
+
+        release {
+            args: func_args![value: "1.2.3"],
+            want: Ok(value!({
+                major: 1,
+                minor: 2,
+                patch: 3,
+                prerelease: (),
+                build: (),
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        prerelease {
+            args: func_args![value: "1.0.0-alpha.1"],
+            want: Ok(value!({
+                major: 1,
+                minor: 0,
+                patch: 0,
+                prerelease: "alpha.1",
+                build: (),
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        build_metadata {
+            args: func_args![value: "1.0.0+20130313144700"],
+            want: Ok(value!({
+                major: 1,
+                minor: 0,
+                patch: 0,
+                prerelease: (),
+                build: "20130313144700",
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        prerelease_and_build_metadata {
+            args: func_args![value: "10.20.30-beta.11+exp.sha.5114f85"],
+            want: Ok(value!({
+                major: 10,
+                minor: 20,
+                patch: 30,
+                prerelease: "beta.11",
+                build: "exp.sha.5114f85",
+            })),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        leading_v {
+            args: func_args![value: "v1.2.3"],
+            want: Err("unable to parse semver: unexpected character 'v' while parsing major version number"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        missing_patch {
+            args: func_args![value: "1.2"],
+            want: Err("unable to parse semver: unexpected end of input while parsing minor version number"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        leading_zero {
+            args: func_args![value: "01.2.3"],
+            want: Err("unable to parse semver: invalid leading zero in major version number"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+
+        too_large {
+            args: func_args![value: "9223372036854775808.0.0"],
+            want: Err("version number 9223372036854775808 is too large"),
+            tdef: TypeDef::object(inner_kind()).fallible(),
+        }
+    ];
+
+    #[test]
+    fn error_codes() {
+        let code = |value: &str| parse_semver(value.into()).unwrap_err().error_code();
+
+        assert_eq!(code("nope"), Some(ErrorCode::Parse));
+        assert_eq!(code("9223372036854775808.0.0"), Some(ErrorCode::OutOfRange));
+    }
+}
@@ -5,16 +5,26 @@ use syslog_loose::{IncompleteDate, Message, ProcId, Protocol, Variant};
 
 pub(crate) fn parse_syslog(value: Value, ctx: &Context) -> Resolved {
     let message = value.try_bytes_utf8_lossy()?;
-    let timezone = match ctx.timezone() {
-        TimeZone::Local => None,
-        TimeZone::Named(tz) => Some(*tz),
-    };
-    let parsed = syslog_loose::parse_message_with_year_exact_tz(
-        &message,
-        resolve_year,
-        timezone,
-        Variant::Either,
-    )?;
+    let parsed = match ctx.timezone() {
+        TimeZone::Local => syslog_loose::parse_message_with_year_exact_tz::<_, chrono_tz::Tz>(
+            &message,
+            resolve_year,
+            None,
+            Variant::Either,
+        ),
+        TimeZone::Named(tz) => syslog_loose::parse_message_with_year_exact_tz(
+            &message,
+            resolve_year,
+            Some(*tz),
+            Variant::Either,
+        ),
+        TimeZone::Fixed(offset) => syslog_loose::parse_message_with_year_exact_tz(
+            &message,
+            resolve_year,
+            Some(*offset),
+            Variant::Either,
+        ),
+    }?;
     Ok(message_to_value(parsed))
 }
 
@@ -1,17 +1,30 @@
 use crate::compiler::conversion::Conversion;
 use crate::compiler::prelude::*;
+use crate::stdlib::util::parse_timezone;
 
-fn parse_timestamp(value: Value, format: Value, ctx: &Context) -> Resolved {
+fn parse_timestamp(
+    value: Value,
+    format: Value,
+    timezone: Option<Value>,
+    ctx: &Context,
+) -> Resolved {
     match value {
         Value::Bytes(v) => {
             let format = format.try_bytes_utf8_lossy()?;
-            Conversion::parse(format!("timestamp|{format}"), *ctx.timezone())
-                .map_err(|e| e.to_string())?
+            let timezone = match timezone {
+                Some(timezone) => parse_timezone(&timezone, *ctx.timezone())?,
+                None => *ctx.timezone(),
+            };
+            Conversion::parse(format!("timestamp|{format}"), timezone)
+                .map_err(|e| ExpressionError::with_code(ErrorCode::InvalidArgument, e.to_string()))?
                 .convert(v)
-                .map_err(|e| e.to_string().into())
+                .map_err(|e| ExpressionError::with_code(e.error_code(), e.to_string()))
         }
         Value::Timestamp(_) => Ok(value),
-        _ => Err("unable to convert value to timestamp".into()),
+        _ => Err(ExpressionError::with_code(
+            ErrorCode::InvalidType,
+            "unable to convert value to timestamp",
+        )),
     }
 }
 
@@ -24,11 +37,23 @@ impl Function for ParseTimestamp {
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "valid",
-            source: r#"parse_timestamp!("11-Feb-2021 16:00 +00:00", format: "%v %R %z")"#,
-            result: Ok("t'2021-02-11T16:00:00Z'"),
-        }]
+        &[
+            Example {
+                title: "valid",
+                source: r#"parse_timestamp!("11-Feb-2021 16:00 +00:00", format: "%v %R %z")"#,
+                result: Ok("t'2021-02-11T16:00:00Z'"),
+            },
+            Example {
+                title: "with timezone",
+                source: r#"parse_timestamp!("11-Feb-2021 16:00", format: "%v %R", timezone: "Europe/Berlin")"#,
+                result: Ok("t'2021-02-11T15:00:00Z'"),
+            },
+            Example {
+                title: "with UTC offset",
+                source: r#"parse_timestamp!("11-Feb-2021 16:00", format: "%v %R", timezone: "-05:00")"#,
+                result: Ok("t'2021-02-11T21:00:00Z'"),
+            },
+        ]
     }
 
     fn compile(
@@ -39,8 +64,14 @@ impl Function for ParseTimestamp {
     ) -> Compiled {
         let value = arguments.required("value");
         let format = arguments.required("format");
+        let timezone = arguments.optional("timezone");
 
-        Ok(ParseTimestampFn { value, format }.as_expr())
+        Ok(ParseTimestampFn {
+            value,
+            format,
+            timezone,
+        }
+        .as_expr())
     }
 
     fn parameters(&self) -> &'static [Parameter] {
@@ -55,21 +86,39 @@ impl Function for ParseTimestamp {
                 kind: kind::BYTES,
                 required: true,
             },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timezone" => Some(Value::Null),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct ParseTimestampFn {
     value: Box<dyn Expression>,
     format: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for ParseTimestampFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
         let format = self.format.resolve(ctx)?;
-        parse_timestamp(value, format, ctx)
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| timezone.resolve(ctx))
+            .transpose()?;
+        parse_timestamp(value, format, timezone, ctx)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -129,5 +178,61 @@ mod tests {
             tdef: TypeDef::timestamp().fallible(),
             tz: TimeZone::Named(chrono_tz::Europe::Paris),
         }
+
+        parse_text_with_explicit_tz {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "America/New_York"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Wed, 16 Oct 2019 16:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: TimeZone::Named(chrono_tz::Europe::Paris),
+        }
+
+        parse_text_with_offset {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "+0530"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Wed, 16 Oct 2019 06:30:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: TimeZone::default(),
+        }
+
+        parse_text_with_local_uses_host_tz {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "local"
+            ],
+            want: Ok(value!(
+                DateTime::parse_from_rfc2822("Wed, 16 Oct 2019 03:00:00 +0000")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: TimeZone::Named(chrono_tz::Asia::Tokyo),
+        }
+
+        parse_text_with_invalid_tz {
+            args: func_args![
+                value: "16/10/2019:12:00:00",
+                format: "%d/%m/%Y:%H:%M:%S",
+                timezone: "Mars/Olympus_Mons"
+            ],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::timestamp().fallible(),
+            tz: TimeZone::default(),
+        }
     ];
 }
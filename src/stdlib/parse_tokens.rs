@@ -1,19 +1,76 @@
+use std::str::FromStr;
+
 use crate::compiler::prelude::*;
 use crate::core::tokenize;
 
-fn parse_tokens(value: Value) -> Resolved {
+fn parse_tokens(value: Value, style: Style) -> Resolved {
     let string = value.try_bytes_utf8_lossy()?;
-    let tokens: Value = tokenize::parse(&string)
-        .into_iter()
-        .map(|token| match token {
-            "" | "-" => Value::Null,
-            _ => token.to_owned().into(),
-        })
-        .collect::<Vec<_>>()
-        .into();
+    let tokens: Value = match style {
+        Style::Log => tokenize::parse(&string)
+            .into_iter()
+            .map(|token| match token {
+                "" | "-" => Value::Null,
+                _ => token.to_owned().into(),
+            })
+            .collect::<Vec<_>>()
+            .into(),
+        Style::Shell => tokenize::parse_quoted(&string)
+            .map_err(|err| format!("unable to parse tokens: {err}"))?
+            .into_iter()
+            .map(Value::from)
+            .collect::<Vec<_>>()
+            .into(),
+    };
     Ok(tokens)
 }
 
+/// How the value is split into tokens.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Style {
+    /// Tokens of a log line: `"..."` and `[...]` spans are single tokens, and empty or `-`
+    /// tokens are `null`.
+    #[default]
+    Log,
+
+    /// Shell-like tokens: single and double quoted spans are part of a token and have their
+    /// quotes removed, and a quote that isn't closed is an error.
+    Shell,
+}
+
+impl Style {
+    fn all_value() -> Vec<Value> {
+        use Style::{Log, Shell};
+
+        vec![Log, Shell]
+            .into_iter()
+            .map(|s| s.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use Style::{Log, Shell};
+
+        match self {
+            Log => "log",
+            Shell => "shell",
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Style::{Log, Shell};
+
+        match s {
+            "log" => Ok(Log),
+            "shell" => Ok(Shell),
+            _ => Err("style not recognized"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ParseTokens;
 
@@ -23,54 +80,86 @@ impl Function for ParseTokens {
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[Example {
-            title: "valid",
-            source: r#"parse_tokens(s'A sentence "with \"a\" sentence inside" and [some brackets]')"#,
-            result: Ok(
-                r#"["A", "sentence", "with \\\"a\\\" sentence inside", "and", "some brackets"]"#,
-            ),
-        }]
+        &[
+            Example {
+                title: "valid",
+                source: r#"parse_tokens(s'A sentence "with \"a\" sentence inside" and [some brackets]')"#,
+                result: Ok(
+                    r#"["A", "sentence", "with \\\"a\\\" sentence inside", "and", "some brackets"]"#,
+                ),
+            },
+            Example {
+                title: "shell style",
+                source: r#"parse_tokens!("git commit -m 'fix \"it\"' --author=\"J Doe\"", style: "shell")"#,
+                result: Ok(r#"["git", "commit", "-m", "fix \"it\"", "--author=J Doe"]"#),
+            },
+        ]
     }
 
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
+        let style = arguments
+            .optional_enum("style", &Style::all_value(), state)?
+            .map(|s| {
+                Style::from_str(&s.try_bytes_utf8_lossy().expect("style not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
 
-        Ok(ParseTokensFn { value }.as_expr())
+        Ok(ParseTokensFn { value, style }.as_expr())
     }
 
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            kind: kind::BYTES,
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "style",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "style" => Some(Style::default().as_str().into()),
+            _ => None,
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 struct ParseTokensFn {
     value: Box<dyn Expression>,
+    style: Style,
 }
 
 impl FunctionExpression for ParseTokensFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
-        parse_tokens(value)
+        parse_tokens(value, self.style)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // Only shell-style quotes can be left unterminated.
         TypeDef::array(Collection::from_unknown(Kind::bytes()))
+            .maybe_fallible(self.style == Style::Shell)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::value;
 
     test_function![
         parse_tokens => ParseTokens;
@@ -89,5 +178,29 @@ mod tests {
                     ]),
             tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())),
         }
+
+        shell {
+            args: func_args![value: r#"foo "bar baz" 'qux'"#, style: "shell"],
+            want: Ok(value!(["foo", "bar baz", "qux"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        shell_escaped_quotes {
+            args: func_args![value: r#"- "say \"hi\"" [x] 'it\'s'"#, style: "shell"],
+            want: Ok(value!(["-", r#"say "hi""#, "[x]", "it's"])),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        shell_unterminated_quote {
+            args: func_args![value: r#"foo "bar baz"#, style: "shell"],
+            want: Err("unable to parse tokens: unterminated quote at position 4"),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())).fallible(),
+        }
+
+        invalid_style {
+            args: func_args![value: "foo", style: "csv"],
+            want: Err(r#"invalid enum variant""#),
+            tdef: TypeDef::array(Collection::from_unknown(Kind::bytes())),
+        }
     ];
 }
@@ -51,6 +51,13 @@ impl Function for ParseUserAgent {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "mode" => Some(Mode::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -189,6 +189,20 @@ impl Function for ParseXml {
             },
         ]
     }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "trim" => Some(true.into()),
+            "include_attr" => Some(true.into()),
+            "attr_prefix" => Some("@".into()),
+            "text_key" => Some("text".into()),
+            "always_use_text_key" => Some(false.into()),
+            "parse_bool" => Some(true.into()),
+            "parse_null" => Some(true.into()),
+            "parse_number" => Some(true.into()),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
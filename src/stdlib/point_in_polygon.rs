@@ -0,0 +1,311 @@
+use crate::compiler::prelude::*;
+
+/// A polygon whose vertices are `(latitude, longitude)` pairs, where the last vertex is joined to
+/// the first one.
+///
+/// Coordinates are treated as points on a plane, so an edge is the straight line between its
+/// vertices on an equirectangular map. This is why edges can't cross the antimeridian: the
+/// shorter way around the globe between `179` and `-179` would be drawn across the whole map.
+#[derive(Clone, Debug)]
+struct Polygon(Vec<(f64, f64)>);
+
+impl Polygon {
+    fn from_value(value: Value) -> Result<Self, &'static str> {
+        let vertices = value
+            .try_array()
+            .map_err(|_| "polygon must be an array")?
+            .into_iter()
+            .map(vertex)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if vertices.len() < 3 {
+            return Err("polygon must have at least 3 vertices");
+        }
+        let crosses_antimeridian = vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .any(|(a, b)| (a.1 - b.1).abs() > 180.0);
+        if crosses_antimeridian {
+            return Err("polygon edges can't cross the antimeridian");
+        }
+
+        Ok(Self(vertices))
+    }
+
+    /// Returns whether the point is inside the polygon, using ray casting. A point that is
+    /// exactly on an edge or a vertex is inside.
+    fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        let mut inside = false;
+
+        let edges = self.0.iter().zip(self.0.iter().cycle().skip(1));
+        for (&(a_lat, a_lon), &(b_lat, b_lon)) in edges {
+            if on_segment((latitude, longitude), (a_lat, a_lon), (b_lat, b_lon)) {
+                return true;
+            }
+            if (a_lat > latitude) != (b_lat > latitude) {
+                let crossing = (b_lon - a_lon) * (latitude - a_lat) / (b_lat - a_lat) + a_lon;
+                if longitude < crossing {
+                    inside = !inside;
+                }
+            }
+        }
+
+        inside
+    }
+}
+
+fn vertex(value: Value) -> Result<(f64, f64), &'static str> {
+    const INVALID: &str = "vertex must be an array of a latitude and a longitude";
+
+    let coordinates = value.try_array().map_err(|_| INVALID)?;
+    let [latitude, longitude] = coordinates.as_slice() else {
+        return Err(INVALID);
+    };
+    let latitude = coordinate(latitude).ok_or(INVALID)?;
+    let longitude = coordinate(longitude).ok_or(INVALID)?;
+
+    if !(-90.0..=90.0).contains(&latitude) {
+        return Err("latitude must be between -90 and 90");
+    }
+    if !(-180.0..=180.0).contains(&longitude) {
+        return Err("longitude must be between -180 and 180");
+    }
+
+    Ok((latitude, longitude))
+}
+
+#[allow(clippy::cast_precision_loss)] // coordinates are far from the integers f64 can't represent
+fn coordinate(value: &Value) -> Option<f64> {
+    match value {
+        Value::Float(float) => Some(float.into_inner()),
+        Value::Integer(int) => Some(*int as f64),
+        _ => None,
+    }
+}
+
+#[allow(clippy::float_cmp)] // only exactly collinear points are on the edge
+fn on_segment(point: (f64, f64), a: (f64, f64), b: (f64, f64)) -> bool {
+    let cross = (b.0 - a.0) * (point.1 - a.1) - (b.1 - a.1) * (point.0 - a.0);
+
+    cross == 0.0
+        && point.0 >= a.0.min(b.0)
+        && point.0 <= a.0.max(b.0)
+        && point.1 >= a.1.min(b.1)
+        && point.1 <= a.1.max(b.1)
+}
+
+fn point_in_polygon(latitude: &Value, longitude: &Value, polygon: &Polygon) -> Resolved {
+    let expected = || Kind::float() | Kind::integer();
+    let latitude = coordinate(latitude).ok_or_else(|| ValueError::Expected {
+        got: latitude.kind(),
+        expected: expected(),
+    })?;
+    let longitude = coordinate(longitude).ok_or_else(|| ValueError::Expected {
+        got: longitude.kind(),
+        expected: expected(),
+    })?;
+
+    Ok(polygon.contains(latitude, longitude).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PointInPolygon;
+
+impl Function for PointInPolygon {
+    fn identifier(&self) -> &'static str {
+        "point_in_polygon"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "latitude",
+                kind: kind::FLOAT | kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "longitude",
+                kind: kind::FLOAT | kind::INTEGER,
+                required: true,
+            },
+            Parameter {
+                keyword: "polygon",
+                kind: kind::ARRAY,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "inside",
+                source: r#"point_in_polygon(48.8584, 2.2945, [[48.9, 2.2], [48.9, 2.4], [48.8, 2.4], [48.8, 2.2]])"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "outside",
+                source: r#"point_in_polygon(51.5072, -0.1276, [[48.9, 2.2], [48.9, 2.4], [48.8, 2.4], [48.8, 2.2]])"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let latitude = arguments.required("latitude");
+        let longitude = arguments.required("longitude");
+        let polygon = arguments.required("polygon");
+
+        // A literal polygon is validated once, at compile time.
+        let constant = match polygon.resolve_constant(state) {
+            Some(value) => Some(Polygon::from_value(value.clone()).map_err(|error| {
+                function::Error::InvalidArgument {
+                    keyword: "polygon",
+                    value,
+                    error,
+                }
+            })?),
+            None => None,
+        };
+
+        Ok(PointInPolygonFn {
+            latitude,
+            longitude,
+            polygon,
+            constant,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PointInPolygonFn {
+    latitude: Box<dyn Expression>,
+    longitude: Box<dyn Expression>,
+    polygon: Box<dyn Expression>,
+    constant: Option<Polygon>,
+}
+
+impl FunctionExpression for PointInPolygonFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let latitude = self.latitude.resolve(ctx)?;
+        let longitude = self.longitude.resolve(ctx)?;
+
+        if let Some(polygon) = &self.constant {
+            return point_in_polygon(&latitude, &longitude, polygon);
+        }
+
+        let polygon = Polygon::from_value(self.polygon.resolve(ctx)?)
+            .map_err(|err| format!("invalid polygon: {err}"))?;
+
+        point_in_polygon(&latitude, &longitude, &polygon)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // A polygon that is only known at runtime can be invalid.
+        TypeDef::boolean().maybe_fallible(self.constant.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        point_in_polygon => PointInPolygon;
+
+        inside {
+            args: func_args![
+                latitude: 1.0,
+                longitude: 1.5,
+                polygon: value!([[0.0, 0.0], [0.0, 3.0], [2.0, 4.0], [3.0, 0.0]]),
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        outside {
+            args: func_args![
+                latitude: 2.5,
+                longitude: 3.5,
+                polygon: value!([[0.0, 0.0], [0.0, 3.0], [2.0, 4.0], [3.0, 0.0]]),
+            ],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        outside_in_line_with_vertex {
+            args: func_args![
+                latitude: 2,
+                longitude: (-1),
+                polygon: value!([[0, 2], [2, 4], [4, 2], [2, 0]]),
+            ],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        on_edge {
+            args: func_args![
+                latitude: 1.0,
+                longitude: 2.0,
+                polygon: value!([[0.0, 0.0], [2.0, 4.0], [3.0, 0.0]]),
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        on_closing_edge {
+            args: func_args![
+                latitude: 0.0,
+                longitude: 1.5,
+                polygon: value!([[0.0, 0.0], [0.0, 3.0], [2.0, 4.0], [3.0, 0.0]]),
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        on_vertex {
+            args: func_args![
+                latitude: 2,
+                longitude: 4,
+                polygon: value!([[0, 0], [0, 3], [2, 4], [3, 0]]),
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        too_few_vertices {
+            args: func_args![latitude: 0, longitude: 0, polygon: value!([[0, 0], [1, 1]])],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        invalid_vertex {
+            args: func_args![latitude: 0, longitude: 0, polygon: value!([[0, 0], [1, 1], [1]])],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        latitude_out_of_range {
+            args: func_args![latitude: 0, longitude: 0, polygon: value!([[0, 0], [91, 1], [1, 2]])],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        crosses_antimeridian {
+            args: func_args![
+                latitude: 0,
+                longitude: 180,
+                polygon: value!([[(-10), 170], [10, 170], [10, (-170)], [(-10), (-170)]]),
+            ],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+    ];
+}
@@ -1,4 +1,7 @@
 use crate::compiler::prelude::*;
+use crate::stdlib::util::{
+    max_depth_exceeded, may_exceed_max_depth, resolve_max_depth, DEFAULT_MAX_DEPTH,
+};
 use once_cell::sync::Lazy;
 use std::{
     borrow::Cow,
@@ -38,9 +41,21 @@ impl Function for Redact {
                 kind: kind::ARRAY,
                 required: true,
             },
+            Parameter {
+                keyword: "max_depth",
+                kind: kind::INTEGER,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "max_depth" => Some(DEFAULT_MAX_DEPTH.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -89,11 +104,13 @@ impl Function for Redact {
             .collect::<std::result::Result<Vec<Filter>, _>>()?;
 
         let redactor = Redactor::Full;
+        let max_depth = arguments.optional("max_depth");
 
         Ok(RedactFn {
             value,
             filters,
             redactor,
+            max_depth,
         }
         .as_expr())
     }
@@ -106,33 +123,42 @@ struct RedactFn {
     value: Box<dyn Expression>,
     filters: Vec<Filter>,
     redactor: Redactor,
+    max_depth: Option<Box<dyn Expression>>,
 }
 
-fn redact(value: Value, filters: &[Filter], redactor: &Redactor) -> Value {
-    match value {
-        Value::Bytes(bytes) => {
-            let input = String::from_utf8_lossy(&bytes);
-            let output = filters.iter().fold(input, |input, filter| {
-                filter.redact(&input, redactor).into_owned().into()
-            });
-            Value::Bytes(output.into_owned().into())
-        }
-        Value::Array(values) => {
-            let values = values
-                .into_iter()
-                .map(|value| redact(value, filters, redactor))
-                .collect();
-            Value::Array(values)
-        }
-        Value::Object(map) => {
-            let map = map
-                .into_iter()
-                .map(|(key, value)| (key, redact(value, filters, redactor)))
-                .collect();
-            Value::Object(map)
+/// Redacts all strings in `value`, including those nested in collections.
+///
+/// Nested collections are walked using an explicit stack, so deeply nested input can't overflow
+/// the call stack.
+fn redact(mut value: Value, filters: &[Filter], redactor: &Redactor, max_depth: usize) -> Resolved {
+    let mut stack = vec![(&mut value, 0)];
+    let mut exceeded = false;
+
+    while let Some((value, depth)) = stack.pop() {
+        match value {
+            Value::Bytes(bytes) => {
+                let input = String::from_utf8_lossy(bytes);
+                let output = filters.iter().fold(input, |input, filter| {
+                    filter.redact(&input, redactor).into_owned().into()
+                });
+                *bytes = output.into_owned().into();
+            }
+            Value::Array(_) | Value::Object(_) if depth >= max_depth => {
+                exceeded = true;
+                break;
+            }
+            Value::Array(values) => stack.extend(values.iter_mut().map(|value| (value, depth + 1))),
+            Value::Object(map) => stack.extend(map.values_mut().map(|value| (value, depth + 1))),
+            _ => {}
         }
-        _ => value,
     }
+
+    if exceeded {
+        value.dismantle();
+        return Err(max_depth_exceeded(max_depth));
+    }
+
+    Ok(value)
 }
 
 impl FunctionExpression for RedactFn {
@@ -140,12 +166,16 @@ impl FunctionExpression for RedactFn {
         let value = self.value.resolve(ctx)?;
         let filters = &self.filters;
         let redactor = &self.redactor;
+        let max_depth = resolve_max_depth(self.max_depth.as_deref(), ctx)?;
 
-        Ok(redact(value, filters, redactor))
+        redact(value, filters, redactor, max_depth)
     }
 
     fn type_def(&self, state: &state::TypeState) -> TypeDef {
-        self.value.type_def(state).infallible()
+        let td = self.value.type_def(state);
+        let fallible = may_exceed_max_depth(td.kind(), self.max_depth.as_deref(), state);
+
+        td.maybe_fallible(fallible)
     }
 }
 
@@ -272,6 +302,23 @@ mod test {
     use crate::value;
     use regex::Regex;
 
+    #[test]
+    fn hostile_depth() {
+        // Deep enough to overflow the stack if it were cloned or dropped recursively.
+        let value = (0..50_000).fold(value!("123-12-1234"), |value, _| value!({"a": [value]}));
+
+        assert_eq!(
+            redact(
+                value,
+                &[Filter::UsSocialSecurityNumber],
+                &Redactor::Full,
+                DEFAULT_MAX_DEPTH
+            )
+            .map_err(|err| err.to_string()),
+            Err("maximum depth of 128 exceeded".to_owned())
+        );
+    }
+
     test_function![
         redact => Redact;
 
@@ -328,5 +375,38 @@ mod test {
              want: Err("invalid argument"),
              tdef: TypeDef::bytes().infallible(),
         }
+
+        nested_within_max_depth {
+             args: func_args![
+                 value: nested(10, "123-12-1234".into()),
+                 filters: vec!["us_social_security_number"],
+             ],
+             want: Ok(nested(10, "[REDACTED]".into())),
+             tdef: TypeDef::from(Kind::from(&nested(10, "".into()))).infallible(),
+        }
+
+        too_deeply_nested {
+             args: func_args![
+                 value: nested(130, "123-12-1234".into()),
+                 filters: vec!["us_social_security_number"],
+             ],
+             want: Err("maximum depth of 128 exceeded"),
+             tdef: TypeDef::from(Kind::from(&nested(130, "".into()))).fallible(),
+        }
+
+        explicit_max_depth {
+             args: func_args![
+                 value: nested(3, "123-12-1234".into()),
+                 filters: vec!["us_social_security_number"],
+                 max_depth: 2,
+             ],
+             want: Err("maximum depth of 2 exceeded"),
+             tdef: TypeDef::from(Kind::from(&nested(3, "".into()))).fallible(),
+        }
     ];
+
+    /// Wraps `value` in `depth` levels of objects.
+    fn nested(depth: usize, value: Value) -> Value {
+        (0..depth).fold(value, |value, _| value!({"a": value}))
+    }
 }
@@ -1,4 +1,7 @@
+use std::fmt;
+
 use crate::compiler::prelude::*;
+use crate::diagnostic::{Label, Note, Span};
 
 fn replace(value: Value, with_value: Value, count: Value, pattern: Value) -> Resolved {
     let value = value.try_bytes_utf8_lossy()?;
@@ -38,6 +41,147 @@ fn replace(value: Value, with_value: Value, count: Value, pattern: Value) -> Res
     }
 }
 
+fn replace_with_closure<T>(
+    value: Value,
+    pattern: Value,
+    count: Value,
+    ctx: &mut Context,
+    runner: closure::Runner<T>,
+) -> Resolved
+where
+    T: Fn(&mut Context) -> Resolved,
+{
+    let value = value.try_bytes_utf8_lossy()?;
+    let count = match count.try_integer()? {
+        i if i < 0 => usize::MAX,
+        i => i as usize,
+    };
+
+    let mut replaced = String::with_capacity(value.len());
+    let mut last_end = 0;
+
+    // Runs the closure for a single match, and appends the text before the match, followed by the
+    // replacement.
+    let mut push_replacement = |ctx: &mut Context,
+                                start: usize,
+                                end: usize,
+                                captures: Value|
+     -> Result<(), ExpressionError> {
+        let replacement = runner.run_key_value(ctx, &value[start..end], &captures)?;
+
+        replaced.push_str(&value[last_end..start]);
+        replaced.push_str(&replacement.try_bytes_utf8_lossy()?);
+        last_end = end;
+
+        Ok(())
+    };
+
+    match pattern {
+        Value::Bytes(bytes) => {
+            let pattern = String::from_utf8_lossy(&bytes);
+            for (start, matched) in value.match_indices(pattern.as_ref()).take(count) {
+                push_replacement(ctx, start, start + matched.len(), Value::Array(vec![]))?;
+            }
+        }
+        Value::Regex(regex) => {
+            let named = regex.capture_names().flatten().next().is_some();
+
+            for captures in regex.captures_iter(&value).take(count) {
+                let matched = captures.get(0).expect("capture group 0 always exists");
+                let groups = if named {
+                    regex
+                        .capture_names()
+                        .flatten()
+                        .map(|name| (name.into(), capture_value(captures.name(name))))
+                        .collect::<ObjectMap>()
+                        .into()
+                } else {
+                    captures
+                        .iter()
+                        .skip(1)
+                        .map(capture_value)
+                        .collect::<Vec<_>>()
+                        .into()
+                };
+
+                push_replacement(ctx, matched.start(), matched.end(), groups)?;
+            }
+        }
+        value => {
+            return Err(ValueError::Expected {
+                got: value.kind(),
+                expected: Kind::regex() | Kind::bytes(),
+            }
+            .into())
+        }
+    }
+
+    replaced.push_str(&value[last_end..]);
+
+    Ok(replaced.into())
+}
+
+fn capture_value(capture: Option<regex::Match<'_>>) -> Value {
+    capture.map_or(Value::Null, |capture| capture.as_str().into())
+}
+
+/// The kind of the `captures` closure variable: either an array of the capture groups, or an
+/// object of the named capture groups if there are any. Groups that didn't participate in the
+/// match are `null`.
+fn captures_kind() -> Kind {
+    Kind::array(Collection::from_unknown(Kind::bytes().or_null()))
+        .or_object(Collection::from_unknown(Kind::bytes().or_null()))
+}
+
+#[derive(Debug)]
+enum Error {
+    WithAndClosure,
+    MissingReplacement,
+    ClosureReturnKind(Kind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::WithAndClosure => f.write_str("conflicting replacement arguments"),
+            Error::MissingReplacement => f.write_str("missing replacement"),
+            Error::ClosureReturnKind(_) => f.write_str("type mismatch in closure return type"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl DiagnosticMessage for Error {
+    fn code(&self) -> usize {
+        match self {
+            Error::WithAndClosure | Error::MissingReplacement => 107,
+            Error::ClosureReturnKind(_) => 122,
+        }
+    }
+
+    fn labels(&self) -> Vec<Label> {
+        match self {
+            Error::WithAndClosure => vec![Label::primary(
+                r#"the "with" argument can't be combined with a closure"#,
+                Span::default(),
+            )],
+            Error::MissingReplacement => vec![Label::primary(
+                r#"either the "with" argument or a closure is required"#,
+                Span::default(),
+            )],
+            Error::ClosureReturnKind(kind) => vec![
+                Label::primary("the closure must return a string", Span::default()),
+                Label::context(format!("received: {kind}"), Span::default()),
+            ],
+        }
+    }
+
+    fn notes(&self) -> Vec<Note> {
+        vec![Note::SeeCodeDocs(self.code())]
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Replace;
 
@@ -61,7 +205,7 @@ impl Function for Replace {
             Parameter {
                 keyword: "with",
                 kind: kind::BYTES,
-                required: true,
+                required: false,
             },
             Parameter {
                 keyword: "count",
@@ -71,6 +215,14 @@ impl Function for Replace {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "count" => Some((-1).into()),
+            "with" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -93,6 +245,11 @@ impl Function for Replace {
                 source: r#"replace("foo123bar", r'foo(?P<num>\d+)bar', "$num")"#,
                 result: Ok(r#""123""#),
             },
+            Example {
+                title: "replace with closure",
+                source: r#"replace("user=alice user=bob", r'user=(\w+)') -> |_match, captures| { "user=" + upcase(string!(captures[0])) }"#,
+                result: Ok(r#""user=ALICE user=BOB""#),
+            },
         ]
     }
 
@@ -104,9 +261,22 @@ impl Function for Replace {
     ) -> Compiled {
         let value = arguments.required("value");
         let pattern = arguments.required("pattern");
-        let with = arguments.required("with");
         let count = arguments.optional("count").unwrap_or(expr!(-1));
 
+        let with = match (arguments.optional("with"), arguments.optional_closure()) {
+            (Some(with), None) => With::Template(with),
+            (None, Some(closure)) => {
+                let kind = closure.block_type_def.kind();
+                if !kind.contains_bytes() {
+                    return Err(Box::new(Error::ClosureReturnKind(kind.clone())) as _);
+                }
+
+                With::Closure(closure.clone())
+            }
+            (Some(_), Some(_)) => return Err(Box::new(Error::WithAndClosure) as _),
+            (None, None) => return Err(Box::new(Error::MissingReplacement) as _),
+        };
+
         Ok(ReplaceFn {
             value,
             pattern,
@@ -115,28 +285,83 @@ impl Function for Replace {
         }
         .as_expr())
     }
+
+    fn closure(&self) -> Option<closure::Definition> {
+        use closure::{Definition, Input, Output, Variable, VariableKind};
+
+        Some(Definition {
+            inputs: vec![Input {
+                parameter_keyword: "pattern",
+                kind: Kind::bytes().or_regex(),
+                variables: vec![
+                    Variable {
+                        kind: VariableKind::Exact(Kind::bytes()),
+                    },
+                    Variable {
+                        kind: VariableKind::Exact(captures_kind()),
+                    },
+                ],
+                // The closure must return a string, but any kind is accepted here, to make the
+                // function fallible instead of rejecting closures that might return a string.
+                output: Output::Kind(Kind::any()),
+                example: Example {
+                    title: "replace with closure",
+                    source: r#"replace("a1b22", r'\d+') -> |match, _captures| { to_string(strlen(match)) }"#,
+                    result: Ok(r#""a1b2""#),
+                },
+            }],
+            is_iterator: false,
+            is_optional: true,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum With {
+    /// A replacement string, which can refer to capture groups using `$name`.
+    Template(Box<dyn Expression>),
+
+    /// A closure computing the replacement for each match.
+    Closure(FunctionClosure),
 }
 
 #[derive(Debug, Clone)]
 struct ReplaceFn {
     value: Box<dyn Expression>,
     pattern: Box<dyn Expression>,
-    with: Box<dyn Expression>,
+    with: With,
     count: Box<dyn Expression>,
 }
 
 impl FunctionExpression for ReplaceFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let value = self.value.resolve(ctx)?;
-        let with_value = self.with.resolve(ctx)?;
         let count = self.count.resolve(ctx)?;
         let pattern = self.pattern.resolve(ctx)?;
 
-        replace(value, with_value, count, pattern)
+        match &self.with {
+            With::Template(with) => {
+                let with_value = with.resolve(ctx)?;
+                replace(value, with_value, count, pattern)
+            }
+            With::Closure(FunctionClosure {
+                variables,
+                block,
+                block_type_def: _,
+            }) => {
+                let runner = closure::Runner::new(variables, |ctx| block.resolve(ctx));
+                replace_with_closure(value, pattern, count, ctx, runner)
+            }
+        }
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::bytes().infallible()
+        match &self.with {
+            With::Template(_) => TypeDef::bytes().infallible(),
+            With::Closure(closure) => {
+                TypeDef::bytes().maybe_fallible(!closure.block_type_def.kind().is_bytes())
+            }
+        }
     }
 }
 
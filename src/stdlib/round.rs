@@ -1,15 +1,23 @@
+use std::str::FromStr;
+
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal, RoundingStrategy,
+};
+
 use crate::compiler::prelude::*;
 
 use super::util::round_to_precision;
 
-fn round(precision: Value, value: Value) -> Resolved {
+fn round(precision: Value, value: Value, mode: Mode) -> Resolved {
     let precision = precision.try_integer()?;
     match value {
-        Value::Float(f) => Ok(Value::from_f64_or_zero(round_to_precision(
+        Value::Float(f) => Ok(Value::from_f64_or_zero(round_float(
             f.into_inner(),
             precision,
-            f64::round,
+            mode,
         ))),
+        Value::Integer(i) if precision < 0 => Ok(round_integer(i, precision, mode).into()),
         value @ Value::Integer(_) => Ok(value),
         value => Err(ValueError::Expected {
             got: value.kind(),
@@ -19,6 +27,136 @@ fn round(precision: Value, value: Value) -> Resolved {
     }
 }
 
+/// Rounds the shortest decimal representation of `num`, so that a float like `1.005`, which is
+/// slightly less than that in binary, is rounded the way it reads. Floats that are out of the
+/// range of a decimal are rounded as they are.
+fn round_float(num: f64, precision: i64, mode: Mode) -> f64 {
+    Decimal::from_f64(num)
+        .and_then(|decimal| {
+            if precision >= 0 {
+                let precision = u32::try_from(precision).unwrap_or(u32::MAX);
+                return Some(decimal.round_dp_with_strategy(precision, mode.strategy()));
+            }
+
+            let factor = u32::try_from(-precision)
+                .ok()
+                .and_then(|exponent| 10_i128.checked_pow(exponent))
+                .and_then(Decimal::from_i128)?;
+            (decimal / factor)
+                .round_dp_with_strategy(0, mode.strategy())
+                .checked_mul(factor)
+        })
+        .and_then(|decimal| decimal.to_f64())
+        .unwrap_or_else(|| round_to_precision(num, precision, |num| mode.round(num)))
+}
+
+/// Rounds `num` to a negative `precision`, that is to tens, hundreds, and so on. Results that
+/// don't fit in an integer saturate.
+fn round_integer(num: i64, precision: i64, mode: Mode) -> i64 {
+    let Some(factor) = u32::try_from(-precision)
+        .ok()
+        .and_then(|exponent| 10_i128.checked_pow(exponent))
+    else {
+        return 0;
+    };
+
+    let num = i128::from(num);
+    let (quotient, remainder) = (num / factor, num % factor);
+    let away_from_zero = match mode {
+        Mode::HalfUp => remainder.abs() * 2 >= factor,
+        Mode::HalfEven => {
+            remainder.abs() * 2 > factor || (remainder.abs() * 2 == factor && quotient % 2 != 0)
+        }
+        Mode::TowardZero => false,
+    };
+    let quotient = if away_from_zero {
+        quotient + num.signum()
+    } else {
+        quotient
+    };
+
+    quotient
+        .checked_mul(factor)
+        .and_then(|rounded| i64::try_from(rounded).ok())
+        .unwrap_or(if num < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// How a value is rounded, when it's not already at the requested precision.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Mode {
+    /// Round to the nearest value, and away from zero when halfway between two values.
+    #[default]
+    HalfUp,
+
+    /// Round to the nearest value, and to the even one when halfway between two values, which
+    /// is also known as banker's rounding.
+    HalfEven,
+
+    /// Truncate the digits beyond the precision.
+    TowardZero,
+}
+
+impl Mode {
+    fn all_value() -> Vec<Value> {
+        use Mode::{HalfEven, HalfUp, TowardZero};
+
+        vec![HalfUp, HalfEven, TowardZero]
+            .into_iter()
+            .map(|m| m.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use Mode::{HalfEven, HalfUp, TowardZero};
+
+        match self {
+            HalfUp => "half_up",
+            HalfEven => "half_even",
+            TowardZero => "toward_zero",
+        }
+    }
+
+    const fn strategy(self) -> RoundingStrategy {
+        match self {
+            Self::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Self::HalfEven => RoundingStrategy::MidpointNearestEven,
+            Self::TowardZero => RoundingStrategy::ToZero,
+        }
+    }
+
+    // Only an exact midpoint is rounded to even.
+    #[allow(clippy::float_cmp)]
+    fn round(self, num: f64) -> f64 {
+        match self {
+            Self::HalfUp => num.round(),
+            Self::HalfEven => {
+                let rounded = num.round();
+                if (rounded - num).abs() == 0.5 {
+                    2.0 * (num / 2.0).round()
+                } else {
+                    rounded
+                }
+            }
+            Self::TowardZero => num.trunc(),
+        }
+    }
+}
+
+impl FromStr for Mode {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use Mode::{HalfEven, HalfUp, TowardZero};
+
+        match s {
+            "half_up" => Ok(HalfUp),
+            "half_even" => Ok(HalfEven),
+            "toward_zero" => Ok(TowardZero),
+            _ => Err("mode not recognized"),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Round;
 
@@ -39,9 +177,22 @@ impl Function for Round {
                 kind: kind::INTEGER,
                 required: false,
             },
+            Parameter {
+                keyword: "mode",
+                kind: kind::BYTES,
+                required: false,
+            },
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "precision" => Some(0.into()),
+            "mode" => Some(Mode::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -59,19 +210,41 @@ impl Function for Round {
                 source: r#"round(5.45, 1)"#,
                 result: Ok("5.5"),
             },
+            Example {
+                title: "negative precision",
+                source: r#"round(1250, -2)"#,
+                result: Ok("1300"),
+            },
+            Example {
+                title: "banker's rounding",
+                source: r#"round(2.5, mode: "half_even")"#,
+                result: Ok("2.0"),
+            },
         ]
     }
 
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let precision = arguments.optional("precision").unwrap_or(expr!(0));
+        let mode = arguments
+            .optional_enum("mode", &Mode::all_value(), state)?
+            .map(|s| {
+                Mode::from_str(&s.try_bytes_utf8_lossy().expect("mode not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
 
-        Ok(RoundFn { value, precision }.as_expr())
+        Ok(RoundFn {
+            value,
+            precision,
+            mode,
+        }
+        .as_expr())
     }
 }
 
@@ -79,6 +252,7 @@ impl Function for Round {
 struct RoundFn {
     value: Box<dyn Expression>,
     precision: Box<dyn Expression>,
+    mode: Mode,
 }
 
 impl FunctionExpression for RoundFn {
@@ -86,7 +260,7 @@ impl FunctionExpression for RoundFn {
         let precision = self.precision.resolve(ctx)?;
         let value = self.value.resolve(ctx)?;
 
-        round(precision, value)
+        round(precision, value, self.mode)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -142,5 +316,84 @@ mod tests {
              want: Ok(9_876_543_210_123_456_789_098_765_432_101_234_567_890_987_654_321.987_65),
              tdef: TypeDef::integer().infallible(),
          }
+
+        half_up_negative {
+             args: func_args![value: (-2.5)],
+             want: Ok(-3.0),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        half_even {
+             args: func_args![value: 2.5, precision: 0, mode: "half_even"],
+             want: Ok(2.0),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        half_even_odd {
+             args: func_args![value: 3.5, mode: "half_even"],
+             want: Ok(4.0),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        half_even_precision {
+             args: func_args![value: 0.125, precision: 2, mode: "half_even"],
+             want: Ok(0.12),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        toward_zero {
+             args: func_args![value: (-1.99), precision: 1, mode: "toward_zero"],
+             want: Ok(-1.9),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        // 1.005 is slightly less than that as a binary float.
+        decimal_representation {
+             args: func_args![value: 1.005, precision: 2],
+             want: Ok(1.01),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision {
+             args: func_args![value: 1234.5, precision: -2],
+             want: Ok(1200.0),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision_integer {
+             args: func_args![value: 1250, precision: -2],
+             want: Ok(1300),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision_integer_half_even {
+             args: func_args![value: (-1250), precision: -2, mode: "half_even"],
+             want: Ok(-1200),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision_integer_toward_zero {
+             args: func_args![value: 1299, precision: -2, mode: "toward_zero"],
+             want: Ok(1200),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision_beyond_value {
+             args: func_args![value: 1234, precision: -30],
+             want: Ok(0),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        negative_precision_saturates {
+             args: func_args![value: i64::MAX, precision: -1],
+             want: Ok(i64::MAX),
+             tdef: TypeDef::integer().infallible(),
+         }
+
+        invalid_mode {
+             args: func_args![value: 1.5, mode: "half_down"],
+             want: Err(r#"invalid enum variant""#),
+             tdef: TypeDef::integer().infallible(),
+         }
     ];
 }
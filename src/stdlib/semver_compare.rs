@@ -0,0 +1,213 @@
+use std::cmp::Ordering;
+
+use semver::Version;
+
+use crate::compiler::prelude::*;
+use crate::stdlib::parse_semver::parse_version;
+
+fn semver_compare(a: Value, b: Value) -> Resolved {
+    let a = parse_version(a)?;
+    let b = parse_version(b)?;
+
+    let ordering = match precedence(&a).cmp(&precedence(&b)) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    };
+    Ok(ordering.into())
+}
+
+/// The parts of a version that determine its precedence. Build metadata is left out, as the
+/// specification says it must be ignored, and a prerelease sorts before its release.
+fn precedence(version: &Version) -> (u64, u64, u64, &semver::Prerelease) {
+    (version.major, version.minor, version.patch, &version.pre)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SemverCompare;
+
+impl Function for SemverCompare {
+    fn identifier(&self) -> &'static str {
+        "semver_compare"
+    }
+
+    fn summary(&self) -> &'static str {
+        "compare two semantic versions"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Compares two versions following the Semantic Versioning 2.0.0 specification, and
+            returns `-1` if `a` has lower precedence than `b`, `0` if they have the same
+            precedence, and `1` if `a` has higher precedence.
+
+            Versions are compared by their `major`, `minor` and `patch` numbers, then by their
+            prerelease identifiers, so `"1.0.0-alpha"` is lower than `"1.0.0-alpha.1"`, which is
+            lower than `"1.0.0"`. Build metadata is ignored, so `"1.0.0+a"` and `"1.0.0+b"` have
+            the same precedence.
+
+            Versions are parsed the same way as by `parse_semver`, and the function fails if
+            either isn't a valid version.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "a",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "b",
+                kind: kind::BYTES,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "lower",
+                source: r#"semver_compare!("1.2.3", "1.10.0")"#,
+                result: Ok("-1"),
+            },
+            Example {
+                title: "prerelease",
+                source: r#"semver_compare!("1.0.0", "1.0.0-rc.1")"#,
+                result: Ok("1"),
+            },
+            Example {
+                title: "build metadata",
+                source: r#"semver_compare!("1.0.0+linux", "1.0.0+darwin")"#,
+                result: Ok("0"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let a = arguments.required("a");
+        let b = arguments.required("b");
+
+        Ok(SemverCompareFn { a, b }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SemverCompareFn {
+    a: Box<dyn Expression>,
+    b: Box<dyn Expression>,
+}
+
+impl FunctionExpression for SemverCompareFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let a = self.a.resolve(ctx)?;
+        let b = self.b.resolve(ctx)?;
+
+        semver_compare(a, b)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::integer().fallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        semver_compare => SemverCompare;
+
+        equal {
+            args: func_args![a: "1.2.3", b: "1.2.3"],
+            want: Ok(0),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        numeric_not_lexical {
+            args: func_args![a: "1.10.0", b: "1.9.0"],
+            want: Ok(1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        major {
+            args: func_args![a: "1.99.99", b: "2.0.0"],
+            want: Ok(-1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        prerelease_before_release {
+            args: func_args![a: "1.0.0-alpha", b: "1.0.0"],
+            want: Ok(-1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        release_after_prerelease {
+            args: func_args![a: "1.0.0", b: "1.0.0-rc.1"],
+            want: Ok(1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        prerelease_numeric_identifiers {
+            args: func_args![a: "1.0.0-beta.2", b: "1.0.0-beta.11"],
+            want: Ok(-1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        prerelease_numeric_before_alphanumeric {
+            args: func_args![a: "1.0.0-alpha.beta", b: "1.0.0-alpha.1"],
+            want: Ok(1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        prerelease_more_identifiers {
+            args: func_args![a: "1.0.0-alpha", b: "1.0.0-alpha.1"],
+            want: Ok(-1),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        build_metadata_ignored {
+            args: func_args![a: "1.0.0+build.2", b: "1.0.0+build.1"],
+            want: Ok(0),
+            tdef: TypeDef::integer().fallible(),
+        }
+
+        invalid {
+            args: func_args![a: "1.0.0", b: "latest"],
+            want: Err("unable to parse semver: unexpected character 'l' while parsing major version number"),
+            tdef: TypeDef::integer().fallible(),
+        }
+    ];
+
+    #[test]
+    fn specification_order() {
+        let versions = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+
+        for (i, a) in versions.iter().enumerate() {
+            for (j, b) in versions.iter().enumerate() {
+                let want = Value::from(i.cmp(&j) as i64);
+                assert_eq!(
+                    semver_compare((*a).into(), (*b).into()).unwrap(),
+                    want,
+                    "{a} vs {b}"
+                );
+            }
+        }
+    }
+}
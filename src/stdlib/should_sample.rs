@@ -0,0 +1,180 @@
+use crate::compiler::prelude::*;
+
+const INVALID_RATE_ERR: &str = "rate must be between 0.0 and 1.0";
+
+/// Keeps a key iff its hash falls within the first `rate` fraction of the hash space. `seahash` is
+/// used as it's stable across runs and platforms, so the same key always gets the same decision.
+#[allow(clippy::cast_precision_loss)]
+fn should_sample(key: &[u8], rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+
+    (seahash::hash(key) as f64) < rate * u64::MAX as f64
+}
+
+fn get_rate(rate: &Value) -> std::result::Result<f64, &'static str> {
+    #[allow(clippy::cast_precision_loss)]
+    let rate = match rate {
+        Value::Integer(rate) => *rate as f64,
+        Value::Float(rate) => rate.into_inner(),
+        _ => return Err("rate must be a number"),
+    };
+
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(INVALID_RATE_ERR);
+    }
+
+    Ok(rate)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShouldSample;
+
+impl Function for ShouldSample {
+    fn identifier(&self) -> &'static str {
+        "should_sample"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "key",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "rate",
+                kind: kind::INTEGER | kind::FLOAT,
+                required: true,
+            },
+        ]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "sample a key",
+                source: r#"should_sample("trace-2", 0.5)"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "keep everything",
+                source: r#"should_sample("trace-1", 1)"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "keep nothing",
+                source: r#"should_sample("trace-1", 0.0)"#,
+                result: Ok("false"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let key = arguments.required("key");
+        let rate = arguments.required("rate");
+
+        if let Some(value) = rate.resolve_constant(state) {
+            get_rate(&value).map_err(|err| function::Error::InvalidArgument {
+                keyword: "rate",
+                value,
+                error: err,
+            })?;
+        }
+
+        Ok(ShouldSampleFn { key, rate }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ShouldSampleFn {
+    key: Box<dyn Expression>,
+    rate: Box<dyn Expression>,
+}
+
+impl FunctionExpression for ShouldSampleFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let key = self.key.resolve(ctx)?;
+        let rate = get_rate(&self.rate.resolve(ctx)?)?;
+
+        Ok(should_sample(&key.try_bytes()?, rate).into())
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        match self.rate.resolve_constant(state) {
+            Some(rate) if get_rate(&rate).is_ok() => TypeDef::boolean().infallible(),
+            _ => TypeDef::boolean().fallible(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        should_sample => ShouldSample;
+
+        keep_all {
+            args: func_args![key: "foo", rate: 1],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        keep_none {
+            args: func_args![key: "foo", rate: 0.0],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        rate_too_high {
+            args: func_args![key: "foo", rate: 1.5],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+
+        rate_negative {
+            args: func_args![key: "foo", rate: value!(-0.1)],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+    ];
+
+    #[test]
+    fn keeps_rate_fraction() {
+        for rate in [0.01, 0.1, 0.5, 0.9] {
+            let kept = (0..100_000)
+                .filter(|i| should_sample(format!("key-{i}").as_bytes(), rate))
+                .count();
+
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = kept as f64 / 100_000.0;
+            assert!(
+                (fraction - rate).abs() < 0.01,
+                "rate {rate} kept {fraction}"
+            );
+        }
+    }
+
+    #[test]
+    fn decision_is_stable() {
+        for i in 0..1_000 {
+            let key = format!("key-{i}");
+            let decision = should_sample(key.as_bytes(), 0.3);
+
+            assert_eq!(decision, should_sample(key.as_bytes(), 0.3));
+            // raising the rate never drops a key that was kept
+            assert!(!decision || should_sample(key.as_bytes(), 0.6));
+        }
+
+        assert!(should_sample(b"trace-2", 0.5));
+        assert!(!should_sample(b"trace-1", 0.5));
+    }
+}
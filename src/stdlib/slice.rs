@@ -17,10 +17,14 @@ fn slice(start: i64, end: Option<i64>, value: Value) -> Resolved {
         };
 
         match () {
-            _ if start < 0 || start > len => {
-                Err(format!(r#""start" must be between "{}" and "{len}""#, -len).into())
-            }
-            _ if end < start => Err(r#""end" must be greater or equal to "start""#.into()),
+            _ if start < 0 || start > len => Err(ExpressionError::with_code(
+                ErrorCode::OutOfRange,
+                format!(r#""start" must be between "{}" and "{len}""#, -len),
+            )),
+            _ if end < start => Err(ExpressionError::with_code(
+                ErrorCode::OutOfRange,
+                r#""end" must be greater or equal to "start""#,
+            )),
             _ if end > len => Ok(start as usize..len as usize),
             _ => Ok(start as usize..end as usize),
         }
@@ -68,6 +72,13 @@ impl Function for Slice {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "end" => Some(Value::Null),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -0,0 +1,240 @@
+use chrono::{DateTime, Days, Duration, Local, NaiveDateTime, NaiveTime, Utc};
+
+use crate::compiler::prelude::*;
+use crate::compiler::TimeZone;
+use crate::stdlib::util::parse_timezone;
+
+fn start_of_day(ts: Value, timezone: Option<Value>, host_timezone: TimeZone) -> Resolved {
+    let ts = ts.try_timestamp()?;
+    let timezone = resolve_timezone(timezone, host_timezone)?;
+
+    Ok(day_start(ts, timezone, Days::new(0))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+        .into())
+}
+
+/// Resolves an optional `timezone` argument, which is UTC by default rather than the host
+/// timezone.
+pub(crate) fn resolve_timezone(
+    timezone: Option<Value>,
+    host_timezone: TimeZone,
+) -> ExpressionResult<TimeZone> {
+    match timezone {
+        Some(timezone) => parse_timezone(&timezone, host_timezone),
+        None => Ok(TimeZone::Named(chrono_tz::UTC)),
+    }
+}
+
+/// Returns the first instant of the day `days` after the one that `ts` falls on in `timezone`,
+/// or `None` if it's outside the range of timestamps.
+pub(crate) fn day_start(
+    ts: DateTime<Utc>,
+    timezone: TimeZone,
+    days: Days,
+) -> Option<DateTime<Utc>> {
+    match timezone {
+        TimeZone::Named(tz) => day_start_in(ts, &tz, days),
+        TimeZone::Fixed(offset) => day_start_in(ts, &offset, days),
+        TimeZone::Local => day_start_in(ts, &Local, days),
+    }
+}
+
+fn day_start_in<Tz: chrono::TimeZone>(
+    ts: DateTime<Utc>,
+    tz: &Tz,
+    days: Days,
+) -> Option<DateTime<Utc>> {
+    let date = ts.with_timezone(tz).date_naive().checked_add_days(days)?;
+    let midnight = date.and_time(NaiveTime::MIN);
+    let instant = |local: &NaiveDateTime| tz.from_local_datetime(local).earliest();
+
+    if let Some(start) = instant(&midnight) {
+        return Some(start.with_timezone(&Utc));
+    }
+
+    // On days when the clocks skip over midnight, the day starts when they've skipped forward,
+    // which is the first valid local time after it. Transitions happen on whole seconds, so the
+    // first valid minute is found first, then the first valid second before it. A day can be
+    // skipped entirely, in which case the next one starts instead.
+    let minute = (1..=2 * 24 * 60)
+        .map(|minutes| midnight + Duration::minutes(minutes))
+        .find(|local| instant(local).is_some())?;
+    let local = (1..60)
+        .rev()
+        .map(|seconds| minute - Duration::seconds(seconds))
+        .find(|local| instant(local).is_some())
+        .unwrap_or(minute);
+
+    instant(&local).map(|start| start.with_timezone(&Utc))
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StartOfDay;
+
+impl Function for StartOfDay {
+    fn identifier(&self) -> &'static str {
+        "start_of_day"
+    }
+
+    fn summary(&self) -> &'static str {
+        "get the start of the day of a timestamp"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the first instant of the day that `value` falls on in `timezone`, which is
+            midnight unless the clocks skip over midnight that day.
+
+            `timezone` is UTC by default. It can be a name from the TZ database, such as
+            `"America/New_York"`, an offset such as `"-05:00"`, or `"local"` for the timezone
+            VRL runs in. The function only fails if `timezone` isn't one of these.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::TIMESTAMP,
+                required: true,
+            },
+            Parameter {
+                keyword: "timezone",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "timezone" => Some("UTC".into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "utc",
+                source: r#"start_of_day(t'2021-02-10T23:32:00Z')"#,
+                result: Ok("t'2021-02-10T00:00:00Z'"),
+            },
+            Example {
+                title: "timezone",
+                source: r#"start_of_day!(t'2021-02-11T03:32:00Z', timezone: "America/New_York")"#,
+                result: Ok("t'2021-02-10T05:00:00Z'"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let timezone = arguments.optional("timezone");
+
+        Ok(StartOfDayFn { value, timezone }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StartOfDayFn {
+    value: Box<dyn Expression>,
+    timezone: Option<Box<dyn Expression>>,
+}
+
+impl FunctionExpression for StartOfDayFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let timezone = self
+            .timezone
+            .as_ref()
+            .map(|timezone| timezone.resolve(ctx))
+            .transpose()?;
+
+        start_of_day(value, timezone, *ctx.timezone())
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::timestamp().maybe_fallible(self.timezone.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone as _;
+
+    test_function![
+        start_of_day => StartOfDay;
+
+        utc {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).unwrap()],
+            want: Ok(Utc.with_ymd_and_hms(2021, 2, 10, 0, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().infallible(),
+        }
+
+        already_midnight {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 0, 0, 0).unwrap()],
+            want: Ok(Utc.with_ymd_and_hms(2021, 2, 10, 0, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().infallible(),
+        }
+
+        // 03:32 UTC is still the previous day in New York, so the day starts a day earlier than
+        // it does in UTC, and five hours after midnight UTC.
+        negative_offset {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 11, 3, 32, 0).unwrap(),
+                             timezone: "America/New_York"],
+            want: Ok(Utc.with_ymd_and_hms(2021, 2, 10, 5, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        positive_offset {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).unwrap(),
+                             timezone: "+09:00"],
+            want: Ok(Utc.with_ymd_and_hms(2021, 2, 10, 15, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        // Clocks in Havana skipped from midnight to 01:00 on 2019-03-10.
+        midnight_skipped {
+            args: func_args![value: Utc.with_ymd_and_hms(2019, 3, 10, 12, 0, 0).unwrap(),
+                             timezone: "America/Havana"],
+            want: Ok(Utc.with_ymd_and_hms(2019, 3, 10, 5, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        // Clocks in Havana went back from 01:00 to midnight on 2019-11-03, so the day starts at
+        // the first midnight.
+        midnight_repeated {
+            args: func_args![value: Utc.with_ymd_and_hms(2019, 11, 3, 12, 0, 0).unwrap(),
+                             timezone: "America/Havana"],
+            want: Ok(Utc.with_ymd_and_hms(2019, 11, 3, 4, 0, 0).unwrap()),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+
+        invalid_timezone {
+            args: func_args![value: Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).unwrap(),
+                             timezone: "Mars/Olympus_Mons"],
+            want: Err("unable to parse timezone: Mars/Olympus_Mons"),
+            tdef: TypeDef::timestamp().fallible(),
+        }
+    ];
+
+    #[test]
+    fn negative_offset_differs_from_utc() {
+        let ts = Utc.with_ymd_and_hms(2021, 2, 11, 3, 32, 0).unwrap();
+        let new_york = TimeZone::Named(chrono_tz::America::New_York);
+        let utc = TimeZone::Named(chrono_tz::UTC);
+
+        let local_start = day_start(ts, new_york, Days::new(0)).unwrap();
+        let utc_start = day_start(ts, utc, Days::new(0)).unwrap();
+
+        assert_ne!(local_start, utc_start);
+        assert_eq!(utc_start - local_start, Duration::hours(19));
+    }
+}
@@ -1,4 +1,7 @@
 use crate::compiler::prelude::*;
+use crate::stdlib::string_utils::{
+    any_needle, compile_needles, needles_are_fallible, Anchor, Needles,
+};
 
 struct Chars<'a> {
     bytes: &'a Bytes,
@@ -39,6 +42,7 @@ impl<'a> Iterator for Chars<'a> {
     }
 }
 
+#[derive(Clone, Copy)]
 enum Case {
     Sensitive,
     Insensitive,
@@ -85,7 +89,7 @@ impl Function for StartsWith {
             },
             Parameter {
                 keyword: "substring",
-                kind: kind::BYTES,
+                kind: kind::BYTES | kind::ARRAY,
                 required: true,
             },
             Parameter {
@@ -96,6 +100,13 @@ impl Function for StartsWith {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "case_sensitive" => Some(true.into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
@@ -113,23 +124,30 @@ impl Function for StartsWith {
                 source: r#"starts_with("foobar", "bar")"#,
                 result: Ok("false"),
             },
+            Example {
+                title: "any of",
+                source: r#"starts_with("/api/v2/users", ["/health", "/api/", "/static/"])"#,
+                result: Ok("true"),
+            },
         ]
     }
 
     fn compile(
         &self,
-        _state: &state::TypeState,
+        state: &state::TypeState,
         _ctx: &mut FunctionCompileContext,
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
         let substring = arguments.required("substring");
         let case_sensitive = arguments.optional("case_sensitive").unwrap_or(expr!(true));
+        let needles = compile_needles(state, &arguments, case_sensitive.as_ref(), Anchor::Start)?;
 
         Ok(StartsWithFn {
             value,
             substring,
             case_sensitive,
+            needles,
         }
         .as_expr())
     }
@@ -140,10 +158,16 @@ struct StartsWithFn {
     value: Box<dyn Expression>,
     substring: Box<dyn Expression>,
     case_sensitive: Box<dyn Expression>,
+    needles: Option<Needles>,
 }
 
 impl FunctionExpression for StartsWithFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
+        if let Some(needles) = &self.needles {
+            let value = self.value.resolve(ctx)?;
+            return Ok(needles.is_match(&value.try_bytes()?).into());
+        }
+
         let case_sensitive = if self.case_sensitive.resolve(ctx)?.try_boolean()? {
             Case::Sensitive
         } else {
@@ -151,22 +175,28 @@ impl FunctionExpression for StartsWithFn {
         };
 
         let substring = self.substring.resolve(ctx)?;
-        let substring = substring.try_bytes()?;
 
         let value = self.value.resolve(ctx)?;
         let value = value.try_bytes()?;
 
-        Ok(starts_with(&value, &substring, case_sensitive).into())
+        Ok(any_needle(substring, |substring| {
+            Ok(starts_with(&value, &substring.try_bytes()?, case_sensitive))
+        })?
+        .into())
     }
 
-    fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        TypeDef::boolean().infallible()
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        let fallible =
+            self.needles.is_none() && needles_are_fallible(self.substring.type_def(state).kind());
+
+        TypeDef::boolean().maybe_fallible(fallible)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::value;
 
     test_function![
         starts_with => StartsWith;
@@ -271,5 +301,54 @@ mod tests {
             want: Ok(true),
             tdef: TypeDef::boolean().infallible(),
         }
+
+        array_any {
+            args: func_args![value: "/api/v2/users",
+                             substring: value!(["/health", "/api/", "/static/"])
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_none {
+            args: func_args![value: "/metrics",
+                             substring: value!(["/health", "/api/", "/static/"])
+            ],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_not_at_start {
+            args: func_args![value: "/v1/api/",
+                             substring: value!(["/api/", "api"])
+            ],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_case_insensitive {
+            args: func_args![value: "GET /index.html",
+                             substring: value!(["post", "get"]),
+                             case_sensitive: false
+            ],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_empty {
+            args: func_args![value: "foo",
+                             substring: value!([])
+            ],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array_not_strings {
+            args: func_args![value: "foo",
+                             substring: value!(["foo", 1])
+            ],
+            want: Err("unexpected expression type"),
+            tdef: TypeDef::boolean().infallible(),
+        }
     ];
 }
@@ -1,4 +1,8 @@
-use crate::prelude::{Value, ValueError, VrlValueConvert};
+use std::borrow::Cow;
+
+use aho_corasick::{AhoCorasick, Anchored, Input, StartKind};
+
+use crate::compiler::prelude::*;
 
 pub(crate) fn convert_to_string(value: Value, to_lowercase: bool) -> Result<String, ValueError> {
     let string = value.try_bytes_utf8_lossy()?;
@@ -7,3 +11,148 @@ pub(crate) fn convert_to_string(value: Value, to_lowercase: bool) -> Result<Stri
         false => string.to_lowercase(),
     })
 }
+
+/// Where a substring has to be found in a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Anchor {
+    Start,
+    End,
+    Anywhere,
+}
+
+/// Matches an array of substrings at once, for the functions whose `substring` argument can be
+/// an array. It's built when the array is a literal, so that the cost of a match doesn't grow
+/// with the number of substrings.
+#[derive(Clone, Debug)]
+pub(crate) struct Needles {
+    automaton: AhoCorasick,
+    anchor: Anchor,
+    case_sensitive: bool,
+}
+
+impl Needles {
+    fn new(needles: &[Bytes], anchor: Anchor, case_sensitive: bool) -> Option<Self> {
+        let start_kind = match anchor {
+            Anchor::Anywhere => StartKind::Unanchored,
+            Anchor::Start | Anchor::End => StartKind::Anchored,
+        };
+        let automaton = AhoCorasick::builder()
+            .start_kind(start_kind)
+            .build(
+                needles
+                    .iter()
+                    .map(|needle| normalize(needle, anchor, case_sensitive)),
+            )
+            .ok()?;
+
+        Some(Self {
+            automaton,
+            anchor,
+            case_sensitive,
+        })
+    }
+
+    pub(crate) fn is_match(&self, value: &[u8]) -> bool {
+        let anchored = match self.anchor {
+            Anchor::Anywhere => Anchored::No,
+            Anchor::Start | Anchor::End => Anchored::Yes,
+        };
+        let value = normalize(value, self.anchor, self.case_sensitive);
+
+        self.automaton
+            .is_match(Input::new(value.as_ref()).anchored(anchored))
+    }
+}
+
+/// Lowercases `bytes` for case insensitive matching, and reverses them so that a suffix can be
+/// matched as an anchored prefix.
+fn normalize(bytes: &[u8], anchor: Anchor, case_sensitive: bool) -> Cow<'_, [u8]> {
+    let mut bytes = if case_sensitive {
+        Cow::Borrowed(bytes)
+    } else {
+        Cow::Owned(String::from_utf8_lossy(bytes).to_lowercase().into_bytes())
+    };
+    if anchor == Anchor::End {
+        bytes.to_mut().reverse();
+    }
+    bytes
+}
+
+/// Compiles the `substring` argument of a function that accepts a string or an array of strings.
+///
+/// Array elements that are known not to be strings are rejected. When the argument is an array
+/// literal and the case sensitivity is known, a [`Needles`] matcher is built for it.
+pub(crate) fn compile_needles(
+    state: &state::TypeState,
+    arguments: &ArgumentList,
+    case_sensitive: &dyn Expression,
+    anchor: Anchor,
+) -> Result<Option<Needles>, function::Error> {
+    let substring = arguments.required("substring");
+
+    if let Some(array) = substring.type_def(state).kind().as_array() {
+        if element_kinds(array).any(|kind| !kind.contains_bytes()) {
+            return Err(function::Error::UnexpectedExpression {
+                keyword: "substring",
+                expected: "string or array of strings",
+                expr: arguments.required_expr("substring"),
+            });
+        }
+    }
+
+    let (Some(Value::Array(needles)), Some(Value::Boolean(case_sensitive))) = (
+        substring.resolve_constant(state),
+        case_sensitive.resolve_constant(state),
+    ) else {
+        return Ok(None);
+    };
+    let needles = needles
+        .into_iter()
+        .map(Value::try_bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("elements are strings");
+
+    Needles::new(&needles, anchor, case_sensitive)
+        .map(Some)
+        .ok_or_else(|| function::Error::InvalidArgument {
+            keyword: "substring",
+            value: Value::from(needles),
+            error: "too many substrings",
+        })
+}
+
+/// Returns whether the `substring` argument can be an array with elements that aren't strings,
+/// which is only found out at runtime.
+pub(crate) fn needles_are_fallible(substring: &Kind) -> bool {
+    substring.as_array().map_or(false, |array| {
+        element_kinds(array).any(|kind| !kind.is_bytes())
+    })
+}
+
+/// Returns the kinds the elements of `array` can have. The unknown elements of an array of a
+/// known length are undefined, and aren't elements.
+fn element_kinds(array: &Collection<Index>) -> impl Iterator<Item = Kind> + '_ {
+    let unknown = array.unknown_kind();
+    let unknown = (!unknown.is_never() && !unknown.is_undefined()).then_some(unknown);
+
+    array.known().values().cloned().chain(unknown)
+}
+
+/// Calls `is_match` with the `substring` argument, or with each of its elements when it's an
+/// array, returning whether any of them matched.
+pub(crate) fn any_needle(
+    substring: Value,
+    mut is_match: impl FnMut(Value) -> Result<bool, ValueError>,
+) -> Result<bool, ValueError> {
+    match substring {
+        Value::Array(needles) => {
+            for needle in needles {
+                if is_match(needle)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        substring => is_match(substring),
+    }
+}
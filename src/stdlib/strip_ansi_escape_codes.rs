@@ -1,9 +1,10 @@
 use crate::compiler::prelude::*;
+use crate::stdlib::ansi;
 use bytes::Bytes;
 
-fn strip_ansi_escape_codes(bytes: Value) -> Resolved {
+fn strip_ansi_escape_codes(bytes: Value, keep_content_of_links: bool) -> Resolved {
     let bytes = bytes.try_bytes()?;
-    let stripped_bytes = Bytes::from(strip_ansi_escapes::strip(&bytes));
+    let stripped_bytes = Bytes::from(ansi::strip(&bytes, keep_content_of_links));
     Ok(stripped_bytes.into())
 }
 
@@ -15,16 +16,59 @@ impl Function for StripAnsiEscapeCodes {
         "strip_ansi_escape_codes"
     }
 
+    fn usage(&self) -> &'static str {
+        indoc! {"
+            Removes ANSI escape sequences from the provided `value`: control sequences such as
+            colors and cursor movements, operating system commands such as window titles and
+            hyperlinks, device control and other control strings, and single-character escapes.
+            Their 8-bit forms, starting with a C1 control character instead of `ESC`, are removed
+            too. Other control characters, such as newlines and tabs, are kept.
+
+            The visible text of OSC 8 hyperlinks is kept, unless `keep_content_of_links` is
+            `false`.
+        "}
+    }
+
     fn parameters(&self) -> &'static [Parameter] {
-        &[Parameter {
-            keyword: "value",
-            kind: kind::BYTES,
-            required: true,
-        }]
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "keep_content_of_links",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "keep_content_of_links" => Some(true.into()),
+            _ => None,
+        }
     }
 
     fn examples(&self) -> &'static [Example] {
-        &[]
+        &[
+            Example {
+                title: "colors",
+                source: r#"strip_ansi_escape_codes(decode_percent("%1B[1;31mred%1B[0m text"))"#,
+                result: Ok("red text"),
+            },
+            Example {
+                title: "window title",
+                source: r#"strip_ansi_escape_codes(decode_percent("%1B]0;title%07$ ls"))"#,
+                result: Ok("$ ls"),
+            },
+            Example {
+                title: "hyperlink",
+                source: r#"strip_ansi_escape_codes(decode_percent("see %1B]8;;https://vrl.dev%1B\\the docs%1B]8;;%1B\\"), keep_content_of_links: false)"#,
+                result: Ok("see "),
+            },
+        ]
     }
 
     fn compile(
@@ -34,27 +78,34 @@ impl Function for StripAnsiEscapeCodes {
         arguments: ArgumentList,
     ) -> Compiled {
         let value = arguments.required("value");
+        let keep_content_of_links = arguments.optional("keep_content_of_links");
 
-        Ok(StripAnsiEscapeCodesFn { value }.as_expr())
+        Ok(StripAnsiEscapeCodesFn {
+            value,
+            keep_content_of_links,
+        }
+        .as_expr())
     }
 }
 
 #[derive(Debug, Clone)]
 struct StripAnsiEscapeCodesFn {
     value: Box<dyn Expression>,
+    keep_content_of_links: Option<Box<dyn Expression>>,
 }
 
 impl FunctionExpression for StripAnsiEscapeCodesFn {
     fn resolve(&self, ctx: &mut Context) -> Resolved {
         let bytes = self.value.resolve(ctx)?;
+        let keep_content_of_links = match &self.keep_content_of_links {
+            Some(expr) => expr.resolve(ctx)?.try_boolean()?,
+            None => true,
+        };
 
-        strip_ansi_escape_codes(bytes)
+        strip_ansi_escape_codes(bytes, keep_content_of_links)
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
-        // We're marking this as infallible, because `strip_ansi_escapes` only
-        // fails if it can't write to the buffer, which is highly unlikely to
-        // occur.
         TypeDef::bytes().infallible()
     }
 }
@@ -89,5 +140,32 @@ mod tests {
             want: Ok("foo bar"),
             tdef: TypeDef::bytes().infallible(),
         }
+
+        window_title {
+            args: func_args![value: "\x1b]0;title\x07foo bar"],
+            want: Ok("foo bar"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        hyperlink {
+            args: func_args![value: "\x1b]8;;https://vrl.dev\x1b\\foo\x1b]8;;\x1b\\ bar"],
+            want: Ok("foo bar"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        hyperlink_without_content {
+            args: func_args![
+                value: "\x1b]8;;https://vrl.dev\x1b\\foo\x1b]8;;\x1b\\ bar",
+                keep_content_of_links: false
+            ],
+            want: Ok(" bar"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        c1 {
+            args: func_args![value: "\u{9b}1mfoo\u{9b}0m bar\u{85}"],
+            want: Ok("foo bar"),
+            tdef: TypeDef::bytes().infallible(),
+        }
     ];
 }
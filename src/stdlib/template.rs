@@ -0,0 +1,274 @@
+use std::str::FromStr;
+
+use crate::compiler::prelude::*;
+
+fn template(pattern: &[u8], values: &ObjectMap, on_missing: OnMissing) -> Resolved {
+    let pattern = String::from_utf8_lossy(pattern);
+    let mut output = String::with_capacity(pattern.len());
+    let mut rest = pattern.as_ref();
+
+    while let Some(start) = rest.find(['{', '}']) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        // `{{` and `}}` are escaped braces.
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            output.push_str(&rest[..1]);
+            rest = &rest[2..];
+            continue;
+        }
+
+        // A lone closing brace, or an opening brace without a matching closing brace, is kept as
+        // is.
+        let end = match rest.find('}') {
+            Some(end) if rest.starts_with('{') => end,
+            _ => {
+                output.push_str(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        let name = &rest[1..end];
+        match values.get(name) {
+            Some(Value::Bytes(bytes)) => output.push_str(&String::from_utf8_lossy(bytes)),
+            Some(Value::Null) => {}
+            Some(value) => output.push_str(&value.to_string_lossy()),
+            None => match on_missing {
+                OnMissing::Keep => output.push_str(&rest[..=end]),
+                OnMissing::Error => {
+                    return Err(format!(r#"no value found for placeholder "{name}""#).into())
+                }
+            },
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    output.push_str(rest);
+
+    Ok(output.into())
+}
+
+/// Controls what happens to placeholders that don't have a matching field in `values`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum OnMissing {
+    /// Leave the placeholder in the output as is.
+    #[default]
+    Keep,
+
+    /// Return an error.
+    Error,
+}
+
+impl OnMissing {
+    fn all_value() -> Vec<Value> {
+        use OnMissing::{Error, Keep};
+
+        vec![Keep, Error]
+            .into_iter()
+            .map(|p| p.as_str().into())
+            .collect::<Vec<_>>()
+    }
+
+    const fn as_str(self) -> &'static str {
+        use OnMissing::{Error, Keep};
+
+        match self {
+            Keep => "keep",
+            Error => "error",
+        }
+    }
+}
+
+impl FromStr for OnMissing {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use OnMissing::{Error, Keep};
+
+        match s {
+            "keep" => Ok(Keep),
+            "error" => Ok(Error),
+            _ => Err("on_missing not recognized"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Template;
+
+impl Function for Template {
+    fn identifier(&self) -> &'static str {
+        "template"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "pattern",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "values",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "on_missing",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "on_missing" => Some(OnMissing::default().as_str().into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "multiple fields",
+                source: r#"template("{user} logged in from {ip}", {"user": "alice", "ip": "10.0.0.1"})"#,
+                result: Ok(r#""alice logged in from 10.0.0.1""#),
+            },
+            Example {
+                title: "escaped braces",
+                source: r#"template("\{{{status}\}}", {"status": 200})"#,
+                result: Ok(r#""{200}""#),
+            },
+            Example {
+                title: "missing placeholder",
+                source: r#"template("{user} did {action}", {"user": "alice"})"#,
+                result: Ok(r#""alice did {action}""#),
+            },
+            Example {
+                title: "missing placeholder error",
+                source: r#"template!("{user} did {action}", {"user": "alice"}, on_missing: "error")"#,
+                result: Err(
+                    r#"function call error for "template" at (0:72): no value found for placeholder "action""#,
+                ),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let pattern = arguments.required("pattern");
+        let values = arguments.required("values");
+        let on_missing = arguments
+            .optional_enum("on_missing", &OnMissing::all_value(), state)?
+            .map(|s| {
+                OnMissing::from_str(&s.try_bytes_utf8_lossy().expect("on_missing not bytes"))
+                    .expect("validated enum")
+            })
+            .unwrap_or_default();
+
+        Ok(TemplateFn {
+            pattern,
+            values,
+            on_missing,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TemplateFn {
+    pattern: Box<dyn Expression>,
+    values: Box<dyn Expression>,
+    on_missing: OnMissing,
+}
+
+impl FunctionExpression for TemplateFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let pattern = self.pattern.resolve(ctx)?;
+        let values = self.values.resolve(ctx)?;
+
+        template(
+            &pattern.try_bytes()?,
+            &values.try_object()?,
+            self.on_missing,
+        )
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().maybe_fallible(self.on_missing == OnMissing::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        template => Template;
+
+        multiple_fields {
+            args: func_args![
+                pattern: "{user} ({id}) logged in at {ts}: {tags}",
+                values: value!({"user": "alice", "id": 42, "ts": "2024-01-01", "tags": ["a", "b"]}),
+            ],
+            want: Ok(r#"alice (42) logged in at 2024-01-01: ["a","b"]"#),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        repeated_field {
+            args: func_args![pattern: "{a}-{a}", values: value!({"a": true})],
+            want: Ok("true-true"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        null_field {
+            args: func_args![pattern: "[{a}]", values: value!({"a": null})],
+            want: Ok("[]"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        escaped_braces {
+            args: func_args![pattern: "{{a}} }} {{{a}}}", values: value!({"a": 1})],
+            want: Ok("{a} } {1}"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        unbalanced_braces {
+            args: func_args![pattern: "} {a} {b", values: value!({"a": 1})],
+            want: Ok("} 1 {b"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        missing_keep {
+            args: func_args![pattern: "{a} {missing}", values: value!({"a": 1})],
+            want: Ok("1 {missing}"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        missing_explicit_keep {
+            args: func_args![pattern: "{a} {missing}", values: value!({"a": 1}), on_missing: "keep"],
+            want: Ok("1 {missing}"),
+            tdef: TypeDef::bytes().infallible(),
+        }
+
+        missing_error {
+            args: func_args![pattern: "{a} {missing}", values: value!({"a": 1}), on_missing: "error"],
+            want: Err(r#"no value found for placeholder "missing""#),
+            tdef: TypeDef::bytes().fallible(),
+        }
+
+        no_missing_with_error {
+            args: func_args![pattern: "{a}", values: value!({"a": "b"}), on_missing: "error"],
+            want: Ok("b"),
+            tdef: TypeDef::bytes().fallible(),
+        }
+    ];
+}
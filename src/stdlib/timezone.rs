@@ -0,0 +1,177 @@
+use crate::compiler::prelude::*;
+use crate::compiler::TimeZone;
+use chrono::{FixedOffset, Local, Utc};
+
+fn timezone(timezone: TimeZone) -> Value {
+    match timezone {
+        TimeZone::Named(tz) => tz.name().into(),
+        TimeZone::Fixed(offset) => offset_name(offset).into(),
+        TimeZone::Local => local_name().into(),
+    }
+}
+
+/// The name of the system timezone, or its current offset if the system doesn't name it.
+#[cfg(not(target_os = "wasi"))]
+fn local_name() -> String {
+    iana_time_zone::get_timezone()
+        .ok()
+        .filter(|name| name.parse::<chrono_tz::Tz>().is_ok())
+        .unwrap_or_else(local_offset_name)
+}
+
+/// WASI has no way to look up the name of the system timezone, so this is its current offset.
+#[cfg(target_os = "wasi")]
+fn local_name() -> String {
+    local_offset_name()
+}
+
+fn local_offset_name() -> String {
+    offset_name(*Utc::now().with_timezone(&Local).offset())
+}
+
+/// UTC itself is returned by name, any other offset as `+HH:MM`.
+fn offset_name(offset: FixedOffset) -> String {
+    match offset.local_minus_utc() {
+        0 => "UTC".to_owned(),
+        _ => offset.to_string(),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Timezone;
+
+impl Function for Timezone {
+    fn identifier(&self) -> &'static str {
+        "timezone"
+    }
+
+    fn summary(&self) -> &'static str {
+        "get the timezone configured by the host"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns the timezone the host runs the program with, which is the default for the
+            `timezone` arguments of other functions.
+
+            Named timezones are returned by their name in the TZ database, such as
+            `"Europe/Berlin"`, and fixed offsets as `"+HH:MM"`, or `"UTC"` for no offset. When
+            the host uses the system timezone, this is the name of the system timezone. If the
+            system doesn't name its timezone, it's the system's offset at the time the function
+            is called, which can change with daylight saving time, rather than the offset of any
+            particular timestamp.
+        "#}
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[Example {
+            title: "timezone",
+            source: r#"timezone()"#,
+            result: Ok(r#""UTC""#),
+        }]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        _: ArgumentList,
+    ) -> Compiled {
+        Ok(TimezoneFn.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimezoneFn;
+
+impl FunctionExpression for TimezoneFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        Ok(timezone(*ctx.timezone()))
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::bytes().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{compile, state::RuntimeState, TargetValue};
+    use crate::value::Secrets;
+
+    fn run_with_timezone(source: &str, timezone: TimeZone) -> Resolved {
+        let program = compile(source, &crate::stdlib::all())
+            .expect("program should compile")
+            .program;
+        let mut target = TargetValue {
+            value: Value::from(ObjectMap::new()),
+            metadata: Value::from(ObjectMap::new()),
+            secrets: Secrets::default(),
+        };
+        let mut state = RuntimeState::default();
+        let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+        program.resolve(&mut ctx)
+    }
+
+    #[test]
+    fn program_honors_host_timezone() {
+        let source = r#"
+            ts = parse_timestamp!("2021-02-10 23:32", "%Y-%m-%d %H:%M")
+            timezone() + " " + format_timestamp!(ts, "%+", timezone: "local")
+        "#;
+
+        let berlin = run_with_timezone(source, TimeZone::Named(chrono_tz::Europe::Berlin));
+        let tokyo = run_with_timezone(source, TimeZone::Named(chrono_tz::Asia::Tokyo));
+        let offset = run_with_timezone(
+            source,
+            TimeZone::Fixed(FixedOffset::east_opt(5 * 3600).unwrap()),
+        );
+
+        assert_eq!(berlin, Ok("Europe/Berlin 2021-02-10T23:32:00+01:00".into()));
+        assert_eq!(tokyo, Ok("Asia/Tokyo 2021-02-10T23:32:00+09:00".into()));
+        assert_eq!(offset, Ok("+05:00 2021-02-10T23:32:00+05:00".into()));
+    }
+
+    #[test]
+    fn local_is_named_like_other_timezones() {
+        let name = local_name();
+        let is_offset = name == "UTC" || name.starts_with(['+', '-']);
+
+        assert!(is_offset || name.parse::<chrono_tz::Tz>().is_ok(), "{name}");
+        assert_eq!(timezone(TimeZone::Local), Value::from(name));
+    }
+
+    test_function![
+        timezone => Timezone;
+
+        named {
+            args: func_args![],
+            want: Ok("Europe/Berlin"),
+            tdef: TypeDef::bytes().infallible(),
+            tz: TimeZone::Named(chrono_tz::Europe::Berlin),
+        }
+
+        utc {
+            args: func_args![],
+            want: Ok("UTC"),
+            tdef: TypeDef::bytes().infallible(),
+            tz: TimeZone::Named(chrono_tz::Tz::UTC),
+        }
+
+        fixed_offset {
+            args: func_args![],
+            want: Ok("-03:30"),
+            tdef: TypeDef::bytes().infallible(),
+            tz: TimeZone::Fixed(FixedOffset::west_opt(3 * 3600 + 1800).unwrap()),
+        }
+
+        zero_offset {
+            args: func_args![],
+            want: Ok("UTC"),
+            tdef: TypeDef::bytes().infallible(),
+            tz: TimeZone::Fixed(FixedOffset::east_opt(0).unwrap()),
+        }
+    ];
+}
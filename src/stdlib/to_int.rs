@@ -11,9 +11,12 @@ fn to_int(value: Value) -> Resolved {
         Null => Ok(0.into()),
         Bytes(v) => Conversion::Integer
             .convert(v)
-            .map_err(|e| e.to_string().into()),
+            .map_err(|e| ExpressionError::with_code(e.error_code(), e.to_string())),
         Timestamp(v) => Ok(v.timestamp().into()),
-        v => Err(format!("unable to coerce {} into integer", v.kind()).into()),
+        v => Err(ExpressionError::with_code(
+            ErrorCode::InvalidType,
+            format!("unable to coerce {} into integer", v.kind()),
+        )),
     }
 }
 
@@ -142,6 +145,9 @@ mod tests {
     use chrono::{DateTime, Utc};
 
     use super::*;
+    use crate::compiler::{compile, runtime::Runtime, TargetValue};
+    use crate::value;
+    use crate::value::Secrets;
 
     test_function![
         to_int => ToInt;
@@ -166,4 +172,28 @@ mod tests {
              tdef: TypeDef::integer().infallible(),
          }
     ];
+
+    #[test]
+    fn error_codes() {
+        let functions: Vec<Box<dyn Function>> = vec![Box::new(ToInt)];
+        let program = compile("to_int!(.value)", &functions).unwrap().program;
+        let code = |value: Value| {
+            let mut target = TargetValue {
+                value: value!({ "value": value }),
+                metadata: value!({}),
+                secrets: Secrets::default(),
+            };
+            Runtime::default()
+                .resolve(&mut target, &program, &TimeZone::default())
+                .unwrap_err()
+                .code()
+        };
+
+        assert_eq!(code("nope".into()), Some(ErrorCode::Parse));
+        assert_eq!(
+            code("99999999999999999999".into()),
+            Some(ErrorCode::OutOfRange)
+        );
+        assert_eq!(code(value!([1])), Some(ErrorCode::InvalidType));
+    }
 }
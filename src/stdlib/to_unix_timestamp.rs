@@ -60,6 +60,13 @@ impl Function for ToUnixTimestamp {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "unit" => Some(Unit::default().as_str().into()),
+            _ => None,
+        }
+    }
+
     fn compile(
         &self,
         state: &state::TypeState,
@@ -58,6 +58,14 @@ impl Function for Truncate {
         ]
     }
 
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "ellipsis" => Some(false.into()),
+            "suffix" => Some("".into()),
+            _ => None,
+        }
+    }
+
     fn examples(&self) -> &'static [Example] {
         &[
             Example {
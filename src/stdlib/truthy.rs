@@ -0,0 +1,219 @@
+use crate::compiler::prelude::*;
+
+/// Returns `false` for `null`, `false`, zero, the empty string and empty collections, and `true`
+/// for everything else.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Boolean(false) | Value::Integer(0) => false,
+        Value::Float(float) => *float != 0.0,
+        Value::Bytes(bytes) => !bytes.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+        Value::Array(array) => !array.is_empty(),
+        Value::Boolean(true) | Value::Integer(_) | Value::Timestamp(_) | Value::Regex(_) => true,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Truthy;
+
+impl Function for Truthy {
+    fn identifier(&self) -> &'static str {
+        "truthy"
+    }
+
+    fn summary(&self) -> &'static str {
+        "determine whether a value is truthy"
+    }
+
+    fn usage(&self) -> &'static str {
+        indoc! {r#"
+            Returns `false` if `value` is one of:
+
+            - `null`
+            - `false`
+            - `0` or `0.0`, including `-0.0`
+            - `""`, the empty string
+            - `[]` or `{}`, an empty array or object
+
+            and `true` for any other value, including strings made only of whitespace, such as
+            `" "`, and any timestamp or regex. Floats can't be `NaN`, so `NaN` is never passed to
+            `truthy`.
+
+            This never fails and always returns a boolean, so it can be used as an `if` condition
+            on a value of any type, such as an optional boolean field: `if truthy(.flag) { ... }`.
+        "#}
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[Parameter {
+            keyword: "value",
+            kind: kind::ANY,
+            required: true,
+        }]
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "null",
+                source: r#"truthy(null)"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "empty string",
+                source: r#"truthy("")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "non-empty array",
+                source: r#"truthy([0])"#,
+                result: Ok("true"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        Ok(TruthyFn { value }.as_expr())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct TruthyFn {
+    value: Box<dyn Expression>,
+}
+
+impl FunctionExpression for TruthyFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        Ok(truthy(&value).into())
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        TypeDef::boolean().infallible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value;
+    use chrono::{TimeZone, Utc};
+
+    test_function![
+        truthy => Truthy;
+
+        null {
+            args: func_args![value: value!(null)],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        boolean_false {
+            args: func_args![value: value!(false)],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        boolean_true {
+            args: func_args![value: value!(true)],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        integer_zero {
+            args: func_args![value: value!(0)],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        integer {
+            args: func_args![value: value!(-1)],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        float_zero {
+            args: func_args![value: value!(0.0)],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        float_negative_zero {
+            args: func_args![value: value!(-0.0)],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        float {
+            args: func_args![value: value!(0.1)],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        float_infinity {
+            args: func_args![value: Value::from_f64_or_zero(f64::INFINITY)],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        empty_string {
+            args: func_args![value: value!("")],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        whitespace_string {
+            args: func_args![value: value!(" \t\n")],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        string {
+            args: func_args![value: value!("false")],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        empty_array {
+            args: func_args![value: value!([])],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        array {
+            args: func_args![value: value!([null])],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        empty_object {
+            args: func_args![value: value!({})],
+            want: Ok(value!(false)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        object {
+            args: func_args![value: value!({"a": null})],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        timestamp {
+            args: func_args![value: Value::Timestamp(Utc.timestamp_opt(0, 0).unwrap())],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        regex {
+            args: func_args![value: Value::from(regex::Regex::new("").unwrap())],
+            want: Ok(value!(true)),
+            tdef: TypeDef::boolean().infallible(),
+        }
+    ];
+}
@@ -0,0 +1,199 @@
+use crate::compiler::prelude::*;
+
+static DEFAULT_SEPARATOR: &str = ".";
+
+/// Nests the fields of an object by splitting their keys on `separator`, undoing `flatten`.
+///
+/// Fields are visited in key order. If a key needs a field to be an object while it has some
+/// other value (as for `{"a": 1, "a.b": 2}`), the last of them wins. Values are kept as they are,
+/// and arrays aren't recreated from numeric keys.
+pub(crate) fn unflatten(value: Value, separator: &Value) -> Resolved {
+    let separator = separator.try_bytes_utf8_lossy()?;
+    if separator.is_empty() {
+        return Err("separator must not be empty".into());
+    }
+
+    let mut unflattened = ObjectMap::new();
+    for (key, value) in value.try_object()? {
+        let mut segments = key.split(separator.as_ref()).peekable();
+        let mut fields = &mut unflattened;
+
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                fields.insert(segment.into(), value);
+                break;
+            }
+
+            let field = fields
+                .entry(segment.into())
+                .or_insert_with(|| Value::from(ObjectMap::new()));
+            if !field.is_object() {
+                *field = ObjectMap::new().into();
+            }
+            fields = field.as_object_mut().expect("field is an object");
+        }
+    }
+
+    Ok(unflattened.into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Unflatten;
+
+impl Function for Unflatten {
+    fn identifier(&self) -> &'static str {
+        "unflatten"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::OBJECT,
+                required: true,
+            },
+            Parameter {
+                keyword: "separator",
+                kind: kind::BYTES,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "separator" => Some(DEFAULT_SEPARATOR.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "object",
+                source: r#"unflatten({ "foo.bar": true, "foo.baz": 1, "qux": "a" })"#,
+                result: Ok(r#"{ "foo": { "bar": true, "baz": 1 }, "qux": "a" }"#),
+            },
+            Example {
+                title: "round trip",
+                source: r#"unflatten(flatten({ "a": { "b": [1, { "c": 2 }] }, "d": true }))"#,
+                result: Ok(r#"{ "a": { "b": [1, { "c": 2 }] }, "d": true }"#),
+            },
+            Example {
+                title: "custom separator",
+                source: r#"unflatten({ "foo_bar": true }, "_")"#,
+                result: Ok(r#"{ "foo": { "bar": true } }"#),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        _state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let separator = arguments
+            .optional("separator")
+            .unwrap_or_else(|| expr!(DEFAULT_SEPARATOR));
+
+        Ok(UnflattenFn { value, separator }.as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct UnflattenFn {
+    value: Box<dyn Expression>,
+    separator: Box<dyn Expression>,
+}
+
+impl FunctionExpression for UnflattenFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+        let separator = self.separator.resolve(ctx)?;
+
+        unflatten(value, &separator)
+    }
+
+    fn type_def(&self, state: &state::TypeState) -> TypeDef {
+        // Only an empty separator is an error.
+        let fallible = self
+            .separator
+            .resolve_constant(state)
+            .and_then(|separator| separator.as_bytes().map(Bytes::is_empty))
+            .unwrap_or(true);
+
+        TypeDef::object(Collection::any()).maybe_fallible(fallible)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::value;
+
+    test_function![
+        unflatten => Unflatten;
+
+        nested {
+            args: func_args![value: value!({
+                "parent.child1": 1,
+                "parent.child2.grandchild1": 1,
+                "parent.child2.grandchild2": [1, [2, 3], 4],
+                key: "val",
+            })],
+            want: Ok(value!({
+                parent: {
+                    child1: 1,
+                    child2: {grandchild1: 1, grandchild2: [1, [2, 3], 4]},
+                },
+                key: "val",
+            })),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        separator {
+            args: func_args![value: value!({"a_b": 1, "a_c.d": 2}), separator: "_"],
+            want: Ok(value!({"a": {"b": 1, "c.d": 2}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        multi_character_separator {
+            args: func_args![value: value!({"a::b::c": 1}), separator: "::"],
+            want: Ok(value!({"a": {"b": {"c": 1}}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        nested_values_are_merged {
+            args: func_args![value: value!({"a": {"x": 1}, "a.b": 2, "c.d": {"e.f": 3}})],
+            want: Ok(value!({"a": {"b": 2, "x": 1}, "c": {"d": {"e.f": 3}}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        // Fields are visited in key order, and the last one to need a field wins.
+        key_collision {
+            args: func_args![value: value!({"a": 1, "a.b": 2, "a.b.c": 3})],
+            want: Ok(value!({"a": {"b": {"c": 3}}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        numeric_keys {
+            args: func_args![value: value!({"a.0": 1, "a.1": 2})],
+            want: Ok(value!({"a": {"0": 1, "1": 2}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        empty_segments {
+            args: func_args![value: value!({".a": 1, "b.": 2})],
+            want: Ok(value!({"": {"a": 1}, "b": {"": 2}})),
+            tdef: TypeDef::object(Collection::any()),
+        }
+
+        empty_separator {
+            args: func_args![value: value!({"a": 1}), separator: ""],
+            want: Err("separator must not be empty"),
+            tdef: TypeDef::object(Collection::any()).fallible(),
+        }
+    ];
+}
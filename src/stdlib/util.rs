@@ -1,4 +1,8 @@
-use crate::value::{KeyString, ObjectMap};
+use crate::compiler::prelude::{
+    Context, ErrorCode, Expression, ExpressionError, ExpressionResult, Kind, TimeZone, TypeState,
+    Value, VrlValueConvert,
+};
+use crate::value::{kind::Collection, KeyString, ObjectMap};
 
 /// Rounds the given number to the given precision.
 /// Takes a function parameter so the exact rounding function (ceil, floor or round)
@@ -46,9 +50,7 @@ pub(crate) fn capture_regex_to_map(
 
 pub(crate) fn regex_kind(
     regex: &regex::Regex,
-) -> std::collections::BTreeMap<crate::value::kind::Field, crate::value::kind::Kind> {
-    use crate::value::kind::Kind;
-
+) -> std::collections::BTreeMap<crate::value::kind::Field, Kind> {
     let mut inner_type = std::collections::BTreeMap::new();
 
     // Add typedefs for each capture by numerical index.
@@ -79,6 +81,172 @@ pub(crate) fn is_nullish(value: &crate::value::Value) -> bool {
     }
 }
 
+/// Parses a `timezone` function argument.
+///
+/// "local" refers to the timezone configured by the host, which is only the system local timezone
+/// if the host didn't configure another one.
+pub(crate) fn parse_timezone(timezone: &Value, host: TimeZone) -> ExpressionResult<TimeZone> {
+    let timezone = timezone.try_bytes_utf8_lossy()?;
+
+    match TimeZone::parse(&timezone) {
+        Some(TimeZone::Local) => Ok(host),
+        Some(timezone) => Ok(timezone),
+        None => Err(ExpressionError::with_code(
+            ErrorCode::InvalidArgument,
+            format!("unable to parse timezone: {timezone}"),
+        )),
+    }
+}
+
+/// The number of levels of nested collections functions descend into, unless they're called with
+/// an explicit `max_depth` argument.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Resolves an optional `max_depth` function argument.
+pub(crate) fn resolve_max_depth(
+    max_depth: Option<&dyn Expression>,
+    ctx: &mut Context,
+) -> ExpressionResult<usize> {
+    match max_depth {
+        None => Ok(DEFAULT_MAX_DEPTH),
+        Some(expr) => match expr.resolve(ctx)?.try_integer()? {
+            max_depth if max_depth >= 1 => Ok(usize::try_from(max_depth).unwrap_or(usize::MAX)),
+            _ => Err("max_depth must be at least 1".into()),
+        },
+    }
+}
+
+pub(crate) fn max_depth_exceeded(max_depth: usize) -> ExpressionError {
+    format!("maximum depth of {max_depth} exceeded").into()
+}
+
+/// Resolves an optional `max_size` function argument, which is `None` if there is no limit.
+pub(crate) fn resolve_max_size(
+    max_size: Option<&dyn Expression>,
+    ctx: &mut Context,
+) -> ExpressionResult<Option<usize>> {
+    match max_size {
+        None => Ok(None),
+        Some(expr) => match usize::try_from(expr.resolve(ctx)?.try_integer()?) {
+            Ok(max_size) => Ok(Some(max_size)),
+            Err(_) => Err("max_size must not be negative".into()),
+        },
+    }
+}
+
+/// Decompresses all members of a stream of concatenated compressed members, such as the gzip
+/// members of a file that was compressed in parts.
+///
+/// `decode_member` decompresses the member at the start of its input, appending at most `limit`
+/// bytes to the output, and advances its input to where it stopped reading, even if it fails.
+/// Decompression stops at the end of the input, or with an error at trailing data that
+/// `is_member` says is not the start of another member.
+pub(crate) fn decompress_members(
+    codec: &str,
+    mut input: &[u8],
+    max_size: Option<usize>,
+    is_member: fn(&[u8]) -> bool,
+    mut decode_member: impl FnMut(&mut &[u8], u64, &mut Vec<u8>) -> std::io::Result<()>,
+) -> ExpressionResult<Value> {
+    let mut output = Vec::new();
+
+    loop {
+        let limit = max_size.map_or(u64::MAX, |max_size| {
+            u64::try_from(max_size - output.len())
+                .unwrap_or(u64::MAX)
+                .saturating_add(1)
+        });
+        let member = input;
+        decode_member(&mut input, limit, &mut output).map_err(|err| {
+            // Decoders don't all report the end of the input as such, but a member that only
+            // fails once all of its input has been read is cut short.
+            if err.kind() == std::io::ErrorKind::UnexpectedEof
+                || (input.is_empty() && is_member(member))
+            {
+                format!("unable to decode value with {codec} decoder: stream is truncated")
+            } else {
+                format!("unable to decode value with {codec} decoder")
+            }
+        })?;
+
+        if let Some(max_size) = max_size.filter(|&max_size| output.len() > max_size) {
+            return Err(
+                format!("decoded value is larger than max_size of {max_size} bytes").into(),
+            );
+        }
+        if input.is_empty() {
+            return Ok(Value::Bytes(output.into()));
+        }
+        if !is_member(input) {
+            return Err(format!(
+                "unable to decode value with {codec} decoder: trailing data after the compressed stream"
+            )
+            .into());
+        }
+    }
+}
+
+/// Returns `true` if the `recursive` argument is given, unless it's known to be `false`.
+pub(crate) fn is_recursive(recursive: Option<&dyn Expression>, state: &TypeState) -> bool {
+    recursive.map_or(false, |recursive| {
+        recursive
+            .resolve_constant(state)
+            .map_or(true, |recursive| recursive.as_boolean() != Some(false))
+    })
+}
+
+/// Returns `true` if values of the given kind can contain collections nested deeper than the
+/// `max_depth` argument allows.
+///
+/// This is used to only make functions fallible if they can actually encounter values that are
+/// nested too deeply.
+pub(crate) fn may_exceed_max_depth(
+    kind: &Kind,
+    max_depth: Option<&dyn Expression>,
+    state: &TypeState,
+) -> bool {
+    let max_depth = match max_depth {
+        None => Some(DEFAULT_MAX_DEPTH),
+        Some(expr) => expr
+            .resolve_constant(state)
+            .and_then(|value| value.as_integer())
+            .and_then(|max_depth| usize::try_from(max_depth).ok()),
+    };
+
+    match (kind_depth(kind), max_depth) {
+        (Some(depth), Some(max_depth)) => depth > max_depth,
+        _ => true,
+    }
+}
+
+/// Returns the number of levels of nested collections in values of the given kind, or `None` if
+/// that is unbounded.
+fn kind_depth(kind: &Kind) -> Option<usize> {
+    fn collection_depth<T: Ord + Clone>(collection: &Collection<T>) -> Option<usize> {
+        let unknown = collection.unknown_kind();
+        let unknown_depth = if collection.is_unknown_exact() {
+            kind_depth(&unknown)?
+        } else if unknown.contains_object() || unknown.contains_array() {
+            return None;
+        } else {
+            0
+        };
+
+        collection
+            .known()
+            .values()
+            .try_fold(unknown_depth, |depth, kind| {
+                kind_depth(kind).map(|known| depth.max(known))
+            })
+            .map(|depth| depth + 1)
+    }
+
+    let object = kind.as_object().map_or(Some(0), collection_depth)?;
+    let array = kind.as_array().map_or(Some(0), collection_depth)?;
+
+    Some(object.max(array))
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Base64Charset {
     Standard,
@@ -115,3 +283,27 @@ impl std::str::FromStr for Base64Charset {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::value::Value;
+
+    /// Builds two concatenated members, which decode to `"first second"`, given a function that
+    /// compresses a single member.
+    pub(crate) fn concatenated_members(encode: fn(&str) -> Vec<u8>) -> Value {
+        Value::Bytes([encode("first "), encode("second")].concat().into())
+    }
+
+    /// Builds a member followed by bytes that aren't a member.
+    pub(crate) fn trailing_data(encode: fn(&str) -> Vec<u8>) -> Value {
+        Value::Bytes([encode("sample"), b"junk".to_vec()].concat().into())
+    }
+
+    /// Builds the first half of a member.
+    pub(crate) fn truncated(encode: fn(&str) -> Vec<u8>) -> Value {
+        let text = (0..1000).map(|i| i.to_string()).collect::<String>();
+        let encoded = encode(&text);
+
+        Value::Bytes(encoded[..encoded.len() / 2].to_vec().into())
+    }
+}
@@ -13,12 +13,20 @@ impl WasmUnsupportedFunction {
     }
 }
 
+const UNSUPPORTED_MESSAGE: &str = "This function is not supported in WebAssembly";
+
 impl FunctionExpression for WasmUnsupportedFunction {
     fn resolve(&self, _: &mut Context) -> Resolved {
-        Err(ExpressionError::Abort {
-            span: self.span,
-            message: Some("This function is not supported in WebAssembly".to_owned()),
-        })
+        // Fallible functions surface a regular error that programs can handle, infallible ones
+        // have no way to report an error and abort instead.
+        if self.type_def.is_fallible() {
+            Err(UNSUPPORTED_MESSAGE.into())
+        } else {
+            Err(ExpressionError::Abort {
+                span: self.span,
+                message: Some(UNSUPPORTED_MESSAGE.to_owned()),
+            })
+        }
     }
 
     fn type_def(&self, _: &state::TypeState) -> TypeDef {
@@ -0,0 +1,331 @@
+use crate::compiler::prelude::*;
+use regex::{Regex, RegexBuilder};
+
+/// Translates a glob `pattern` into an anchored regular expression.
+///
+/// `*` matches any sequence of characters (including none), `?` matches a single character and
+/// `[...]` matches one of the characters of the class, which can contain ranges (`[a-z]`) and be
+/// negated with a leading `!` or `^`. A `\` matches the next character literally. Every other
+/// character, including an unclosed `[`, is matched literally.
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::with_capacity(pattern.len() + 8);
+    regex.push_str("(?s)^");
+
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            '*' => {
+                regex.push_str(".*");
+                // consecutive stars are equivalent to a single one
+                while chars.get(i + 1) == Some(&'*') {
+                    i += 1;
+                }
+            }
+            '?' => regex.push('.'),
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                push_escaped(&mut regex, chars[i]);
+            }
+            '[' => match class_end(&chars, i) {
+                Some(end) => {
+                    push_class(&mut regex, &chars[i + 1..end]);
+                    i = end;
+                }
+                None => push_escaped(&mut regex, '['),
+            },
+            _ => push_escaped(&mut regex, c),
+        }
+        i += 1;
+    }
+
+    regex.push('$');
+    regex
+}
+
+/// Returns the index of the `]` closing the class opened at `start`. A `]` right after the
+/// opening bracket (or after the negation) is part of the class.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if matches!(chars.get(i), Some('!' | '^')) {
+        i += 1;
+    }
+    if chars.get(i) == Some(&']') {
+        i += 1;
+    }
+
+    chars[i..]
+        .iter()
+        .position(|&c| c == ']')
+        .map(|offset| i + offset)
+}
+
+fn push_class(regex: &mut String, class: &[char]) {
+    let (negated, class) = match class.first() {
+        Some('!' | '^') => (true, &class[1..]),
+        _ => (false, class),
+    };
+
+    regex.push('[');
+    if negated {
+        regex.push('^');
+    }
+    for (i, &c) in class.iter().enumerate() {
+        // a `-` is a range, unless it's the first or last character of the class
+        if c == '-' && i != 0 && i != class.len() - 1 {
+            regex.push('-');
+        } else if c.is_ascii_punctuation() {
+            regex.push('\\');
+            regex.push(c);
+        } else {
+            regex.push(c);
+        }
+    }
+    regex.push(']');
+}
+
+fn push_escaped(regex: &mut String, c: char) {
+    let mut buf = [0; 4];
+    regex.push_str(&regex::escape(c.encode_utf8(&mut buf)));
+}
+
+fn compile_pattern(pattern: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&glob_to_regex(pattern))
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+fn wildcard_match(value: &Value, matcher: &Regex) -> Resolved {
+    let value = value.try_bytes_utf8_lossy()?;
+
+    Ok(matcher.is_match(&value).into())
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct WildcardMatch;
+
+impl Function for WildcardMatch {
+    fn identifier(&self) -> &'static str {
+        "wildcard_match"
+    }
+
+    fn parameters(&self) -> &'static [Parameter] {
+        &[
+            Parameter {
+                keyword: "value",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "pattern",
+                kind: kind::BYTES,
+                required: true,
+            },
+            Parameter {
+                keyword: "case_sensitive",
+                kind: kind::BOOLEAN,
+                required: false,
+            },
+        ]
+    }
+
+    fn parameter_default(&self, keyword: &str) -> Option<Value> {
+        match keyword {
+            "case_sensitive" => Some(true.into()),
+            _ => None,
+        }
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        &[
+            Example {
+                title: "match",
+                source: r#"wildcard_match("app-01.example.com", "app-??.*.com")"#,
+                result: Ok("true"),
+            },
+            Example {
+                title: "character class",
+                source: r#"wildcard_match("db-7", "db-[0-5]")"#,
+                result: Ok("false"),
+            },
+            Example {
+                title: "case insensitive",
+                source: r#"wildcard_match("ERROR: disk full", "error:*", case_sensitive: false)"#,
+                result: Ok("true"),
+            },
+        ]
+    }
+
+    fn compile(
+        &self,
+        state: &state::TypeState,
+        _ctx: &mut FunctionCompileContext,
+        arguments: ArgumentList,
+    ) -> Compiled {
+        let value = arguments.required("value");
+        let pattern = arguments.required("pattern");
+        let case_sensitive = arguments
+            .optional("case_sensitive")
+            .unwrap_or_else(|| expr!(true));
+
+        // The matcher is compiled once when both the pattern and the case sensitivity are known.
+        let matcher = match (
+            pattern.resolve_constant(state),
+            case_sensitive.resolve_constant(state),
+        ) {
+            (Some(Value::Bytes(bytes)), Some(Value::Boolean(case_sensitive))) => {
+                let matcher = compile_pattern(&String::from_utf8_lossy(&bytes), case_sensitive)
+                    .map_err(|_| function::Error::InvalidArgument {
+                        keyword: "pattern",
+                        value: Value::Bytes(bytes),
+                        error: "invalid wildcard pattern",
+                    })?;
+                Some(matcher)
+            }
+            _ => None,
+        };
+
+        Ok(WildcardMatchFn {
+            value,
+            pattern,
+            case_sensitive,
+            matcher,
+        }
+        .as_expr())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WildcardMatchFn {
+    value: Box<dyn Expression>,
+    pattern: Box<dyn Expression>,
+    case_sensitive: Box<dyn Expression>,
+    matcher: Option<Regex>,
+}
+
+impl FunctionExpression for WildcardMatchFn {
+    fn resolve(&self, ctx: &mut Context) -> Resolved {
+        let value = self.value.resolve(ctx)?;
+
+        if let Some(matcher) = &self.matcher {
+            return wildcard_match(&value, matcher);
+        }
+
+        let pattern = self.pattern.resolve(ctx)?;
+        let case_sensitive = self.case_sensitive.resolve(ctx)?.try_boolean()?;
+        let matcher = compile_pattern(&pattern.try_bytes_utf8_lossy()?, case_sensitive)
+            .map_err(|err| format!("invalid wildcard pattern: {err}"))?;
+
+        wildcard_match(&value, &matcher)
+    }
+
+    fn type_def(&self, _: &state::TypeState) -> TypeDef {
+        // A pattern that is only known at runtime can be invalid, as in `[z-a]`.
+        TypeDef::boolean().maybe_fallible(self.matcher.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test_function![
+        wildcard_match => WildcardMatch;
+
+        star_spans_characters {
+            args: func_args![value: "foo/bar/baz.log", pattern: "foo*.log"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        star_matches_nothing {
+            args: func_args![value: "foo", pattern: "f**oo*"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        star_is_anchored {
+            args: func_args![value: "xfoo", pattern: "foo*"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        question_mark_single_char {
+            args: func_args![value: "café", pattern: "caf?"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        question_mark_not_empty {
+            args: func_args![value: "caf", pattern: "caf?"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        character_class {
+            args: func_args![value: "host-b3", pattern: "host-[abc][0-9]"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        negated_character_class {
+            args: func_args![value: "host-b3", pattern: "host-[!abc]3"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        class_with_special_characters {
+            args: func_args![value: "a]-\\", pattern: "a[]][-x][\\\\]"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        literal_dot {
+            args: func_args![value: "fooxlog", pattern: "foo.log"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        regex_characters_are_literal {
+            args: func_args![value: "(a+b)|{c}$^", pattern: "(a+b)|{c}$^"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        escaped_metacharacter {
+            args: func_args![value: "what?", pattern: "what\\?"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        unclosed_bracket_is_literal {
+            args: func_args![value: "[abc", pattern: "[abc"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        multiline_value {
+            args: func_args![value: "first\nsecond", pattern: "first*"],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        case_sensitive_by_default {
+            args: func_args![value: "FOO.TXT", pattern: "*.txt"],
+            want: Ok(false),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        case_insensitive {
+            args: func_args![value: "FOO.TXT", pattern: "*.txt", case_sensitive: false],
+            want: Ok(true),
+            tdef: TypeDef::boolean().infallible(),
+        }
+
+        invalid_range {
+            args: func_args![value: "a", pattern: "[z-a]"],
+            want: Err("invalid argument"),
+            tdef: TypeDef::boolean().fallible(),
+        }
+    ];
+}
@@ -121,6 +121,9 @@ pub fn run_tests<T>(
         for (path, recursive) in &test.read_only_paths {
             config.set_read_only_path(path.clone(), *recursive);
         }
+        if let Some(unused_expression) = test.unused_expression {
+            config.set_unused_expression(unused_expression);
+        }
 
         let (result, compile_duration) = measure_time(|| {
             compile_with_external(&test.source, functions, &ExternalEnv::default(), config)
@@ -1,6 +1,7 @@
 use std::{collections::BTreeMap, fs, path::Path};
 
 use crate::compiler::function::Example;
+use crate::compiler::UnusedExpression;
 use crate::path::parse_value_path;
 use crate::path::OwnedTargetPath;
 use crate::test::{example_vrl_path, test_prefix};
@@ -18,6 +19,8 @@ pub struct Test {
     pub skip: bool,
     // paths set to read-only
     pub read_only_paths: Vec<(OwnedTargetPath, bool)>,
+    // how unused expressions are reported, if not as configured by the test runner
+    pub unused_expression: Option<UnusedExpression>,
 }
 
 enum CaptureMode {
@@ -39,6 +42,7 @@ impl Test {
         let mut result_approx = false;
 
         let mut read_only_paths = vec![];
+        let mut unused_expression = None;
 
         let mut capture_mode = CaptureMode::None;
         for mut line in content.lines() {
@@ -93,6 +97,18 @@ impl Test {
                         true,
                     ));
                     continue;
+                } else if line.starts_with("unused_expression:") {
+                    unused_expression = match line
+                        .strip_prefix("unused_expression:")
+                        .expect("unused_expression")
+                        .trim()
+                    {
+                        "allow" => Some(UnusedExpression::Allow),
+                        "warn" => Some(UnusedExpression::Warn),
+                        "deny" => Some(UnusedExpression::Deny),
+                        other => panic!("invalid unused_expression: {other}"),
+                    };
+                    continue;
                 }
 
                 match capture_mode {
@@ -135,6 +151,7 @@ impl Test {
             result_approx,
             skip: content.starts_with("# SKIP"),
             read_only_paths,
+            unused_expression,
         }
     }
 
@@ -155,6 +172,7 @@ impl Test {
             result_approx: false,
             skip: false,
             read_only_paths: vec![],
+            unused_expression: None,
         }
     }
 }
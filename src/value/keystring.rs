@@ -1,20 +1,61 @@
 use std::borrow::Cow;
-use std::fmt::{self, Display, Formatter};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
-use serde::{Deserialize, Serialize};
+use bytes::Bytes;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// The key type value. This is a simple zero-overhead wrapper set up to make it explicit that
 /// object keys are read-only and their underlying type is opaque and may change for efficiency.
-#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
-#[serde(transparent)]
-pub struct KeyString(String);
+///
+/// Keys are backed by reference counted [`Bytes`] holding valid UTF-8, so that cloning and
+/// slicing them shares the same buffer rather than copying it.
+#[derive(Clone)]
+pub struct KeyString(Bytes);
+
+/// The error returned when slicing a [`KeyString`] with an invalid range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SliceError {
+    /// The range extends past the end of the key, or starts after it ends.
+    OutOfBounds {
+        /// The requested range.
+        range: Range<usize>,
+        /// The length of the key in bytes.
+        len: usize,
+    },
+
+    /// A bound of the range falls inside a multi-byte character.
+    NotCharBoundary {
+        /// The offending byte index.
+        index: usize,
+    },
+}
+
+impl Display for SliceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds { range, len } => write!(
+                f,
+                "range {}..{} is out of bounds for key of length {len}",
+                range.start, range.end
+            ),
+            Self::NotCharBoundary { index } => {
+                write!(f, "byte index {index} is not a char boundary")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SliceError {}
 
 impl KeyString {
     /// Convert the key into a boxed slice of bytes (`u8`).
     #[inline]
     #[must_use]
     pub fn into_bytes(self) -> Box<[u8]> {
-        self.0.into_bytes().into()
+        Vec::from(self.0).into()
     }
 
     /// Is this string empty?
@@ -35,60 +76,176 @@ impl KeyString {
     #[inline]
     #[must_use]
     pub fn as_str(&self) -> &str {
-        &self.0
+        // SAFETY: the bytes are only ever constructed from a `str`, sliced on char boundaries, or
+        // replaced by `map_buffer`, which checks that they're valid UTF-8.
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Returns the subkey of the given byte range, sharing the buffer of this key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the range is out of bounds, or if either end of it doesn't fall on a
+    /// char boundary.
+    pub fn slice(&self, range: Range<usize>) -> Result<Self, SliceError> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(SliceError::OutOfBounds {
+                range,
+                len: self.len(),
+            });
+        }
+
+        for index in [range.start, range.end] {
+            if !self.as_str().is_char_boundary(index) {
+                return Err(SliceError::NotCharBoundary { index });
+            }
+        }
+
+        Ok(Self(self.0.slice(range)))
+    }
+
+    /// Splits the key on the first occurrence of `delimiter`, returning the parts before and
+    /// after it, which share the buffer of this key.
+    #[must_use]
+    pub fn split_once(&self, delimiter: char) -> Option<(Self, Self)> {
+        let index = self.as_str().find(delimiter)?;
+
+        Some((
+            Self(self.0.slice(..index)),
+            Self(self.0.slice(index + delimiter.len_utf8()..)),
+        ))
+    }
+
+    /// Returns the key without `prefix`, sharing the buffer of this key, or `None` if the key
+    /// doesn't start with it.
+    #[must_use]
+    pub fn strip_prefix(&self, prefix: &str) -> Option<Self> {
+        self.as_str()
+            .starts_with(prefix)
+            .then(|| Self(self.0.slice(prefix.len()..)))
+    }
+
+    /// Replaces the buffer of this key with the one `f` returns for it, which should hold the same
+    /// bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the returned bytes aren't valid UTF-8, as keys must always be.
+    pub(crate) fn map_buffer(self, f: impl FnOnce(Bytes) -> Bytes) -> Self {
+        let bytes = f(self.0);
+        assert!(
+            std::str::from_utf8(&bytes).is_ok(),
+            "key buffer replaced with invalid UTF-8"
+        );
+        Self(bytes)
+    }
+}
+
+impl Debug for KeyString {
+    fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("KeyString").field(&self.as_str()).finish()
     }
 }
 
 impl Display for KeyString {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        self.0.fmt(fmt)
+        Display::fmt(self.as_str(), fmt)
+    }
+}
+
+// The comparison traits are implemented on the string, so that they agree with `Borrow<str>`.
+impl PartialEq for KeyString {
+    fn eq(&self, that: &Self) -> bool {
+        self.as_str() == that.as_str()
+    }
+}
+
+impl Eq for KeyString {}
+
+impl PartialOrd for KeyString {
+    fn partial_cmp(&self, that: &Self) -> Option<Ordering> {
+        Some(self.cmp(that))
+    }
+}
+
+impl Ord for KeyString {
+    fn cmp(&self, that: &Self) -> Ordering {
+        self.as_str().cmp(that.as_str())
+    }
+}
+
+impl Hash for KeyString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Serialize for KeyString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
     }
 }
 
 impl AsRef<str> for KeyString {
     fn as_ref(&self) -> &str {
-        &self.0
+        self.as_str()
     }
 }
 
 impl std::ops::Deref for KeyString {
     type Target = str;
     fn deref(&self) -> &str {
-        &self.0
+        self.as_str()
     }
 }
 
 impl std::borrow::Borrow<str> for KeyString {
     fn borrow(&self) -> &str {
-        &self.0
+        self.as_str()
     }
 }
 
 impl PartialEq<str> for KeyString {
     fn eq(&self, that: &str) -> bool {
-        self.0[..].eq(that)
+        self.as_str().eq(that)
     }
 }
 
 impl From<&str> for KeyString {
     fn from(s: &str) -> Self {
-        Self(s.into())
+        Self(Bytes::copy_from_slice(s.as_bytes()))
     }
 }
 
 impl From<String> for KeyString {
     fn from(s: String) -> Self {
-        Self(s)
+        Self(s.into())
     }
 }
 
 impl From<Cow<'_, str>> for KeyString {
     fn from(s: Cow<'_, str>) -> Self {
-        Self(s.into())
+        match s {
+            Cow::Borrowed(s) => s.into(),
+            Cow::Owned(s) => s.into(),
+        }
     }
 }
 
 impl From<KeyString> for String {
+    fn from(s: KeyString) -> Self {
+        // SAFETY: the bytes are valid UTF-8, for the same reasons as in `KeyString::as_str`.
+        unsafe { Self::from_utf8_unchecked(s.0.into()) }
+    }
+}
+
+impl From<KeyString> for Bytes {
     fn from(s: KeyString) -> Self {
         s.0
     }
@@ -101,7 +258,7 @@ impl quickcheck::Arbitrary for KeyString {
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
-        let s = self.0.to_string();
+        let s = self.as_str().to_string();
         Box::new(s.shrink().map(Into::into))
     }
 }
@@ -121,7 +278,162 @@ mod lua {
 
     impl<'a> IntoLua<'a> for KeyString {
         fn into_lua(self, lua: &'a Lua) -> LuaResult<LuaValue<'_>> {
-            self.0.into_lua(lua)
+            self.as_str().into_lua(lua)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shares_buffer(key: &KeyString, subkey: &KeyString, offset: usize) {
+        assert_eq!(subkey.as_str().as_ptr(), key.as_str()[offset..].as_ptr());
+    }
+
+    #[test]
+    fn slice() {
+        let key = KeyString::from("kubernetes.pod_labels.app");
+
+        let slice = key.slice(11..21).unwrap();
+        assert_eq!(slice, *"pod_labels");
+        shares_buffer(&key, &slice, 11);
+
+        assert_eq!(key.slice(0..0).unwrap(), *"");
+        assert_eq!(key.slice(0..key.len()).unwrap(), key);
+    }
+
+    #[test]
+    fn slice_multibyte() {
+        // "é" and "ü" are two bytes, "日" is three.
+        let key = KeyString::from("clé.über.日本");
+
+        let slice = key.slice(5..10).unwrap();
+        assert_eq!(slice, *"über");
+        shares_buffer(&key, &slice, 5);
+
+        assert_eq!(key.slice(11..14).unwrap(), *"日");
+        assert_eq!(
+            key.slice(3..5),
+            Err(SliceError::NotCharBoundary { index: 3 })
+        );
+        assert_eq!(
+            key.slice(11..13),
+            Err(SliceError::NotCharBoundary { index: 13 })
+        );
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn slice_out_of_bounds() {
+        let key = KeyString::from("abc");
+
+        assert_eq!(
+            key.slice(1..4),
+            Err(SliceError::OutOfBounds {
+                range: 1..4,
+                len: 3
+            })
+        );
+        assert_eq!(
+            key.slice(2..1),
+            Err(SliceError::OutOfBounds {
+                range: 2..1,
+                len: 3
+            })
+        );
+        assert_eq!(
+            key.slice(1..4).unwrap_err().to_string(),
+            "range 1..4 is out of bounds for key of length 3"
+        );
+    }
+
+    #[test]
+    fn split_once() {
+        let key = KeyString::from("kubernetes.pod_labels.app");
+
+        let (head, tail) = key.split_once('.').unwrap();
+        assert_eq!(head, *"kubernetes");
+        assert_eq!(tail, *"pod_labels.app");
+        shares_buffer(&key, &head, 0);
+        shares_buffer(&key, &tail, 11);
+
+        let (head, tail) = tail.split_once('.').unwrap();
+        assert_eq!(head, *"pod_labels");
+        assert_eq!(tail, *"app");
+        shares_buffer(&key, &tail, 22);
+
+        assert_eq!(tail.split_once('.'), None);
+    }
+
+    #[test]
+    fn split_once_multibyte() {
+        let key = KeyString::from("日本→東京");
+
+        let (head, tail) = key.split_once('→').unwrap();
+        assert_eq!(head, *"日本");
+        assert_eq!(tail, *"東京");
+        shares_buffer(&key, &tail, 9);
+    }
+
+    #[test]
+    fn strip_prefix() {
+        let key = KeyString::from("kubernetes.pod_labels.app");
+
+        let stripped = key.strip_prefix("kubernetes.").unwrap();
+        assert_eq!(stripped, *"pod_labels.app");
+        shares_buffer(&key, &stripped, 11);
+
+        assert_eq!(key.strip_prefix("pod"), None);
+        assert_eq!(
+            KeyString::from("ünïcode").strip_prefix("ü").unwrap(),
+            *"nïcode"
+        );
+    }
+
+    #[test]
+    fn map_buffer() {
+        let key = KeyString::from("kubernetes.app")
+            .strip_prefix("kubernetes.")
+            .unwrap();
+
+        let mapped = key.map_buffer(|bytes| Bytes::copy_from_slice(&bytes));
+        assert_eq!(mapped, *"app");
+    }
+
+    #[test]
+    #[should_panic(expected = "key buffer replaced with invalid UTF-8")]
+    fn map_buffer_invalid_utf8() {
+        KeyString::from("app").map_buffer(|_| Bytes::from_static(b"\xff"));
+    }
+
+    #[test]
+    fn hash_and_order_agree_with_str() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let key = KeyString::from("kubernetes.app")
+            .strip_prefix("kubernetes.")
+            .unwrap();
+
+        let hashed = HashMap::from([(key.clone(), 1)]);
+        assert_eq!(hashed.get("app"), Some(&1));
+
+        let ordered = BTreeMap::from([(key, 1), ("b".into(), 2), ("ab".into(), 3)]);
+        assert_eq!(ordered.get("app"), Some(&1));
+        assert_eq!(
+            ordered.keys().map(KeyString::as_str).collect::<Vec<_>>(),
+            ["ab", "app", "b"]
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let key = KeyString::from("pod.app").strip_prefix("pod.").unwrap();
+
+        assert_eq!(format!("{key:?}"), r#"KeyString("app")"#);
+        assert_eq!(String::from(key.clone()), "app");
+        assert_eq!(Bytes::from(key.clone()), "app");
+        assert_eq!(serde_json::to_string(&key).unwrap(), r#""app""#);
+        assert_eq!(serde_json::from_str::<KeyString>(r#""app""#).unwrap(), key);
+    }
+}
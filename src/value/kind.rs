@@ -37,22 +37,13 @@ pub struct Kind {
     object: Option<Collection<Field>>,
 }
 
-impl std::fmt::Display for Kind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.is_any() {
-            return f.write_str("any");
-        }
-
-        // For collections, we expand to a more descriptive representation only
-        // if the type can only be this collection.
-        if self.is_exact() {
-            if let Some(object) = &self.object {
-                return object.fmt(f);
-            } else if let Some(array) = &self.array {
-                return array.fmt(f);
-            }
-        }
-
+impl Kind {
+    /// Returns the names of the primitive types this kind can be, in the same order (and using
+    /// the same names) as its [`Display`](std::fmt::Display) representation.
+    ///
+    /// Collections are reported as `"array"` or `"object"`, regardless of their contents.
+    #[must_use]
+    pub fn primitive_names(&self) -> Vec<&'static str> {
         let mut kinds = vec![];
 
         if self.contains_bytes() {
@@ -86,6 +77,28 @@ impl std::fmt::Display for Kind {
             kinds.push("object");
         }
 
+        kinds
+    }
+}
+
+impl std::fmt::Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_any() {
+            return f.write_str("any");
+        }
+
+        // For collections, we expand to a more descriptive representation only
+        // if the type can only be this collection.
+        if self.is_exact() {
+            if let Some(object) = &self.object {
+                return object.fmt(f);
+            } else if let Some(array) = &self.array {
+                return array.fmt(f);
+            }
+        }
+
+        let kinds = self.primitive_names();
+
         if kinds.is_empty() {
             return f.write_str("never");
         }
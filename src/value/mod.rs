@@ -44,7 +44,7 @@ mod keystring;
 
 pub use kind::Kind;
 
-pub use self::keystring::KeyString;
+pub use self::keystring::{KeyString, SliceError};
 pub use self::secrets::Secrets;
 pub use self::value::{ObjectMap, Value, ValueRegex};
 
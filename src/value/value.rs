@@ -3,20 +3,31 @@
 use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, SecondsFormat, Utc};
 use ordered_float::NotNan;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
-pub use iter::{IterItem, ValueIter};
+pub use builder::{ArrayBuilder, ObjectBuilder, ValueBuilder};
+pub use flat_map::FlatMapError;
+pub use iter::{IterItem, MaxDepthExceeded, ValueIter};
+pub use json_reader::JsonReaderError;
+pub use partition_hash::PARTITION_HASH_LUA;
+pub use typed_array::TypedArrayError;
 
 pub use super::value::regex::ValueRegex;
 use super::KeyString;
-use crate::path::ValuePath;
+use crate::path::{OwnedValuePath, ValuePath};
 
+mod builder;
 mod convert;
 mod crud;
 mod display;
+mod flat_map;
 mod iter;
+mod json_reader;
+mod otel;
+mod partition_hash;
 mod path;
 mod regex;
+mod typed_array;
 
 #[cfg(any(test, feature = "arbitrary"))]
 mod arbitrary;
@@ -65,7 +76,7 @@ pub enum Value {
 
 impl Value {
     /// Returns a string description of the value type
-    pub const fn kind_str(&self) -> &str {
+    pub const fn kind_str(&self) -> &'static str {
         match self {
             Self::Bytes(_) | Self::Regex(_) => "string",
             Self::Timestamp(_) => "timestamp",
@@ -170,6 +181,167 @@ impl Value {
     pub fn contains<'a>(&self, path: impl ValuePath<'a>) -> bool {
         self.get(path).is_some()
     }
+
+    /// Determines whether the path exists within the value, without cloning what it points to.
+    ///
+    /// A path set to `null` exists, while a path that's missing (or traverses into something
+    /// that isn't a collection) doesn't. Segments are resolved the same way as by [`Value::get`],
+    /// including negative array indices and coalesced fields.
+    ///
+    /// ```rust
+    /// use vrl::{owned_value_path, value};
+    ///
+    /// let value = value!({"a": {"b": null}, "c": [1]});
+    ///
+    /// assert!(value.path_exists(&owned_value_path!("a", "b")));
+    /// assert!(!value.path_exists(&owned_value_path!("a", "x")));
+    /// assert!(value.path_exists(&owned_value_path!("c", -1)));
+    /// assert!(!value.path_exists(&owned_value_path!("c", 1)));
+    /// ```
+    #[must_use]
+    pub fn path_exists(&self, path: &OwnedValuePath) -> bool {
+        self.contains(path)
+    }
+
+    /// Removes all object fields whose value is `Null`, in place.
+    ///
+    /// If `recursive` is `true`, this also applies to objects nested in objects or arrays.
+    /// Null elements of arrays are always kept, since removing them would shift the indices of
+    /// the elements that follow.
+    ///
+    /// ```rust
+    /// use vrl::value;
+    ///
+    /// let mut val = value!({"a": null, "b": {"c": null, "d": [null, {"e": null}]}});
+    /// val.strip_nulls(true);
+    /// assert_eq!(val, value!({"b": {"d": [null, {}]}}));
+    /// ```
+    pub fn strip_nulls(&mut self, recursive: bool) {
+        match self {
+            Self::Object(map) => {
+                map.retain(|_, value| !value.is_null());
+
+                if recursive {
+                    map.values_mut().for_each(|value| value.strip_nulls(true));
+                }
+            }
+            Self::Array(array) if recursive => {
+                array.iter_mut().for_each(|value| value.strip_nulls(true));
+            }
+            _ => {}
+        }
+    }
+
+    /// Releases excess capacity held by this value, recursively.
+    ///
+    /// Arrays are shrunk to their length, and bytes and object keys are moved into buffers of
+    /// their exact size, which also releases any larger buffer they were sliced from. Buffers that
+    /// already have their exact size are kept as they are, and values that share a buffer keep
+    /// sharing it. This is useful for values that are kept around for a long time after being
+    /// heavily mutated.
+    ///
+    /// `Bytes` can't tell buffers shared with something outside of this value, or static ones,
+    /// apart from those it owns until they're converted, so those are copied.
+    ///
+    /// ```rust
+    /// use vrl::value::Value;
+    ///
+    /// let mut array = Vec::with_capacity(100);
+    /// array.push(Value::from(1));
+    /// let mut val = Value::from(array);
+    /// val.shrink_to_fit();
+    /// assert_eq!(val.as_array_mut().unwrap().capacity(), 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit_with(&mut HashMap::new());
+    }
+
+    fn shrink_to_fit_with(&mut self, repacked: &mut Repacked) {
+        match self {
+            Self::Bytes(bytes) => *bytes = repack(std::mem::take(bytes), repacked),
+            Self::Regex(_)
+            | Self::Integer(_)
+            | Self::Float(_)
+            | Self::Boolean(_)
+            | Self::Timestamp(_)
+            | Self::Null => {}
+            Self::Object(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut value)| {
+                        value.shrink_to_fit_with(repacked);
+                        (key.map_buffer(|bytes| repack(bytes, repacked)), value)
+                    })
+                    .collect();
+            }
+            Self::Array(array) => {
+                array
+                    .iter_mut()
+                    .for_each(|value| value.shrink_to_fit_with(repacked));
+                array.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Returns an object of the fields whose flattened path starts with `prefix`.
+    ///
+    /// Nested objects are flattened the same way as by the `flatten` function, joining their keys
+    /// with `.`, while arrays are kept as they are. The prefix is matched against the flattened
+    /// keys as a string, so `"http."` selects the fields of the `http` object and `"http"` also
+    /// selects an `https` field. If `strip_prefix` is `true`, the prefix is removed from the keys
+    /// of the result. Values other than objects have no fields, and return an empty object.
+    ///
+    /// ```rust
+    /// use vrl::value;
+    ///
+    /// let value = value!({"http": {"method": "GET", "status": 200}, "host": "example.com"});
+    ///
+    /// assert_eq!(
+    ///     value.subset_by_prefix("http.", false),
+    ///     value!({"http.method": "GET", "http.status": 200}),
+    /// );
+    /// assert_eq!(
+    ///     value.subset_by_prefix("http.", true),
+    ///     value!({"method": "GET", "status": 200}),
+    /// );
+    /// ```
+    #[must_use]
+    pub fn subset_by_prefix(&self, prefix: &str, strip_prefix: bool) -> Self {
+        let mut subset = ObjectMap::new();
+        let Self::Object(map) = self else {
+            return subset.into();
+        };
+
+        // Nested objects are walked using an explicit stack, skipping those that can't contain a
+        // matching field.
+        let mut stack = vec![(None::<String>, map.iter())];
+        while let Some((parent, fields)) = stack.last_mut() {
+            let Some((key, value)) = fields.next() else {
+                stack.pop();
+                continue;
+            };
+
+            let key = parent
+                .as_ref()
+                .map_or_else(|| key.to_string(), |parent| format!("{parent}.{key}"));
+
+            match value {
+                Self::Object(map) => {
+                    if key.starts_with(prefix) || prefix.starts_with(key.as_str()) {
+                        stack.push((Some(key), map.iter()));
+                    }
+                }
+                value => {
+                    if let Some(stripped) = key.strip_prefix(prefix) {
+                        let key = if strip_prefix { stripped.into() } else { key };
+                        subset.insert(key.into(), value.clone());
+                    }
+                }
+            }
+        }
+
+        subset.into()
+    }
 }
 
 /// Converts a timestamp to a `String`.
@@ -178,6 +350,37 @@ pub fn timestamp_to_string(timestamp: &DateTime<Utc>) -> String {
     timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
 }
 
+/// The buffers [`Value::shrink_to_fit`] copied, by the address and length of the bytes they were
+/// copied from, so that the other values sharing those bytes reuse the copy.
+type Repacked = HashMap<(usize, usize), Bytes>;
+
+/// Moves `bytes` into a buffer of their exact size.
+///
+/// `Vec::from` takes over the buffer of bytes that don't share it, moving them to its start, so
+/// that it's only reallocated if it's larger than them. Shared and static buffers are copied.
+fn repack(bytes: Bytes, repacked: &mut Repacked) -> Bytes {
+    if bytes.is_empty() {
+        return Bytes::new();
+    }
+
+    let source = (bytes.as_ptr() as usize, bytes.len());
+    if let Some(bytes) = repacked.get(&source) {
+        return bytes.clone();
+    }
+
+    let mut buffer = Vec::from(bytes);
+    buffer.shrink_to_fit();
+    let moved = buffer.as_ptr() as usize != source.0;
+    let bytes = Bytes::from(buffer);
+
+    // Cloning promotes the buffer to a shared one, so only copies are recorded.
+    if moved {
+        repacked.insert(source, bytes.clone());
+    }
+
+    bytes
+}
+
 #[cfg(test)]
 mod test {
     use quickcheck::{QuickCheck, TestResult};
@@ -262,4 +465,292 @@ mod test {
             .max_tests(200)
             .quickcheck(inner as fn(Vec<BorrowedSegment<'static>>) -> TestResult);
     }
+
+    mod path_exists {
+        use crate::{owned_value_path, value};
+
+        #[test]
+        fn null_exists() {
+            let value = value!({"a": null, "b": {"c": null}, "d": [null]});
+
+            assert!(value.path_exists(&owned_value_path!("a")));
+            assert!(value.path_exists(&owned_value_path!("b", "c")));
+            assert!(value.path_exists(&owned_value_path!("d", 0)));
+        }
+
+        #[test]
+        fn missing_does_not_exist() {
+            let value = value!({"a": null, "b": {"c": 1}, "d": [1, 2]});
+
+            assert!(!value.path_exists(&owned_value_path!("x")));
+            assert!(!value.path_exists(&owned_value_path!("b", "x")));
+            assert!(!value.path_exists(&owned_value_path!("d", 2)));
+            assert!(!value.path_exists(&owned_value_path!("d", -3)));
+        }
+
+        #[test]
+        fn through_non_collection() {
+            let value = value!({"a": null, "b": "text", "c": [1]});
+
+            assert!(!value.path_exists(&owned_value_path!("a", "b")));
+            assert!(!value.path_exists(&owned_value_path!("b", 0)));
+            assert!(!value.path_exists(&owned_value_path!("c", "x")));
+        }
+
+        #[test]
+        fn consistent_with_get() {
+            let value = value!({"a": [{"b": null}, {"c": true}], "d": {"e": [[null]]}});
+
+            for path in [
+                owned_value_path!(),
+                owned_value_path!("a", 0, "b"),
+                owned_value_path!("a", -1, "c"),
+                owned_value_path!("a", 1, "b"),
+                owned_value_path!("d", "e", 0, 0),
+                owned_value_path!("d", "e", 0, 1),
+                owned_value_path!(vec!["x", "d"], "e"),
+            ] {
+                assert_eq!(
+                    value.path_exists(&path),
+                    value.get(&path).is_some(),
+                    "{path}"
+                );
+            }
+        }
+    }
+
+    mod subset_by_prefix {
+        use crate::value;
+
+        fn event() -> crate::value::Value {
+            value!({
+                "host": "example.com",
+                "http": {
+                    "method": "GET",
+                    "request": {"path": "/", "headers": {"accept": "*/*"}},
+                    "status": 200,
+                    "tags": ["a", "b"],
+                },
+                "https": true,
+                "message": "hello",
+            })
+        }
+
+        #[test]
+        fn prefixed_fields() {
+            assert_eq!(
+                event().subset_by_prefix("http.", false),
+                value!({
+                    "http.method": "GET",
+                    "http.request.headers.accept": "*/*",
+                    "http.request.path": "/",
+                    "http.status": 200,
+                    "http.tags": ["a", "b"],
+                })
+            );
+        }
+
+        #[test]
+        fn stripped_prefix() {
+            assert_eq!(
+                event().subset_by_prefix("http.", true),
+                value!({
+                    "method": "GET",
+                    "request.headers.accept": "*/*",
+                    "request.path": "/",
+                    "status": 200,
+                    "tags": ["a", "b"],
+                })
+            );
+            assert_eq!(
+                event().subset_by_prefix("http.request.", true),
+                value!({"headers.accept": "*/*", "path": "/"})
+            );
+        }
+
+        #[test]
+        fn string_prefix() {
+            assert_eq!(
+                event().subset_by_prefix("http", true),
+                value!({
+                    ".method": "GET",
+                    ".request.headers.accept": "*/*",
+                    ".request.path": "/",
+                    ".status": 200,
+                    ".tags": ["a", "b"],
+                    "s": true,
+                })
+            );
+            assert_eq!(
+                event().subset_by_prefix("http.st", false),
+                value!({"http.status": 200})
+            );
+        }
+
+        #[test]
+        fn empty_prefix() {
+            let value = value!({"a": {"b": 1}, "c": 2});
+
+            assert_eq!(
+                value.subset_by_prefix("", false),
+                value!({"a.b": 1, "c": 2})
+            );
+        }
+
+        #[test]
+        fn no_match() {
+            assert_eq!(event().subset_by_prefix("dns.", false), value!({}));
+            assert_eq!(
+                value!("http.method").subset_by_prefix("http.", false),
+                value!({})
+            );
+            assert_eq!(
+                value!([{"http": 1}]).subset_by_prefix("", false),
+                value!({})
+            );
+        }
+    }
+
+    mod strip_nulls {
+        use crate::value;
+
+        #[test]
+        fn top_level_only() {
+            let mut value = value!({"a": null, "b": 1, "c": {"d": null}});
+            value.strip_nulls(false);
+
+            assert_eq!(value, value!({"b": 1, "c": {"d": null}}));
+        }
+
+        #[test]
+        fn recursive() {
+            let mut value = value!({
+                "a": null,
+                "b": {"c": null, "d": {"e": null, "f": "g"}},
+                "h": [{"i": null, "j": true}],
+            });
+            value.strip_nulls(true);
+
+            assert_eq!(value, value!({"b": {"d": {"f": "g"}}, "h": [{"j": true}]}));
+        }
+
+        #[test]
+        fn array_nulls_are_kept() {
+            let mut value = value!({"a": [null, 1, null, [null]]});
+            value.strip_nulls(true);
+
+            assert_eq!(value, value!({"a": [null, 1, null, [null]]}));
+        }
+
+        #[test]
+        fn non_containers_are_unchanged() {
+            let mut value = value!(null);
+            value.strip_nulls(true);
+
+            assert_eq!(value, value!(null));
+        }
+    }
+
+    mod shrink_to_fit {
+        use bytes::Bytes;
+
+        use super::*;
+        use crate::value;
+
+        #[test]
+        fn array_capacity() {
+            let mut value = Value::from((0..1000).map(Value::from).collect::<Vec<_>>());
+            for _ in 0..990 {
+                value.as_array_mut().unwrap().pop();
+            }
+            assert!(value.as_array_mut().unwrap().capacity() >= 1000);
+
+            value.shrink_to_fit();
+
+            assert_eq!(value.as_array_mut().unwrap().capacity(), 10);
+            assert_eq!(
+                value,
+                Value::from((0..10).map(Value::from).collect::<Vec<_>>())
+            );
+        }
+
+        #[test]
+        fn nested_array_capacity() {
+            let mut inner = Vec::with_capacity(500);
+            inner.push(value!(true));
+            let mut value = value!({"a": [{"b": inner}]});
+
+            value.shrink_to_fit();
+
+            let inner = value
+                .get_mut("a[0].b")
+                .and_then(Value::as_array_mut)
+                .expect("nested array");
+            assert_eq!(inner.capacity(), 1);
+            assert_eq!(value, value!({"a": [{"b": [true]}]}));
+        }
+
+        /// The size of the buffer behind `bytes`, which `Vec::from` takes over if they don't share
+        /// it.
+        fn capacity(bytes: Bytes) -> usize {
+            Vec::from(bytes).capacity()
+        }
+
+        #[test]
+        fn sliced_bytes_are_repacked() {
+            let buffer = Bytes::from(vec![b'x'; 4096]);
+            let slice = buffer.slice(100..104);
+            drop(buffer);
+            let mut value = Value::Bytes(slice);
+
+            value.shrink_to_fit();
+
+            let Value::Bytes(bytes) = value else {
+                panic!("bytes")
+            };
+            assert_eq!(bytes, "xxxx");
+            assert_eq!(capacity(bytes), 4);
+        }
+
+        #[test]
+        fn exact_bytes_are_kept() {
+            let bytes = Bytes::from(b"foo".to_vec());
+            let ptr = bytes.as_ptr();
+            let mut value = Value::Bytes(bytes);
+
+            value.shrink_to_fit();
+
+            assert_eq!(value.as_bytes().expect("bytes").as_ptr(), ptr);
+        }
+
+        #[test]
+        fn shared_bytes_stay_shared() {
+            let buffer = Bytes::from(vec![b'x'; 4096]);
+            let slice = buffer.slice(..4);
+            let mut value = Value::from(vec![Value::Bytes(slice.clone()), Value::Bytes(slice)]);
+
+            value.shrink_to_fit();
+
+            let array = value.as_array().expect("array");
+            let first = array[0].as_bytes().expect("bytes");
+            let second = array[1].as_bytes().expect("bytes");
+            assert_eq!(first, "xxxx");
+            assert_eq!(first.as_ptr(), second.as_ptr());
+            assert_ne!(first.as_ptr(), buffer.as_ptr());
+        }
+
+        #[test]
+        fn object_keys_are_repacked() {
+            let mut key = String::with_capacity(4096);
+            key.push_str("key");
+            let mut value = Value::from(ObjectMap::from([(key.into(), value!({"nested": 1}))]));
+
+            value.shrink_to_fit();
+
+            let map = value.into_object().expect("object");
+            assert_eq!(map, value!({"key": {"nested": 1}}).into_object().unwrap());
+            let key = Bytes::from(map.into_keys().next().expect("key"));
+            assert_eq!(capacity(key), 3);
+        }
+    }
 }
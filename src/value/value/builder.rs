@@ -0,0 +1,202 @@
+use super::{KeyString, ObjectMap, Value};
+
+/// Builds objects and arrays in Rust code, as a more readable alternative to nesting
+/// [`Value::Object`] and [`Value::Array`] by hand.
+///
+/// ```
+/// use vrl::value::value::ValueBuilder;
+///
+/// let event = ValueBuilder::object()
+///     .field("message", "disk full")
+///     .field("host", ValueBuilder::object().field("name", "a").field("port", 8080))
+///     .field("tags", ValueBuilder::array().element("env:prod").element("tier:db"))
+///     .build();
+/// ```
+///
+/// Builders convert into a [`Value`], so a nested builder can be passed as a field or element
+/// without calling `build` on it.
+#[derive(Debug, Clone, Copy)]
+pub struct ValueBuilder;
+
+impl ValueBuilder {
+    /// Starts building an object with no fields.
+    #[must_use]
+    pub fn object() -> ObjectBuilder {
+        ObjectBuilder::default()
+    }
+
+    /// Starts building an array with no elements.
+    #[must_use]
+    pub fn array() -> ArrayBuilder {
+        ArrayBuilder::default()
+    }
+}
+
+/// Builds a [`Value::Object`], as started by [`ValueBuilder::object`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectBuilder {
+    fields: ObjectMap,
+}
+
+impl ObjectBuilder {
+    /// Sets a field, replacing any value it was already set to.
+    #[must_use]
+    pub fn field(mut self, key: impl Into<KeyString>, value: impl Into<Value>) -> Self {
+        self.fields.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets a field if `value` is `Some`, and leaves it out otherwise, rather than setting it to
+    /// `null`.
+    #[must_use]
+    pub fn field_if_some(self, key: impl Into<KeyString>, value: Option<impl Into<Value>>) -> Self {
+        match value {
+            Some(value) => self.field(key, value),
+            None => self,
+        }
+    }
+
+    /// Sets several fields, in order, so later ones replace earlier ones with the same key.
+    #[must_use]
+    pub fn fields<K, V>(mut self, fields: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<KeyString>,
+        V: Into<Value>,
+    {
+        self.fields.extend(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Returns the object.
+    #[must_use]
+    pub fn build(self) -> Value {
+        Value::Object(self.fields)
+    }
+}
+
+impl From<ObjectBuilder> for Value {
+    fn from(builder: ObjectBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Builds a [`Value::Array`], as started by [`ValueBuilder::array`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArrayBuilder {
+    elements: Vec<Value>,
+}
+
+impl ArrayBuilder {
+    /// Appends an element.
+    #[must_use]
+    pub fn element(mut self, value: impl Into<Value>) -> Self {
+        self.elements.push(value.into());
+        self
+    }
+
+    /// Appends several elements, in order.
+    #[must_use]
+    pub fn elements<V: Into<Value>>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        self.elements.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns the array.
+    #[must_use]
+    pub fn build(self) -> Value {
+        Value::Array(self.elements)
+    }
+}
+
+impl From<ArrayBuilder> for Value {
+    fn from(builder: ArrayBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value;
+
+    use super::*;
+
+    #[test]
+    fn nested_structure() {
+        let built = ValueBuilder::object()
+            .field("message", "disk full")
+            .field(String::from("level"), "warn")
+            .field(
+                "host",
+                ValueBuilder::object()
+                    .field("name", "a")
+                    .field("port", 8080)
+                    .field("load", 0.75),
+            )
+            .field(
+                "tags",
+                ValueBuilder::array().element("env:prod").element("tier:db"),
+            )
+            .field(
+                "checks",
+                ValueBuilder::array()
+                    .element(ValueBuilder::object().field("ok", true))
+                    .element(ValueBuilder::object().field("ok", false).field("error", ())),
+            )
+            .field("empty", ValueBuilder::object())
+            .build();
+
+        let parsed: Value = serde_json::from_str(
+            r#"{
+                "message": "disk full",
+                "level": "warn",
+                "host": {"name": "a", "port": 8080, "load": 0.75},
+                "tags": ["env:prod", "tier:db"],
+                "checks": [{"ok": true}, {"ok": false, "error": null}],
+                "empty": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn later_fields_replace_earlier_ones() {
+        let built = ValueBuilder::object()
+            .fields([("a", 1), ("b", 2)])
+            .field("a", 3)
+            .build();
+
+        assert_eq!(built, value!({a: 3, b: 2}));
+    }
+
+    #[test]
+    fn optional_fields() {
+        let built = ValueBuilder::object()
+            .field_if_some("present", Some("yes"))
+            .field_if_some("absent", None::<&str>)
+            .field("null", None::<&str>)
+            .build();
+
+        assert_eq!(built, value!({present: "yes", null: null}));
+    }
+
+    #[test]
+    fn arrays() {
+        let built = ValueBuilder::array()
+            .element(1)
+            .elements(vec!["a", "b"])
+            .element(ValueBuilder::array())
+            .build();
+
+        assert_eq!(
+            built,
+            Value::Array(vec![value!(1), value!("a"), value!("b"), value!([])])
+        );
+        assert_eq!(ValueBuilder::array().build(), value!([]));
+    }
+}
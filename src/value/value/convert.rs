@@ -290,7 +290,7 @@ impl From<String> for Value {
 
 impl From<KeyString> for Value {
     fn from(string: KeyString) -> Self {
-        Self::Bytes(string.into_bytes().into())
+        Self::Bytes(string.into())
     }
 }
 
@@ -0,0 +1,361 @@
+use std::collections::{btree_map, BTreeMap};
+use std::fmt;
+
+use super::{KeyString, ObjectMap, Value};
+
+const SEPARATOR: char = '.';
+
+/// The error returned when a flat map can't be turned into a nested [`Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlatMapError {
+    /// The key needs a field to be something else than what another key made it: a value
+    /// rather than an object or an array, or an array rather than an object.
+    Conflict {
+        /// The key that conflicts with one of the keys before it.
+        key: String,
+    },
+
+    /// An array has a larger index, but no element at this one.
+    MissingIndex {
+        /// The dotted path of the array.
+        path: String,
+        /// The first index without an element.
+        index: usize,
+    },
+}
+
+impl fmt::Display for FlatMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict { key } => write!(f, "key {key:?} conflicts with another key"),
+            Self::MissingIndex { path, index } => {
+                write!(f, "array {path:?} has no element at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FlatMapError {}
+
+/// A field of the value being built, whose arrays have their elements by index until all keys
+/// have been seen.
+enum Node {
+    Vacant,
+    Leaf(Value),
+    Object(BTreeMap<KeyString, Node>),
+    Array(BTreeMap<usize, Node>),
+}
+
+impl Node {
+    /// Returns the field of this object or array for `segment`, or `None` if this is a value or
+    /// the other kind of container. Fields of the top level object can have numeric keys.
+    fn field(&mut self, segment: &str, top_level: bool) -> Option<&mut Self> {
+        match (self, index(segment)) {
+            (Self::Object(fields), None) => {
+                Some(fields.entry(segment.into()).or_insert(Self::Vacant))
+            }
+            (Self::Object(fields), Some(_)) if top_level => {
+                Some(fields.entry(segment.into()).or_insert(Self::Vacant))
+            }
+            (Self::Array(elements), Some(index)) => {
+                Some(elements.entry(index).or_insert(Self::Vacant))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts the node into a value, using an explicit stack rather than recursion, as keys can
+    /// be nested arbitrarily deep.
+    fn into_value(self) -> Result<Value, FlatMapError> {
+        let mut stack = Vec::new();
+        let mut value = Frame::enter(self, &mut stack);
+
+        loop {
+            let Some(frame) = stack.last_mut() else {
+                return Ok(value.expect("the root is converted"));
+            };
+            if let Some(value) = value.take() {
+                frame.insert(value);
+            }
+
+            value = match frame.next() {
+                Ok(Some(node)) => Frame::enter(node, &mut stack),
+                Ok(None) => stack.pop().map(Frame::finish),
+                Err(index) => {
+                    return Err(FlatMapError::MissingIndex {
+                        path: Frame::path(&stack),
+                        index,
+                    })
+                }
+            };
+        }
+    }
+}
+
+/// An object or array being converted, with the fields converted so far.
+enum Frame {
+    Object {
+        fields: btree_map::IntoIter<KeyString, Node>,
+        /// The key of the field being converted.
+        key: Option<KeyString>,
+        converted: ObjectMap,
+    },
+    Array {
+        elements: btree_map::IntoIter<usize, Node>,
+        converted: Vec<Value>,
+    },
+}
+
+impl Frame {
+    /// Returns the value of a leaf, or pushes the frame of an object or array onto the stack.
+    fn enter(node: Node, stack: &mut Vec<Self>) -> Option<Value> {
+        match node {
+            Node::Vacant => Some(Value::Null),
+            Node::Leaf(value) => Some(value),
+            Node::Object(fields) => {
+                stack.push(Self::Object {
+                    fields: fields.into_iter(),
+                    key: None,
+                    converted: ObjectMap::new(),
+                });
+                None
+            }
+            Node::Array(elements) => {
+                stack.push(Self::Array {
+                    converted: Vec::with_capacity(elements.len()),
+                    elements: elements.into_iter(),
+                });
+                None
+            }
+        }
+    }
+
+    /// Returns the next field to convert, or the first missing index of an array.
+    fn next(&mut self) -> Result<Option<Node>, usize> {
+        match self {
+            Self::Object { fields, key, .. } => Ok(fields.next().map(|(field, node)| {
+                *key = Some(field);
+                node
+            })),
+            Self::Array {
+                elements,
+                converted,
+            } => match elements.next() {
+                Some((index, _)) if index != converted.len() => Err(converted.len()),
+                element => Ok(element.map(|(_, node)| node)),
+            },
+        }
+    }
+
+    /// Stores the converted value of the field returned by `next`.
+    fn insert(&mut self, value: Value) {
+        match self {
+            Self::Object { key, converted, .. } => {
+                converted.insert(key.take().expect("a field was returned"), value);
+            }
+            Self::Array { converted, .. } => converted.push(value),
+        }
+    }
+
+    fn finish(self) -> Value {
+        match self {
+            Self::Object { converted, .. } => converted.into(),
+            Self::Array { converted, .. } => converted.into(),
+        }
+    }
+
+    /// Returns the dotted path of the innermost frame of the stack, made of the fields its outer
+    /// frames are converting. It's only built for errors, as building it for every field would
+    /// take quadratic time for deeply nested keys.
+    fn path(stack: &[Self]) -> String {
+        let segments = stack[..stack.len() - 1].iter().map(|frame| match frame {
+            Self::Object { key, .. } => key.as_deref().unwrap_or_default().to_owned(),
+            Self::Array { converted, .. } => converted.len().to_string(),
+        });
+
+        segments.collect::<Vec<_>>().join(&SEPARATOR.to_string())
+    }
+}
+
+/// Returns the array index a segment stands for, if it's a number.
+fn index(segment: &str) -> Option<usize> {
+    if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    segment.parse().ok()
+}
+
+impl Value {
+    /// Builds an object from flat `(key, value)` pairs, nesting the values of dotted keys as the
+    /// `unflatten` function does.
+    ///
+    /// Unlike `unflatten`, numeric segments are array indexes rather than keys (except at the top
+    /// level), so that `{"a.0": 1, "a.1": 2}` becomes `{"a": [1, 2]}`, and conflicting keys are
+    /// an error rather than the last one winning. Pairs can come in any order, but every index of
+    /// an array must have an element.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key conflicts with another one, as when both `a` and `a.b` are
+    /// given, or `a.0` and `a.b`, or the same key twice. Also returns an error if an array is
+    /// missing an element.
+    pub fn from_flat_map<I, K, V>(iter: I) -> Result<Self, FlatMapError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<Self>,
+    {
+        let mut root = Node::Object(BTreeMap::new());
+
+        for (key, value) in iter {
+            let key = key.as_ref();
+            let conflict = || FlatMapError::Conflict {
+                key: key.to_owned(),
+            };
+
+            let mut segments = key.split(SEPARATOR).peekable();
+            let mut node = &mut root;
+            let mut top_level = true;
+            while let Some(segment) = segments.next() {
+                node = node.field(segment, top_level).ok_or_else(conflict)?;
+                top_level = false;
+
+                match (&node, segments.peek()) {
+                    (Node::Vacant, Some(next)) if index(next).is_some() => {
+                        *node = Node::Array(BTreeMap::new());
+                    }
+                    (Node::Vacant, Some(_)) => *node = Node::Object(BTreeMap::new()),
+                    (Node::Vacant, None) | (_, Some(_)) => {}
+                    (_, None) => return Err(conflict()),
+                }
+            }
+            *node = Node::Leaf(value.into());
+        }
+
+        root.into_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::value;
+
+    use super::*;
+
+    #[test]
+    fn nested_object_and_array() {
+        let flat = HashMap::from([
+            ("host", "localhost"),
+            ("http.method", "GET"),
+            ("http.headers.accept", "*/*"),
+            ("tags.1", "b"),
+            ("tags.0", "a"),
+            ("tags.2.name", "c"),
+        ]);
+
+        assert_eq!(
+            Value::from_flat_map(flat),
+            Ok(value!({
+                host: "localhost",
+                http: {method: "GET", headers: {accept: "*/*"}},
+                tags: ["a", "b", {name: "c"}],
+            }))
+        );
+    }
+
+    #[test]
+    fn nested_arrays() {
+        let flat = vec![("a.0.1", 2), ("a.0.0", 1), ("a.1.0", 3)];
+
+        assert_eq!(Value::from_flat_map(flat), Ok(value!({a: [[1, 2], [3]]})));
+    }
+
+    #[test]
+    fn top_level_numeric_keys() {
+        let flat = vec![("0", true), ("1.a", false)];
+
+        assert_eq!(
+            Value::from_flat_map(flat),
+            Ok(value!({"0": true, "1": {a: false}}))
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let flat: Vec<(String, Value)> = vec![];
+
+        assert_eq!(Value::from_flat_map(flat), Ok(value!({})));
+    }
+
+    #[test]
+    fn value_and_object_conflict() {
+        let conflict = |key: &str| {
+            Err(FlatMapError::Conflict {
+                key: key.to_owned(),
+            })
+        };
+
+        assert_eq!(
+            Value::from_flat_map(vec![("a", 1), ("a.b", 2)]),
+            conflict("a.b")
+        );
+        assert_eq!(
+            Value::from_flat_map(vec![("a.b", 2), ("a", 1)]),
+            conflict("a")
+        );
+        assert_eq!(
+            Value::from_flat_map(vec![("a.b", 1), ("a.b", 2)]),
+            conflict("a.b")
+        );
+    }
+
+    #[test]
+    fn array_and_object_conflict() {
+        let error = Value::from_flat_map(vec![("a.0", 1), ("a.b", 2)]).unwrap_err();
+
+        assert_eq!(
+            error,
+            FlatMapError::Conflict {
+                key: "a.b".to_owned()
+            }
+        );
+        assert_eq!(error.to_string(), r#"key "a.b" conflicts with another key"#);
+        assert!(Value::from_flat_map(vec![("a.b", 2), ("a.0", 1)]).is_err());
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let depth = 100_000;
+        let key = vec!["a"; depth].join(".");
+        let mut value = Value::from_flat_map([(key, 1)]).expect("value");
+
+        // The value is taken apart one level at a time, as comparing or dropping it as a whole
+        // would recurse as deep as it's nested.
+        for _ in 0..depth {
+            value = value
+                .into_object()
+                .and_then(|mut fields| fields.remove("a"))
+                .expect("nested object");
+        }
+        assert_eq!(value, value!(1));
+    }
+
+    #[test]
+    fn missing_index() {
+        let error = Value::from_flat_map(vec![("a.b.0", 1), ("a.b.2", 3)]).unwrap_err();
+
+        assert_eq!(
+            error,
+            FlatMapError::MissingIndex {
+                path: "a.b".to_owned(),
+                index: 1,
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            r#"array "a.b" has no element at index 1"#
+        );
+    }
+}
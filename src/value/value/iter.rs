@@ -1,4 +1,5 @@
-use std::{marker::PhantomData, ops::IndexMut};
+use std::fmt;
+use std::marker::PhantomData;
 
 use super::{KeyString, Value};
 
@@ -43,18 +44,142 @@ impl Value {
 
         ValueIter::new(data, recursive)
     }
+
+    /// Replaces every value in the collection with the result of calling `f` with it, the same
+    /// way the `map_values` function does for a VRL closure.
+    ///
+    /// If `recursive` is `true`, nested collections are first passed to `f` themselves, and then
+    /// recursed into, following the rules of [`Value::into_iter`]. Non-collection types are passed
+    /// to `f` as a single value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if recursion reaches a collection nested more than `max_depth` levels
+    /// deep, counting the value itself as the first level. This includes collections returned by
+    /// `f`, which could otherwise add another level every time they're recursed into.
+    pub fn map_values(
+        self,
+        recursive: bool,
+        max_depth: usize,
+        mut f: impl FnMut(&Self) -> Self,
+    ) -> Result<Self, MaxDepthExceeded> {
+        let mut iter = self.into_iter(recursive).with_max_depth(max_depth);
+
+        for item in iter.by_ref() {
+            let value = match item {
+                IterItem::KeyValue(_, value)
+                | IterItem::IndexValue(_, value)
+                | IterItem::Value(value) => value,
+            };
+
+            *value = f(value);
+        }
+
+        if iter.max_depth_exceeded() {
+            // What wasn't recursed into can be nested arbitrarily deep.
+            Self::from(iter).dismantle();
+            return Err(MaxDepthExceeded { max_depth });
+        }
+
+        Ok(iter.into())
+    }
+
+    /// Returns `true` if collections are nested more than `max_depth` levels deep in this value,
+    /// which is the first level if it's a collection itself.
+    ///
+    /// Nested collections are walked using an explicit stack, so deeply nested values can't
+    /// overflow the call stack.
+    pub(crate) fn exceeds_depth(&self, max_depth: usize) -> bool {
+        let mut stack = vec![(self, 1)];
+
+        while let Some((value, depth)) = stack.pop() {
+            match value {
+                Self::Object(_) | Self::Array(_) if depth > max_depth => return true,
+                Self::Object(object) => {
+                    stack.extend(object.values().map(|value| (value, depth + 1)));
+                }
+                Self::Array(array) => stack.extend(array.iter().map(|value| (value, depth + 1))),
+                _ => {}
+            }
+        }
+
+        false
+    }
+
+    /// Drops this value without recursing into nested collections.
+    ///
+    /// Dropping a value recurses as deep as its collections are nested, so values that are too
+    /// deeply nested to be processed should be dropped this way instead.
+    pub(crate) fn dismantle(self) {
+        let mut stack = vec![self];
+
+        while let Some(value) = stack.pop() {
+            match value {
+                Self::Object(object) => stack.extend(object.into_values()),
+                Self::Array(array) => stack.extend(array),
+                _ => {}
+            }
+        }
+    }
+
+    /// Keeps the fields of an object, or the elements of an array, for which `predicate` returns
+    /// `true`, in their original order, the same way the `filter` function does for a VRL
+    /// closure.
+    ///
+    /// Nested collections aren't recursed into, and non-collection types are returned as-is.
+    #[must_use]
+    pub fn filter(self, mut predicate: impl FnMut(&Self) -> bool) -> Self {
+        match self {
+            Self::Object(mut object) => {
+                object.retain(|_, value| predicate(value));
+                Self::Object(object)
+            }
+            Self::Array(mut array) => {
+                array.retain(|value| predicate(value));
+                Self::Array(array)
+            }
+            value => value,
+        }
+    }
+}
+
+/// The error returned by [`Value::map_values`] when a value is nested too deeply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxDepthExceeded {
+    /// The number of levels of nested collections that was exceeded.
+    pub max_depth: usize,
+}
+
+impl fmt::Display for MaxDepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "maximum depth of {} exceeded", self.max_depth)
+    }
 }
 
+impl std::error::Error for MaxDepthExceeded {}
+
 /// An [`Iterator`] over a [`Value`].
+///
+/// Nested collections are iterated using an explicit stack instead of recursion, so that deeply
+/// nested values can't overflow the call stack.
 pub struct ValueIter<'a> {
-    data: IterData,
+    /// The collections currently being iterated, with the outermost one at the bottom.
+    ///
+    /// This is never empty.
+    stack: Vec<Frame>,
     recursive: bool,
-    index: usize,
-    recursive_iter: Option<Box<Self>>,
+    max_depth: usize,
+    max_depth_exceeded: bool,
     must_prepare_recursion: bool,
     phantom: PhantomData<&'a mut ()>,
 }
 
+/// A single collection being iterated, and the index of the next element to return.
+struct Frame {
+    data: IterData,
+    index: usize,
+}
+
 /// The [`Iterator::Item`] returned by the [`ValueIter`] iterator.
 #[derive(Debug, PartialEq, Eq)]
 #[allow(clippy::enum_variant_names)]
@@ -79,18 +204,58 @@ enum IterData {
     Array(Vec<Value>),
 }
 
+impl IterData {
+    /// Get the value at `index`, if this is a collection with that many elements.
+    fn get_mut(&mut self, index: usize) -> Option<&mut Value> {
+        match self {
+            Self::Object(object) => object.get_mut(index).map(|(_, value)| value),
+            Self::Array(array) => array.get_mut(index),
+            Self::Value(_) => None,
+        }
+    }
+}
+
 impl<'a> ValueIter<'a> {
     /// Create a new iterator over the relevant [`IterData`].
     fn new(data: IterData, recursive: bool) -> Self {
         Self {
-            data,
+            stack: vec![Frame { data, index: 0 }],
             recursive,
-            index: 0,
-            recursive_iter: None,
+            max_depth: usize::MAX,
+            max_depth_exceeded: false,
             must_prepare_recursion: false,
             phantom: PhantomData,
         }
     }
+
+    /// Limit how many levels of nested collections a recursive iterator descends into.
+    ///
+    /// The collection being iterated is the first level. Once a collection nested deeper than
+    /// `max_depth` is encountered, the iterator stops, and [`ValueIter::max_depth_exceeded`]
+    /// returns `true`.
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Returns `true` if the iterator stopped early, because the value is nested deeper than the
+    /// configured maximum depth.
+    #[must_use]
+    pub fn max_depth_exceeded(&self) -> bool {
+        self.max_depth_exceeded
+    }
+
+    /// Finish iterating the innermost nested collection, and store it (including any mutations
+    /// done to its elements) back into its parent collection.
+    fn pop_frame(&mut self) {
+        let frame = self.stack.pop().expect("stack is never empty");
+        let parent = self.stack.last_mut().expect("nested frame has a parent");
+
+        if let Some(value) = parent.data.get_mut(parent.index - 1) {
+            *value = frame.data.into();
+        }
+    }
 }
 
 impl<'a> Iterator for ValueIter<'a> {
@@ -98,121 +263,119 @@ impl<'a> Iterator for ValueIter<'a> {
 
     #[allow(clippy::deref_addrof)]
     fn next(&mut self) -> Option<Self::Item> {
+        if self.max_depth_exceeded {
+            return None;
+        }
+
         // If this returns true, it means on the last iteration cycle, we've
         // returned a collection value-type, and the caller asked to recurse
         // into that collection.
         //
-        // We're going to prepare recursion by embedding a new recursive
-        // iterator into `ValueIter`. This preparation is delayed to this new
-        // cycle, to allow the caller to first mutate the collection we're going
-        // to recurse into.
+        // We're going to prepare recursion by pushing the collection onto the
+        // stack. This preparation is delayed to this new cycle, to allow the
+        // caller to first mutate the collection we're going to recurse into.
         if self.must_prepare_recursion {
             self.must_prepare_recursion = false;
 
-            // Get the relevant collection `Value` type we want to iterate over.
-            let value = match &mut self.data {
-                IterData::Object(object) => &mut object.get_mut(self.index - 1)?.1,
-                IterData::Array(array) => array.get_mut(self.index - 1)?,
-                IterData::Value(_) => unreachable!("cannot recurse into non-container type"),
-            };
+            let depth = self.stack.len();
+            let frame = self.stack.last_mut().expect("stack is never empty");
+            let value = frame.data.get_mut(frame.index - 1)?;
 
-            // Create a new `IterData` type we're going to embed recursively
-            // into this iterator, to allow iterating over.
-            let data = match value {
-                Value::Object(object) => {
-                    Some(IterData::Object(object.clone().into_iter().collect()))
+            // It's possible the [`Value`] we're trying to iterate over is
+            // a non-collection type. This happens if the caller changed the
+            // value type of the collection to a non-collection type in the
+            // last iteration cycle.
+            //
+            // That is, given this:
+            //
+            // ```
+            // { "foo": [true] }
+            // ```
+            //
+            // If they iterate over `(foo, [true])` and change `[true]` to
+            // a non-collection type (e.g. `null`), then we abort the recursion.
+            if value.is_object() || value.is_array() {
+                if depth >= self.max_depth {
+                    self.max_depth_exceeded = true;
+                    return None;
                 }
-                Value::Array(array) => Some(IterData::Array(array.clone())),
 
-                // It's possible the [`Value`] we're trying to iterate over is
-                // a non-collection type. This happens if the caller changed the
-                // value type of the collection to a non-collection type in the
-                // last iteration cycle.
-                //
-                // That is, given this:
-                //
-                // ```
-                // { "foo": [true] }
-                // ```
-                //
-                // If they iterate over `(foo, [true])` and change `[true]` to
-                // a non-collection type (e.g. `null`), then this branch gets
-                // hit, and we abort the recursion.
-                _ => None,
-            };
+                // The collection is moved out of its parent while it's being
+                // iterated, and moved back once it's exhausted.
+                let data = match std::mem::replace(value, Value::Null) {
+                    Value::Object(object) => IterData::Object(object.into_iter().collect()),
+                    Value::Array(array) => IterData::Array(array),
+                    _ => unreachable!("value is a collection"),
+                };
 
-            self.recursive_iter = data.map(|data| Box::new(Self::new(data, self.recursive)));
+                self.stack.push(Frame { data, index: 0 });
+            }
         }
 
-        // If we have a recursive iterator stored, it means we're asked to
-        // continue iterating that iterator until it is exhausted.
-        if let Some(iter) = &mut self.recursive_iter {
-            match iter.next() {
-                // Keep returning recursive items until the iterator is
-                // exhausted.
-                item @ Some(..) => return item,
-
-                // Now that we're done recursing, we need to update the relevant `Value`
-                // collection type stored in this iterator, with the updated
-                // inner elements that the recursive iterator might have
-                // mutated.
-                None => {
-                    let value = match &mut self.data {
-                        IterData::Object(object) => &mut object.index_mut(self.index - 1).1,
-                        IterData::Array(array) => array.index_mut(self.index - 1),
-                        IterData::Value(_) => {
-                            unreachable!("cannot recurse into non-container type")
+        loop {
+            let recursive = self.recursive;
+            let depth = self.stack.len();
+            let frame = self.stack.last_mut().expect("stack is never empty");
+
+            let item = match &mut frame.data {
+                // An `IterData::Object` variant indicates the caller requested
+                // to recursively iterate the value type, and the value itself is an
+                // actual object.
+                //
+                // If no recursion was requested, the object `Value` type is stored
+                // in `IterData::Value` instead.
+                IterData::Object(object) => match object.get_mut(frame.index) {
+                    Some((key, value)) => {
+                        if value.is_object() || value.is_array() {
+                            // We *only* want to recurse deeper into nested
+                            // collections, if requested by the caller.
+                            self.must_prepare_recursion = recursive;
                         }
-                    };
 
-                    *value = (*self.recursive_iter.take().unwrap()).into();
-                }
-            }
-        };
+                        // SAFETY:
+                        //
+                        // - We borrow each item in the collection *exactly once*.
+                        // - We take a `&mut self`, so we also only borrow the
+                        //   collection itself exactly once.
+                        let key_mut = unsafe { &mut *std::ptr::addr_of_mut!(*key) };
+                        let value_mut = unsafe { &mut *std::ptr::addr_of_mut!(*value) };
 
-        // If we got here, we are either done with recursively iterating the
-        // active value, or we haven't started recursively iterating yet (which
-        // would be the case if we haven't encountered a collection `Value` type
-        // yet).
-        let item = match &mut self.data {
-            // An `IterData::Object` variant indicates the caller requested
-            // to recursively iterate the value type, and the value itself is an
-            // actual object.
-            //
-            // If no recursion was requested, the object `Value` type is stored
-            // in `IterData::Value` instead.
-            IterData::Object(object) => match object.get_mut(self.index) {
-                Some((key, value)) => {
-                    if value.is_object() || value.is_array() {
-                        // We *only* want to recurse deeper into nested
-                        // collections, if requested by the caller.
-                        self.must_prepare_recursion = self.recursive;
+                        Some(IterItem::KeyValue(key_mut, value_mut))
                     }
 
-                    // SAFETY:
-                    //
-                    // - We borrow each item in the collection *exactly once*.
-                    // - We take a `&mut self`, so we also only borrow the
-                    //   collection itself exactly once.
-                    let key_mut = unsafe { &mut *std::ptr::addr_of_mut!(*key) };
-                    let value_mut = unsafe { &mut *std::ptr::addr_of_mut!(*value) };
+                    None => None,
+                },
 
-                    IterItem::KeyValue(key_mut, value_mut)
-                }
+                // The same principle as above applies here, except for array
+                // collection types.
+                IterData::Array(array) => match array.get_mut(frame.index) {
+                    Some(value) => {
+                        if value.is_object() || value.is_array() {
+                            // We *only* want to recurse deeper into nested
+                            // collections, if requested by the caller.
+                            self.must_prepare_recursion = recursive;
+                        }
 
-                None => return None,
-            },
+                        // SAFETY:
+                        //
+                        // - We borrow each item in the collection *exactly once*.
+                        // - We take a `&mut self`, so we also only borrow the
+                        //   collection itself exactly once.
+                        let value_mut = unsafe { &mut *std::ptr::addr_of_mut!(*value) };
 
-            // The same principle as above applies here, except for array
-            // collection types.
-            IterData::Array(array) => match array.get_mut(self.index) {
-                Some(value) => {
-                    if value.is_object() || value.is_array() {
-                        // We *only* want to recurse deeper into nested
-                        // collections, if requested by the caller.
-                        self.must_prepare_recursion = self.recursive;
+                        Some(IterItem::IndexValue(frame.index, value_mut))
                     }
 
+                    None => None,
+                },
+
+                // The `IterData::Value` variant indicates we want to return
+                // a non-recursive value. This could also be a collection type, if
+                // the caller has not requested recursive behavior of the iterator.
+                //
+                // We check if `frame.index == 0` as a means to ensure we ever only
+                // return this value once.
+                IterData::Value(value) if frame.index == 0 => {
                     // SAFETY:
                     //
                     // - We borrow each item in the collection *exactly once*.
@@ -220,43 +383,36 @@ impl<'a> Iterator for ValueIter<'a> {
                     //   collection itself exactly once.
                     let value_mut = unsafe { &mut *std::ptr::addr_of_mut!(*value) };
 
-                    IterItem::IndexValue(self.index, value_mut)
+                    Some(IterItem::Value(value_mut))
                 }
 
-                None => return None,
-            },
+                // This case means `frame.index` was non-zero, and no recursion was
+                // requested, so we're done "iterating" over the single-value type.
+                IterData::Value(_) => return None,
+            };
 
-            // The `IterData::Value` variant indicates we want to return
-            // a non-recursive value. This could also be a collection type, if
-            // the caller has not requested recursive behavior of the iterator.
-            //
-            // We check if `self.index == 0` as a means to ensure we ever only
-            // return this value once.
-            IterData::Value(value) if self.index == 0 => {
-                // SAFETY:
-                //
-                // - We borrow each item in the collection *exactly once*.
-                // - We take a `&mut self`, so we also only borrow the
-                //   collection itself exactly once.
-                let value_mut = unsafe { &mut *std::ptr::addr_of_mut!(*value) };
+            match item {
+                Some(item) => {
+                    frame.index += 1;
+                    return Some(item);
+                }
 
-                IterItem::Value(value_mut)
+                // Once a nested collection is exhausted, continue with the
+                // collection it is part of.
+                None if depth > 1 => self.pop_frame(),
+                None => return None,
             }
-
-            // This case means `self.index` was non-zero, and no recursion was
-            // requested, so we're done "iterating" over the single-value type.
-            IterData::Value(_) => return None,
-        };
-
-        self.index += 1;
-
-        Some(item)
+        }
     }
 }
 
 impl<'a> From<ValueIter<'a>> for Value {
-    fn from(iter: ValueIter<'a>) -> Self {
-        iter.data.into()
+    fn from(mut iter: ValueIter<'a>) -> Self {
+        while iter.stack.len() > 1 {
+            iter.pop_frame();
+        }
+
+        iter.stack.pop().expect("stack is never empty").data.into()
     }
 }
 
@@ -458,4 +614,158 @@ mod tests {
         assert_eq!(values, vec![true.into()]);
         assert!(changed);
     }
+    #[test]
+    fn test_max_depth() {
+        let data: Value = BTreeMap::from([(
+            "foo".into(),
+            vec![Value::from(BTreeMap::from([("bar".into(), true.into())]))].into(),
+        )])
+        .into();
+
+        let mut iter = data.clone().into_iter(true).with_max_depth(3);
+        assert_eq!(iter.by_ref().count(), 3);
+        assert!(!iter.max_depth_exceeded());
+        assert_eq!(Value::from(iter), data);
+
+        let mut iter = data.clone().into_iter(true).with_max_depth(2);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert!(iter.max_depth_exceeded());
+        assert_eq!(iter.next(), None);
+
+        // The value is left intact, even though iteration stopped early.
+        assert_eq!(Value::from(iter), data);
+    }
+
+    #[test]
+    fn test_map_values() {
+        let data = Value::Array(vec![1.into(), "foo".into(), 3.into()]);
+
+        let doubled = data
+            .map_values(false, 1, |value| match value {
+                Value::Integer(int) => Value::Integer(int * 2),
+                value => value.clone(),
+            })
+            .unwrap();
+
+        assert_eq!(
+            doubled,
+            Value::Array(vec![2.into(), "foo".into(), 6.into()])
+        );
+    }
+
+    #[test]
+    fn test_map_values_recursive() {
+        let data: Value = BTreeMap::from([
+            ("foo".into(), vec![Value::from(1), Value::from(2)].into()),
+            ("bar".into(), 3.into()),
+        ])
+        .into();
+
+        let mut visited = vec![];
+        let doubled = data
+            .map_values(true, 2, |value| {
+                visited.push(value.clone());
+                match value {
+                    Value::Integer(int) => Value::Integer(int * 2),
+                    value => value.clone(),
+                }
+            })
+            .unwrap();
+
+        assert_eq!(
+            doubled,
+            BTreeMap::from([
+                ("foo".into(), vec![Value::from(2), Value::from(4)].into()),
+                ("bar".into(), 6.into()),
+            ])
+            .into()
+        );
+        assert_eq!(
+            visited,
+            vec![
+                3.into(),
+                vec![Value::from(1), Value::from(2)].into(),
+                1.into(),
+                2.into()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_values_max_depth() {
+        let data = Value::Array(vec![vec![Value::from(1)].into()]);
+
+        assert_eq!(
+            data.clone().map_values(true, 1, Clone::clone),
+            Err(MaxDepthExceeded { max_depth: 1 })
+        );
+        assert_eq!(data.clone().map_values(true, 2, Clone::clone), Ok(data));
+
+        // Every nested collection is wrapped in another one, which is then recursed into.
+        let wrapped = Value::Array(vec![Value::Array(vec![])])
+            .map_values(true, 128, |value| Value::Array(vec![value.clone()]));
+        assert_eq!(
+            wrapped.map_err(|err| err.to_string()),
+            Err("maximum depth of 128 exceeded".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let data: Value = BTreeMap::from([
+            ("foo".into(), Value::Null),
+            ("bar".into(), true.into()),
+            (
+                "baz".into(),
+                BTreeMap::from([("qux".into(), Value::Null)]).into(),
+            ),
+        ])
+        .into();
+
+        assert_eq!(
+            data.filter(|value| !value.is_null()),
+            BTreeMap::from([
+                ("bar".into(), true.into()),
+                (
+                    "baz".into(),
+                    BTreeMap::from([("qux".into(), Value::Null)]).into()
+                ),
+            ])
+            .into()
+        );
+
+        let data = Value::Array(vec![Value::Null, 1.into(), Value::Null, 2.into()]);
+        assert_eq!(
+            data.filter(|value| !value.is_null()),
+            Value::Array(vec![1.into(), 2.into()])
+        );
+
+        assert_eq!(Value::Null.filter(|_| false), Value::Null);
+    }
+
+    #[test]
+    fn test_exceeds_depth() {
+        let data = Value::Array(vec![
+            1.into(),
+            BTreeMap::from([("foo".into(), 2.into())]).into(),
+        ]);
+
+        assert!(!data.exceeds_depth(2));
+        assert!(data.exceeds_depth(1));
+        assert!(!Value::Null.exceeds_depth(0));
+        assert!(Value::Array(vec![]).exceeds_depth(0));
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        let depth = 10_000;
+        let data = (0..depth).fold(Value::Null, |value, _| Value::Array(vec![value]));
+
+        let mut iter = data.into_iter(true);
+        assert_eq!(iter.by_ref().count(), depth);
+        assert!(!iter.max_depth_exceeded());
+
+        // Dropping a value this deep recurses, so take it apart iteratively instead.
+        Value::from(iter).dismantle();
+    }
 }
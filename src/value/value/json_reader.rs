@@ -0,0 +1,170 @@
+use std::fmt;
+use std::io::{self, BufReader, Read};
+
+use super::Value;
+
+/// The error returned when a [`Value`] can't be read from a JSON source.
+#[derive(Debug)]
+pub enum JsonReaderError {
+    /// The source is longer than the size limit.
+    TooLarge {
+        /// The size limit, in bytes.
+        max_size: usize,
+    },
+
+    /// The source isn't valid JSON, or couldn't be read.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for JsonReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { max_size } => write!(f, "JSON is larger than {max_size} bytes"),
+            Self::Json(error) => write!(f, "unable to read JSON: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for JsonReaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::TooLarge { .. } => None,
+            Self::Json(error) => Some(error),
+        }
+    }
+}
+
+impl Value {
+    /// Parses a JSON document from `reader`, such as a file or a network body, without reading
+    /// all of it into memory first.
+    ///
+    /// Reading stops as soon as more than `max_size` bytes have been read, which bounds the
+    /// memory used by the parsed value. `reader` is buffered here, so it doesn't need to be a
+    /// `BufReader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source is longer than `max_size` bytes, including whitespace after
+    /// the document, if it isn't a single valid JSON document, or if reading from it fails.
+    pub fn from_json_reader<R: Read>(reader: R, max_size: usize) -> Result<Self, JsonReaderError> {
+        let mut limited = LimitedReader {
+            inner: reader,
+            remaining: max_size,
+            exceeded: false,
+        };
+
+        serde_json::from_reader(BufReader::new(&mut limited)).map_err(|error| {
+            if limited.exceeded {
+                JsonReaderError::TooLarge { max_size }
+            } else {
+                JsonReaderError::Json(error)
+            }
+        })
+    }
+}
+
+/// Reads at most `remaining` bytes, and fails once the inner reader has more than that.
+struct LimitedReader<R> {
+    inner: R,
+    remaining: usize,
+    exceeded: bool,
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.remaining == 0 {
+            // Only an end of input at the limit is fine, so check for one more byte.
+            if self.inner.read(&mut [0])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(io::Error::new(io::ErrorKind::Other, "size limit exceeded"));
+        }
+
+        let len = buf.len().min(self.remaining);
+        let read = self.inner.read(&mut buf[..len])?;
+        self.remaining -= read;
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::value;
+
+    use super::*;
+
+    #[test]
+    fn parses_from_cursor() {
+        let json = r#"{"message": "disk full", "host": {"name": "a", "port": 8080}, "tags": ["env:prod"], "load": 0.5, "ok": false, "error": null}"#;
+
+        let parsed = Value::from_json_reader(Cursor::new(json), json.len()).unwrap();
+
+        assert_eq!(
+            parsed,
+            value!({
+                message: "disk full",
+                host: {name: "a", port: 8080},
+                tags: ["env:prod"],
+                load: 0.5,
+                ok: false,
+                error: null,
+            })
+        );
+    }
+
+    #[test]
+    fn oversized_stream() {
+        let json = format!("[{}1]", "1, ".repeat(1000));
+
+        let error = Value::from_json_reader(Cursor::new(&json), json.len() - 1).unwrap_err();
+
+        assert!(
+            matches!(error, JsonReaderError::TooLarge { max_size } if max_size == json.len() - 1)
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("JSON is larger than {} bytes", json.len() - 1)
+        );
+    }
+
+    #[test]
+    fn trailing_whitespace_counts_towards_limit() {
+        let error = Value::from_json_reader(Cursor::new("1    "), 3).unwrap_err();
+
+        assert!(matches!(error, JsonReaderError::TooLarge { max_size: 3 }));
+    }
+
+    #[test]
+    fn endless_stream() {
+        let reader = b"[".chain(io::repeat(b' '));
+
+        let error = Value::from_json_reader(reader, 1024 * 1024).unwrap_err();
+
+        assert!(matches!(error, JsonReaderError::TooLarge { .. }));
+    }
+
+    #[test]
+    fn invalid_json() {
+        let error = Value::from_json_reader(Cursor::new(r#"{"a": }"#), 1024).unwrap_err();
+
+        assert!(matches!(error, JsonReaderError::Json(_)));
+        assert_eq!(
+            error.to_string(),
+            "unable to read JSON: expected value at line 1 column 7"
+        );
+    }
+
+    #[test]
+    fn trailing_data() {
+        let error = Value::from_json_reader(Cursor::new("{} {}"), 1024).unwrap_err();
+
+        assert!(matches!(error, JsonReaderError::Json(_)));
+    }
+}
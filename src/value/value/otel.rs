@@ -0,0 +1,213 @@
+use super::{timestamp_to_string, ObjectMap, Value};
+
+const SEPARATOR: char = '.';
+
+/// The kind of an OpenTelemetry attribute value, as named in the `type` of the values
+/// [`Value::to_otel_attributes`] builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtelKind {
+    String,
+    Bytes,
+    Bool,
+    Int,
+    Double,
+}
+
+impl OtelKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Bytes => "bytes",
+            Self::Bool => "bool",
+            Self::Int => "int",
+            Self::Double => "double",
+        }
+    }
+}
+
+/// Returns the kind and value of an OpenTelemetry primitive for a value, or `None` for null and
+/// collections, which aren't primitives.
+fn primitive(value: &Value) -> Option<(OtelKind, Value)> {
+    let primitive = match value {
+        Value::Bytes(bytes) if std::str::from_utf8(bytes).is_ok() => {
+            (OtelKind::String, value.clone())
+        }
+        Value::Bytes(_) => (OtelKind::Bytes, value.clone()),
+        Value::Boolean(_) => (OtelKind::Bool, value.clone()),
+        Value::Integer(_) => (OtelKind::Int, value.clone()),
+        Value::Float(_) => (OtelKind::Double, value.clone()),
+        Value::Timestamp(timestamp) => (OtelKind::String, timestamp_to_string(timestamp).into()),
+        Value::Regex(regex) => (OtelKind::String, regex.as_str().into()),
+        Value::Null | Value::Object(_) | Value::Array(_) => return None,
+    };
+    Some(primitive)
+}
+
+fn typed(kind: &str, value: Value) -> Value {
+    ObjectMap::from([("type".into(), kind.into()), ("value".into(), value)]).into()
+}
+
+/// Returns the elements of an array as OpenTelemetry primitives, if they all have the same kind.
+fn homogeneous(array: &[Value]) -> Option<Vec<Value>> {
+    let mut array_kind = None;
+    array
+        .iter()
+        .map(|element| {
+            let (kind, value) = primitive(element)?;
+            if *array_kind.get_or_insert(kind) != kind {
+                return None;
+            }
+            Some(typed(kind.as_str(), value))
+        })
+        .collect()
+}
+
+fn push_attributes(key: &str, value: &Value, attributes: &mut Vec<Value>) {
+    let nested_key = |field: &str| {
+        if key.is_empty() {
+            field.to_owned()
+        } else {
+            format!("{key}{SEPARATOR}{field}")
+        }
+    };
+
+    let value = match value {
+        Value::Null => return,
+        Value::Object(object) => {
+            for (field, value) in object {
+                push_attributes(&nested_key(field), value, attributes);
+            }
+            return;
+        }
+        Value::Array(array) => match homogeneous(array) {
+            Some(elements) => typed("array", elements.into()),
+            None => {
+                for (index, value) in array.iter().enumerate() {
+                    push_attributes(&nested_key(&index.to_string()), value, attributes);
+                }
+                return;
+            }
+        },
+        value => {
+            let (kind, value) = primitive(value).expect("value is a primitive");
+            typed(kind.as_str(), value)
+        }
+    };
+
+    attributes.push(ObjectMap::from([("key".into(), key.into()), ("value".into(), value)]).into());
+}
+
+impl Value {
+    /// Flattens an object into a list of OpenTelemetry attributes, each an object of the form
+    /// `{"key": "http.method", "value": {"type": "string", "value": "GET"}}`, sorted by key.
+    ///
+    /// The keys of nested objects are joined with `.`. Strings have the `string` type, or
+    /// `bytes` if they aren't valid UTF-8, and integers, floats and booleans have the `int`,
+    /// `double` and `bool` types. OpenTelemetry has no timestamps or regexes, so these become
+    /// RFC 3339 strings and patterns.
+    ///
+    /// Arrays whose elements are all primitives of the same type have the `array` type, with
+    /// the typed elements as the value. Other arrays, which OpenTelemetry can't represent, are
+    /// flattened like objects, using the index of each element as its key.
+    ///
+    /// Null values have no attribute, and neither do values that aren't objects.
+    #[must_use]
+    pub fn to_otel_attributes(&self) -> Self {
+        let mut attributes = Vec::new();
+        if let Self::Object(_) = self {
+            push_attributes("", self, &mut attributes);
+        }
+        attributes.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::value;
+
+    #[test]
+    fn nested_object() {
+        let value = value!({
+            service: {name: "checkout", version: 2},
+            http: {method: "GET", status_code: 200, ok: true, duration: 1.5},
+            empty: {},
+            missing: null,
+        });
+
+        assert_eq!(
+            value.to_otel_attributes(),
+            value!([
+                {key: "http.duration", value: {type: "double", value: 1.5}},
+                {key: "http.method", value: {type: "string", value: "GET"}},
+                {key: "http.ok", value: {type: "bool", value: true}},
+                {key: "http.status_code", value: {type: "int", value: 200}},
+                {key: "service.name", value: {type: "string", value: "checkout"}},
+                {key: "service.version", value: {type: "int", value: 2}},
+            ])
+        );
+    }
+
+    #[test]
+    fn homogeneous_array() {
+        let value = value!({tags: ["a", "b"], ports: [80, 443], none: []});
+
+        assert_eq!(
+            value.to_otel_attributes(),
+            value!([
+                {key: "none", value: {type: "array", value: []}},
+                {key: "ports", value: {type: "array", value: [
+                    {type: "int", value: 80},
+                    {type: "int", value: 443},
+                ]}},
+                {key: "tags", value: {type: "array", value: [
+                    {type: "string", value: "a"},
+                    {type: "string", value: "b"},
+                ]}},
+            ])
+        );
+    }
+
+    #[test]
+    fn mixed_array() {
+        let value = value!({values: ["a", 1, null, {b: [true, false]}]});
+
+        assert_eq!(
+            value.to_otel_attributes(),
+            value!([
+                {key: "values.0", value: {type: "string", value: "a"}},
+                {key: "values.1", value: {type: "int", value: 1}},
+                {key: "values.3.b", value: {type: "array", value: [
+                    {type: "bool", value: true},
+                    {type: "bool", value: false},
+                ]}},
+            ])
+        );
+    }
+
+    #[test]
+    fn strings_bytes_and_timestamps() {
+        let timestamp = Utc.with_ymd_and_hms(2023, 5, 1, 12, 0, 0).unwrap();
+        let value = value!({
+            text: "héllo",
+            binary: (bytes::Bytes::from_static(b"\xff\x00")),
+            at: (timestamp),
+        });
+
+        assert_eq!(
+            value.to_otel_attributes(),
+            value!([
+                {key: "at", value: {type: "string", value: "2023-05-01T12:00:00Z"}},
+                {key: "binary", value: {type: "bytes", value: (bytes::Bytes::from_static(b"\xff\x00"))}},
+                {key: "text", value: {type: "string", value: "héllo"}},
+            ])
+        );
+    }
+
+    #[test]
+    fn not_an_object() {
+        assert_eq!(value!("foo").to_otel_attributes(), value!([]));
+        assert_eq!(value!([1, 2]).to_otel_attributes(), value!([]));
+    }
+}
@@ -0,0 +1,294 @@
+-- The Lua counterpart of `Value::partition_hash`, for Lua 5.3 and later.
+--
+-- Loading this chunk returns a table with:
+--
+-- * `partition_hash(value, buckets)`, the bucket in `0` to `buckets - 1` for a Lua value, which
+--   is what `Value::partition_hash` returns for the same value.
+-- * `encode(value)`, the canonical JSON of a Lua value, which is what `partition_hash` hashes.
+-- * `murmur3_32(data, seed)`, the 32-bit x86 variant of MurmurHash3 of a string.
+-- * `null` and `array(table)`, to write values that plain Lua values can't express.
+--
+-- Lua values map to values as follows:
+--
+-- * `nil` and `null` are null, and booleans are booleans.
+-- * Integers are integers, and floats are floats, even when they have no fraction, so `1.0` is
+--   not the same value as `1`. Non-finite floats are null, as in JSON.
+-- * Strings are strings. Invalid UTF-8 sequences are replaced with `U+FFFD`, as in Rust.
+-- * Tables whose keys are exactly `1` to `#table` are arrays, and other tables are objects, whose
+--   keys must be strings. The empty table is an empty object, and `array(table)` marks a table,
+--   empty or not, as an array. Arrays can't hold `nil`, so use `null` for null elements.
+--
+-- Timestamps and regexes have no Lua counterpart: pass the strings they serialize to, an
+-- RFC 3339 timestamp such as "2021-02-10T23:32:00Z" or the pattern of the regex.
+
+local null = setmetatable({}, {
+  __tostring = function()
+    return "null"
+  end,
+})
+
+local array_marker = {}
+
+local function array(t)
+  return setmetatable(t, array_marker)
+end
+
+-- The byte ranges of the second byte of a UTF-8 sequence, by its first byte. The remaining bytes
+-- are always in `0x80` to `0xbf`.
+local function second_byte_range(b)
+  if b >= 0xc2 and b <= 0xdf then
+    return 1, 0x80, 0xbf
+  elseif b == 0xe0 then
+    return 2, 0xa0, 0xbf
+  elseif (b >= 0xe1 and b <= 0xec) or b == 0xee or b == 0xef then
+    return 2, 0x80, 0xbf
+  elseif b == 0xed then
+    return 2, 0x80, 0x9f
+  elseif b == 0xf0 then
+    return 3, 0x90, 0xbf
+  elseif b >= 0xf1 and b <= 0xf3 then
+    return 3, 0x80, 0xbf
+  elseif b == 0xf4 then
+    return 3, 0x80, 0x8f
+  end
+end
+
+-- Replaces each maximal invalid subpart with `U+FFFD`, as Rust's `String::from_utf8_lossy` does.
+local function utf8_lossy(s)
+  if utf8.len(s) and not s:find("[\xed\xf4-\xff]") then
+    -- Valid even by the rules of Lua 5.3, which accepts surrogates and code points beyond
+    -- `U+10FFFF`, and has none of the lead bytes those need.
+    return s
+  end
+
+  local parts = {}
+  local i, len = 1, #s
+  while i <= len do
+    local b = s:byte(i)
+    local continuations, low, high = second_byte_range(b)
+    if b < 0x80 then
+      parts[#parts + 1] = string.char(b)
+      i = i + 1
+    elseif not continuations then
+      parts[#parts + 1] = "\u{fffd}"
+      i = i + 1
+    else
+      -- The sequence ends at the first byte that can't continue it.
+      local j = i + 1
+      while j <= i + continuations do
+        local c = s:byte(j)
+        if not c or c < low or c > high then
+          break
+        end
+        j = j + 1
+        low, high = 0x80, 0xbf
+      end
+
+      if j > i + continuations then
+        parts[#parts + 1] = s:sub(i, j - 1)
+      else
+        parts[#parts + 1] = "\u{fffd}"
+      end
+      i = j
+    end
+  end
+
+  return table.concat(parts)
+end
+
+local escapes = {
+  ['"'] = '\\"',
+  ["\\"] = "\\\\",
+  ["\b"] = "\\b",
+  ["\f"] = "\\f",
+  ["\n"] = "\\n",
+  ["\r"] = "\\r",
+  ["\t"] = "\\t",
+}
+
+local function encode_string(s)
+  return '"'
+    .. utf8_lossy(s):gsub('[\0-\31"\\]', function(c)
+      return escapes[c] or string.format("\\u%04x", c:byte())
+    end)
+    .. '"'
+end
+
+-- Writes a float in the shortest form that round trips, in the notation Rust's `ryu` uses.
+local function encode_float(x)
+  if x ~= x or x == math.huge or x == -math.huge then
+    return "null"
+  end
+
+  local sign = (x < 0 or 1 / x < 0) and "-" or ""
+  x = math.abs(x)
+  if x == 0 then
+    return sign .. "0.0"
+  end
+
+  local mantissa, exponent
+  for precision = 0, 16 do
+    local s = string.format("%." .. precision .. "e", x)
+    if tonumber(s) == x then
+      mantissa, exponent = s:match("^(%d[%.%d]*)e([-+]%d+)$")
+      break
+    end
+  end
+
+  local digits = mantissa:gsub("%.", "")
+  local length = #digits
+  -- The position of the decimal point relative to the first digit.
+  local point = tonumber(exponent) + 1
+
+  if point >= length and point <= 16 then
+    return sign .. digits .. string.rep("0", point - length) .. ".0"
+  elseif point > 0 and point <= 16 then
+    return sign .. digits:sub(1, point) .. "." .. digits:sub(point + 1)
+  elseif point > -5 and point <= 0 then
+    return sign .. "0." .. string.rep("0", -point) .. digits
+  elseif length == 1 then
+    return sign .. digits .. "e" .. (point - 1)
+  else
+    return sign .. digits:sub(1, 1) .. "." .. digits:sub(2) .. "e" .. (point - 1)
+  end
+end
+
+-- Compares strings by their bytes, since `<` on strings follows the locale.
+local function less_bytes(a, b)
+  local n = math.min(#a, #b)
+  for i = 1, n do
+    local x, y = a:byte(i), b:byte(i)
+    if x ~= y then
+      return x < y
+    end
+  end
+  return #a < #b
+end
+
+local function is_array(t)
+  if getmetatable(t) == array_marker then
+    return true
+  end
+
+  local n = #t
+  if n == 0 then
+    return false
+  end
+
+  for key in pairs(t) do
+    if math.type(key) ~= "integer" or key < 1 or key > n then
+      return false
+    end
+  end
+  return true
+end
+
+local encode
+
+local function encode_table(t)
+  if is_array(t) then
+    local elements = {}
+    for i = 1, #t do
+      elements[i] = encode(t[i])
+    end
+    return "[" .. table.concat(elements, ",") .. "]"
+  end
+
+  local keys, fields = {}, {}
+  for key, value in pairs(t) do
+    if type(key) ~= "string" then
+      error("object keys must be strings, got " .. type(key))
+    end
+    -- Keys are sorted after replacing invalid UTF-8, as they are in Rust.
+    local normalized = utf8_lossy(key)
+    keys[#keys + 1] = normalized
+    fields[normalized] = value
+  end
+  table.sort(keys, less_bytes)
+
+  for i, key in ipairs(keys) do
+    keys[i] = encode_string(key) .. ":" .. encode(fields[key])
+  end
+  return "{" .. table.concat(keys, ",") .. "}"
+end
+
+function encode(value)
+  local kind = type(value)
+  if value == nil or value == null then
+    return "null"
+  elseif kind == "boolean" then
+    return tostring(value)
+  elseif kind == "number" then
+    if math.type(value) == "integer" then
+      return string.format("%d", value)
+    end
+    return encode_float(value)
+  elseif kind == "string" then
+    return encode_string(value)
+  elseif kind == "table" then
+    return encode_table(value)
+  end
+  error("can't encode a " .. kind)
+end
+
+local function mul32(a, b)
+  -- Integer multiplication wraps around, which keeps the low 32 bits right.
+  return (a * b) & 0xffffffff
+end
+
+local function rotl32(x, r)
+  return ((x << r) | (x >> (32 - r))) & 0xffffffff
+end
+
+local function mix(k)
+  k = mul32(k, 0xcc9e2d51)
+  k = rotl32(k, 15)
+  return mul32(k, 0x1b873593)
+end
+
+local function murmur3_32(data, seed)
+  local h = seed & 0xffffffff
+  local len = #data
+  local blocks = len // 4
+
+  for i = 0, blocks - 1 do
+    local k = string.unpack("<I4", data, i * 4 + 1)
+    h = h ~ mix(k)
+    h = rotl32(h, 13)
+    h = (h * 5 + 0xe6546b64) & 0xffffffff
+  end
+
+  local tail = blocks * 4
+  local remaining = len & 3
+  local k = 0
+  if remaining >= 3 then
+    k = k ~ (data:byte(tail + 3) << 16)
+  end
+  if remaining >= 2 then
+    k = k ~ (data:byte(tail + 2) << 8)
+  end
+  if remaining >= 1 then
+    k = k ~ data:byte(tail + 1)
+    h = h ~ mix(k)
+  end
+
+  h = h ~ (len & 0xffffffff)
+  h = h ~ (h >> 16)
+  h = mul32(h, 0x85ebca6b)
+  h = h ~ (h >> 13)
+  h = mul32(h, 0xc2b2ae35)
+  return h ~ (h >> 16)
+end
+
+local function partition_hash(value, buckets)
+  assert(buckets > 0, "buckets must be positive")
+  return murmur3_32(encode(value), 0) % buckets
+end
+
+return {
+  array = array,
+  encode = encode,
+  murmur3_32 = murmur3_32,
+  null = null,
+  partition_hash = partition_hash,
+}
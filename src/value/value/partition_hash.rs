@@ -0,0 +1,344 @@
+use std::num::NonZeroU32;
+
+use super::Value;
+
+/// The Lua counterpart of [`Value::partition_hash`], for Lua 5.3 and later.
+///
+/// Loading it returns a table with a `partition_hash(value, buckets)` function, which returns the
+/// same bucket as [`Value::partition_hash`] for a Lua value, and an `encode(value)` function,
+/// which returns its canonical JSON. The helper's comments describe how Lua values map to values,
+/// including the `null` and `array(table)` helpers for nulls in arrays and empty arrays.
+///
+/// Timestamps and regexes have no Lua counterpart, so they're passed as the strings they're
+/// serialized to.
+pub const PARTITION_HASH_LUA: &str = include_str!("partition_hash.lua");
+
+const C1: u32 = 0xcc9e_2d51;
+const C2: u32 = 0x1b87_3593;
+
+fn mix(k: u32) -> u32 {
+    k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2)
+}
+
+/// The 32-bit x86 variant of `MurmurHash3`.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    let mut h = seed;
+
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let k = u32::from_le_bytes(block.try_into().expect("block of 4 bytes"));
+        h ^= mix(k);
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let k = tail
+            .iter()
+            .rev()
+            .fold(0, |k, byte| (k << 8) | u32::from(*byte));
+        h ^= mix(k);
+    }
+
+    // The length is mixed in modulo 2^32, as in the reference implementation.
+    #[allow(clippy::cast_possible_truncation)]
+    let len = data.len() as u32;
+    h ^= len;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+impl Value {
+    /// Returns the bucket in `0..buckets` that the value falls in, so that equal values are
+    /// partitioned the same way wherever they're hashed.
+    ///
+    /// The bucket is the 32-bit x86 `MurmurHash3`, with a seed of `0`, of the value's canonical
+    /// JSON, modulo `buckets`. The canonical JSON is the value serialized as compact JSON, with
+    /// no whitespace and object keys sorted by their bytes, as [`Value`] serializes with
+    /// `serde_json`:
+    ///
+    /// * Strings that aren't valid UTF-8 have the invalid bytes replaced with `U+FFFD`, and only
+    ///   `"`, `\` and control characters are escaped.
+    /// * Floats are written in the shortest form that round trips, always with a fraction or an
+    ///   exponent, so `1.0` is `1.0` and the integer `1` is `1`.
+    /// * Timestamps are RFC 3339 strings, as in `"2021-02-10T23:32:00Z"`, and regexes are their
+    ///   pattern.
+    ///
+    /// [`PARTITION_HASH_LUA`] computes the same bucket for Lua values, and the algorithm won't
+    /// change, since that would move values between buckets.
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)] // Values always serialize to JSON.
+    pub fn partition_hash(&self, buckets: NonZeroU32) -> u32 {
+        let json = serde_json::to_vec(self).expect("values always serialize to JSON");
+        murmur3_32(&json, 0) % buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use mlua::{Lua, Table};
+
+    use crate::value;
+
+    use super::*;
+
+    #[test]
+    fn murmur3_32_vectors() {
+        let vectors: [(&[u8], u32, u32); 9] = [
+            (b"", 0, 0),
+            (b"", 1, 0x514e_28b7),
+            (b"", 0xffff_ffff, 0x81f1_6f39),
+            (b"\0\0\0\0", 0, 0x2362_f9de),
+            (b"aaaa", 0x9747_b28c, 0x5a97_808a),
+            (b"test", 0, 0xba6b_d213),
+            (b"Hello, world!", 0, 0xc036_3e43),
+            (b"Hello, world!", 0x9747_b28c, 0x2488_4cba),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                0x9747_b28c,
+                0x2fa8_26cd,
+            ),
+        ];
+
+        let lua = Lua::new();
+        let lua_murmur3_32: mlua::Function = helper(&lua).get("murmur3_32").unwrap();
+
+        for (data, seed, hash) in vectors {
+            assert_eq!(murmur3_32(data, seed), hash, "data: {data:?}");
+
+            let data = lua.create_string(data).unwrap();
+            assert_eq!(
+                lua_murmur3_32.call::<_, u32>((data, seed)).unwrap(),
+                hash,
+                "seed: {seed}"
+            );
+        }
+    }
+
+    fn buckets(buckets: u32) -> NonZeroU32 {
+        NonZeroU32::new(buckets).unwrap()
+    }
+
+    fn values() -> Vec<Value> {
+        vec![
+            value!("foo"),
+            value!(42),
+            value!({a: 1, b: [true, null]}),
+            value!(1.5),
+            value!(null),
+            value!("héllo"),
+            value!([]),
+            value!({}),
+        ]
+    }
+
+    #[test]
+    fn buckets_are_stable() {
+        let buckets = values()
+            .iter()
+            .map(|value| {
+                (
+                    value.partition_hash(buckets(16)),
+                    value.partition_hash(buckets(1000)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            buckets,
+            vec![
+                (12, 684),
+                (6, 814),
+                (7, 111),
+                (1, 689),
+                (2, 794),
+                (4, 516),
+                (12, 396),
+                (8, 72),
+            ]
+        );
+    }
+
+    fn helper(lua: &Lua) -> Table<'_> {
+        let helper: Table = lua.load(PARTITION_HASH_LUA).eval().unwrap();
+        lua.globals().set("helper", helper.clone()).unwrap();
+        helper
+    }
+
+    #[test]
+    fn lua_tables_match() {
+        let lua = Lua::new();
+        helper(&lua);
+
+        let timestamp = Utc.with_ymd_and_hms(2021, 2, 10, 23, 32, 0).unwrap();
+        let vectors = [
+            ("'foo'", value!("foo")),
+            ("42", value!(42)),
+            ("-2", Value::Integer(-2)),
+            ("1.5", value!(1.5)),
+            ("1.0", value!(1.0)),
+            ("1e300", value!(1e300)),
+            ("0/0", value!(null)),
+            ("nil", value!(null)),
+            ("helper.null", value!(null)),
+            ("true", value!(true)),
+            ("'h\\u{e9}llo'", value!("héllo")),
+            (
+                "'\"quoted\"\\n\\t\\1\\127'",
+                value!("\"quoted\"\n\t\u{1}\u{7f}"),
+            ),
+            (
+                "'\\xffa\\xe2\\x82b\\xed\\xa0\\x80'",
+                value!("\u{fffd}a\u{fffd}b\u{fffd}\u{fffd}\u{fffd}"),
+            ),
+            (
+                "{a = 1, b = {true, helper.null}}",
+                value!({a: 1, b: [true, null]}),
+            ),
+            (
+                "{message = 'disk full', host = {port = 8080, name = 'a'}}",
+                value!({message: "disk full", host: {name: "a", port: 8080}}),
+            ),
+            (
+                "{['b'] = 1, ['B'] = 2, ['\\u{e9}'] = 3, ['a\\0'] = 4}",
+                value!({"b": 1, "B": 2, "é": 3, "a\0": 4}),
+            ),
+            ("{}", value!({})),
+            ("helper.array({})", value!([])),
+            ("'2021-02-10T23:32:00Z'", Value::Timestamp(timestamp)),
+        ];
+
+        for (lua_value, value) in vectors {
+            let json: mlua::String = lua
+                .load(format!("return helper.encode({lua_value})"))
+                .eval()
+                .unwrap();
+            assert_eq!(
+                String::from_utf8_lossy(json.as_bytes()),
+                serde_json::to_string(&value).unwrap(),
+                "lua value: {lua_value}"
+            );
+
+            for count in [1, 7, 16, 1000, u32::MAX] {
+                let bucket: u32 = lua
+                    .load(format!(
+                        "return helper.partition_hash({lua_value}, {count})"
+                    ))
+                    .eval()
+                    .unwrap();
+                assert_eq!(
+                    bucket,
+                    value.partition_hash(buckets(count)),
+                    "lua value: {lua_value}, buckets: {count}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lua_integer_keys_are_rejected() {
+        let lua = Lua::new();
+        helper(&lua);
+
+        for table in ["{[1] = 'a', [3] = 'b'}", "{'a', 'b', x = 'c'}"] {
+            assert!(
+                lua.load(format!("return helper.encode({table})"))
+                    .eval::<mlua::String>()
+                    .is_err(),
+                "table: {table}"
+            );
+        }
+    }
+
+    /// Returns the next number of a xorshift sequence, which is enough to spread test inputs.
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn lua_floats_match() {
+        let lua = Lua::new();
+        let encode: mlua::Function = helper(&lua).get("encode").unwrap();
+
+        let mut floats = vec![
+            0.1,
+            -0.0,
+            1.0 / 3.0,
+            1e15,
+            1e16,
+            1e17,
+            1e21,
+            123_456.789,
+            1_234_567_890_123_456.7,
+            9_007_199_254_740_993.0,
+            0.000_1,
+            0.000_01,
+            1e-7,
+            2.5e-10,
+            5e-324,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::INFINITY,
+        ];
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        floats.extend((0..10_000).map(|_| f64::from_bits(xorshift(&mut state))));
+
+        for float in floats.into_iter().filter(|float| !float.is_nan()) {
+            let json: mlua::String = encode.call(float).unwrap();
+            let value = Value::from_f64_or_zero(float);
+            assert_eq!(
+                json.as_bytes(),
+                serde_json::to_vec(&value).unwrap(),
+                "float: {float:e}"
+            );
+        }
+    }
+
+    #[test]
+    fn lua_invalid_utf8_matches() {
+        let lua = Lua::new();
+        let encode: mlua::Function = helper(&lua).get("encode").unwrap();
+
+        // Bytes around the boundaries of UTF-8 sequences, which make up mostly invalid strings.
+        let alphabet = [
+            b'a', b'"', 0x00, 0x7f, 0x80, 0x8f, 0x90, 0x9f, 0xa0, 0xbf, 0xc0, 0xc2, 0xdf, 0xe0,
+            0xe1, 0xed, 0xef, 0xf0, 0xf4, 0xf5, 0xff,
+        ];
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+
+        for _ in 0..10_000 {
+            let random = xorshift(&mut state).to_le_bytes();
+            let len = usize::from(random[0] % 8);
+            let bytes = random[1..=len]
+                .iter()
+                .map(|byte| alphabet[usize::from(*byte) % alphabet.len()])
+                .collect::<Vec<_>>();
+
+            let json: mlua::String = encode.call(lua.create_string(&bytes).unwrap()).unwrap();
+            assert_eq!(
+                json.as_bytes(),
+                serde_json::to_vec(&Value::Bytes(bytes.clone().into())).unwrap(),
+                "bytes: {bytes:x?}"
+            );
+        }
+    }
+
+    #[test]
+    fn independent_of_key_order() {
+        let mut value = value!({});
+        value.insert("b", 2);
+        value.insert("a", 1);
+
+        assert_eq!(
+            value.partition_hash(buckets(1000)),
+            value!({a: 1, b: 2}).partition_hash(buckets(1000))
+        );
+    }
+}
@@ -191,16 +191,18 @@ impl<'de> Deserialize<'de> for Value {
     }
 }
 
+/// Converts a JSON value into a `Value`.
+///
+/// JSON numbers become integers if they fit into an `i64`, and floats otherwise.
 impl From<serde_json::Value> for Value {
     fn from(json_value: serde_json::Value) -> Self {
         match json_value {
             serde_json::Value::Bool(b) => Self::Boolean(b),
             serde_json::Value::Number(n) if n.is_i64() => n.as_i64().unwrap().into(),
-            serde_json::Value::Number(n) if n.is_f64() => {
+            serde_json::Value::Number(n) => {
                 // JSON doesn't support NaN values
                 NotNan::new(n.as_f64().unwrap()).unwrap().into()
             }
-            serde_json::Value::Number(n) => n.to_string().into(),
             serde_json::Value::String(s) => Self::Bytes(Bytes::from(s)),
             serde_json::Value::Object(obj) => Self::Object(
                 obj.into_iter()
@@ -219,31 +221,62 @@ impl From<&serde_json::Value> for Value {
     }
 }
 
-impl TryInto<serde_json::Value> for Value {
+/// Converts a `Value` into a JSON value.
+///
+/// JSON has no equivalent for some values, so this direction is lossy:
+///
+/// - Timestamps become RFC 3339 strings.
+/// - Regular expressions become their pattern string.
+///
+/// # Errors
+///
+/// Returns an error if the value contains bytes that aren't valid UTF-8.
+impl TryFrom<Value> for serde_json::Value {
     type Error = StdError;
 
-    fn try_into(self) -> Result<serde_json::Value, Self::Error> {
-        match self {
-            Self::Boolean(v) => Ok(serde_json::Value::from(v)),
-            Self::Integer(v) => Ok(serde_json::Value::from(v)),
-            Self::Float(v) => Ok(serde_json::Value::from(v.into_inner())),
-            Self::Bytes(v) => Ok(serde_json::Value::from(String::from_utf8(v.to_vec())?)),
-            Self::Regex(regex) => Ok(serde_json::Value::from(regex.as_str().to_string())),
-            Self::Object(v) => Ok(serde_json::to_value(v)?),
-            Self::Array(v) => Ok(serde_json::to_value(v)?),
-            Self::Null => Ok(serde_json::Value::Null),
-            Self::Timestamp(v) => Ok(serde_json::Value::from(timestamp_to_string(&v))),
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Boolean(v) => Ok(Self::from(v)),
+            Value::Integer(v) => Ok(Self::from(v)),
+            Value::Float(v) => Ok(Self::from(v.into_inner())),
+            Value::Bytes(v) => Ok(Self::from(String::from_utf8(v.to_vec())?)),
+            Value::Regex(regex) => Ok(Self::from(regex.as_str().to_string())),
+            Value::Object(v) => Ok(Self::Object(
+                v.into_iter()
+                    .map(|(key, value)| Ok((key.into(), Self::try_from(value)?)))
+                    .collect::<Result<_, Self::Error>>()?,
+            )),
+            Value::Array(v) => Ok(Self::Array(
+                v.into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Value::Null => Ok(Self::Null),
+            Value::Timestamp(v) => Ok(Self::from(timestamp_to_string(&v))),
         }
     }
 }
 
+/// Converts a borrowed `Value` into a JSON value, see the owned conversion for details.
+impl TryFrom<&Value> for serde_json::Value {
+    type Error = StdError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value.clone().try_into()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::fs;
     use std::io::Read;
     use std::path::Path;
+    use std::sync::Arc;
 
-    use crate::value::Value;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use crate::{btreemap, value::Value};
 
     pub fn parse_artifact(path: impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
         let mut test_file = match fs::File::open(path) {
@@ -312,4 +345,65 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn json_round_trip() {
+        let json = json!({
+            "string": "foo",
+            "integer": -42,
+            "float": 1.5,
+            "large": u64::MAX,
+            "boolean": true,
+            "null": null,
+            "array": [1, "two", [3.0], {"four": 4}],
+            "object": {"nested": {"deeper": []}},
+        });
+
+        let value = Value::from(json.clone());
+        assert_eq!(
+            value.get("large"),
+            Some(&Value::from(1.844_674_407_370_955_2e19))
+        );
+
+        let round_tripped = serde_json::Value::try_from(&value).unwrap();
+        assert_eq!(round_tripped["large"], json!(1.844_674_407_370_955_2e19));
+
+        let mut json = json;
+        json["large"] = json!(1.844_674_407_370_955_2e19);
+        assert_eq!(round_tripped, json);
+        assert_eq!(Value::from(round_tripped), value);
+    }
+
+    #[test]
+    fn json_lossy_conversions() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 2, 29, 12, 30, 0).unwrap();
+        let regex = Arc::new(regex::Regex::new(r"^\d+$").unwrap());
+        let value = Value::from(btreemap! {
+            "timestamp" => Value::Timestamp(timestamp),
+            "regex" => Value::from(regex),
+            "nested" => vec![Value::Timestamp(timestamp + chrono::Duration::milliseconds(5))],
+        });
+
+        let json: serde_json::Value = value.try_into().unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "timestamp": "2024-02-29T12:30:00Z",
+                "regex": r"^\d+$",
+                "nested": ["2024-02-29T12:30:00.005Z"],
+            })
+        );
+
+        // The conversion back yields strings, as JSON has no timestamp or regex type.
+        assert_eq!(
+            Value::from(json).get("timestamp"),
+            Some(&Value::from("2024-02-29T12:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn json_invalid_utf8() {
+        let value = Value::from(vec![Value::from(&b"\xff"[..])]);
+        assert!(serde_json::Value::try_from(value).is_err());
+    }
 }
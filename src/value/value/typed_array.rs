@@ -0,0 +1,234 @@
+use std::fmt;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+
+use super::{ObjectMap, Value};
+
+/// The error returned when a [`Value`] can't be converted into a typed homogeneous `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedArrayError {
+    /// The value itself is not an array.
+    NotArray {
+        /// The type of the value that was found instead.
+        got: &'static str,
+    },
+
+    /// An element of the array does not have the expected type.
+    Element {
+        /// The index of the first offending element.
+        index: usize,
+        /// The expected element type.
+        expected: &'static str,
+        /// The actual type of the offending element.
+        got: &'static str,
+    },
+}
+
+impl fmt::Display for TypedArrayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotArray { got } => write!(f, "expected array, got {got}"),
+            Self::Element {
+                index,
+                expected,
+                got,
+            } => write!(f, "expected {expected} at index {index}, got {got}"),
+        }
+    }
+}
+
+impl std::error::Error for TypedArrayError {}
+
+impl Value {
+    /// Converts an array of integers into a `Vec<i64>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not an integer.
+    pub fn try_into_vec_i64(self) -> Result<Vec<i64>, TypedArrayError> {
+        self.try_into_vec("integer", |value| match value {
+            Self::Integer(v) => Ok(v),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of floats into a `Vec<f64>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not a float.
+    pub fn try_into_vec_f64(self) -> Result<Vec<f64>, TypedArrayError> {
+        self.try_into_vec("float", |value| match value {
+            Self::Float(v) => Ok(v.into_inner()),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of booleans into a `Vec<bool>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not a boolean.
+    pub fn try_into_vec_bool(self) -> Result<Vec<bool>, TypedArrayError> {
+        self.try_into_vec("boolean", |value| match value {
+            Self::Boolean(v) => Ok(v),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of strings into a `Vec<Bytes>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not a string.
+    pub fn try_into_vec_bytes(self) -> Result<Vec<Bytes>, TypedArrayError> {
+        self.try_into_vec("string", |value| match value {
+            Self::Bytes(v) => Ok(v),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of strings into a `Vec<String>`.
+    ///
+    /// Invalid UTF-8 sequences are replaced, in the same way as [`Value::as_str`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not a string.
+    pub fn try_into_vec_string(self) -> Result<Vec<String>, TypedArrayError> {
+        self.try_into_vec("string", |value| match value {
+            Self::Bytes(v) => Ok(String::from_utf8_lossy(&v).into_owned()),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of timestamps into a `Vec<DateTime<Utc>>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not a timestamp.
+    pub fn try_into_vec_timestamp(self) -> Result<Vec<DateTime<Utc>>, TypedArrayError> {
+        self.try_into_vec("timestamp", |value| match value {
+            Self::Timestamp(v) => Ok(v),
+            value => Err(value),
+        })
+    }
+
+    /// Converts an array of objects into a `Vec<ObjectMap>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not an array, or names the index of the first element
+    /// that is not an object.
+    pub fn try_into_vec_object(self) -> Result<Vec<ObjectMap>, TypedArrayError> {
+        self.try_into_vec("map", |value| match value {
+            Self::Object(v) => Ok(v),
+            value => Err(value),
+        })
+    }
+
+    fn try_into_vec<T>(
+        self,
+        expected: &'static str,
+        convert: impl Fn(Self) -> Result<T, Self>,
+    ) -> Result<Vec<T>, TypedArrayError> {
+        let array = match self {
+            Self::Array(array) => array,
+            value => {
+                return Err(TypedArrayError::NotArray {
+                    got: element_kind(&value),
+                })
+            }
+        };
+
+        array
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                convert(value).map_err(|value| TypedArrayError::Element {
+                    index,
+                    expected,
+                    got: element_kind(&value),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Like [`Value::kind_str`], but names regexes distinctly, since they aren't accepted where a
+/// string is expected.
+const fn element_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Regex(_) => "regex",
+        value => value.kind_str(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value;
+
+    use super::*;
+
+    #[test]
+    fn integer_array() {
+        let value = value!([1, 2, 3]);
+        assert_eq!(value.try_into_vec_i64(), Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn string_array() {
+        let value = value!(["foo", "bar"]);
+        assert_eq!(
+            value.try_into_vec_string(),
+            Ok(vec!["foo".to_owned(), "bar".to_owned()])
+        );
+    }
+
+    #[test]
+    fn empty_array() {
+        assert_eq!(value!([]).try_into_vec_bool(), Ok(vec![]));
+    }
+
+    #[test]
+    fn mixed_array_reports_first_offending_index() {
+        let value = value!([1, 2, "three", 4, "five"]);
+        let error = value.try_into_vec_i64().unwrap_err();
+
+        assert_eq!(
+            error,
+            TypedArrayError::Element {
+                index: 2,
+                expected: "integer",
+                got: "string",
+            }
+        );
+        assert_eq!(error.to_string(), "expected integer at index 2, got string");
+    }
+
+    #[test]
+    fn regex_element_is_not_a_string() {
+        let value = Value::Array(vec![
+            value!("foo"),
+            Value::Regex(regex::Regex::new("bar").unwrap().into()),
+        ]);
+        let error = value.try_into_vec_string().unwrap_err();
+
+        assert_eq!(error.to_string(), "expected string at index 1, got regex");
+    }
+
+    #[test]
+    fn not_an_array() {
+        assert_eq!(
+            value!({"foo": 1}).try_into_vec_i64(),
+            Err(TypedArrayError::NotArray { got: "map" })
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Compiles and runs VRL programs on `wasm32` targets.
+//!
+//! Run with `wasm-pack test --node -- --no-default-features --features wasm --test wasm`.
+
+#![cfg(target_arch = "wasm32")]
+
+use std::collections::BTreeMap;
+
+use vrl::compiler::{state::RuntimeState, Context, Resolved, TargetValue, TimeZone};
+use vrl::value;
+use vrl::value::{Secrets, Value};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn run(source: &str, event: Value) -> (Resolved, Value) {
+    let fns = vrl::stdlib::all();
+    let result = vrl::compiler::compile(source, &fns).expect("program should compile");
+
+    let mut target = TargetValue {
+        value: event,
+        metadata: Value::Object(BTreeMap::new()),
+        secrets: Secrets::default(),
+    };
+    let mut state = RuntimeState::default();
+    let timezone = TimeZone::default();
+    let mut ctx = Context::new(&mut target, &mut state, &timezone);
+
+    let resolved = result.program.resolve(&mut ctx);
+
+    (resolved, target.value)
+}
+
+#[wasm_bindgen_test]
+fn compile_and_resolve() {
+    let source = r#"
+        . = parse_json!(.message)
+        .level = upcase!(.level)
+        .checksum = crc32!(.user)
+        .day = format_timestamp!(t'2021-02-10T23:32:00Z', "%A", timezone: "Europe/Berlin")
+        .tags = unique(split!(.tags, ","))
+        .
+    "#;
+    let event = value!({"message": r#"{"level": "info", "user": "vrl", "tags": "a,b,a"}"#});
+
+    let (resolved, target) = run(source, event);
+
+    let expected = value!({
+        "level": "INFO",
+        "user": "vrl",
+        "checksum": 4_130_405_367_u32,
+        "day": "Thursday",
+        "tags": ["a", "b"],
+    });
+    assert_eq!(resolved, Ok(expected.clone()));
+    assert_eq!(target, expected);
+}
+
+#[wasm_bindgen_test]
+fn unsupported_fallible_function_errors() {
+    let (resolved, _) = run(
+        r#"hostname, err = get_hostname()
+        err"#,
+        value!({}),
+    );
+
+    assert_eq!(
+        resolved,
+        Ok(value!("This function is not supported in WebAssembly"))
+    );
+}